@@ -2,6 +2,7 @@
 // 提供图标搜索、选择、保存等功能的响应式状态管理
 
 import { invoke } from '@tauri-apps/api/core'
+import { useDialog } from 'naive-ui'
 import { computed, reactive, ref } from 'vue'
 import {
   type ClearCacheRequest,
@@ -16,6 +17,36 @@ import {
   type IconSearchResult,
 } from '../types/icon'
 
+// 转换为后端保存请求格式（snake_case）
+function toBackendSaveRequest(request: IconSaveRequest) {
+  return {
+    icons: request.icons.map(icon => ({
+      id: icon.id,
+      name: icon.name,
+      font_class: icon.fontClass,
+      unicode: icon.unicode,
+      svg_content: icon.svgContent,
+      preview_url: icon.previewUrl,
+      author: icon.author,
+      repository_name: icon.repositoryName,
+      repository_id: icon.repositoryId,
+      created_at: icon.createdAt,
+    })),
+    save_path: request.savePath,
+    format: request.format,
+    png_size: request.pngSize,
+    optimize: request.optimize
+      ? {
+          enabled: request.optimize.enabled,
+          grid_size: request.optimize.gridSize,
+          current_color: request.optimize.currentColor,
+          precision: request.optimize.precision,
+        }
+      : undefined,
+    overwrite: request.overwrite,
+  }
+}
+
 // 后端返回的图标搜索结果（snake_case）
 interface IconSearchResultRaw {
   icons: any[]
@@ -30,6 +61,8 @@ interface IconSearchResultRaw {
  * 提供完整的图标搜索、选择、保存功能
  */
 export function useIconSearch() {
+  const dialog = useDialog()
+
   // ============ 状态定义 ============
 
   /** 是否正在加载 */
@@ -275,6 +308,37 @@ export function useIconSearch() {
     }
   }
 
+  /**
+   * 目标文件已存在时询问是否覆盖
+   * 返回 true 覆盖、false 跳过已存在的文件、null 取消保存
+   */
+  async function confirmOverwrite(request: IconSaveRequest): Promise<boolean | null> {
+    let existing: string[] = []
+    try {
+      existing = await invoke<string[]>('find_existing_icons', { request: toBackendSaveRequest(request) })
+    }
+    catch (e) {
+      console.error('检查已存在文件失败:', e)
+    }
+    if (existing.length === 0)
+      return true
+
+    const more = existing.length > 10 ? `\n…… 另有 ${existing.length - 10} 个文件` : ''
+    return new Promise((resolve) => {
+      dialog.warning({
+        title: '文件已存在',
+        content: `以下 ${existing.length} 个文件已存在，是否覆盖？\n\n${existing.slice(0, 10).join('\n')}${more}`,
+        positiveText: '覆盖',
+        negativeText: '跳过已存在的文件',
+        onPositiveClick: () => resolve(true),
+        onNegativeClick: () => resolve(false),
+        onClose: () => resolve(null),
+        onEsc: () => resolve(null),
+        onMaskClick: () => resolve(null),
+      })
+    })
+  }
+
   /**
    * 保存图标到本地
    */
@@ -283,26 +347,7 @@ export function useIconSearch() {
     error.value = null
 
     try {
-      // 转换为后端格式
-      const backendRequest = {
-        icons: request.icons.map(icon => ({
-          id: icon.id,
-          name: icon.name,
-          font_class: icon.fontClass,
-          unicode: icon.unicode,
-          svg_content: icon.svgContent,
-          preview_url: icon.previewUrl,
-          author: icon.author,
-          repository_name: icon.repositoryName,
-          repository_id: icon.repositoryId,
-          created_at: icon.createdAt,
-        })),
-        save_path: request.savePath,
-        format: request.format,
-        png_size: request.pngSize,
-      }
-
-      const result = await invoke<any>('save_icons', { request: backendRequest })
+      const result = await invoke<any>('save_icons', { request: toBackendSaveRequest(request) })
 
       return {
         items: result.items.map((item: any) => ({
@@ -311,10 +356,19 @@ export function useIconSearch() {
           success: item.success,
           savedPaths: item.saved_paths,
           error: item.error,
+          optimizeReport: item.optimize_report
+            ? {
+                originalBytes: item.optimize_report.original_bytes,
+                optimizedBytes: item.optimize_report.optimized_bytes,
+                flattened: item.optimize_report.flattened,
+              }
+            : undefined,
         })),
         successCount: result.success_count,
         failedCount: result.failed_count,
         savePath: result.save_path,
+        bundleFiles: result.bundle_files,
+        bytesSaved: result.bytes_saved,
       }
     }
     catch (e) {
@@ -338,6 +392,7 @@ export function useIconSearch() {
         expiredEntries: result.expired_entries,
         cacheExpiryMinutes: result.cache_expiry_minutes,
         memoryUsageBytes: result.memory_usage_bytes,
+        diskUsageBytes: result.disk_usage_bytes,
       }
     }
     catch (e) {
@@ -376,6 +431,8 @@ export function useIconSearch() {
         defaultFormat: result.default_format,
         defaultPngSize: result.default_png_size,
         cacheExpiryMinutes: result.cache_expiry_minutes,
+        provider: result.provider,
+        localSources: result.local_sources,
       }
     }
     catch (e) {
@@ -383,6 +440,25 @@ export function useIconSearch() {
     }
   }
 
+  /**
+   * 加载项目级配置（全局配置叠加项目根目录下的 .sanshu/icons.json）
+   * 仅用于保存时的默认值，不写回全局配置
+   */
+  async function loadProjectConfig(projectRoot: string): Promise<IconConfig | null> {
+    try {
+      const result = await invoke<any>('get_project_icon_config', { projectRoot })
+      return {
+        defaultSavePath: result.default_save_path,
+        defaultFormat: result.default_format,
+        defaultPngSize: result.default_png_size,
+      }
+    }
+    catch (e) {
+      console.error('加载项目图标配置失败:', e)
+      return null
+    }
+  }
+
   /**
    * 保存配置
    */
@@ -394,6 +470,8 @@ export function useIconSearch() {
           default_format: newConfig.defaultFormat,
           default_png_size: newConfig.defaultPngSize,
           cache_expiry_minutes: newConfig.cacheExpiryMinutes,
+          provider: newConfig.provider,
+          local_sources: newConfig.localSources,
         },
       })
       config.value = newConfig
@@ -461,11 +539,13 @@ export function useIconSearch() {
     toggleSelectAll,
     clearSelection,
     copyToClipboard,
+    confirmOverwrite,
     saveIcons,
     getCacheStats,
     clearCache,
     loadConfig,
     saveConfig,
+    loadProjectConfig,
     selectSaveDirectory,
     resetSearchParams,
   }
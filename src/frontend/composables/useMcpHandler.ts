@@ -84,19 +84,21 @@ export function useMcpHandler() {
       console.error('播放音频通知失败:', error)
     }
 
-    // 启动Telegram同步（无论是否显示弹窗都启动）
+    // 同步到 Telegram 及其他通知渠道（无论是否显示弹窗都启动，未启用任何渠道时直接返回）
     try {
       if (request?.message) {
-        await invoke('start_telegram_sync', {
+        await invoke('start_notification_sync', {
           message: request.message,
           predefinedOptions: request.predefined_options || [],
           isMarkdown: request.is_markdown || false,
+          fields: request.fields || null,
+          requestId: request.id || null,
+          projectRootPath: request.project_root_path || null,
         })
-        console.log('✅ Telegram同步启动成功')
       }
     }
     catch (error) {
-      console.error('启动Telegram同步失败:', error)
+      console.error('启动通知渠道同步失败:', error)
     }
   }
 
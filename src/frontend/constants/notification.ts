@@ -0,0 +1,120 @@
+// 通知渠道前端常量定义（Telegram 之外）
+
+export type NotificationChannelName = 'slack' | 'discord' | 'feishu' | 'dingtalk' | 'ntfy' | 'gotify' | 'email'
+
+/** 渠道配置字段描述 */
+export interface ChannelField {
+  key: string
+  label: string
+  placeholder: string
+  /** 逗号分隔的列表（如邮件收件人） */
+  list?: boolean
+  /** 数字字段（如 SMTP 端口） */
+  number?: boolean
+}
+
+export interface ChannelDefinition {
+  name: NotificationChannelName
+  label: string
+  description: string
+  /** 是否支持在渠道中直接答复 */
+  supportsReply: boolean
+  fields: ChannelField[]
+}
+
+/** 渠道列表 */
+export const CHANNELS: ChannelDefinition[] = [
+  {
+    name: 'slack',
+    label: 'Slack',
+    description: '在请求消息的讨论串中回复即可答复（需 chat:write、channels:history 权限）',
+    supportsReply: true,
+    fields: [
+      { key: 'bot_token', label: 'Bot Token', placeholder: 'xoxb-...' },
+      { key: 'channel_id', label: 'Channel ID', placeholder: '例如: C0123456789' },
+      { key: 'api_base_url', label: 'API 地址', placeholder: 'https://slack.com/api' },
+      { key: 'allowed_user_ids', label: '允许答复的成员', placeholder: '成员 ID（U 开头），多个用逗号分隔，留空不限制', list: true },
+    ],
+  },
+  {
+    name: 'discord',
+    label: 'Discord',
+    description: '在频道中回复请求消息即可答复（需开启 Message Content Intent）',
+    supportsReply: true,
+    fields: [
+      { key: 'bot_token', label: 'Bot Token', placeholder: '从 Developer Portal 获取' },
+      { key: 'channel_id', label: 'Channel ID', placeholder: '例如: 123456789012345678' },
+      { key: 'api_base_url', label: 'API 地址', placeholder: 'https://discord.com/api/v10' },
+      { key: 'allowed_user_ids', label: '允许答复的用户', placeholder: '用户 ID，多个用逗号分隔，留空不限制', list: true },
+    ],
+  },
+  {
+    name: 'feishu',
+    label: '飞书机器人',
+    description: '群自定义机器人，仅发送通知',
+    supportsReply: false,
+    fields: [
+      { key: 'webhook_url', label: 'Webhook 地址', placeholder: 'https://open.feishu.cn/open-apis/bot/v2/hook/...' },
+      { key: 'secret', label: '签名密钥', placeholder: '未开启签名校验时留空' },
+    ],
+  },
+  {
+    name: 'dingtalk',
+    label: '钉钉机器人',
+    description: '群自定义机器人，仅发送通知',
+    supportsReply: false,
+    fields: [
+      { key: 'webhook_url', label: 'Webhook 地址', placeholder: 'https://oapi.dingtalk.com/robot/send?access_token=...' },
+      { key: 'secret', label: '加签密钥', placeholder: 'SEC 开头，未开启加签时留空' },
+    ],
+  },
+  {
+    name: 'ntfy',
+    label: 'ntfy',
+    description: '点击通知上的按钮，或发布到 <话题>-reply 即可答复',
+    supportsReply: true,
+    fields: [
+      { key: 'server_url', label: '服务器地址', placeholder: 'https://ntfy.sh' },
+      { key: 'topic', label: '话题', placeholder: '建议使用不易猜测的名称' },
+      { key: 'access_token', label: 'Access Token', placeholder: '公开服务器可留空' },
+    ],
+  },
+  {
+    name: 'gotify',
+    label: 'Gotify',
+    description: '自建推送服务，仅发送通知',
+    supportsReply: false,
+    fields: [
+      { key: 'server_url', label: '服务器地址', placeholder: 'https://gotify.example.com' },
+      { key: 'app_token', label: '应用 Token', placeholder: '在 Gotify 中创建应用获取' },
+    ],
+  },
+  {
+    name: 'email',
+    label: '邮件 (SMTP)',
+    description: '发送邮件提醒，仅发送通知',
+    supportsReply: false,
+    fields: [
+      { key: 'smtp_host', label: 'SMTP 服务器', placeholder: 'smtp.example.com' },
+      { key: 'smtp_port', label: '端口', placeholder: '587', number: true },
+      { key: 'security', label: '加密方式', placeholder: 'starttls / tls / none' },
+      { key: 'username', label: '用户名', placeholder: '通常为邮箱地址' },
+      { key: 'password', label: '密码', placeholder: '邮箱密码或授权码' },
+      { key: 'from', label: '发件人', placeholder: 'sanshu@example.com' },
+      { key: 'to', label: '收件人', placeholder: '多个地址用逗号分隔', list: true },
+    ],
+  },
+]
+
+/** 默认通知渠道配置 */
+export const DEFAULT_NOTIFICATION_CONFIG = {
+  slack: { enabled: false, bot_token: '', channel_id: '', api_base_url: 'https://slack.com/api', allowed_user_ids: [] as string[] },
+  discord: { enabled: false, bot_token: '', channel_id: '', api_base_url: 'https://discord.com/api/v10', allowed_user_ids: [] as string[] },
+  feishu: { enabled: false, webhook_url: '', secret: '' },
+  dingtalk: { enabled: false, webhook_url: '', secret: '' },
+  ntfy: { enabled: false, server_url: 'https://ntfy.sh', topic: '', access_token: '' },
+  gotify: { enabled: false, server_url: '', app_token: '' },
+  email: { enabled: false, smtp_host: '', smtp_port: 587, security: 'starttls', username: '', password: '', from: '', to: [] as string[] },
+}
+
+export type NotificationConfig = typeof DEFAULT_NOTIFICATION_CONFIG
@@ -16,4 +16,10 @@ export const DEFAULT_CONFIG = {
   chat_id: '',
   hide_frontend_popup: false,
   api_base_url: API_BASE_URL,
+  allowed_user_ids: [] as number[],
+  use_forum_topics: false,
+  webhook_enabled: false,
+  webhook_bind_addr: '127.0.0.1:8787',
+  webhook_public_url: '',
+  webhook_secret: '',
 } as const
@@ -61,7 +61,7 @@ export interface IconSearchResult {
 // ============ 保存相关类型 ============
 
 /** 图标格式枚举 */
-export type IconFormat = 'svg' | 'png' | 'both'
+export type IconFormat = 'svg' | 'png' | 'both' | 'sprite' | 'font' | 'png_set' | 'favicon' | 'react' | 'vue' | 'svelte'
 
 /** 图标保存请求 */
 export interface IconSaveRequest {
@@ -71,8 +71,34 @@ export interface IconSaveRequest {
   savePath: string
   /** 保存格式 */
   format: IconFormat
-  /** PNG 尺寸（仅当 format 包含 PNG 时有效） */
+  /** PNG 尺寸（PNG 格式为输出尺寸，多倍图为 1x 尺寸） */
   pngSize?: number
+  /** SVG 优化选项（未指定时使用默认优化） */
+  optimize?: SvgOptimizeOptions
+  /** 目标文件已存在时是否覆盖（未指定时覆盖） */
+  overwrite?: boolean
+}
+
+/** SVG 优化选项 */
+export interface SvgOptimizeOptions {
+  /** 是否优化（关闭时仅校验） */
+  enabled: boolean
+  /** 归一化的正方形网格尺寸，未指定时保持原尺寸 */
+  gridSize?: number
+  /** 单色图标转换为 currentColor */
+  currentColor: boolean
+  /** 坐标保留的小数位数 */
+  precision: number
+}
+
+/** SVG 优化结果 */
+export interface SvgOptimizeReport {
+  /** 优化前字节数 */
+  originalBytes: number
+  /** 优化后字节数 */
+  optimizedBytes: number
+  /** 是否已展平为纯路径 */
+  flattened: boolean
 }
 
 /** 单个图标保存结果 */
@@ -87,6 +113,8 @@ export interface IconSaveItem {
   savedPaths: string[]
   /** 错误信息（如果失败） */
   error?: string
+  /** SVG 优化结果 */
+  optimizeReport?: SvgOptimizeReport
 }
 
 /** 图标保存结果 */
@@ -99,6 +127,10 @@ export interface IconSaveResult {
   failedCount: number
   /** 保存目录 */
   savePath: string
+  /** 汇总生成的文件（雪碧图、组件索引文件） */
+  bundleFiles?: string[]
+  /** SVG 优化共节省的字节数 */
+  bytesSaved?: number
 }
 
 // ============ 内容获取相关类型 ============
@@ -141,6 +173,8 @@ export interface IconCacheStats {
   cacheExpiryMinutes: number
   /** 预估内存占用（字节） */
   memoryUsageBytes?: number
+  /** 磁盘缓存占用（字节） */
+  diskUsageBytes?: number
 }
 
 /** 清空缓存请求 */
@@ -169,8 +203,15 @@ export interface IconConfig {
   defaultPngSize?: number
   /** 缓存过期时间（分钟） */
   cacheExpiryMinutes?: number
+  /** 图标来源 */
+  provider?: IconProvider
+  /** 本地图标库路径（SVG 目录或 Iconify JSON 图标集） */
+  localSources?: string[]
 }
 
+/** 图标来源: iconfont(在线) | local(本地图标库) */
+export type IconProvider = 'iconfont' | 'local'
+
 // ============ UI 状态类型 ============
 
 /** 筛选选项配置 */
@@ -181,6 +222,8 @@ export interface IconFilterOptions {
   fills: Array<{ label: string, value: string }>
   /** 排序方式选项 */
   sortTypes: Array<{ label: string, value: string }>
+  /** 图标来源选项 */
+  providers: Array<{ label: string, value: IconProvider }>
 }
 
 /** 图标工坊 UI 状态 */
@@ -219,6 +262,10 @@ export const DEFAULT_FILTER_OPTIONS: IconFilterOptions = {
     { label: '最新', value: 'new' },
     { label: '最热', value: 'hot' },
   ],
+  providers: [
+    { label: 'Iconfont', value: 'iconfont' },
+    { label: '本地图标库', value: 'local' },
+  ],
 }
 
 /** 默认搜索参数 */
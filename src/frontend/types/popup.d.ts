@@ -9,8 +9,36 @@ export interface McpRequest {
   uiux_intent?: 'none' | 'beautify' | 'page_refactor' | 'uiux_search'
   uiux_context_policy?: 'auto' | 'force' | 'forbid'
   uiux_reason?: string
+  fields?: ZhiField[]
 }
 
+// 结构化表单字段
+export type ZhiFieldKind
+  = | 'single_select'
+    | 'multi_select'
+    | 'text'
+    | 'number'
+    | 'boolean'
+    | 'file_path'
+    | 'confirm'
+
+export interface ZhiField {
+  key: string
+  label: string
+  type: ZhiFieldKind
+  description?: string
+  required?: boolean
+  options?: string[]
+  default?: unknown
+  min?: number // number 为数值下限，text 为最小长度，multi_select 为最少选择数
+  max?: number // number 为数值上限，text 为最大长度，multi_select 为最多选择数
+  pattern?: string
+  directory?: boolean
+  danger_level?: 'low' | 'medium' | 'high'
+}
+
+export type ZhiFormValues = Record<string, unknown>
+
 // 自定义prompt类型定义
 export interface CustomPrompt {
   id: string
@@ -73,6 +101,8 @@ export interface McpResponse {
   user_input: string | null
   selected_options: string[]
   images: ImageAttachment[]
+  form_values?: ZhiFormValues
+  attachments?: FileAttachment[]
   metadata: ResponseMetadata
 }
 
@@ -82,6 +112,13 @@ export interface ImageAttachment {
   filename: string | null
 }
 
+// 非图片附件（如 Telegram 中发送的文档），data 为 Base64
+export interface FileAttachment {
+  data: string
+  media_type: string
+  filename: string
+}
+
 export interface ResponseMetadata {
   timestamp: string | null
   request_id: string | null
@@ -105,6 +105,17 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
             crate::mcp::tools::context7::commands::test_context7_connection,
             crate::mcp::tools::context7::commands::get_context7_config,
             crate::mcp::tools::context7::commands::save_context7_config,
+            crate::mcp::tools::context7::commands::create_context7_snapshot,
+            crate::mcp::tools::context7::commands::list_context7_snapshots,
+            crate::mcp::tools::context7::commands::clear_context7_cache,
+
+            // 技能包管理命令
+            crate::mcp::tools::skills::commands::list_installed_skills,
+            crate::mcp::tools::skills::commands::install_skill,
+            crate::mcp::tools::skills::commands::update_skills,
+            crate::mcp::tools::skills::commands::sync_skills,
+            crate::mcp::tools::skills::commands::remove_skill,
+            crate::mcp::tools::skills::commands::skills_doctor,
 
             // 自定义prompt命令
             get_custom_prompt_config,
@@ -128,7 +139,12 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
             set_telegram_config,
             test_telegram_connection_cmd,
             auto_get_chat_id,
-            start_telegram_sync,
+
+            // 通知渠道命令
+            crate::notify::commands::get_notification_config,
+            crate::notify::commands::set_notification_config,
+            crate::notify::commands::test_notification_channel,
+            crate::notify::commands::start_notification_sync,
 
             // 系统命令
             open_external_url,
@@ -155,10 +171,12 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
             crate::mcp::tools::icon::commands::search_icons,
             crate::mcp::tools::icon::commands::get_icon_content,
             crate::mcp::tools::icon::commands::save_icons,
+            crate::mcp::tools::icon::commands::find_existing_icons,
             crate::mcp::tools::icon::commands::get_icon_cache_stats,
             crate::mcp::tools::icon::commands::clear_icon_cache,
             crate::mcp::tools::icon::commands::get_icon_config,
             crate::mcp::tools::icon::commands::set_icon_config,
+            crate::mcp::tools::icon::commands::get_project_icon_config,
             crate::mcp::tools::icon::commands::copy_icon_to_clipboard,
             crate::mcp::tools::icon::commands::select_icon_save_directory,
 
@@ -173,6 +191,7 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
             crate::mcp::tools::interaction::commands::add_zhi_history,
             crate::mcp::tools::interaction::commands::get_zhi_history,
             crate::mcp::tools::interaction::commands::clear_zhi_history,
+            crate::mcp::tools::interaction::commands::select_zhi_form_path,
 
             // 记忆管理命令
             get_memory_list,
@@ -1,5 +1,6 @@
 use crate::config::load_standalone_telegram_config;
 use crate::mcp::types::PopupRequest;
+use crate::mcp::tools::interaction::form::{validate_field_specs, ZhiField};
 use crate::mcp::utils::generate_request_id;
 use crate::telegram::handle_telegram_only_mcp_request;
 use crate::log_important;
@@ -66,6 +67,7 @@ fn handle_cli_mode(args: &[String]) -> Result<()> {
     let mut uiux_intent: Option<String> = None;
     let mut uiux_context_policy: Option<String> = None;
     let mut uiux_reason: Option<String> = None;
+    let mut fields: Vec<ZhiField> = Vec::new();
 
     let mut i = 0;
     while i < args.len() {
@@ -107,6 +109,16 @@ fn handle_cli_mode(args: &[String]) -> Result<()> {
                 uiux_reason = Some(args[i + 1].clone());
                 i += 2;
             }
+            "--fields" if i + 1 < args.len() => {
+                fields = match serde_json::from_str(&args[i + 1]) {
+                    Ok(parsed) => parsed,
+                    Err(e) => {
+                        eprintln!("无效的 --fields JSON: {}", e);
+                        std::process::exit(2);
+                    }
+                };
+                i += 2;
+            }
             "--help" | "-h" => {
                 print_help();
                 return Ok(());
@@ -146,6 +158,11 @@ fn handle_cli_mode(args: &[String]) -> Result<()> {
         }
     }
 
+    if let Err(e) = validate_field_specs(&fields) {
+        eprintln!("无效的 --fields: {}", e);
+        std::process::exit(2);
+    }
+
     // 记录 UI/UX 上下文控制信号，便于审计排查
     if uiux_intent.is_some() || uiux_context_policy.is_some() || uiux_reason.is_some() {
         log_important!(
@@ -167,6 +184,7 @@ fn handle_cli_mode(args: &[String]) -> Result<()> {
         uiux_intent,
         uiux_context_policy,
         uiux_reason,
+        fields,
     };
     let request_json = serde_json::to_string(&request)?;
     std::env::set_var("SANSHU_CLI_MODE", "true");
@@ -294,6 +312,7 @@ fn print_help() {
     println!("  --uiux-intent <值>                   none/beautify/page_refactor/uiux_search");
     println!("  --uiux-context-policy <值>           auto/force/forbid");
     println!("  --uiux-reason <内容>                  UI/UX 上下文追加原因");
+    println!("  --fields <JSON>                      结构化表单字段（JSON 数组）");
     println!();
     println!("图标搜索选项:");
     println!("  --query <关键词>      预设搜索关键词");
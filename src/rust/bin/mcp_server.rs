@@ -3,6 +3,18 @@ use sanshu::{mcp::run_server, utils::auto_init_logger, log_important};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // 技能管理子命令：三术 skill <install|update|remove|list|doctor>
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("skill") {
+        let code = sanshu::mcp::tools::skills::cli::run(&args[2..]).await;
+        std::process::exit(code);
+    }
+    // 文档快照子命令：三术 context7 <snapshot|list|clear-cache>
+    if args.get(1).map(String::as_str) == Some("context7") {
+        let code = sanshu::mcp::tools::context7::cli::run(&args[2..]).await;
+        std::process::exit(code);
+    }
+
     // 自动初始化日志系统
     auto_init_logger()?;
 
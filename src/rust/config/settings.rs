@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Mutex;
-use crate::constants::{window, theme, audio, mcp, telegram, font};
+use crate::constants::{window, theme, audio, mcp, telegram, font, notification};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AppConfig {
@@ -21,6 +21,8 @@ pub struct AppConfig {
     pub shortcut_config: ShortcutConfig, // 自定义快捷键配置
     #[serde(default = "default_proxy_config")]
     pub proxy_config: ProxyConfig, // 代理配置
+    #[serde(default = "default_notification_config")]
+    pub notification_config: NotificationConfig, // Telegram之外的远程通知渠道配置
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -126,7 +128,14 @@ pub struct McpConfig {
     pub acemcp_proxy_username: Option<String>, // 代理用户名（可选）
     pub acemcp_proxy_password: Option<String>, // 代理密码（可选）
     pub context7_api_key: Option<String>, // Context7 API密钥 (可选，免费使用时可为空)
+    pub context7_cache_ttl_minutes: Option<u64>, // Context7 文档缓存有效期（分钟，默认 1440 即 24 小时）
     pub skill_python_path: Option<String>, // Skill Python 路径（可选，默认走 PATH）
+    pub skill_node_path: Option<String>, // Skill Node.js 路径（可选，默认走 PATH）
+    pub skill_bash_path: Option<String>, // Skill Bash 路径（可选，默认走 PATH）
+    pub skill_pwsh_path: Option<String>, // Skill PowerShell 路径（可选，默认走 PATH）
+    pub skill_require_approval: Option<bool>, // 未签名技能首次运行是否弹窗审批（默认开启）
+    pub skill_trusted_keys: Option<Vec<String>>, // 受信任的技能签名公钥（Ed25519，base64）
+    pub skill_default_project_root: Option<String>, // 客户端未提供 roots 时的默认项目目录（可选，默认为工作目录）
 
     // UI/UX Pro Max 配置
     /// 默认语言（"zh" | "en"）
@@ -147,6 +156,10 @@ pub struct McpConfig {
     pub icon_default_png_size: Option<u32>,
     /// 缓存过期时间（分钟），默认 30
     pub icon_cache_expiry_minutes: Option<u64>,
+    /// 图标来源: "iconfont" | "local"，默认 iconfont
+    pub icon_provider: Option<String>,
+    /// 本地图标库路径（SVG 目录或 Iconify JSON 图标集）
+    pub icon_local_sources: Option<Vec<String>>,
 }
 
 
@@ -226,6 +239,123 @@ pub struct TelegramConfig {
     pub hide_frontend_popup: bool, // 是否隐藏前端弹窗，仅使用Telegram交互
     #[serde(default = "default_telegram_api_base_url")]
     pub api_base_url: String, // Telegram API基础URL
+    #[serde(default = "default_telegram_allowed_user_ids")]
+    pub allowed_user_ids: Vec<i64>, // 允许答复的用户ID，为空时不限制
+    #[serde(default = "default_telegram_use_forum_topics")]
+    pub use_forum_topics: bool, // 群组开启话题时，每个项目使用独立话题
+    #[serde(default = "default_telegram_webhook_enabled")]
+    pub webhook_enabled: bool, // 使用Webhook接收更新（替代长轮询）
+    #[serde(default = "default_telegram_webhook_bind_addr")]
+    pub webhook_bind_addr: String, // Webhook本地监听地址
+    #[serde(default = "default_telegram_webhook_public_url")]
+    pub webhook_public_url: String, // Telegram推送的公网HTTPS地址
+    #[serde(default = "default_telegram_webhook_secret")]
+    pub webhook_secret: String, // Webhook密钥，为空时由Bot Token派生
+}
+
+/// 远程通知渠道配置（Telegram 之外）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NotificationConfig {
+    #[serde(default = "default_slack_config")]
+    pub slack: SlackConfig,
+    #[serde(default = "default_discord_config")]
+    pub discord: DiscordConfig,
+    #[serde(default = "default_webhook_bot_config")]
+    pub feishu: WebhookBotConfig,
+    #[serde(default = "default_webhook_bot_config")]
+    pub dingtalk: WebhookBotConfig,
+    #[serde(default = "default_ntfy_config")]
+    pub ntfy: NtfyConfig,
+    #[serde(default = "default_gotify_config")]
+    pub gotify: GotifyConfig,
+    #[serde(default = "default_email_config")]
+    pub email: EmailConfig,
+}
+
+/// Slack Bot 配置（需 chat:write、channels:history 权限）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SlackConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub bot_token: String, // xoxb- 开头的 Bot Token
+    #[serde(default)]
+    pub channel_id: String,
+    #[serde(default = "default_slack_api_base_url")]
+    pub api_base_url: String,
+    #[serde(default)]
+    pub allowed_user_ids: Vec<String>, // 允许答复的成员ID（U 开头），为空时不限制
+}
+
+/// Discord Bot 配置（需 Message Content Intent 才能读取回复）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiscordConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub bot_token: String,
+    #[serde(default)]
+    pub channel_id: String,
+    #[serde(default = "default_discord_api_base_url")]
+    pub api_base_url: String,
+    #[serde(default)]
+    pub allowed_user_ids: Vec<String>, // 允许答复的用户ID，为空时不限制
+}
+
+/// 飞书 / 钉钉自定义机器人配置（仅单向通知）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WebhookBotConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub webhook_url: String,
+    #[serde(default)]
+    pub secret: String, // 签名校验密钥，未开启签名时留空
+}
+
+/// ntfy 配置（通过 `<topic>-reply` 话题接收回复）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NtfyConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_ntfy_server_url")]
+    pub server_url: String,
+    #[serde(default)]
+    pub topic: String,
+    #[serde(default)]
+    pub access_token: String,
+}
+
+/// Gotify 配置（仅单向通知）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GotifyConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub server_url: String,
+    #[serde(default)]
+    pub app_token: String,
+}
+
+/// SMTP 邮件配置（仅单向通知）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EmailConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub smtp_host: String,
+    #[serde(default = "default_smtp_port")]
+    pub smtp_port: u16,
+    #[serde(default = "default_smtp_security")]
+    pub security: String, // "starttls" | "tls" | "none"
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    #[serde(default)]
+    pub from: String,
+    #[serde(default)]
+    pub to: Vec<String>,
 }
 
 /// 代理配置
@@ -276,6 +406,7 @@ impl Default for AppConfig {
             custom_prompt_config: default_custom_prompt_config(),
             shortcut_config: default_shortcut_config(),
             proxy_config: default_proxy_config(),
+            notification_config: default_notification_config(),
         }
     }
 }
@@ -327,7 +458,14 @@ pub fn default_mcp_config() -> McpConfig {
         acemcp_proxy_username: None,
         acemcp_proxy_password: None,
         context7_api_key: None,
+        context7_cache_ttl_minutes: None, // 使用默认值 24 小时
         skill_python_path: None,
+        skill_node_path: None,
+        skill_bash_path: None,
+        skill_pwsh_path: None,
+        skill_require_approval: Some(true),
+        skill_trusted_keys: None,
+        skill_default_project_root: None,
         // UI/UX Pro Max 默认配置
         uiux_default_lang: Some("zh".to_string()),
         uiux_output_format: Some("json".to_string()),
@@ -338,6 +476,8 @@ pub fn default_mcp_config() -> McpConfig {
         icon_default_format: None,          // 默认 SVG
         icon_default_png_size: None,        // 默认 64px
         icon_cache_expiry_minutes: None,    // 默认 30 分钟
+        icon_provider: None,                // 默认 iconfont
+        icon_local_sources: None,
     }
 }
 
@@ -348,6 +488,12 @@ pub fn default_telegram_config() -> TelegramConfig {
         chat_id: default_telegram_chat_id(),
         hide_frontend_popup: default_telegram_hide_frontend_popup(),
         api_base_url: default_telegram_api_base_url(),
+        allowed_user_ids: default_telegram_allowed_user_ids(),
+        use_forum_topics: default_telegram_use_forum_topics(),
+        webhook_enabled: default_telegram_webhook_enabled(),
+        webhook_bind_addr: default_telegram_webhook_bind_addr(),
+        webhook_public_url: default_telegram_webhook_public_url(),
+        webhook_secret: default_telegram_webhook_secret(),
     }
 }
 
@@ -489,6 +635,30 @@ pub fn default_telegram_api_base_url() -> String {
     telegram::API_BASE_URL.to_string()
 }
 
+pub fn default_telegram_allowed_user_ids() -> Vec<i64> {
+    Vec::new()
+}
+
+pub fn default_telegram_use_forum_topics() -> bool {
+    telegram::DEFAULT_USE_FORUM_TOPICS
+}
+
+pub fn default_telegram_webhook_enabled() -> bool {
+    telegram::DEFAULT_WEBHOOK_ENABLED
+}
+
+pub fn default_telegram_webhook_bind_addr() -> String {
+    telegram::DEFAULT_WEBHOOK_BIND_ADDR.to_string()
+}
+
+pub fn default_telegram_webhook_public_url() -> String {
+    String::new()
+}
+
+pub fn default_telegram_webhook_secret() -> String {
+    String::new()
+}
+
 impl WindowConfig {
     // 获取当前模式的宽度
     pub fn current_width(&self) -> f64 {
@@ -846,3 +1016,93 @@ pub fn default_proxy_only_for_cn() -> bool {
     true // 默认仅在中国大陆地区使用代理
 }
 
+pub fn default_notification_config() -> NotificationConfig {
+    NotificationConfig {
+        slack: default_slack_config(),
+        discord: default_discord_config(),
+        feishu: default_webhook_bot_config(),
+        dingtalk: default_webhook_bot_config(),
+        ntfy: default_ntfy_config(),
+        gotify: default_gotify_config(),
+        email: default_email_config(),
+    }
+}
+
+pub fn default_slack_config() -> SlackConfig {
+    SlackConfig {
+        enabled: false,
+        bot_token: String::new(),
+        channel_id: String::new(),
+        api_base_url: default_slack_api_base_url(),
+        allowed_user_ids: Vec::new(),
+    }
+}
+
+pub fn default_discord_config() -> DiscordConfig {
+    DiscordConfig {
+        enabled: false,
+        bot_token: String::new(),
+        channel_id: String::new(),
+        api_base_url: default_discord_api_base_url(),
+        allowed_user_ids: Vec::new(),
+    }
+}
+
+pub fn default_webhook_bot_config() -> WebhookBotConfig {
+    WebhookBotConfig {
+        enabled: false,
+        webhook_url: String::new(),
+        secret: String::new(),
+    }
+}
+
+pub fn default_ntfy_config() -> NtfyConfig {
+    NtfyConfig {
+        enabled: false,
+        server_url: default_ntfy_server_url(),
+        topic: String::new(),
+        access_token: String::new(),
+    }
+}
+
+pub fn default_gotify_config() -> GotifyConfig {
+    GotifyConfig {
+        enabled: false,
+        server_url: String::new(),
+        app_token: String::new(),
+    }
+}
+
+pub fn default_email_config() -> EmailConfig {
+    EmailConfig {
+        enabled: false,
+        smtp_host: String::new(),
+        smtp_port: default_smtp_port(),
+        security: default_smtp_security(),
+        username: String::new(),
+        password: String::new(),
+        from: String::new(),
+        to: Vec::new(),
+    }
+}
+
+pub fn default_slack_api_base_url() -> String {
+    notification::SLACK_API_BASE_URL.to_string()
+}
+
+pub fn default_discord_api_base_url() -> String {
+    notification::DISCORD_API_BASE_URL.to_string()
+}
+
+pub fn default_ntfy_server_url() -> String {
+    notification::DEFAULT_NTFY_SERVER_URL.to_string()
+}
+
+pub fn default_smtp_port() -> u16 {
+    notification::DEFAULT_SMTP_PORT
+}
+
+pub fn default_smtp_security() -> String {
+    notification::DEFAULT_SMTP_SECURITY.to_string()
+}
+
@@ -0,0 +1,28 @@
+// 远程通知渠道相关常量
+
+/// Slack Web API 基础 URL
+pub const SLACK_API_BASE_URL: &str = "https://slack.com/api";
+
+/// Discord API 基础 URL
+pub const DISCORD_API_BASE_URL: &str = "https://discord.com/api/v10";
+
+/// 默认 ntfy 服务器
+pub const DEFAULT_NTFY_SERVER_URL: &str = "https://ntfy.sh";
+
+/// 默认 SMTP 端口（STARTTLS）
+pub const DEFAULT_SMTP_PORT: u16 = 587;
+
+/// 默认 SMTP 加密方式: "starttls" | "tls" | "none"
+pub const DEFAULT_SMTP_SECURITY: &str = "starttls";
+
+/// 通知请求超时时间 (s)
+pub const REQUEST_TIMEOUT_SECS: u64 = 15;
+
+/// 轮询渠道回复的间隔 (ms)
+pub const REPLY_POLL_INTERVAL_MS: u64 = 3000;
+
+/// Discord 单条消息最大长度
+pub const DISCORD_MAX_MESSAGE_LENGTH: usize = 2000;
+
+/// ntfy 单条通知最多支持的操作按钮数
+pub const NTFY_MAX_ACTIONS: usize = 3;
@@ -12,12 +12,48 @@ pub const DEFAULT_CHAT_ID: &str = "";
 /// 默认隐藏前端弹窗状态
 pub const DEFAULT_HIDE_FRONTEND_POPUP: bool = false;
 
+/// 默认不按项目拆分论坛话题
+pub const DEFAULT_USE_FORUM_TOPICS: bool = false;
+
+/// 默认不启用 Webhook 模式（使用长轮询）
+pub const DEFAULT_WEBHOOK_ENABLED: bool = false;
+
+/// 默认 Webhook 本地监听地址
+pub const DEFAULT_WEBHOOK_BIND_ADDR: &str = "127.0.0.1:8787";
+
+/// Webhook 密钥请求头
+pub const WEBHOOK_SECRET_HEADER: &str = "X-Telegram-Bot-Api-Secret-Token";
+
+/// Webhook 请求体最大字节数
+pub const MAX_WEBHOOK_BODY_BYTES: usize = 1024 * 1024;
+
 /// Telegram API 基础 URL
 pub const API_BASE_URL: &str = "https://api.telegram.org/bot";
 
 /// 消息最大长度
 pub const MAX_MESSAGE_LENGTH: usize = 4096;
 
+/// 代码块超过该行数时以文件形式发送
+pub const MAX_INLINE_CODE_LINES: usize = 60;
+
+/// 代码块超过该字符数时以文件形式发送
+pub const MAX_INLINE_CODE_CHARS: usize = 3000;
+
+/// 接收图片 / 文件的最大字节数（Bot API 下载上限为 20MB）
+pub const MAX_ATTACHMENT_BYTES: usize = 10 * 1024 * 1024;
+
+/// 长轮询超时时间 (s)
+pub const LONG_POLL_TIMEOUT_SECS: u32 = 10;
+
+/// 待答复请求超过该时间未刷新心跳即视为失效 (ms)
+pub const ROUTE_STALE_MS: i64 = 120_000;
+
+/// 非轮询进程读取共享队列的间隔 (ms)
+pub const SPOOL_READ_INTERVAL_MS: u64 = 300;
+
+/// 共享更新队列保留的最大条数
+pub const MAX_SPOOLED_UPDATES: usize = 200;
+
 /// 请求超时时间 (ms)
 pub const REQUEST_TIMEOUT_MS: u64 = 30000;
 
@@ -3,6 +3,7 @@ pub mod config;
 pub mod constants;
 pub mod mcp;
 pub mod network;
+pub mod notify;
 pub mod telegram;
 pub mod ui;
 pub mod utils;
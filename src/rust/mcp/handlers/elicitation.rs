@@ -0,0 +1,321 @@
+use anyhow::Result;
+use rmcp::model::{
+    BooleanSchema, CreateElicitationRequestParam, ElicitationAction, ElicitationSchema,
+    EnumSchema, NumberSchema, PrimitiveSchema, StringSchema,
+};
+use rmcp::{Peer, RoleServer};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+use super::popup::create_tauri_popup;
+use crate::mcp::tools::interaction::form::{validate_field_specs, validate_form_values};
+use crate::mcp::tools::interaction::{ZhiDangerLevel, ZhiField, ZhiFieldKind};
+use crate::mcp::types::{McpResponse, PopupRequest};
+use crate::mcp::utils::generate_request_id;
+use crate::{log_debug, log_important};
+
+/// 弹窗回退时代表“拒绝”的预定义选项
+pub const ELICITATION_DECLINE_OPTION: &str = "拒绝";
+
+/// 服务端发起的询问
+///
+/// 客户端声明了 elicitation 能力时通过 `elicitation/create` 发给客户端，
+/// 否则回退到 zhi 弹窗（含 Telegram 通道）
+#[derive(Debug, Clone)]
+pub struct Elicitation {
+    pub message: String,
+    pub fields: Vec<ZhiField>,
+    pub project_root_path: Option<String>,
+}
+
+impl Elicitation {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            fields: Vec::new(),
+            project_root_path: None,
+        }
+    }
+
+    /// 单个确认项的询问（如“立即重新索引？”“覆盖已有文件？”）
+    pub fn confirm(message: impl Into<String>, label: &str, danger_level: ZhiDangerLevel) -> Self {
+        Self::new(message).field(ZhiField::confirm("confirm", label, danger_level))
+    }
+
+    pub fn field(mut self, field: ZhiField) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    pub fn project_root(mut self, project_root_path: impl Into<String>) -> Self {
+        self.project_root_path = Some(project_root_path.into());
+        self
+    }
+}
+
+/// 询问结果
+#[derive(Debug, Clone)]
+pub struct ElicitationOutcome {
+    pub action: ElicitationAction,
+    /// 已校验的表单值（仅 accept 时存在）
+    pub content: Option<Map<String, Value>>,
+    /// 用户附加的文字说明（仅弹窗回退时可能存在）
+    pub user_input: Option<String>,
+}
+
+impl ElicitationOutcome {
+    fn new(action: ElicitationAction) -> Self {
+        Self {
+            action,
+            content: None,
+            user_input: None,
+        }
+    }
+
+    pub fn is_accepted(&self) -> bool {
+        self.action == ElicitationAction::Accept
+    }
+
+    /// 用户接受且勾选了指定的确认字段
+    pub fn is_confirmed(&self, key: &str) -> bool {
+        self.is_accepted()
+            && self
+                .content
+                .as_ref()
+                .and_then(|c| c.get(key))
+                .and_then(Value::as_bool)
+                .unwrap_or(false)
+    }
+}
+
+/// 发起询问并等待用户答复
+///
+/// `peer` 为 None（非 MCP 调用）或客户端不支持 elicitation 时走 zhi 弹窗
+pub async fn elicit(peer: Option<&Peer<RoleServer>>, request: &Elicitation) -> Result<ElicitationOutcome> {
+    validate_field_specs(&request.fields).map_err(|e| anyhow::anyhow!("询问字段无效: {}", e))?;
+
+    if let Some(peer) = peer.filter(|p| p.supports_elicitation()) {
+        if let Some(schema) = build_elicitation_schema(&request.fields) {
+            match elicit_via_client(peer, request, schema).await {
+                Ok(outcome) => return Ok(outcome),
+                Err(e) => log_important!(warn, "客户端 elicitation 失败，回退到弹窗: {}", e),
+            }
+        } else {
+            log_debug!("询问包含客户端不支持的字段类型，使用弹窗");
+        }
+    }
+
+    // 弹窗会阻塞等待用户答复，不能占用异步运行时线程
+    let request = request.clone();
+    tokio::task::spawn_blocking(move || elicit_via_popup(&request))
+        .await
+        .map_err(|e| anyhow::anyhow!("询问弹窗任务失败: {}", e))?
+}
+
+/// 通过 MCP `elicitation/create` 由客户端向用户询问
+async fn elicit_via_client(
+    peer: &Peer<RoleServer>,
+    request: &Elicitation,
+    schema: ElicitationSchema,
+) -> Result<ElicitationOutcome> {
+    let result = peer
+        .create_elicitation(CreateElicitationRequestParam {
+            message: request.message.clone(),
+            requested_schema: schema,
+        })
+        .await?;
+
+    let mut outcome = ElicitationOutcome::new(result.action.clone());
+    if result.action == ElicitationAction::Accept {
+        let values = match result.content {
+            Some(Value::Object(map)) => map,
+            _ => Map::new(),
+        };
+        outcome.content = Some(validate_outcome_values(&request.fields, &values)?);
+    }
+    Ok(outcome)
+}
+
+/// 通过 zhi 弹窗（或 Telegram）向用户询问
+fn elicit_via_popup(request: &Elicitation) -> Result<ElicitationOutcome> {
+    let popup_request = PopupRequest {
+        id: generate_request_id(),
+        message: request.message.clone(),
+        predefined_options: Some(vec![ELICITATION_DECLINE_OPTION.to_string()]),
+        is_markdown: true,
+        project_root_path: request.project_root_path.clone(),
+        uiux_intent: None,
+        uiux_context_policy: None,
+        uiux_reason: None,
+        fields: request.fields.clone(),
+    };
+
+    let response = create_tauri_popup(&popup_request)?;
+    Ok(parse_popup_outcome(&request.fields, &response))
+}
+
+/// 将弹窗响应映射为 accept/decline/cancel
+fn parse_popup_outcome(fields: &[ZhiField], response: &str) -> ElicitationOutcome {
+    let response = response.trim();
+    if response.is_empty() || response == "CANCELLED" || response == "用户取消了操作" {
+        return ElicitationOutcome::new(ElicitationAction::Cancel);
+    }
+
+    let parsed = match serde_json::from_str::<McpResponse>(response) {
+        Ok(parsed) => parsed,
+        Err(_) => {
+            // 旧格式响应无法区分字段，仅在无需表单时视为接受
+            let mut outcome = ElicitationOutcome::new(if fields.is_empty() {
+                ElicitationAction::Accept
+            } else {
+                ElicitationAction::Decline
+            });
+            outcome.user_input = Some(response.to_string());
+            return outcome;
+        }
+    };
+
+    let user_input = parsed.user_input.filter(|s| !s.trim().is_empty());
+    if parsed.selected_options.iter().any(|o| o == ELICITATION_DECLINE_OPTION) {
+        let mut outcome = ElicitationOutcome::new(ElicitationAction::Decline);
+        outcome.user_input = user_input;
+        return outcome;
+    }
+
+    let values = parsed.form_values.unwrap_or_default();
+    let mut outcome = match validate_form_values(fields, &values) {
+        Ok(values) => {
+            let mut outcome = ElicitationOutcome::new(ElicitationAction::Accept);
+            outcome.content = Some(values);
+            outcome
+        }
+        // 未完成必填项（如未勾选确认）按拒绝处理
+        Err(_) => ElicitationOutcome::new(ElicitationAction::Decline),
+    };
+    outcome.user_input = user_input;
+    outcome
+}
+
+/// 校验客户端返回的值，失败时返回可读错误
+fn validate_outcome_values(fields: &[ZhiField], values: &Map<String, Value>) -> Result<Map<String, Value>> {
+    validate_form_values(fields, values).map_err(|errors| {
+        let detail = errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("; ");
+        anyhow::anyhow!("客户端返回的表单值无效: {}", detail)
+    })
+}
+
+/// 将 zhi 表单字段转换为 elicitation schema
+///
+/// MCP 规范只允许基本类型属性，包含多选字段时返回 None
+pub fn build_elicitation_schema(fields: &[ZhiField]) -> Option<ElicitationSchema> {
+    let mut properties = BTreeMap::new();
+    let mut required = Vec::new();
+
+    for field in fields {
+        let title = field.label.clone();
+        let description = field.description.clone();
+        let schema = match field.kind {
+            ZhiFieldKind::MultiSelect => return None,
+            ZhiFieldKind::SingleSelect => {
+                let mut schema = EnumSchema::new(field.options.clone()).title(title);
+                schema.description = description.map(Into::into);
+                PrimitiveSchema::Enum(schema)
+            }
+            ZhiFieldKind::Text | ZhiFieldKind::FilePath => {
+                let mut schema = StringSchema::new().title(title);
+                schema.description = description.map(Into::into);
+                if field.kind == ZhiFieldKind::Text {
+                    schema.min_length = field.min.map(|v| v as u32);
+                    schema.max_length = field.max.map(|v| v as u32);
+                }
+                PrimitiveSchema::String(schema)
+            }
+            ZhiFieldKind::Number => {
+                let mut schema = NumberSchema::new().title(title);
+                schema.description = description.map(Into::into);
+                schema.minimum = field.min;
+                schema.maximum = field.max;
+                PrimitiveSchema::Number(schema)
+            }
+            ZhiFieldKind::Boolean | ZhiFieldKind::Confirm => {
+                let mut schema = BooleanSchema::new().title(title);
+                schema.description = description.map(Into::into);
+                schema.default = field.default.as_ref().and_then(Value::as_bool);
+                PrimitiveSchema::Boolean(schema)
+            }
+        };
+
+        if field.required {
+            required.push(field.key.clone());
+        }
+        properties.insert(field.key.clone(), schema);
+    }
+
+    let mut schema = ElicitationSchema::new(properties);
+    if !required.is_empty() {
+        schema.required = Some(required);
+    }
+    Some(schema)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn confirm_fields() -> Vec<ZhiField> {
+        vec![ZhiField::confirm("confirm", "覆盖", ZhiDangerLevel::Medium)]
+    }
+
+    #[test]
+    fn test_popup_outcome_actions() {
+        let fields = confirm_fields();
+
+        let cancelled = parse_popup_outcome(&fields, "CANCELLED");
+        assert_eq!(cancelled.action, ElicitationAction::Cancel);
+
+        let declined = json!({
+            "user_input": "先别覆盖",
+            "selected_options": [ELICITATION_DECLINE_OPTION],
+            "images": [],
+            "metadata": {}
+        });
+        let declined = parse_popup_outcome(&fields, &declined.to_string());
+        assert_eq!(declined.action, ElicitationAction::Decline);
+        assert_eq!(declined.user_input.as_deref(), Some("先别覆盖"));
+
+        let accepted = json!({
+            "user_input": null,
+            "selected_options": [],
+            "images": [],
+            "form_values": {"confirm": true},
+            "metadata": {}
+        });
+        let accepted = parse_popup_outcome(&fields, &accepted.to_string());
+        assert!(accepted.is_confirmed("confirm"));
+
+        // 未勾选必填确认项按拒绝处理
+        let unchecked = json!({
+            "user_input": "继续",
+            "selected_options": [],
+            "images": [],
+            "metadata": {}
+        });
+        let unchecked = parse_popup_outcome(&fields, &unchecked.to_string());
+        assert_eq!(unchecked.action, ElicitationAction::Decline);
+    }
+
+    #[test]
+    fn test_build_elicitation_schema() {
+        let schema = build_elicitation_schema(&confirm_fields()).expect("schema");
+        assert_eq!(schema.required, Some(vec!["confirm".to_string()]));
+        assert!(matches!(schema.properties.get("confirm"), Some(PrimitiveSchema::Boolean(_))));
+
+        let multi = ZhiField {
+            kind: ZhiFieldKind::MultiSelect,
+            options: vec!["a".to_string()],
+            ..ZhiField::confirm("tags", "标签", ZhiDangerLevel::Low)
+        };
+        assert!(build_elicitation_schema(&[multi]).is_none());
+    }
+}
@@ -1,7 +1,9 @@
 pub mod popup;
 pub mod response;
 pub mod icon_popup;
+pub mod elicitation;
 
 pub use popup::*;
 pub use response::*;
 pub use icon_popup::*;
+pub use elicitation::*;
@@ -41,6 +41,11 @@ pub fn create_tauri_popup(request: &PopupRequest) -> Result<String> {
     }
 }
 
+/// 是否可以弹出等一下 UI（找不到 UI 命令时弹窗必然失败）
+pub fn popup_available() -> bool {
+    find_ui_command().is_ok()
+}
+
 /// 查找等一下 UI 命令的路径
 ///
 /// 按优先级查找：同目录 -> 全局版本 -> 开发环境
@@ -1,7 +1,8 @@
 use anyhow::Result;
-use rmcp::model::{ErrorData as McpError, Content};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use rmcp::model::{ErrorData as McpError, Content, ResourceContents};
 
-use crate::mcp::types::{McpResponse, McpResponseContent};
+use crate::mcp::types::{FileAttachment, McpResponse, McpResponseContent};
 
 /// 解析 MCP 响应内容
 ///
@@ -133,6 +134,12 @@ fn parse_structured_response(response: McpResponse) -> Result<Vec<Content>, McpE
         }
     }
 
+    // 2.1 处理结构化表单值（完整 JSON 另见 structured_content）
+    if let Some(form_values) = response.form_values.as_ref().filter(|v| !v.is_empty()) {
+        let pretty = serde_json::to_string_pretty(form_values).unwrap_or_default();
+        text_parts.push(format!("表单结果:\n{}", pretty));
+    }
+
     // 3. 处理图片附件
     let mut image_info_parts = Vec::new();
     for (index, image) in response.images.iter().enumerate() {
@@ -148,14 +155,7 @@ fn parse_structured_response(response: McpResponse) -> Result<Vec<Content>, McpE
         };
 
         // 计算图片大小
-        let estimated_size = (base64_len * 3) / 4;
-        let size_str = if estimated_size < 1024 {
-            format!("{} B", estimated_size)
-        } else if estimated_size < 1024 * 1024 {
-            format!("{:.1} KB", estimated_size as f64 / 1024.0)
-        } else {
-            format!("{:.1} MB", estimated_size as f64 / (1024.0 * 1024.0))
-        };
+        let size_str = format_size((base64_len * 3) / 4);
 
         let filename_info = image.filename.as_ref()
             .map(|f| format!("\n文件名: {}", f))
@@ -168,6 +168,31 @@ fn parse_structured_response(response: McpResponse) -> Result<Vec<Content>, McpE
         image_info_parts.push(image_info);
     }
 
+    // 3.1 处理文件附件：文本文件内嵌内容，其余作为二进制资源
+    for (index, file) in response.attachments.iter().enumerate() {
+        let uri = format!("attachment://{}", file.filename);
+        let resource = match decode_text_attachment(file) {
+            Some(text) => ResourceContents::TextResourceContents {
+                uri,
+                mime_type: Some(file.media_type.clone()),
+                text,
+                meta: None,
+            },
+            None => ResourceContents::BlobResourceContents {
+                uri,
+                mime_type: Some(file.media_type.clone()),
+                blob: file.data.clone(),
+                meta: None,
+            },
+        };
+        result.push(Content::resource(resource));
+
+        image_info_parts.push(format!(
+            "=== 附件 {} ===\n文件名: {}\n类型: {}\n大小: {}",
+            index + 1, file.filename, file.media_type, format_size((file.data.len() * 3) / 4)
+        ));
+    }
+
     // 4. 合并所有文本内容
     let mut all_text_parts = text_parts;
     all_text_parts.extend(image_info_parts);
@@ -193,3 +218,43 @@ fn parse_structured_response(response: McpResponse) -> Result<Vec<Content>, McpE
 
     Ok(result)
 }
+
+/// 格式化字节大小
+fn format_size(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+/// 尝试将附件解码为 UTF-8 文本，二进制文件返回 None
+fn decode_text_attachment(file: &FileAttachment) -> Option<String> {
+    let bytes = STANDARD.decode(&file.data).ok()?;
+    let text = String::from_utf8(bytes).ok()?;
+    if text.contains('\0') {
+        return None;
+    }
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attachment(data: &[u8]) -> FileAttachment {
+        FileAttachment {
+            data: STANDARD.encode(data),
+            media_type: "text/plain".to_string(),
+            filename: "error.log".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_decode_text_attachment() {
+        assert_eq!(decode_text_attachment(&attachment("panic: 越界".as_bytes())).as_deref(), Some("panic: 越界"));
+        assert!(decode_text_attachment(&attachment(&[0x89, 0x50, 0x4e, 0x47, 0x00])).is_none());
+    }
+}
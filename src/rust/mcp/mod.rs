@@ -1,4 +1,5 @@
 pub mod commands;
+pub mod roots;
 pub mod server;
 pub mod tools;
 pub mod types;
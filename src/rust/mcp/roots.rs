@@ -0,0 +1,164 @@
+// 项目根目录解析
+// MCP 客户端通常以用户主目录作为服务端工作目录，技能等按项目生效的功能需从客户端 roots（roots/list）获取项目目录
+// 客户端不支持 roots 时依次回退到配置的默认项目目录与当前工作目录
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rmcp::{Peer, RoleServer};
+use tokio::sync::RwLock;
+
+use crate::config::load_standalone_config;
+use crate::{log_debug, log_important};
+
+/// roots/list 请求超时，避免客户端无响应时阻塞工具调用
+const LIST_ROOTS_TIMEOUT_SECS: u64 = 5;
+
+/// roots/list 失败或超时后的重试间隔，期间使用默认项目目录
+const LIST_ROOTS_RETRY_SECS: u64 = 30;
+
+/// 客户端声明的项目根目录（按会话缓存，收到 roots/list_changed 时失效）
+#[derive(Clone, Default)]
+pub struct ClientRoots {
+    cache: Arc<RwLock<Option<CachedRoots>>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum CachedRoots {
+    /// 客户端的答复（不支持 roots 时为空列表）
+    Loaded(Vec<PathBuf>),
+    /// 请求失败或超时，到期后重试
+    Failed { retry_at: Instant },
+}
+
+impl CachedRoots {
+    /// 仍然有效的缓存：成功结果一直有效，失败结果在重试前视为空列表
+    fn current(&self, now: Instant) -> Option<Vec<PathBuf>> {
+        match self {
+            CachedRoots::Loaded(roots) => Some(roots.clone()),
+            CachedRoots::Failed { retry_at } if now < *retry_at => Some(Vec::new()),
+            CachedRoots::Failed { .. } => None,
+        }
+    }
+}
+
+impl ClientRoots {
+    /// 候选项目根目录：客户端 roots，否则为默认项目目录
+    pub async fn project_roots(&self, peer: &Peer<RoleServer>) -> Vec<PathBuf> {
+        let roots = self.client_roots(peer).await;
+        if roots.is_empty() {
+            vec![fallback_root()]
+        } else {
+            roots
+        }
+    }
+
+    /// 丢弃缓存，下次访问时重新请求 roots/list
+    pub async fn invalidate(&self) {
+        *self.cache.write().await = None;
+    }
+
+    async fn client_roots(&self, peer: &Peer<RoleServer>) -> Vec<PathBuf> {
+        if let Some(roots) = self.cache.read().await.as_ref().and_then(|c| c.current(Instant::now())) {
+            return roots;
+        }
+
+        let supported = peer
+            .peer_info()
+            .is_some_and(|info| info.capabilities.roots.is_some());
+        let cached = if supported {
+            match tokio::time::timeout(Duration::from_secs(LIST_ROOTS_TIMEOUT_SECS), peer.list_roots()).await {
+                Ok(Ok(result)) => CachedRoots::Loaded(
+                    result
+                        .roots
+                        .iter()
+                        .filter_map(|root| uri_to_path(&root.uri))
+                        .filter(|path| path.is_dir())
+                        .collect(),
+                ),
+                Ok(Err(e)) => {
+                    log_important!(warn, "获取客户端 roots 失败，{} 秒后重试: {}", LIST_ROOTS_RETRY_SECS, e);
+                    retry_later()
+                }
+                Err(_) => {
+                    log_important!(
+                        warn,
+                        "获取客户端 roots 超时（{} 秒），{} 秒后重试",
+                        LIST_ROOTS_TIMEOUT_SECS,
+                        LIST_ROOTS_RETRY_SECS
+                    );
+                    retry_later()
+                }
+            }
+        } else {
+            CachedRoots::Loaded(Vec::new())
+        };
+
+        let roots = cached.current(Instant::now()).unwrap_or_default();
+        log_debug!("客户端项目根目录: {:?}", roots);
+        *self.cache.write().await = Some(cached);
+        roots
+    }
+}
+
+fn retry_later() -> CachedRoots {
+    CachedRoots::Failed {
+        retry_at: Instant::now() + Duration::from_secs(LIST_ROOTS_RETRY_SECS),
+    }
+}
+
+/// 配置的默认项目目录，未配置时使用当前工作目录
+pub fn fallback_root() -> PathBuf {
+    load_standalone_config()
+        .ok()
+        .and_then(|c| c.mcp_config.skill_default_project_root)
+        .filter(|p| !p.trim().is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// 将 file:// URI 转为本地路径（其他协议返回 None）
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    // file://localhost/path 与 file:///path 等价
+    let rest = rest.strip_prefix("localhost").unwrap_or(rest);
+    let decoded = percent_encoding::percent_decode_str(rest).decode_utf8_lossy().to_string();
+    // Windows：file:///C:/work -> C:/work
+    let bytes = decoded.as_bytes();
+    if bytes.len() >= 3 && bytes[0] == b'/' && bytes[1].is_ascii_alphabetic() && bytes[2] == b':' {
+        return Some(PathBuf::from(&decoded[1..]));
+    }
+    if decoded.is_empty() {
+        return None;
+    }
+    Some(PathBuf::from(decoded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uri_to_path() {
+        assert_eq!(uri_to_path("file:///home/dev/my%20app"), Some(PathBuf::from("/home/dev/my app")));
+        assert_eq!(uri_to_path("file://localhost/srv/repo"), Some(PathBuf::from("/srv/repo")));
+        assert_eq!(uri_to_path("file:///C:/work/%E9%A1%B9%E7%9B%AE"), Some(PathBuf::from("C:/work/项目")));
+        assert_eq!(uri_to_path("https://example.com/repo"), None);
+    }
+
+    #[test]
+    fn test_failed_roots_are_retried() {
+        let now = Instant::now();
+        let loaded = CachedRoots::Loaded(vec![PathBuf::from("/srv/repo")]);
+        assert_eq!(loaded.current(now + Duration::from_secs(3600)), Some(vec![PathBuf::from("/srv/repo")]));
+
+        // 失败结果只在重试间隔内生效，到期后重新请求
+        let failed = CachedRoots::Failed {
+            retry_at: now + Duration::from_secs(LIST_ROOTS_RETRY_SECS),
+        };
+        assert_eq!(failed.current(now), Some(Vec::new()));
+        assert_eq!(failed.current(now + Duration::from_secs(LIST_ROOTS_RETRY_SECS)), None);
+    }
+}
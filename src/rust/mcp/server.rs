@@ -3,11 +3,12 @@ use rmcp::{
     ServerHandler, ServiceExt, RoleServer,
     model::{ErrorData as McpError},
     transport::stdio,
-    service::{RequestContext, ServerInitializeError},
+    service::{NotificationContext, RequestContext, ServerInitializeError},
 };
 use rmcp::model::*;
 use std::collections::HashMap;
 
+use super::roots::ClientRoots;
 use super::tools::{InteractionTool, MemoryTool, AcemcpTool, Context7Tool, IconTool, SkillsTool, UiuxTool, EnhanceTool};
 use super::types::{ZhiRequest, JiyiRequest, TuRequest, SkillRunRequest};
 use crate::mcp::tools::enhance::mcp::EnhanceMcpRequest;
@@ -18,6 +19,8 @@ use crate::{log_important, log_debug};
 #[derive(Clone)]
 pub struct ZhiServer {
     enabled_tools: HashMap<String, bool>,
+    /// 客户端项目根目录（技能按项目发现与执行）
+    roots: ClientRoots,
 }
 
 impl Default for ZhiServer {
@@ -37,7 +40,10 @@ impl ZhiServer {
             }
         };
 
-        Self { enabled_tools }
+        Self {
+            enabled_tools,
+            roots: ClientRoots::default(),
+        }
     }
 
     /// 检查工具是否启用 - 动态读取最新配置
@@ -61,8 +67,11 @@ impl ZhiServer {
 impl ServerHandler for ZhiServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
-            protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            protocol_version: ProtocolVersion::V_2025_06_18,
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_tool_list_changed()
+                .build(),
             server_info: Implementation {
                 name: "Zhi-mcp".to_string(),
                 version: env!("CARGO_PKG_VERSION").to_string(),
@@ -82,10 +91,20 @@ impl ServerHandler for ZhiServer {
         Ok(self.get_info())
     }
 
+    async fn on_roots_list_changed(&self, context: NotificationContext<RoleServer>) {
+        // 项目目录变化后技能列表随之变化，通知客户端重新拉取工具列表
+        self.roots.invalidate().await;
+        let roots = self.roots.project_roots(&context.peer).await;
+        log_important!(info, "客户端项目根目录已变化: {:?}", roots);
+        if let Err(e) = context.peer.notify_tool_list_changed().await {
+            log_debug!("发送工具列表变化通知失败: {}", e);
+        }
+    }
+
     async fn list_tools(
         &self,
         _request: Option<PaginatedRequestParam>,
-        _context: RequestContext<RoleServer>,
+        context: RequestContext<RoleServer>,
     ) -> Result<ListToolsResult, McpError> {
         use std::sync::Arc;
         use std::borrow::Cow;
@@ -112,6 +131,32 @@ impl ServerHandler for ZhiServer {
                 "project_root_path": {
                     "type": "string",
                     "description": "项目根目录绝对路径（必填）"
+                },
+                "fields": {
+                    "type": "array",
+                    "description": "结构化表单字段（可选），一次收集多个决策，结果以 JSON 返回在 structured_content.form_values 中",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "key": {"type": "string", "description": "字段键名（唯一）"},
+                            "label": {"type": "string", "description": "字段显示名称"},
+                            "type": {
+                                "type": "string",
+                                "enum": ["single_select", "multi_select", "text", "number", "boolean", "file_path", "confirm"],
+                                "description": "字段类型"
+                            },
+                            "description": {"type": "string", "description": "字段说明"},
+                            "required": {"type": "boolean", "description": "是否必填"},
+                            "options": {"type": "array", "items": {"type": "string"}, "description": "可选项（选择字段必填）"},
+                            "default": {"description": "默认值"},
+                            "min": {"type": "number", "description": "number 为数值下限，text 为最小长度，multi_select 为最少选择数"},
+                            "max": {"type": "number", "description": "number 为数值上限，text 为最大长度，multi_select 为最多选择数"},
+                            "pattern": {"type": "string", "description": "text 字段的正则校验"},
+                            "directory": {"type": "boolean", "description": "file_path 字段是否选择目录"},
+                            "danger_level": {"type": "string", "enum": ["low", "medium", "high"], "description": "confirm 字段的危险等级"}
+                        },
+                        "required": ["key", "label", "type"]
+                    }
                 }
             },
             "required": ["message", "project_root_path"]
@@ -120,7 +165,7 @@ impl ServerHandler for ZhiServer {
         if let serde_json::Value::Object(schema_map) = zhi_schema {
             tools.push(Tool {
                 name: Cow::Borrowed("zhi"),
-                description: Some(Cow::Borrowed("智能代码审查交互工具，支持预定义选项、自由文本输入和图片上传")),
+                description: Some(Cow::Borrowed("智能代码审查交互工具，支持预定义选项、结构化表单、自由文本输入和图片上传")),
                 input_schema: Arc::new(schema_map),
                 annotations: None,
                 icons: None,
@@ -193,7 +238,7 @@ impl ServerHandler for ZhiServer {
 
         // 代码搜索工具 - 仅在启用时添加
         if self.is_tool_enabled("sou") {
-            tools.push(AcemcpTool::get_tool_definition());
+            tools.extend(AcemcpTool::get_tool_definitions());
         }
 
         // Context7 文档查询工具 - 仅在启用时添加
@@ -203,7 +248,7 @@ impl ServerHandler for ZhiServer {
 
         // 图标工坊工具 - 仅在启用时添加
         if self.is_tool_enabled("icon") {
-            tools.push(IconTool::get_tool_definition());
+            tools.extend(IconTool::get_tool_definitions());
         }
 
         // UI/UX 工具 - 仅在启用时添加
@@ -216,9 +261,9 @@ impl ServerHandler for ZhiServer {
             tools.push(EnhanceTool::get_tool_definition());
         }
 
-        // 技能运行时工具 - 动态发现 skills 并追加工具
-        let project_root = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
-        tools.extend(SkillsTool::list_dynamic_tools(&project_root));
+        // 技能运行时工具 - 按客户端项目根目录动态发现 skills 并追加工具
+        let project_roots = self.roots.project_roots(&context.peer).await;
+        tools.extend(SkillsTool::list_dynamic_tools(&project_roots));
 
         log_debug!("返回给客户端的工具列表: {:?}", tools.iter().map(|t| &t.name).collect::<Vec<_>>());
 
@@ -232,7 +277,7 @@ impl ServerHandler for ZhiServer {
     async fn call_tool(
         &self,
         request: CallToolRequestParam,
-        _context: RequestContext<RoleServer>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
         log_debug!("收到工具调用请求: {}", request.name);
 
@@ -290,6 +335,23 @@ impl ServerHandler for ZhiServer {
                 // 调用代码搜索工具
                 AcemcpTool::search_context(acemcp_request).await
             }
+            "sou_reindex" => {
+                if !self.is_tool_enabled("sou") {
+                    return Err(McpError::internal_error(
+                        "代码搜索工具已被禁用".to_string(),
+                        None
+                    ));
+                }
+
+                let arguments_value = request.arguments
+                    .map(serde_json::Value::Object)
+                    .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
+
+                let reindex_request: crate::mcp::tools::acemcp::types::AcemcpReindexRequest = serde_json::from_value(arguments_value)
+                    .map_err(|e| McpError::invalid_params(format!("参数解析失败: {}", e), None))?;
+
+                AcemcpTool::reindex(reindex_request, Some(&context.peer)).await
+            }
             "context7" => {
                 // 检查 Context7 工具是否启用
                 if !self.is_tool_enabled("context7") {
@@ -304,9 +366,19 @@ impl ServerHandler for ZhiServer {
                     .map(serde_json::Value::Object)
                     .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
 
-                let context7_request: Context7Request = serde_json::from_value(arguments_value)
+                let mut context7_request: Context7Request = serde_json::from_value(arguments_value)
                     .map_err(|e| McpError::invalid_params(format!("参数解析失败: {}", e), None))?;
 
+                // 未指定项目目录时使用客户端工作区，用于查找项目内的离线快照
+                if context7_request.project_root_path.is_none() {
+                    context7_request.project_root_path = self
+                        .roots
+                        .project_roots(&context.peer)
+                        .await
+                        .first()
+                        .map(|p| p.to_string_lossy().to_string());
+                }
+
                 // 调用 Context7 工具
                 Context7Tool::query_docs(context7_request).await
             }
@@ -330,6 +402,21 @@ impl ServerHandler for ZhiServer {
                 // 调用图标工坊工具
                 IconTool::tu(tu_request).await
             }
+            "icon_search" | "icon_save" => {
+                // 检查图标工坊工具是否启用
+                if !self.is_tool_enabled("icon") {
+                    return Err(McpError::internal_error(
+                        "图标工坊工具已被禁用".to_string(),
+                        None
+                    ));
+                }
+
+                let arguments_value = request.arguments
+                    .map(serde_json::Value::Object)
+                    .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
+
+                IconTool::call_tool(request.name.as_ref(), arguments_value, Some(&context.peer)).await
+            }
             // 兼容 Antigravity：UI/UX 工具名使用下划线分隔
             name if name.starts_with("uiux_") => {
                 if !self.is_tool_enabled("uiux") {
@@ -354,8 +441,8 @@ impl ServerHandler for ZhiServer {
                 let skill_request: SkillRunRequest = serde_json::from_value(arguments_value)
                     .map_err(|e| McpError::invalid_params(format!("参数解析失败: {}", e), None))?;
 
-                let project_root = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
-                SkillsTool::call_tool(name, skill_request, &project_root).await
+                let project_roots = self.roots.project_roots(&context.peer).await;
+                SkillsTool::call_tool(name, skill_request, &project_roots, Some(&context.peer)).await
             }
             "enhance" => {
                 // 检查增强工具是否启用
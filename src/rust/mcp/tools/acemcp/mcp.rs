@@ -8,6 +8,9 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
+use rmcp::model::ElicitationAction;
+use rmcp::{Peer, RoleServer};
+
 use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
 use reqwest::Client;
 use ring::digest::{Context as ShaContext, SHA256};
@@ -18,6 +21,7 @@ use globset::{Glob, GlobSet, GlobSetBuilder};
 
 use super::types::{
     AcemcpRequest,
+    AcemcpReindexRequest,
     AcemcpConfig,
     ProjectIndexStatus,
     ProjectsIndexStatus,
@@ -28,6 +32,8 @@ use super::types::{
     NestedProjectInfo,
     ProjectWithNestedStatus,
 };
+use crate::mcp::handlers::{elicit, Elicitation};
+use crate::mcp::tools::interaction::ZhiDangerLevel;
 use crate::log_debug;
 use crate::log_important;
 // 代理模块（在 create_acemcp_client 中使用）
@@ -37,6 +43,8 @@ pub struct AcemcpTool;
 
 impl AcemcpTool {
     /// 执行代码库搜索（仅搜索，不触发索引）
+    ///
+    /// 上次索引失败时不自动重试，在结果中附带失败原因，由调用方决定是否调用 sou_reindex
     pub async fn search_context(request: AcemcpRequest) -> Result<CallToolResult, McpError> {
         log_important!(info,
             "Acemcp搜索请求（仅搜索模式）: project_root_path={}, query={}",
@@ -74,7 +82,16 @@ impl AcemcpTool {
         // 2. 根据状态执行相应操作
         let mut hint_message = String::new();
         match initial_state {
-            InitialIndexState::Missing | InitialIndexState::Idle | InitialIndexState::Failed => {
+            InitialIndexState::Failed => {
+                let last_error = get_project_status(&request.project_root_path)
+                    .last_error
+                    .unwrap_or_else(|| "未知错误".to_string());
+                hint_message = format!(
+                    "\n\n⚠️ 提示：上次索引失败（{}），搜索结果可能不完整。如需重新索引，请调用 sou_reindex 工具。",
+                    last_error
+                );
+            }
+            InitialIndexState::Missing | InitialIndexState::Idle => {
                 // 启动后台索引
                 if let Err(e) = ensure_initial_index_background(&acemcp_config, &request.project_root_path).await {
                     log_debug!("启动后台索引失败（不影响搜索）: {}", e);
@@ -127,6 +144,48 @@ impl AcemcpTool {
         })
     }
 
+    /// 重新索引项目（sou_reindex 工具）
+    ///
+    /// 重新索引会上传项目文件，开始前先向用户确认；确认后在后台执行，立即返回
+    pub async fn reindex(
+        request: AcemcpReindexRequest,
+        peer: Option<&Peer<RoleServer>>,
+    ) -> Result<CallToolResult, McpError> {
+        let project_root = request.project_root_path;
+        if get_initial_index_state(&project_root) == InitialIndexState::Indexing {
+            return Ok(CallToolResult::success(vec![Content::text("项目正在索引中，无需重新索引")]));
+        }
+
+        let confirm = Elicitation::confirm(
+            format!("是否立即重新索引项目 `{}`？\n\n重新索引会在后台上传项目文件，可能需要几分钟。", project_root),
+            "立即重新索引",
+            ZhiDangerLevel::Low,
+        )
+        .project_root(project_root.as_str());
+        let outcome = elicit(peer, &confirm)
+            .await
+            .map_err(|e| McpError::internal_error(format!("询问是否重新索引失败: {}", e), None))?;
+        if !outcome.is_confirmed("confirm") {
+            let text = match outcome.action {
+                ElicitationAction::Cancel => "用户取消了重新索引",
+                _ => "用户拒绝了重新索引，请勿重复请求",
+            };
+            return Ok(CallToolResult::success(vec![Content::text(text)]));
+        }
+
+        let mut acemcp_config = Self::get_acemcp_config()
+            .await
+            .map_err(|e| McpError::internal_error(format!("获取acemcp配置失败: {}", e), None))?;
+        if let Some(base) = &acemcp_config.base_url {
+            acemcp_config.base_url = Some(normalize_base_url(base));
+        }
+        spawn_background_index(&acemcp_config, &project_root);
+
+        Ok(CallToolResult::success(vec![Content::text(
+            "已在后台重新索引，完成后再次调用 sou 可获得更完整的结果",
+        )]))
+    }
+
     /// 执行索引更新（向后兼容的索引+搜索一体化接口）
     pub async fn index_and_search_legacy(request: AcemcpRequest) -> Result<CallToolResult, McpError> {
         log_important!(info,
@@ -271,6 +330,38 @@ impl AcemcpTool {
         })
     }
 
+    /// 获取代码搜索的全部工具定义（sou、sou_reindex）
+    pub fn get_tool_definitions() -> Vec<Tool> {
+        let mut tools = vec![Self::get_tool_definition()];
+
+        let reindex_schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "project_root_path": {
+                    "type": "string",
+                    "description": "项目根目录的绝对路径，使用正斜杠(/)作为分隔符"
+                }
+            },
+            "required": ["project_root_path"]
+        });
+
+        if let serde_json::Value::Object(schema_map) = reindex_schema {
+            tools.push(Tool {
+                name: Cow::Borrowed("sou_reindex"),
+                description: Some(Cow::Borrowed(
+                    "重新索引项目（向用户确认后在后台执行）。仅在 sou 提示上次索引失败时调用。"
+                )),
+                input_schema: Arc::new(schema_map),
+                annotations: None,
+                icons: None,
+                meta: None,
+                output_schema: None,
+                title: Some("重新索引".to_string()),
+            });
+        }
+
+        tools
+    }
 
     /// 获取工具定义
     pub fn get_tool_definition() -> Tool {
@@ -448,18 +539,7 @@ pub async fn ensure_initial_index_background(config: &AcemcpConfig, project_root
     match state {
         InitialIndexState::Missing | InitialIndexState::Idle | InitialIndexState::Failed => {
             // 在后台启动索引任务
-            let config_clone = config.clone();
-            let project_root_clone = project_root.to_string();
-
-            tokio::spawn(async move {
-                log_important!(info, "后台索引任务启动: project_root={}", project_root_clone);
-                if let Err(e) = update_index(&config_clone, &project_root_clone).await {
-                    log_important!(info, "后台索引失败: project_root={}, error={}", project_root_clone, e);
-                } else {
-                    log_important!(info, "后台索引成功: project_root={}", project_root_clone);
-                }
-            });
-
+            spawn_background_index(config, project_root);
             Ok(())
         }
         InitialIndexState::Synced | InitialIndexState::Indexing => {
@@ -469,6 +549,21 @@ pub async fn ensure_initial_index_background(config: &AcemcpConfig, project_root
     }
 }
 
+/// 在后台执行索引任务
+fn spawn_background_index(config: &AcemcpConfig, project_root: &str) {
+    let config_clone = config.clone();
+    let project_root_clone = project_root.to_string();
+
+    tokio::spawn(async move {
+        log_important!(info, "后台索引任务启动: project_root={}", project_root_clone);
+        if let Err(e) = update_index(&config_clone, &project_root_clone).await {
+            log_important!(info, "后台索引失败: project_root={}, error={}", project_root_clone, e);
+        } else {
+            log_important!(info, "后台索引成功: project_root={}", project_root_clone);
+        }
+    });
+}
+
 // ---------------- 整合 temp 逻辑：索引、上传、检索 ----------------
 
 #[derive(Serialize, Deserialize, Clone)]
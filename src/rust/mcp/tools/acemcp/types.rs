@@ -11,6 +11,13 @@ pub struct AcemcpRequest {
     pub query: String,
 }
 
+/// 重新索引请求参数（sou_reindex 工具）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcemcpReindexRequest {
+    /// 项目根目录的绝对路径
+    pub project_root_path: String,
+}
+
 /// Acemcp配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AcemcpConfig {
@@ -0,0 +1,201 @@
+// Context7 文档缓存
+// 在线查询结果按 library / version / topic / page 缓存到磁盘；过期条目保留，网络不可用时作为离线结果返回
+// 总占用超出上限时按最近访问时间 LRU 淘汰
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use super::types::Context7Request;
+use crate::utils::disk_cache::DiskCache;
+
+/// 默认缓存有效期（24 小时）
+pub const DEFAULT_CACHE_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// 缓存目录占用上限（超出后按 LRU 淘汰）
+const MAX_CACHE_BYTES: u64 = 100 * 1024 * 1024;
+
+/// 文档条目命名空间
+const DOCS_NAMESPACE: &str = "docs";
+
+/// 全局文档缓存，有效期取自配置 `context7_cache_ttl_minutes`
+pub static DOC_CACHE: Lazy<DocCache> = Lazy::new(|| {
+    let dir = dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("sanshu")
+        .join("context7_cache");
+    let ttl_secs = crate::config::load_standalone_config()
+        .ok()
+        .and_then(|c| c.mcp_config.context7_cache_ttl_minutes)
+        .map(|minutes| minutes.saturating_mul(60))
+        .unwrap_or(DEFAULT_CACHE_TTL_SECS);
+    DocCache::new(dir, MAX_CACHE_BYTES, ttl_secs)
+});
+
+/// 文档缓存键
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocKey {
+    pub library: String,
+    pub version: Option<String>,
+    pub topic: Option<String>,
+    pub page: u32,
+}
+
+impl DocKey {
+    pub fn from_request(request: &Context7Request) -> Self {
+        let clean = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            library: normalize_library(&request.library),
+            version: clean(&request.version),
+            topic: clean(&request.topic).map(|t| t.to_lowercase()),
+            page: request.page.unwrap_or(1).max(1),
+        }
+    }
+
+    fn cache_key(&self) -> String {
+        format!(
+            "{}\n{}\n{}\n{}",
+            self.library,
+            self.version.as_deref().unwrap_or(""),
+            self.topic.as_deref().unwrap_or(""),
+            self.page
+        )
+    }
+}
+
+/// 库标识符规范化：去掉首尾斜杠与空白，统一小写
+pub fn normalize_library(library: &str) -> String {
+    library.trim().trim_matches('/').to_lowercase()
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedDoc {
+    key: DocKey,
+    content: String,
+}
+
+/// 缓存命中结果
+pub struct CachedContent {
+    pub content: String,
+    pub created_at: u64,
+    pub expired: bool,
+}
+
+/// 文档缓存，基于通用磁盘缓存
+pub struct DocCache {
+    cache: DiskCache,
+}
+
+impl DocCache {
+    pub fn new(dir: PathBuf, max_bytes: u64, ttl_secs: u64) -> Self {
+        Self {
+            cache: DiskCache::new(dir, max_bytes, ttl_secs),
+        }
+    }
+
+    /// 读取条目（包括已过期的条目，由调用方决定是否使用），并刷新其访问时间
+    pub fn get(&self, key: &DocKey) -> Option<CachedContent> {
+        let entry = self.cache.get_entry::<CachedDoc>(DOCS_NAMESPACE, &key.cache_key())?;
+        // 摘要冲突的条目直接忽略
+        if &entry.value.key != key {
+            return None;
+        }
+        Some(CachedContent {
+            content: entry.value.content,
+            created_at: entry.created_at,
+            expired: entry.expired,
+        })
+    }
+
+    /// 写入条目，超出容量时淘汰最久未访问的条目
+    pub fn put(&self, key: &DocKey, content: &str) -> Result<(), String> {
+        let doc = CachedDoc {
+            key: key.clone(),
+            content: content.to_string(),
+        };
+        self.cache
+            .put(DOCS_NAMESPACE, &key.cache_key(), &doc)
+            .map_err(|e| format!("写入文档缓存失败: {}", e))
+    }
+
+    /// 清空缓存，返回删除的条目数
+    pub fn clear(&self) -> usize {
+        self.cache.clear(false).0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn request(library: &str, topic: Option<&str>, page: Option<u32>) -> Context7Request {
+        Context7Request {
+            library: library.to_string(),
+            topic: topic.map(str::to_string),
+            version: Some("v1.0".to_string()),
+            page,
+            project_root_path: None,
+        }
+    }
+
+    #[test]
+    fn test_doc_cache_ttl() {
+        let dir = std::env::temp_dir().join(format!("sanshu-context7-cache-{}", std::process::id()));
+        let key = DocKey::from_request(&request("/Tokio-RS/tokio/", Some(" Runtime "), None));
+        assert_eq!(key.library, "tokio-rs/tokio");
+        assert_eq!(key.topic.as_deref(), Some("runtime"));
+        assert_eq!(key.page, 1);
+        assert_eq!(key, DocKey::from_request(&request("tokio-rs/tokio", Some("runtime"), Some(1))));
+
+        let cache = DocCache::new(dir.clone(), MAX_CACHE_BYTES, 60);
+        assert!(cache.get(&key).is_none());
+        cache.put(&key, "# docs").unwrap();
+        let hit = cache.get(&key).unwrap();
+        assert_eq!(hit.content, "# docs");
+        assert!(!hit.expired);
+
+        // 过期条目仍可读取（离线兜底）
+        let stale = DocCache::new(dir.clone(), MAX_CACHE_BYTES, 0);
+        std::thread::sleep(Duration::from_millis(1100));
+        assert!(stale.get(&key).unwrap().expired);
+
+        assert_eq!(cache.clear(), 1);
+        assert!(cache.get(&key).is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_doc_cache_lru_eviction() {
+        let dir = std::env::temp_dir().join(format!("sanshu-context7-lru-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let cache = DocCache::new(dir.clone(), 700, DEFAULT_CACHE_TTL_SECS);
+        let key = |library: &str| DocKey::from_request(&request(library, None, None));
+        let content = "x".repeat(200);
+        cache.put(&key("a/old"), &content).unwrap();
+
+        // 让 a/old 成为最久未访问的条目
+        for entry in fs::read_dir(dir.join(DOCS_NAMESPACE)).unwrap().flatten() {
+            fs::File::options()
+                .write(true)
+                .open(entry.path())
+                .unwrap()
+                .set_modified(UNIX_EPOCH + Duration::from_secs(1))
+                .unwrap();
+        }
+
+        cache.put(&key("a/recent"), &content).unwrap();
+        cache.put(&key("a/new"), &content).unwrap();
+        assert!(cache.get(&key("a/old")).is_none());
+        assert!(cache.get(&key("a/recent")).is_some());
+        assert!(cache.get(&key("a/new")).is_some());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
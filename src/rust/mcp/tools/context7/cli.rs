@@ -0,0 +1,225 @@
+// Context7 文档快照命令行：三术 context7 <snapshot|list|resolve|clear-cache> ...
+
+use std::path::PathBuf;
+
+use super::cache::DOC_CACHE;
+use super::mcp::Context7Tool;
+use super::resolver::{is_library_id, read_direct_dependencies, resolve};
+use super::snapshot::{create_snapshot, list_snapshots, SnapshotOptions, SnapshotScope, MAX_PAGES};
+use super::types::{Context7Config, Context7Request};
+
+/// 执行 `context7` 子命令，返回进程退出码
+pub async fn run(args: &[String]) -> i32 {
+    match execute(args).await {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            1
+        }
+    }
+}
+
+async fn execute(args: &[String]) -> Result<i32, String> {
+    let Some(command) = args.first() else {
+        print_help();
+        return Ok(1);
+    };
+    let rest = &args[1..];
+    match command.as_str() {
+        "snapshot" => {
+            let mut library = None;
+            let mut all_deps = false;
+            let mut options = SnapshotOptions {
+                library: String::new(),
+                version: None,
+                topics: Vec::new(),
+                max_pages: MAX_PAGES,
+                scope: SnapshotScope::Project,
+                project_root: None,
+            };
+            let mut iter = rest.iter();
+            while let Some(arg) = iter.next() {
+                match arg.as_str() {
+                    "--version" => options.version = Some(next_value(&mut iter, "--version")?),
+                    "--topic" => options.topics.push(next_value(&mut iter, "--topic")?),
+                    "--pages" => {
+                        options.max_pages = next_value(&mut iter, "--pages")?
+                            .parse()
+                            .map_err(|_| "--pages 需要一个正整数".to_string())?
+                    }
+                    "--project" => options.project_root = Some(PathBuf::from(next_value(&mut iter, "--project")?)),
+                    "--user" => options.scope = SnapshotScope::User,
+                    "--deps" => all_deps = true,
+                    other if other.starts_with('-') => return Err(format!("未知参数: {}", other)),
+                    other => library = Some(other.to_string()),
+                }
+            }
+            if all_deps && (library.is_some() || options.version.is_some()) {
+                return Err("--deps 不能与库标识符或 --version 同时使用".to_string());
+            }
+            if options.scope == SnapshotScope::Project && options.project_root.is_none() {
+                options.project_root =
+                    Some(std::env::current_dir().map_err(|e| format!("获取当前目录失败: {}", e))?);
+            }
+
+            let config = Context7Tool::get_config()
+                .await
+                .map_err(|e| format!("获取 Context7 配置失败: {}", e))?;
+            // 包名按项目依赖解析为库标识符与锁定版本
+            let project_root = match &options.project_root {
+                Some(root) => root.clone(),
+                None => std::env::current_dir().map_err(|e| format!("获取当前目录失败: {}", e))?,
+            };
+            if !all_deps {
+                options.library = library.ok_or("缺少库标识符（例如 tokio-rs/tokio）")?;
+                snapshot_library(&config, options, &project_root).await?;
+                return Ok(0);
+            }
+
+            // 逐个为直接依赖保存快照，单个失败不影响其余依赖
+            let dependencies = read_direct_dependencies(&project_root);
+            if dependencies.is_empty() {
+                println!("未在 {} 中发现项目依赖", project_root.display());
+                return Ok(1);
+            }
+            let mut failed = 0;
+            for dep in &dependencies {
+                let options = SnapshotOptions {
+                    library: dep.name.clone(),
+                    ..options.clone()
+                };
+                if let Err(e) = snapshot_library(&config, options, &project_root).await {
+                    println!("⚠️ 跳过 {}：{}", dep.name, e);
+                    failed += 1;
+                }
+            }
+            println!("共 {} 个依赖，{} 个已保存快照", dependencies.len(), dependencies.len() - failed);
+            Ok(if failed == 0 { 0 } else { 1 })
+        }
+        "list" | "ls" => {
+            let project_root = match rest.iter().position(|a| a == "--project") {
+                Some(idx) => rest.get(idx + 1).map(PathBuf::from).ok_or("--project 需要一个值")?,
+                None => std::env::current_dir().map_err(|e| format!("获取当前目录失败: {}", e))?,
+            };
+            let snapshots = list_snapshots(Some(&project_root));
+            if snapshots.is_empty() {
+                println!("未发现文档快照");
+            }
+            for snapshot in snapshots {
+                let pages: u32 = snapshot.manifest.pages.values().sum();
+                println!(
+                    "{}@{} [{}] {} 页 {}",
+                    snapshot.manifest.library,
+                    snapshot.manifest.version.as_deref().unwrap_or("latest"),
+                    snapshot.scope.label(),
+                    pages,
+                    snapshot.path.display()
+                );
+            }
+            Ok(0)
+        }
+        "resolve" => {
+            let mut library = None;
+            let mut project_root = None;
+            let mut iter = rest.iter();
+            while let Some(arg) = iter.next() {
+                match arg.as_str() {
+                    "--project" => project_root = Some(PathBuf::from(next_value(&mut iter, "--project")?)),
+                    other if other.starts_with('-') => return Err(format!("未知参数: {}", other)),
+                    other => library = Some(other.to_string()),
+                }
+            }
+            let library = library.ok_or("缺少包名或库标识符")?;
+            let project_root = match project_root {
+                Some(root) => root,
+                None => std::env::current_dir().map_err(|e| format!("获取当前目录失败: {}", e))?,
+            };
+            let config = Context7Tool::get_config()
+                .await
+                .map_err(|e| format!("获取 Context7 配置失败: {}", e))?;
+            let request = library_request(&library, None, &project_root);
+            let resolution = resolve(&config, &request).await;
+            let version = resolution.request.version.as_deref().unwrap_or("latest");
+            match &resolution.dependency {
+                Some(dep) => println!("{} → {}@{}（来源: {}）", library, resolution.request.library, version, dep.source),
+                None if resolution.request.library != library => {
+                    println!("{} → {}@{}（内置映射，非项目依赖）", library, resolution.request.library, version)
+                }
+                None => {
+                    println!("未在项目依赖中找到 {}", library);
+                    return Ok(1);
+                }
+            }
+            Ok(0)
+        }
+        "clear-cache" => {
+            println!("✅ 已清理 {} 条文档缓存", DOC_CACHE.clear());
+            Ok(0)
+        }
+        "help" | "--help" | "-h" => {
+            print_help();
+            Ok(0)
+        }
+        other => Err(format!("未知的 context7 子命令: {}", other)),
+    }
+}
+
+/// 按项目依赖解析库标识符与锁定版本后保存快照
+async fn snapshot_library(
+    config: &Context7Config,
+    mut options: SnapshotOptions,
+    project_root: &std::path::Path,
+) -> Result<(), String> {
+    let request = library_request(&options.library, options.version.clone(), project_root);
+    let resolution = resolve(config, &request).await;
+    if let Some(note) = resolution.note(&request) {
+        println!("{}", note);
+    }
+    if !is_library_id(&resolution.request.library) {
+        return Err(format!("未找到 {} 对应的 Context7 库", request.library));
+    }
+    options.library = resolution.request.library;
+    options.version = resolution.request.version;
+
+    let info = create_snapshot(config, &options).await?;
+    println!("✅ 已保存快照 {} -> {}", info.manifest.library, info.path.display());
+    for (topic, pages) in &info.manifest.pages {
+        let topic = if topic.is_empty() { "（默认）" } else { topic.as_str() };
+        println!("   主题 {}: {} 页", topic, pages);
+    }
+    Ok(())
+}
+
+fn library_request(library: &str, version: Option<String>, project_root: &std::path::Path) -> Context7Request {
+    Context7Request {
+        library: library.to_string(),
+        topic: None,
+        version,
+        page: None,
+        project_root_path: Some(project_root.to_string_lossy().to_string()),
+    }
+}
+
+fn next_value<'a>(iter: &mut impl Iterator<Item = &'a String>, flag: &str) -> Result<String, String> {
+    iter.next().cloned().ok_or_else(|| format!("{} 需要一个值", flag))
+}
+
+fn print_help() {
+    println!("三术 context7 - 文档缓存与离线快照");
+    println!();
+    println!("用法:");
+    println!("  三术 context7 snapshot <库> [选项]   预取库的全部文档页面并保存为离线快照");
+    println!("  三术 context7 snapshot --deps [选项] 为项目的全部直接依赖保存快照（按锁定版本）");
+    println!("  三术 context7 list [--project <目录>] 列出项目与用户目录下的快照");
+    println!("  三术 context7 resolve <包名> [--project <目录>]  按项目依赖解析库标识符与锁定版本");
+    println!("  三术 context7 clear-cache           清空在线查询缓存");
+    println!();
+    println!("快照选项:");
+    println!("  --version <版本>      固定文档版本（缺省为最新）");
+    println!("  --topic <主题>        额外预取的主题，可重复");
+    println!("  --pages <数量>        每个主题最多预取的页数（默认 {}）", MAX_PAGES);
+    println!("  --project <目录>      保存到 <目录>/.sanshu/context7（默认当前目录）");
+    println!("  --user                保存到 ~/.sanshu/context7");
+    println!();
+    println!("<库> 可为 owner/repo，也可为项目依赖的包名（按锁文件解析并固定为锁定版本）");
+}
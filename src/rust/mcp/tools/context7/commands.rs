@@ -1,5 +1,8 @@
 use tauri::State;
 use crate::config::AppState;
+use super::cache::DOC_CACHE;
+use super::resolver::resolve;
+use super::snapshot::{create_snapshot, list_snapshots, SnapshotInfo, SnapshotOptions, SnapshotScope, MAX_PAGES};
 use super::types::{Context7Request, Context7Config, TestConnectionResponse};
 
 /// 测试 Context7 连接
@@ -31,6 +34,7 @@ pub async fn test_context7_connection(
         topic: test_topic,
         version: None,
         page: Some(1),
+        project_root_path: None,
     };
 
     // 调用内部方法执行查询
@@ -181,3 +185,62 @@ pub async fn save_context7_config(
     Ok(())
 }
 
+
+/// 创建离线文档快照（指定项目目录时保存到项目，否则保存到用户目录）
+#[tauri::command]
+pub async fn create_context7_snapshot(
+    library: String,
+    version: Option<String>,
+    topics: Option<Vec<String>>,
+    max_pages: Option<u32>,
+    project_root: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<SnapshotInfo, String> {
+    let context7_config = {
+        let config = state.config
+            .lock()
+            .map_err(|e| format!("获取配置失败: {}", e))?;
+
+        Context7Config {
+            api_key: config.mcp_config.context7_api_key.clone(),
+            base_url: "https://context7.com/api/v2".to_string(),
+        }
+    };
+
+    let project_root = project_root
+        .filter(|p| !p.trim().is_empty())
+        .map(std::path::PathBuf::from);
+    // 指定项目目录时，包名按项目依赖解析为库标识符与锁定版本
+    let resolved = resolve(&context7_config, &Context7Request {
+        library,
+        topic: None,
+        version,
+        page: None,
+        project_root_path: project_root.as_ref().map(|p| p.to_string_lossy().to_string()),
+    }).await.request;
+
+    let options = SnapshotOptions {
+        library: resolved.library,
+        version: resolved.version,
+        topics: topics.unwrap_or_default(),
+        max_pages: max_pages.unwrap_or(MAX_PAGES),
+        scope: if project_root.is_some() { SnapshotScope::Project } else { SnapshotScope::User },
+        project_root,
+    };
+    create_snapshot(&context7_config, &options).await
+}
+
+/// 列出离线文档快照
+#[tauri::command]
+pub async fn list_context7_snapshots(project_root: Option<String>) -> Result<Vec<SnapshotInfo>, String> {
+    let project_root = project_root
+        .filter(|p| !p.trim().is_empty())
+        .map(std::path::PathBuf::from);
+    Ok(list_snapshots(project_root.as_deref()))
+}
+
+/// 清空 Context7 文档缓存，返回删除的条目数
+#[tauri::command]
+pub async fn clear_context7_cache() -> Result<usize, String> {
+    Ok(DOC_CACHE.clear())
+}
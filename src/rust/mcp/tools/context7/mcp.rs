@@ -4,9 +4,13 @@ use reqwest::header::AUTHORIZATION;
 use reqwest::Client;
 use serde_json::json;
 use std::borrow::Cow;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
+use super::cache::{DocKey, DOC_CACHE};
+use super::resolver;
+use super::snapshot;
 use super::types::{Context7Request, Context7Config, SearchResponse, SearchResult};
 use crate::log_debug;
 use crate::log_important;
@@ -14,6 +18,14 @@ use crate::log_important;
 /// Context7 工具实现
 pub struct Context7Tool;
 
+/// 在线查询的原始结果
+pub(crate) enum RawDocs {
+    /// 文档内容（纯文本 Markdown）
+    Found(String),
+    /// 库不存在（404）
+    NotFound,
+}
+
 impl Context7Tool {
     /// 查询框架文档
     pub async fn query_docs(request: Context7Request) -> Result<CallToolResult, McpError> {
@@ -27,10 +39,17 @@ impl Context7Tool {
             .await
             .map_err(|e| McpError::internal_error(format!("获取 Context7 配置失败: {}", e), None))?;
 
+        // 按项目依赖解析库标识符与版本
+        let resolution = resolver::resolve(&config, &request).await;
+
         // 执行查询
-        match Self::fetch_docs(&config, &request).await {
+        match Self::fetch_docs(&config, &resolution.request, resolution.version_inferred).await {
             Ok(result) => {
                 log_important!(info, "Context7 查询成功");
+                let result = match resolution.note(&request) {
+                    Some(note) => format!("{}{}\n", result, note),
+                    None => result,
+                };
                 Ok(CallToolResult {
                     content: vec![Content::text(result)],
                     is_error: Some(false),
@@ -58,7 +77,7 @@ impl Context7Tool {
             "properties": {
                 "library": {
                     "type": "string",
-                    "description": "库标识符，格式: owner/repo (例如: vercel/next.js, facebook/react, spring-projects/spring-framework)"
+                    "description": "库标识符，格式: owner/repo (例如: vercel/next.js, facebook/react, spring-projects/spring-framework)；也可直接使用项目依赖的包名 (例如: tokio, react)，将按项目锁文件解析为库标识符并自动填入锁定版本"
                 },
                 "topic": {
                     "type": "string",
@@ -73,6 +92,10 @@ impl Context7Tool {
                     "description": "分页页码 (可选，默认1，最大10)",
                     "minimum": 1,
                     "maximum": 10
+                },
+                "project_root_path": {
+                    "type": "string",
+                    "description": "项目根目录 (可选，用于解析项目依赖版本与查找离线文档快照，默认使用客户端工作区)"
                 }
             },
             "required": ["library"]
@@ -95,7 +118,7 @@ impl Context7Tool {
     }
 
     /// 获取配置
-    async fn get_config() -> Result<Context7Config> {
+    pub(crate) async fn get_config() -> Result<Context7Config> {
         // 从配置文件中读取 Context7 配置
         let config = crate::config::load_standalone_config()
             .map_err(|e| anyhow::anyhow!("读取配置文件失败: {}", e))?;
@@ -106,8 +129,90 @@ impl Context7Tool {
         })
     }
 
-    /// 执行 HTTP 请求获取文档
-    async fn fetch_docs(config: &Context7Config, request: &Context7Request) -> Result<String> {
+    /// 获取文档：离线快照 > 未过期缓存 > 在线查询（写入缓存） > 过期缓存（离线兜底）
+    /// `version_inferred` 为 true 时，自动填充的版本未收录则退回最新文档
+    async fn fetch_docs(config: &Context7Config, request: &Context7Request, version_inferred: bool) -> Result<String> {
+        let key = DocKey::from_request(request);
+        let project_root = request
+            .project_root_path
+            .as_deref()
+            .filter(|p| !p.trim().is_empty())
+            .map(Path::new);
+
+        if let Some(hit) = snapshot::lookup(project_root, &key) {
+            log_important!(info, "命中 Context7 离线快照: library={}, version={:?}", key.library, hit.version);
+            // 未指定版本时展示快照固定的版本
+            let mut shown = request.clone();
+            if shown.version.is_none() {
+                shown.version = hit.version.clone();
+            }
+            return Ok(format!(
+                "{}📦 离线快照（{}目录）\n",
+                Self::format_text_response(&hit.content, &shown),
+                hit.scope.label()
+            ));
+        }
+
+        let cached = DOC_CACHE.get(&key);
+        if let Some(hit) = cached.as_ref().filter(|c| !c.expired) {
+            log_debug!("命中 Context7 文档缓存: library={}", key.library);
+            return Ok(format!(
+                "{}💾 缓存于 {}\n",
+                Self::format_text_response(&hit.content, request),
+                Self::format_cache_time(hit.created_at)
+            ));
+        }
+
+        match Self::fetch_raw(config, request).await {
+            Ok(RawDocs::Found(text)) => {
+                if text.trim().is_empty() {
+                    return Ok("未找到相关文档。请尝试调整查询参数。".to_string());
+                }
+                if let Err(e) = DOC_CACHE.put(&key, &text) {
+                    log_debug!("写入 Context7 文档缓存失败: {}", e);
+                }
+                // 格式化输出（添加标题和元信息）
+                Ok(Self::format_text_response(&text, request))
+            }
+            Ok(RawDocs::NotFound) if version_inferred => {
+                let mut latest = request.clone();
+                latest.version = None;
+                match Self::fetch_raw(config, &latest).await {
+                    Ok(RawDocs::Found(text)) if !text.trim().is_empty() => {
+                        if let Err(e) = DOC_CACHE.put(&DocKey::from_request(&latest), &text) {
+                            log_debug!("写入 Context7 文档缓存失败: {}", e);
+                        }
+                        Ok(format!(
+                            "{}⚠️ Context7 未收录版本 {}，以上为最新文档\n",
+                            Self::format_text_response(&text, &latest),
+                            request.version.as_deref().unwrap_or_default()
+                        ))
+                    }
+                    _ => Self::handle_not_found_with_search(config, request).await,
+                }
+            }
+            // 404 错误时触发智能降级：搜索候选库
+            Ok(RawDocs::NotFound) => {
+                log_important!(info, "库 '{}' 不存在，触发智能搜索", request.library);
+                Self::handle_not_found_with_search(config, request).await
+            }
+            Err(e) => match cached {
+                Some(hit) => {
+                    log_important!(warn, "Context7 在线查询失败，使用过期缓存: {}", e);
+                    Ok(format!(
+                        "{}⚠️ 在线查询失败（{}），以上为 {} 的离线缓存\n",
+                        Self::format_text_response(&hit.content, request),
+                        e,
+                        Self::format_cache_time(hit.created_at)
+                    ))
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    /// 执行 HTTP 请求获取原始文档（不经过缓存与快照）
+    pub(crate) async fn fetch_raw(config: &Context7Config, request: &Context7Request) -> Result<RawDocs> {
         let client = Client::builder()
             .timeout(Duration::from_secs(30))
             .build()?;
@@ -146,14 +251,11 @@ impl Context7Tool {
 
         // 处理错误状态码
         if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_else(|_| "无法读取错误信息".to_string());
-
-            // 404 错误时触发智能降级：搜索候选库
             if status.as_u16() == 404 {
-                log_important!(info, "库 '{}' 不存在，触发智能搜索", request.library);
-                return Self::handle_not_found_with_search(config, request).await;
+                return Ok(RawDocs::NotFound);
             }
 
+            let error_text = response.text().await.unwrap_or_else(|_| "无法读取错误信息".to_string());
             return Err(anyhow::anyhow!(
                 "API 请求失败 (状态码: {}): {}",
                 status,
@@ -162,15 +264,14 @@ impl Context7Tool {
         }
 
         // 读取响应文本 (Context7 API 返回纯文本 Markdown，不是 JSON)
-        let response_text = response.text().await?;
-
-        // 如果响应为空
-        if response_text.trim().is_empty() {
-            return Ok("未找到相关文档。请尝试调整查询参数。".to_string());
-        }
+        Ok(RawDocs::Found(response.text().await?))
+    }
 
-        // 格式化输出（添加标题和元信息）
-        Ok(Self::format_text_response(&response_text, request))
+    /// 格式化缓存写入时间
+    fn format_cache_time(created_at: u64) -> String {
+        chrono::DateTime::from_timestamp(created_at as i64, 0)
+            .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "未知时间".to_string())
     }
 
     /// 格式化错误消息
@@ -244,7 +345,7 @@ impl Context7Tool {
     }
 
     /// 搜索库
-    async fn search_libraries(config: &Context7Config, query: &str) -> Result<Vec<SearchResult>> {
+    pub(crate) async fn search_libraries(config: &Context7Config, query: &str) -> Result<Vec<SearchResult>> {
         let client = Client::builder()
             .timeout(Duration::from_secs(15))
             .build()?;
@@ -1,8 +1,12 @@
 pub mod types;
 pub mod mcp;
 pub mod commands;
+pub mod cache;
+pub mod snapshot;
+pub mod resolver;
+pub mod cli;
 
 pub use mcp::Context7Tool;
 pub use types::{Context7Request, Context7Config};
-pub use commands::{test_context7_connection, get_context7_config, save_context7_config};
+pub use commands::{test_context7_connection, get_context7_config, save_context7_config, create_context7_snapshot, list_context7_snapshots, clear_context7_cache};
 
@@ -0,0 +1,842 @@
+// Context7 依赖感知的库解析
+// 从项目清单与锁文件读取依赖及版本，将包名映射为 Context7 库标识符
+// 例如 `library=tokio` 自动解析为 `tokio-rs/tokio` 并填入 Cargo.lock 中锁定的版本
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use super::cache::DEFAULT_CACHE_TTL_SECS;
+use super::mcp::Context7Tool;
+use super::types::{Context7Config, Context7Request, SearchResult};
+use crate::log_debug;
+use crate::utils::disk_cache::DiskCache;
+
+/// 包名 -> Context7 库标识符映射缓存（搜索结果，未找到的条目按文档缓存有效期过期，已找到的按 LIBRARY_MAP_TTL_SECS）
+static LIBRARY_MAP: Lazy<DiskCache> = Lazy::new(|| {
+    let dir = dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("sanshu")
+        .join("context7_library_map");
+    DiskCache::new(dir, MAX_LIBRARY_MAP_BYTES, LIBRARY_MAP_TTL_SECS)
+});
+
+/// 映射缓存命名空间
+const LIBRARY_NAMESPACE: &str = "library";
+
+/// 已找到的映射有效期（库迁移或改名后需重新搜索）
+const LIBRARY_MAP_TTL_SECS: u64 = 7 * DEFAULT_CACHE_TTL_SECS;
+
+/// 映射缓存占用上限
+const MAX_LIBRARY_MAP_BYTES: u64 = 4 * 1024 * 1024;
+
+/// 常用包的内置映射，无需联网搜索
+const KNOWN_LIBRARIES: &[(Ecosystem, &str, &str)] = &[
+    (Ecosystem::Cargo, "tokio", "tokio-rs/tokio"),
+    (Ecosystem::Cargo, "axum", "tokio-rs/axum"),
+    (Ecosystem::Cargo, "serde", "serde-rs/serde"),
+    (Ecosystem::Cargo, "reqwest", "seanmonstar/reqwest"),
+    (Ecosystem::Cargo, "tauri", "tauri-apps/tauri"),
+    (Ecosystem::Cargo, "rmcp", "modelcontextprotocol/rust-sdk"),
+    (Ecosystem::Npm, "react", "facebook/react"),
+    (Ecosystem::Npm, "react-dom", "facebook/react"),
+    (Ecosystem::Npm, "next", "vercel/next.js"),
+    (Ecosystem::Npm, "vue", "vuejs/core"),
+    (Ecosystem::Npm, "vite", "vitejs/vite"),
+    (Ecosystem::Npm, "tailwindcss", "tailwindlabs/tailwindcss"),
+    (Ecosystem::Npm, "@tauri-apps/api", "tauri-apps/tauri"),
+    (Ecosystem::Python, "django", "django/django"),
+    (Ecosystem::Python, "flask", "pallets/flask"),
+    (Ecosystem::Python, "fastapi", "fastapi/fastapi"),
+    (Ecosystem::Python, "pydantic", "pydantic/pydantic"),
+];
+
+/// 依赖所属生态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    Cargo,
+    Npm,
+    Python,
+    Go,
+}
+
+impl Ecosystem {
+    fn key(&self) -> &'static str {
+        match self {
+            Self::Cargo => "cargo",
+            Self::Npm => "npm",
+            Self::Python => "python",
+            Self::Go => "go",
+        }
+    }
+
+    /// 搜索结果描述中表明所属生态的关键词
+    fn hints(&self) -> &'static [&'static str] {
+        match self {
+            Self::Cargo => &["rust", "crate", "crates"],
+            Self::Npm => &["javascript", "typescript", "js", "ts", "node", "nodejs", "npm"],
+            Self::Python => &["python", "pypi"],
+            Self::Go => &["go", "golang"],
+        }
+    }
+}
+
+/// 项目依赖
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    pub ecosystem: Ecosystem,
+    pub name: String,
+    /// 锁定版本，或清单中写明的精确版本
+    pub version: Option<String>,
+    /// 版本来源文件
+    pub source: &'static str,
+}
+
+/// 解析结果
+#[derive(Debug, Clone)]
+pub struct Resolution {
+    pub request: Context7Request,
+    pub dependency: Option<Dependency>,
+    /// 版本是否由依赖自动填充
+    pub version_inferred: bool,
+}
+
+impl Resolution {
+    fn unchanged(request: &Context7Request) -> Self {
+        Self {
+            request: request.clone(),
+            dependency: None,
+            version_inferred: false,
+        }
+    }
+
+    /// 附加到查询结果末尾的解析说明
+    pub fn note(&self, original: &Context7Request) -> Option<String> {
+        let dependency = self.dependency.as_ref()?;
+        if self.request.library == original.library && !self.version_inferred {
+            return None;
+        }
+        let version = self
+            .request
+            .version
+            .as_deref()
+            .map(|v| format!("@{}", v))
+            .unwrap_or_default();
+        Some(format!(
+            "🧭 根据 {} 解析: {} → {}{}",
+            dependency.source, original.library, self.request.library, version
+        ))
+    }
+}
+
+/// 将请求中的包名解析为 Context7 库标识符，并按项目依赖补全版本
+pub async fn resolve(config: &Context7Config, request: &Context7Request) -> Resolution {
+    let library = request.library.trim().trim_matches('/');
+    let Some(project_root) = request
+        .project_root_path
+        .as_deref()
+        .filter(|p| !p.trim().is_empty())
+        .map(Path::new)
+        .filter(|p| p.is_dir())
+    else {
+        return Resolution::unchanged(request);
+    };
+    if library.is_empty() {
+        return Resolution::unchanged(request);
+    }
+
+    let dependencies = read_dependencies(project_root);
+    let mut resolution = Resolution::unchanged(request);
+
+    if is_library_id(library) {
+        // 已是 owner/repo：仅在未指定版本时补全依赖版本
+        if request.version.is_none() {
+            if let Some(dep) = dependencies.iter().find(|d| matches_library_id(d, library, &LIBRARY_MAP)) {
+                resolution.version_inferred = dep.version.is_some();
+                resolution.request.version = dep.version.clone();
+                resolution.dependency = Some(dep.clone());
+            }
+        }
+        return resolution;
+    }
+
+    let Some(dep) = find_dependency(&dependencies, library) else {
+        // 非项目依赖：仅使用内置映射，其余沿用 404 搜索降级
+        if let Some(id) = known_library(None, library) {
+            resolution.request.library = id.to_string();
+        }
+        return resolution;
+    };
+    let Some(id) = library_id(config, dep).await else {
+        return resolution;
+    };
+    log_debug!("Context7 库解析: {} -> {} ({:?})", library, id, dep.version);
+    resolution.request.library = id;
+    if request.version.is_none() && dep.version.is_some() {
+        resolution.request.version = dep.version.clone();
+        resolution.version_inferred = true;
+    }
+    resolution.dependency = Some(dep.clone());
+    resolution
+}
+
+/// owner/repo 形式（Go 模块路径首段含域名，不视为库标识符）
+pub fn is_library_id(library: &str) -> bool {
+    match library.split_once('/') {
+        Some((owner, _)) => !owner.contains('.') && !owner.starts_with('@'),
+        None => false,
+    }
+}
+
+/// 包名规范化：小写，`_` 与 `.` 视同 `-`（PEP 503）
+fn normalize_name(name: &str) -> String {
+    name.trim().to_lowercase().replace(['_', '.'], "-")
+}
+
+/// 按包名查找依赖（Go 模块同时匹配完整路径与末段）
+pub fn find_dependency<'a>(dependencies: &'a [Dependency], name: &str) -> Option<&'a Dependency> {
+    let wanted = normalize_name(name);
+    dependencies.iter().find(|dep| {
+        normalize_name(&dep.name) == wanted
+            || (dep.ecosystem == Ecosystem::Go && go_module_name(&dep.name).is_some_and(|n| normalize_name(n) == wanted))
+    })
+}
+
+/// Go 模块路径末段（跳过 /vN 主版本后缀）
+fn go_module_name(module: &str) -> Option<&str> {
+    let mut segments = module.rsplit('/');
+    let last = segments.next()?;
+    let is_major = last.len() > 1 && last.starts_with('v') && last[1..].chars().all(|c| c.is_ascii_digit());
+    if is_major {
+        segments.next()
+    } else {
+        Some(last)
+    }
+}
+
+fn known_library(ecosystem: Option<Ecosystem>, name: &str) -> Option<&'static str> {
+    let wanted = normalize_name(name);
+    KNOWN_LIBRARIES
+        .iter()
+        .find(|(eco, pkg, _)| ecosystem.is_none_or(|e| e == *eco) && normalize_name(pkg) == wanted)
+        .map(|(_, _, id)| *id)
+}
+
+/// 不联网即可确定的库标识符：内置映射、github.com 模块路径、映射缓存
+fn offline_library_id(dep: &Dependency, map: &DiskCache) -> Option<String> {
+    if let Some(id) = known_library(Some(dep.ecosystem), &dep.name) {
+        return Some(id.to_string());
+    }
+    if dep.ecosystem == Ecosystem::Go {
+        if let Some(rest) = dep.name.strip_prefix("github.com/") {
+            let mut parts = rest.split('/');
+            if let (Some(owner), Some(repo)) = (parts.next(), parts.next()) {
+                return Some(format!("{}/{}", owner, repo).to_lowercase());
+            }
+        }
+    }
+    map.get::<Option<String>>(LIBRARY_NAMESPACE, &map_key(dep)).flatten()
+}
+
+fn matches_library_id(dep: &Dependency, library: &str, map: &DiskCache) -> bool {
+    let repo = library.rsplit('/').next().unwrap_or(library);
+    normalize_name(&dep.name) == normalize_name(repo)
+        || offline_library_id(dep, map).is_some_and(|id| id.eq_ignore_ascii_case(library))
+}
+
+/// 依赖对应的 Context7 库标识符：离线映射优先，否则搜索并缓存结果
+async fn library_id(config: &Context7Config, dep: &Dependency) -> Option<String> {
+    if let Some(id) = offline_library_id(dep, &LIBRARY_MAP) {
+        return Some(id);
+    }
+    let key = map_key(dep);
+    if let Some(entry) = LIBRARY_MAP.get_entry::<Option<String>>(LIBRARY_NAMESPACE, &key) {
+        if entry.value.is_none() && entry.age_secs < DEFAULT_CACHE_TTL_SECS {
+            return None;
+        }
+    }
+
+    let query = match dep.ecosystem {
+        Ecosystem::Go => go_module_name(&dep.name).unwrap_or(&dep.name),
+        // @scope/pkg 按 pkg 搜索
+        _ => dep.name.rsplit('/').next().unwrap_or(&dep.name),
+    };
+    let results = match Context7Tool::search_libraries(config, query).await {
+        Ok(results) => results,
+        Err(e) => {
+            log_debug!("Context7 库搜索失败，跳过映射: {}", e);
+            return None;
+        }
+    };
+    let id = pick_library(&results, query, dep.ecosystem);
+
+    // 未找到匹配库时记录 None，避免重复搜索
+    if let Err(e) = LIBRARY_MAP.put(LIBRARY_NAMESPACE, &key, &id) {
+        log_debug!("写入 Context7 库映射缓存失败: {}", e);
+    }
+    id
+}
+
+/// 从搜索结果中选出仓库名或库名与包名完全一致的库
+///
+/// npm 包另接受带 js 后缀的库名（next -> next.js）；多个结果同名时优先描述中带有所属生态关键词的（如 npm 包优先 JavaScript 库）
+fn pick_library(results: &[SearchResult], query: &str, ecosystem: Ecosystem) -> Option<String> {
+    let wanted = compact(query);
+    let wanted_js = format!("{}js", wanted);
+    let is_wanted = |name: &str| name == wanted || (ecosystem == Ecosystem::Npm && name == wanted_js);
+    let candidates: Vec<&SearchResult> = results
+        .iter()
+        .filter(|result| {
+            let repo = compact(result.id.rsplit('/').next().unwrap_or(&result.id));
+            let title = result.title.as_deref().map(compact).unwrap_or_default();
+            is_wanted(&repo) || is_wanted(&title)
+        })
+        .collect();
+    let mentions_ecosystem = |result: &SearchResult| {
+        let text = format!(
+            "{} {}",
+            result.title.as_deref().unwrap_or_default(),
+            result.description.as_deref().unwrap_or_default()
+        )
+        .to_lowercase();
+        text.split(|c: char| !c.is_ascii_alphanumeric())
+            .any(|word| ecosystem.hints().contains(&word))
+    };
+    candidates
+        .iter()
+        .find(|result| mentions_ecosystem(result))
+        .or_else(|| candidates.first())
+        .map(|result| result.id.trim_start_matches('/').to_string())
+}
+
+/// 仅保留字母数字，用于比较 next / next.js 之类的名称
+fn compact(name: &str) -> String {
+    name.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_lowercase()
+}
+
+fn map_key(dep: &Dependency) -> String {
+    format!("{}:{}", dep.ecosystem.key(), normalize_name(&dep.name))
+}
+
+/// 读取项目依赖：锁文件版本优先，清单仅补充锁文件中没有的依赖
+pub fn read_dependencies(project_root: &Path) -> Vec<Dependency> {
+    let mut deps = Vec::new();
+    let read = |file: &str| fs::read_to_string(project_root.join(file)).ok();
+
+    if let Some(text) = read("Cargo.lock") {
+        extend(&mut deps, parse_toml_lock(&text, Ecosystem::Cargo, "Cargo.lock"));
+    }
+    if let Some(text) = read("Cargo.toml") {
+        extend(&mut deps, parse_cargo_manifest(&text));
+    }
+    if let Some(text) = read("package-lock.json") {
+        extend(&mut deps, parse_package_lock(&text));
+    }
+    if let Some(text) = read("pnpm-lock.yaml") {
+        extend(&mut deps, parse_pnpm_lock(&text));
+    }
+    if let Some(text) = read("yarn.lock") {
+        extend(&mut deps, parse_yarn_lock(&text));
+    }
+    if let Some(text) = read("package.json") {
+        extend(&mut deps, parse_package_json(&text));
+    }
+    for lock in ["uv.lock", "poetry.lock", "pdm.lock"] {
+        if let Some(text) = read(lock) {
+            extend(&mut deps, parse_toml_lock(&text, Ecosystem::Python, lock));
+        }
+    }
+    if let Some(text) = read("pyproject.toml") {
+        extend(&mut deps, parse_pyproject(&text));
+    }
+    if let Some(text) = read("requirements.txt") {
+        extend(&mut deps, parse_requirements(&text));
+    }
+    if let Some(text) = read("go.mod") {
+        extend(&mut deps, parse_go_mod(&text));
+    }
+    deps
+}
+
+/// 清单解析函数
+type ManifestParser = fn(&str) -> Vec<Dependency>;
+
+/// 清单中直接声明的依赖（版本仍按锁文件优先），不含锁文件里的间接依赖
+pub fn read_direct_dependencies(project_root: &Path) -> Vec<Dependency> {
+    let manifests: [(&str, ManifestParser); 5] = [
+        ("Cargo.toml", parse_cargo_manifest),
+        ("package.json", parse_package_json),
+        ("pyproject.toml", parse_pyproject),
+        ("requirements.txt", parse_requirements),
+        ("go.mod", parse_go_mod),
+    ];
+    let declared: Vec<Dependency> = manifests
+        .iter()
+        .filter_map(|(file, parse)| fs::read_to_string(project_root.join(file)).ok().map(|text| parse(&text)))
+        .flatten()
+        .collect();
+    read_dependencies(project_root)
+        .into_iter()
+        .filter(|dep| {
+            let name = normalize_name(&dep.name);
+            declared
+                .iter()
+                .any(|d| d.ecosystem == dep.ecosystem && normalize_name(&d.name) == name)
+        })
+        .collect()
+}
+
+/// 合并依赖，同一生态的同名依赖保留先出现的条目
+fn extend(deps: &mut Vec<Dependency>, found: Vec<Dependency>) {
+    for dep in found {
+        let name = normalize_name(&dep.name);
+        if !deps.iter().any(|d| d.ecosystem == dep.ecosystem && normalize_name(&d.name) == name) {
+            deps.push(dep);
+        }
+    }
+}
+
+fn dependency(ecosystem: Ecosystem, name: &str, version: Option<String>, source: &'static str) -> Dependency {
+    Dependency {
+        ecosystem,
+        name: name.to_string(),
+        version,
+        source,
+    }
+}
+
+/// 版本要求中的精确版本，范围（`^1.2.3`、`~1.2`、`>=1` 等）或标签返回 None
+///
+/// `=1.2.3` 总是精确版本；裸版本号仅在 `bare_is_exact` 时视为精确（npm、Poetry），
+/// Cargo 的 `1.2.3` 等同 `^1.2.3`
+fn exact_version(requirement: &str, bare_is_exact: bool) -> Option<String> {
+    let requirement = requirement.trim();
+    let version = match requirement.strip_prefix('=') {
+        Some(version) => version.trim(),
+        None if bare_is_exact => requirement,
+        None => return None,
+    };
+    let version = version.strip_prefix('v').unwrap_or(version);
+    if version.contains(|c: char| c.is_whitespace() || matches!(c, '|' | '<' | '>' | ',')) {
+        return None;
+    }
+    let parts: Vec<&str> = version.splitn(3, '.').collect();
+    let numeric = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    (parts.len() == 3 && numeric(parts[0]) && numeric(parts[1]) && parts[2].chars().next().is_some_and(|c| c.is_ascii_digit()))
+        .then(|| version.to_string())
+}
+
+/// Cargo.lock / uv.lock / poetry.lock / pdm.lock：[[package]] name + version
+/// 同名多版本时取最后出现的（锁文件按版本升序排列，即最高版本）
+fn parse_toml_lock(text: &str, ecosystem: Ecosystem, source: &'static str) -> Vec<Dependency> {
+    let Ok(lock) = text.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let mut versions: BTreeMap<String, String> = BTreeMap::new();
+    for package in lock.get("package").and_then(|p| p.as_array()).into_iter().flatten() {
+        if let (Some(name), Some(version)) = (
+            package.get("name").and_then(|v| v.as_str()),
+            package.get("version").and_then(|v| v.as_str()),
+        ) {
+            versions.insert(name.to_string(), version.to_string());
+        }
+    }
+    versions
+        .into_iter()
+        .map(|(name, version)| dependency(ecosystem, &name, Some(version), source))
+        .collect()
+}
+
+fn parse_cargo_manifest(text: &str) -> Vec<Dependency> {
+    let Ok(manifest) = text.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let workspace = manifest.get("workspace").and_then(|w| w.get("dependencies"));
+    let tables = ["dependencies", "dev-dependencies", "build-dependencies"]
+        .iter()
+        .filter_map(|key| manifest.get(*key))
+        .chain(workspace);
+
+    let mut deps = Vec::new();
+    for table in tables.filter_map(|t| t.as_table()) {
+        for (key, value) in table {
+            // 重命名依赖：foo = { package = "bar", ... }
+            let name = value.get("package").and_then(|p| p.as_str()).unwrap_or(key);
+            let requirement = value.as_str().or_else(|| value.get("version").and_then(|v| v.as_str()));
+            deps.push(dependency(
+                Ecosystem::Cargo,
+                name,
+                requirement.and_then(|r| exact_version(r, false)),
+                "Cargo.toml",
+            ));
+        }
+    }
+    deps
+}
+
+fn parse_package_lock(text: &str) -> Vec<Dependency> {
+    let Ok(lock) = serde_json::from_str::<serde_json::Value>(text) else {
+        return Vec::new();
+    };
+    let mut deps = Vec::new();
+    // lockfileVersion 2/3：packages["node_modules/<name>"]，只取顶层依赖
+    if let Some(packages) = lock.get("packages").and_then(|p| p.as_object()) {
+        for (path, info) in packages {
+            let Some(name) = path.strip_prefix("node_modules/") else {
+                continue;
+            };
+            if name.contains("/node_modules/") {
+                continue;
+            }
+            let version = info.get("version").and_then(|v| v.as_str()).map(str::to_string);
+            deps.push(dependency(Ecosystem::Npm, name, version, "package-lock.json"));
+        }
+    } else if let Some(dependencies) = lock.get("dependencies").and_then(|d| d.as_object()) {
+        // lockfileVersion 1
+        for (name, info) in dependencies {
+            let version = info.get("version").and_then(|v| v.as_str()).map(str::to_string);
+            deps.push(dependency(Ecosystem::Npm, name, version, "package-lock.json"));
+        }
+    }
+    deps
+}
+
+/// 拆分 `name@version`（作用域包以 @ 开头）
+fn split_at_version(spec: &str) -> Option<(&str, &str)> {
+    let idx = spec.get(1..)?.find('@')? + 1;
+    Some((&spec[..idx], &spec[idx + 1..]))
+}
+
+/// pnpm-lock.yaml 的 packages 段：`/name@1.0.0:`（v6）或 `name@1.0.0:`（v9）
+fn parse_pnpm_lock(text: &str) -> Vec<Dependency> {
+    let mut deps = Vec::new();
+    let mut in_packages = false;
+    for line in text.lines() {
+        if !line.starts_with(' ') && !line.trim().is_empty() {
+            in_packages = line.trim_end() == "packages:";
+            continue;
+        }
+        let Some(entry) = line.strip_prefix("  ") else {
+            continue;
+        };
+        if !in_packages || entry.starts_with(' ') || !entry.ends_with(':') {
+            continue;
+        }
+        let spec = entry.trim_end_matches(':').trim_matches(['\'', '"']).trim_start_matches('/');
+        // 去掉 peer 依赖后缀：name@1.0.0(react@18.2.0)
+        let spec = spec.split('(').next().unwrap_or(spec);
+        if let Some((name, version)) = split_at_version(spec) {
+            deps.push(dependency(Ecosystem::Npm, name, Some(version.to_string()), "pnpm-lock.yaml"));
+        }
+    }
+    deps
+}
+
+/// yarn.lock：`"react@^18.2.0", react@^18:` 之后的 `version "18.2.0"`（v1）或 `version: 18.2.0`（berry）
+fn parse_yarn_lock(text: &str) -> Vec<Dependency> {
+    let mut deps = Vec::new();
+    let mut current: Option<String> = None;
+    for line in text.lines() {
+        if !line.starts_with(' ') && line.ends_with(':') && !line.starts_with('#') {
+            current = line
+                .trim_end_matches(':')
+                .split(',')
+                .next()
+                .map(|spec| spec.trim().trim_matches('"'))
+                .and_then(split_at_version)
+                .map(|(name, _)| name.to_string());
+            continue;
+        }
+        let trimmed = line.trim();
+        let version = trimmed
+            .strip_prefix("version ")
+            .or_else(|| trimmed.strip_prefix("version: "));
+        if let Some(version) = version {
+            if let Some(name) = current.take() {
+                let version = version.trim().trim_matches('"');
+                deps.push(dependency(Ecosystem::Npm, &name, Some(version.to_string()), "yarn.lock"));
+            }
+        }
+    }
+    deps
+}
+
+fn parse_package_json(text: &str) -> Vec<Dependency> {
+    let Ok(manifest) = serde_json::from_str::<serde_json::Value>(text) else {
+        return Vec::new();
+    };
+    ["dependencies", "devDependencies", "peerDependencies"]
+        .iter()
+        .filter_map(|key| manifest.get(*key).and_then(|d| d.as_object()))
+        .flatten()
+        .map(|(name, requirement)| {
+            let version = requirement.as_str().and_then(|r| exact_version(r, true));
+            dependency(Ecosystem::Npm, name, version, "package.json")
+        })
+        .collect()
+}
+
+/// PEP 508 依赖串：`fastapi[all]==0.110.0; python_version >= "3.9"`
+fn parse_requirement(line: &str) -> Option<(String, Option<String>)> {
+    let line = line.split(['#', ';']).next()?.trim();
+    let end = line
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(line.len());
+    let name = &line[..end];
+    if name.is_empty() {
+        return None;
+    }
+    let rest = line[end..].trim_start();
+    let rest = match rest.strip_prefix('[') {
+        Some(extras) => extras.split_once(']').map(|(_, r)| r.trim_start()).unwrap_or(""),
+        None => rest,
+    };
+    let version = rest
+        .strip_prefix("==")
+        .filter(|v| !v.contains(','))
+        .and_then(|v| exact_version(v, true));
+    Some((name.to_string(), version))
+}
+
+fn parse_pyproject(text: &str) -> Vec<Dependency> {
+    let Ok(manifest) = text.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let mut deps = Vec::new();
+    let project = manifest.get("project");
+    let optional = project
+        .and_then(|p| p.get("optional-dependencies"))
+        .and_then(|o| o.as_table())
+        .into_iter()
+        .flat_map(|groups| groups.values());
+    let lists = project.and_then(|p| p.get("dependencies")).into_iter().chain(optional);
+    for requirement in lists.filter_map(|l| l.as_array()).flatten().filter_map(|r| r.as_str()) {
+        if let Some((name, version)) = parse_requirement(requirement) {
+            deps.push(dependency(Ecosystem::Python, &name, version, "pyproject.toml"));
+        }
+    }
+
+    // Poetry：[tool.poetry.dependencies] name = "^1.0" / { version = "1.0.0" }，裸版本号即精确版本
+    let poetry = manifest
+        .get("tool")
+        .and_then(|t| t.get("poetry"))
+        .and_then(|p| p.get("dependencies"))
+        .and_then(|d| d.as_table());
+    for (name, value) in poetry.into_iter().flatten() {
+        if name == "python" {
+            continue;
+        }
+        let requirement = value.as_str().or_else(|| value.get("version").and_then(|v| v.as_str()));
+        deps.push(dependency(
+            Ecosystem::Python,
+            name,
+            requirement
+                .map(|r| r.trim().strip_prefix("==").unwrap_or(r))
+                .and_then(|r| exact_version(r, true)),
+            "pyproject.toml",
+        ));
+    }
+    deps
+}
+
+fn parse_requirements(text: &str) -> Vec<Dependency> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with(['#', '-']))
+        .filter_map(parse_requirement)
+        .map(|(name, version)| dependency(Ecosystem::Python, &name, version, "requirements.txt"))
+        .collect()
+}
+
+/// go.mod：`require x v1.2.3` 与 `require ( ... )` 块
+fn parse_go_mod(text: &str) -> Vec<Dependency> {
+    let mut deps = Vec::new();
+    let mut in_block = false;
+    for line in text.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        let entry = if in_block {
+            if line == ")" {
+                in_block = false;
+                continue;
+            }
+            line
+        } else if line == "require (" {
+            in_block = true;
+            continue;
+        } else if let Some(rest) = line.strip_prefix("require ") {
+            rest
+        } else {
+            continue;
+        };
+        let mut parts = entry.split_whitespace();
+        if let (Some(module), Some(version)) = (parts.next(), parts.next()) {
+            deps.push(dependency(Ecosystem::Go, module, Some(version.to_string()), "go.mod"));
+        }
+    }
+    deps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_dependencies() {
+        let root = std::env::temp_dir().join(format!("sanshu-context7-deps-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let write = |file: &str, content: &str| fs::write(root.join(file), content).unwrap();
+        write(
+            "Cargo.lock",
+            "version = 3\n\n[[package]]\nname = \"tokio\"\nversion = \"1.38.0\"\n\n[[package]]\nname = \"tokio\"\nversion = \"1.40.0\"\n",
+        );
+        write(
+            "Cargo.toml",
+            "[dependencies]\ntokio = \"1\"\nanyhow = \"1.0.86\"\nmy-serde = { package = \"serde\", version = \"=1.0.200\" }\n",
+        );
+        write(
+            "package-lock.json",
+            r#"{"lockfileVersion":3,"packages":{"":{},"node_modules/react":{"version":"18.3.1"},"node_modules/a/node_modules/react":{"version":"17.0.0"}}}"#,
+        );
+        write("pnpm-lock.yaml", "lockfileVersion: '9.0'\n\npackages:\n\n  '@babel/core@7.24.0':\n    resolution: {}\n\n  next@14.2.3(react@18.3.1):\n    resolution: {}\n");
+        write("yarn.lock", "\"vite@^5.0.0\", vite@^5.2:\n  resolved \"https://registry.yarnpkg.com/vite\"\n  version \"5.2.11\"\n");
+        write("package.json", r#"{"dependencies":{"vue":"^3.4.21","axios":"1.6.8","svelte":"=4.2.12","lodash":"latest"}}"#);
+        write("pyproject.toml", "[project]\ndependencies = [\"fastapi[all]==0.110.0; python_version >= '3.9'\", \"Pydantic_Core>=2\"]\n");
+        write("go.mod", "module example.com/app\n\nrequire github.com/gin-gonic/gin v1.9.1\n\nrequire (\n\tgithub.com/jackc/pgx/v5 v5.5.5 // indirect\n)\n");
+
+        let deps = read_dependencies(&root);
+        let version = |name: &str| find_dependency(&deps, name).and_then(|d| d.version.clone());
+        // 锁文件优先，多版本取最高
+        assert_eq!(version("tokio").as_deref(), Some("1.40.0"));
+        assert_eq!(find_dependency(&deps, "tokio").unwrap().source, "Cargo.lock");
+        assert_eq!(version("serde").as_deref(), Some("1.0.200"));
+        // 嵌套 node_modules 不覆盖顶层版本
+        assert_eq!(version("react").as_deref(), Some("18.3.1"));
+        assert_eq!(version("@babel/core").as_deref(), Some("7.24.0"));
+        assert_eq!(version("next").as_deref(), Some("14.2.3"));
+        assert_eq!(version("vite").as_deref(), Some("5.2.11"));
+        // 只取精确版本：Cargo 裸版本号与 npm ^ 范围都不算
+        assert!(version("anyhow").is_none());
+        assert!(version("vue").is_none());
+        assert_eq!(version("axios").as_deref(), Some("1.6.8"));
+        assert_eq!(version("svelte").as_deref(), Some("4.2.12"));
+        assert!(find_dependency(&deps, "lodash").unwrap().version.is_none());
+        assert_eq!(version("fastapi").as_deref(), Some("0.110.0"));
+        assert!(find_dependency(&deps, "pydantic-core").is_some());
+        // Go 模块可按末段匹配（跳过 /vN）
+        assert_eq!(version("gin").as_deref(), Some("v1.9.1"));
+        assert_eq!(version("pgx").as_deref(), Some("v5.5.5"));
+
+        // 锁文件中的间接依赖不算直接依赖
+        let direct = read_direct_dependencies(&root);
+        assert!(find_dependency(&direct, "tokio").is_some_and(|d| d.source == "Cargo.lock"));
+        assert!(find_dependency(&direct, "vue").is_some());
+        assert!(find_dependency(&direct, "react").is_none());
+        assert!(find_dependency(&direct, "vite").is_none());
+
+        let gin = find_dependency(&deps, "gin").unwrap();
+        let map = DiskCache::new(root.join("library_map"), MAX_LIBRARY_MAP_BYTES, LIBRARY_MAP_TTL_SECS);
+        assert_eq!(offline_library_id(gin, &map).as_deref(), Some("gin-gonic/gin"));
+        let tokio = find_dependency(&deps, "tokio").unwrap();
+        assert!(matches_library_id(tokio, "tokio-rs/tokio", &map));
+        let next = find_dependency(&deps, "next").unwrap();
+        assert!(matches_library_id(next, "vercel/next.js", &map));
+        assert!(is_library_id("tokio-rs/tokio"));
+        assert!(!is_library_id("github.com/gin-gonic/gin"));
+        assert!(!is_library_id("@babel/core"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_exact_version() {
+        assert_eq!(exact_version("=1.2.3", false).as_deref(), Some("1.2.3"));
+        assert!(exact_version("1.2.3", false).is_none());
+        assert_eq!(exact_version("1.2.3", true).as_deref(), Some("1.2.3"));
+        assert_eq!(exact_version("v2.0.0-beta.1", true).as_deref(), Some("2.0.0-beta.1"));
+        for range in ["^1.2.3", "~1.2.3", ">=1.2.3", "1.2.x", "1.2", "1.2.3 - 2.0.0", "1.2.3 || 2.0.0", "latest"] {
+            assert!(exact_version(range, true).is_none(), "{}", range);
+        }
+        // PEP 440：== 后的通配符不是精确版本
+        assert_eq!(parse_requirement("requests==2.31.0").unwrap().1.as_deref(), Some("2.31.0"));
+        assert!(parse_requirement("urllib3==2.2.*").unwrap().1.is_none());
+    }
+
+    #[test]
+    fn test_pick_library() {
+        let result = |id: &str, title: &str, description: &str| SearchResult {
+            id: id.to_string(),
+            title: Some(title.to_string()),
+            description: Some(description.to_string()),
+            stars: None,
+            trust_score: None,
+            benchmark_score: None,
+        };
+        let results = vec![
+            result("/acme/serde-extras", "Serde Extras", "Rust helpers"),
+            result("/someone/serde", "serde", "Serde for Go"),
+            result("/serde-rs/serde", "Serde", "Rust serialization framework"),
+        ];
+        // 包含关系不算匹配；同名时按生态关键词选择
+        assert_eq!(pick_library(&results, "serde", Ecosystem::Cargo).as_deref(), Some("serde-rs/serde"));
+        assert_eq!(pick_library(&results, "serde", Ecosystem::Go).as_deref(), Some("someone/serde"));
+        assert_eq!(pick_library(&results, "serde", Ecosystem::Python).as_deref(), Some("someone/serde"));
+        assert!(pick_library(&results, "extras", Ecosystem::Cargo).is_none());
+        assert_eq!(
+            pick_library(&[result("/vercel/next.js", "Next.js", "")], "next", Ecosystem::Npm).as_deref(),
+            Some("vercel/next.js")
+        );
+        assert!(pick_library(&[result("/vercel/next.js", "Next.js", "")], "next", Ecosystem::Python).is_none());
+    }
+
+    #[test]
+    fn test_library_map_entries_expire() {
+        let dir = std::env::temp_dir().join(format!("sanshu-context7-library-map-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let map = DiskCache::new(dir.clone(), MAX_LIBRARY_MAP_BYTES, LIBRARY_MAP_TTL_SECS);
+        let dep = dependency(Ecosystem::Npm, "left-pad", None, "package.json");
+        map.put(LIBRARY_NAMESPACE, &map_key(&dep), &Some("stevemao/left-pad")).unwrap();
+        assert_eq!(offline_library_id(&dep, &map).as_deref(), Some("stevemao/left-pad"));
+
+        map.set_ttl_secs(0);
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        assert!(offline_library_id(&dep, &map).is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_from_lockfile() {
+        let root = std::env::temp_dir().join(format!("sanshu-context7-resolve-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("Cargo.lock"), "[[package]]\nname = \"tokio\"\nversion = \"1.40.0\"\n").unwrap();
+
+        let request = Context7Request {
+            library: "tokio".to_string(),
+            topic: None,
+            version: None,
+            page: None,
+            project_root_path: Some(root.to_string_lossy().to_string()),
+        };
+        let resolution = resolve(&Context7Config::default(), &request).await;
+        assert_eq!(resolution.request.library, "tokio-rs/tokio");
+        assert_eq!(resolution.request.version.as_deref(), Some("1.40.0"));
+        assert!(resolution.version_inferred);
+        assert_eq!(
+            resolution.note(&request).as_deref(),
+            Some("🧭 根据 Cargo.lock 解析: tokio → tokio-rs/tokio@1.40.0")
+        );
+
+        // 显式版本不被覆盖
+        let pinned = Context7Request {
+            version: Some("1.0.0".to_string()),
+            ..request.clone()
+        };
+        let resolution = resolve(&Context7Config::default(), &pinned).await;
+        assert_eq!(resolution.request.version.as_deref(), Some("1.0.0"));
+        assert!(!resolution.version_inferred);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,356 @@
+// Context7 离线文档快照
+// 将固定版本库的全部页面预取到项目（<project>/.sanshu/context7）或用户目录（~/.sanshu/context7）
+// 查询时先于缓存与网络命中快照，离线可用；项目快照可随仓库提交，团队共享同一版本的文档
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::cache::{normalize_library, DocKey, DOC_CACHE};
+use super::mcp::{Context7Tool, RawDocs};
+use super::types::{Context7Config, Context7Request};
+use crate::log_important;
+
+const SNAPSHOT_DIR: &str = "context7";
+const MANIFEST_FILE: &str = "snapshot.json";
+/// 未指定版本时的目录名
+const LATEST_DIR: &str = "latest";
+/// 未指定主题时的目录名
+const DEFAULT_TOPIC_DIR: &str = "_default";
+/// Context7 单次查询最多 10 页
+pub const MAX_PAGES: u32 = 10;
+
+/// 快照保存位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotScope {
+    Project,
+    User,
+}
+
+impl SnapshotScope {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Project => "项目",
+            Self::User => "用户",
+        }
+    }
+}
+
+/// 快照清单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub library: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    /// 预取的主题（不含默认主题）
+    #[serde(default)]
+    pub topics: Vec<String>,
+    /// 已保存的页数（主题 -> 页数，默认主题记为空字符串）
+    #[serde(default)]
+    pub pages: std::collections::BTreeMap<String, u32>,
+    pub created_at: String,
+}
+
+/// 已保存的快照
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotInfo {
+    pub scope: SnapshotScope,
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub manifest: SnapshotManifest,
+}
+
+/// 快照命中结果
+pub struct SnapshotHit {
+    pub content: String,
+    pub scope: SnapshotScope,
+    pub version: Option<String>,
+}
+
+/// 快照创建参数
+#[derive(Debug, Clone)]
+pub struct SnapshotOptions {
+    pub library: String,
+    pub version: Option<String>,
+    pub topics: Vec<String>,
+    pub max_pages: u32,
+    pub scope: SnapshotScope,
+    pub project_root: Option<PathBuf>,
+}
+
+/// 快照根目录：项目在前，用户在后
+pub fn snapshot_roots(project_root: Option<&Path>) -> Vec<(SnapshotScope, PathBuf)> {
+    let mut roots = Vec::new();
+    if let Some(root) = project_root {
+        roots.push((SnapshotScope::Project, root.join(".sanshu").join(SNAPSHOT_DIR)));
+    }
+    if let Some(home) = dirs::home_dir() {
+        roots.push((SnapshotScope::User, home.join(".sanshu").join(SNAPSHOT_DIR)));
+    }
+    roots
+}
+
+/// 路径片段只保留安全字符，避免库名或版本中的 `..`、分隔符逃逸出快照目录
+fn sanitize_segment(segment: &str) -> String {
+    let cleaned: String = segment
+        .trim()
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.' | '@') { ch } else { '_' })
+        .collect();
+    if cleaned.is_empty() || cleaned.chars().all(|ch| ch == '.') {
+        "_".to_string()
+    } else {
+        cleaned
+    }
+}
+
+fn library_dir(root: &Path, library: &str) -> PathBuf {
+    normalize_library(library)
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .fold(root.to_path_buf(), |dir, segment| dir.join(sanitize_segment(segment)))
+}
+
+fn snapshot_dir(root: &Path, library: &str, version: Option<&str>) -> PathBuf {
+    library_dir(root, library).join(version.map(sanitize_segment).unwrap_or_else(|| LATEST_DIR.to_string()))
+}
+
+fn page_path(dir: &Path, topic: Option<&str>, page: u32) -> PathBuf {
+    let topic_dir = topic
+        .map(|t| sanitize_segment(&t.to_lowercase()))
+        .unwrap_or_else(|| DEFAULT_TOPIC_DIR.to_string());
+    dir.join(topic_dir).join(format!("page-{}.md", page))
+}
+
+fn read_manifest(dir: &Path) -> Option<SnapshotManifest> {
+    let text = fs::read_to_string(dir.join(MANIFEST_FILE)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+/// 查找快照中的页面；请求未指定版本时使用该库最新创建的快照（项目快照优先）
+pub fn lookup(project_root: Option<&Path>, key: &DocKey) -> Option<SnapshotHit> {
+    for (scope, root) in snapshot_roots(project_root) {
+        let candidates: Vec<(PathBuf, SnapshotManifest)> = match &key.version {
+            Some(version) => {
+                let dir = snapshot_dir(&root, &key.library, Some(version));
+                read_manifest(&dir).map(|m| (dir, m)).into_iter().collect()
+            }
+            None => {
+                let mut found: Vec<(PathBuf, SnapshotManifest)> = fs::read_dir(library_dir(&root, &key.library))
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter_map(|dir| read_manifest(&dir).map(|m| (dir, m)))
+                    .collect();
+                found.sort_by(|a, b| b.1.created_at.cmp(&a.1.created_at));
+                found
+            }
+        };
+
+        for (dir, manifest) in candidates {
+            if normalize_library(&manifest.library) != key.library {
+                continue;
+            }
+            if let Ok(content) = fs::read_to_string(page_path(&dir, key.topic.as_deref(), key.page)) {
+                return Some(SnapshotHit {
+                    content,
+                    scope,
+                    version: manifest.version,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// 预取库的全部页面并保存为快照（同时写入文档缓存）
+pub async fn create_snapshot(config: &Context7Config, options: &SnapshotOptions) -> Result<SnapshotInfo, String> {
+    let library = normalize_library(&options.library);
+    if library.is_empty() {
+        return Err("缺少库标识符".to_string());
+    }
+    let version = options
+        .version
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string);
+    let root = match options.scope {
+        SnapshotScope::Project => {
+            let project_root = options.project_root.as_deref().ok_or("保存到项目时需要指定项目目录")?;
+            if !project_root.is_dir() {
+                return Err(format!("项目目录不存在: {}", project_root.display()));
+            }
+            project_root.join(".sanshu").join(SNAPSHOT_DIR)
+        }
+        SnapshotScope::User => snapshot_roots(None)
+            .into_iter()
+            .next()
+            .map(|(_, root)| root)
+            .ok_or("无法确定用户目录")?,
+    };
+    let dir = snapshot_dir(&root, &library, version.as_deref());
+    let staging = dir.with_extension(format!("partial{}", std::process::id()));
+    let _ = fs::remove_dir_all(&staging);
+
+    let result = fetch_pages(config, options, &library, version.as_deref(), &staging).await;
+    let pages = match result {
+        Ok(pages) => pages,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+    };
+
+    let manifest = SnapshotManifest {
+        library: library.clone(),
+        version: version.clone(),
+        topics: options.topics.clone(),
+        pages,
+        created_at: chrono::Local::now().to_rfc3339(),
+    };
+    let text = serde_json::to_string_pretty(&manifest).map_err(|e| format!("序列化快照清单失败: {}", e))?;
+    fs::write(staging.join(MANIFEST_FILE), text).map_err(|e| format!("写入快照清单失败: {}", e))?;
+
+    if dir.exists() {
+        fs::remove_dir_all(&dir).map_err(|e| format!("移除旧快照失败: {}", e))?;
+    }
+    fs::rename(&staging, &dir).map_err(|e| format!("保存快照失败: {}", e))?;
+    log_important!(
+        info,
+        "Context7 快照已保存: library={}, version={:?}, path={}",
+        library,
+        version,
+        dir.display()
+    );
+
+    Ok(SnapshotInfo {
+        scope: options.scope,
+        path: dir,
+        manifest,
+    })
+}
+
+/// 逐个主题翻页，直到空页、重复页或达到页数上限
+async fn fetch_pages(
+    config: &Context7Config,
+    options: &SnapshotOptions,
+    library: &str,
+    version: Option<&str>,
+    staging: &Path,
+) -> Result<std::collections::BTreeMap<String, u32>, String> {
+    let mut pages = std::collections::BTreeMap::new();
+    let max_pages = options.max_pages.clamp(1, MAX_PAGES);
+    let topics = std::iter::once(None).chain(options.topics.iter().map(|t| Some(t.as_str())));
+
+    for topic in topics {
+        let mut previous = String::new();
+        let mut saved = 0;
+        for page in 1..=max_pages {
+            let request = Context7Request {
+                library: library.to_string(),
+                topic: topic.map(str::to_string),
+                version: version.map(str::to_string),
+                page: Some(page),
+                project_root_path: None,
+            };
+            let text = match Context7Tool::fetch_raw(config, &request).await {
+                Ok(RawDocs::Found(text)) if !text.trim().is_empty() && text != previous => text,
+                Ok(RawDocs::NotFound) if page == 1 && topic.is_none() => {
+                    return Err(format!("库不存在: {}", library));
+                }
+                Err(e) if page == 1 && topic.is_none() => return Err(e.to_string()),
+                // 后续页失败或无内容视为已到末页
+                _ => break,
+            };
+
+            let path = page_path(staging, topic, page);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("创建快照目录失败: {}", e))?;
+            }
+            fs::write(&path, &text).map_err(|e| format!("写入快照页面失败: {}", e))?;
+            let _ = DOC_CACHE.put(&DocKey::from_request(&request), &text);
+            previous = text;
+            saved = page;
+        }
+        pages.insert(topic.unwrap_or_default().to_string(), saved);
+    }
+    Ok(pages)
+}
+
+/// 列出项目与用户目录下的快照
+pub fn list_snapshots(project_root: Option<&Path>) -> Vec<SnapshotInfo> {
+    let mut snapshots = Vec::new();
+    for (scope, root) in snapshot_roots(project_root) {
+        let manifests = ignore::WalkBuilder::new(&root)
+            .standard_filters(false)
+            .max_depth(Some(6))
+            .build()
+            .flatten()
+            .filter(|entry| entry.file_name() == MANIFEST_FILE);
+        for entry in manifests {
+            let Some(dir) = entry.path().parent() else {
+                continue;
+            };
+            if let Some(manifest) = read_manifest(dir) {
+                snapshots.push(SnapshotInfo {
+                    scope,
+                    path: dir.to_path_buf(),
+                    manifest,
+                });
+            }
+        }
+    }
+    snapshots.sort_by(|a, b| (a.scope as u8, &a.manifest.library).cmp(&(b.scope as u8, &b.manifest.library)));
+    snapshots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_lookup() {
+        let project = std::env::temp_dir().join(format!("sanshu-context7-snapshot-{}", std::process::id()));
+        let root = project.join(".sanshu").join(SNAPSHOT_DIR);
+        let dir = snapshot_dir(&root, "/Tokio-RS/tokio", Some("1.40.0"));
+        assert_eq!(dir, root.join("tokio-rs").join("tokio").join("1.40.0"));
+        // 路径穿越片段被替换
+        assert_eq!(library_dir(&root, "../etc"), root.join("_").join("etc"));
+
+        fs::create_dir_all(&dir).unwrap();
+        let manifest = SnapshotManifest {
+            library: "tokio-rs/tokio".to_string(),
+            version: Some("1.40.0".to_string()),
+            topics: vec!["runtime".to_string()],
+            pages: Default::default(),
+            created_at: "2026-01-01T00:00:00+00:00".to_string(),
+        };
+        fs::write(dir.join(MANIFEST_FILE), serde_json::to_string(&manifest).unwrap()).unwrap();
+        let page = page_path(&dir, Some("Runtime"), 2);
+        fs::create_dir_all(page.parent().unwrap()).unwrap();
+        fs::write(&page, "# runtime p2").unwrap();
+
+        let key = |version: Option<&str>, topic: Option<&str>, page: u32| DocKey {
+            library: "tokio-rs/tokio".to_string(),
+            version: version.map(str::to_string),
+            topic: topic.map(str::to_string),
+            page,
+        };
+        // 未指定版本时使用已有快照的版本
+        let hit = lookup(Some(&project), &key(None, Some("runtime"), 2)).unwrap();
+        assert_eq!(hit.content, "# runtime p2");
+        assert_eq!(hit.scope, SnapshotScope::Project);
+        assert_eq!(hit.version.as_deref(), Some("1.40.0"));
+        assert!(lookup(Some(&project), &key(Some("1.40.0"), Some("runtime"), 2)).is_some());
+        assert!(lookup(Some(&project), &key(Some("1.41.0"), Some("runtime"), 2)).is_none());
+        assert!(lookup(Some(&project), &key(None, Some("runtime"), 3)).is_none());
+
+        let listed = list_snapshots(Some(&project));
+        assert!(listed.iter().any(|s| s.scope == SnapshotScope::Project && s.path == dir));
+
+        let _ = fs::remove_dir_all(&project);
+    }
+}
@@ -3,8 +3,8 @@ use serde::{Deserialize, Serialize};
 /// Context7 查询请求参数
 #[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct Context7Request {
-    /// 库标识符，格式: owner/repo (例如: "vercel/next.js", "facebook/react")
-    #[schemars(description = "库标识符，格式: owner/repo (例如: vercel/next.js, facebook/react)")]
+    /// 库标识符，格式: owner/repo (例如: "vercel/next.js", "facebook/react")，也可为项目依赖的包名
+    #[schemars(description = "库标识符，格式: owner/repo (例如: vercel/next.js, facebook/react)；也可为项目依赖的包名 (例如: tokio)")]
     pub library: String,
     /// 查询主题 (可选，例如: "routing", "authentication")
     #[schemars(description = "查询主题 (可选，例如: routing, authentication)")]
@@ -18,6 +18,10 @@ pub struct Context7Request {
     #[schemars(description = "分页页码 (可选，默认1，最大10)")]
     #[serde(default)]
     pub page: Option<u32>,
+    /// 项目根目录 (可选，用于解析项目依赖版本与查找离线文档快照)
+    #[schemars(description = "项目根目录 (可选，用于解析项目依赖版本与查找离线文档快照，默认使用客户端工作区)")]
+    #[serde(default)]
+    pub project_root_path: Option<String>,
 }
 
 /// Context7 配置
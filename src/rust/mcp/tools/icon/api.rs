@@ -2,14 +2,14 @@
 // 负责与 iconfont.cn 的 API 进行通信
 
 use anyhow::{anyhow, Result};
-use once_cell::sync::Lazy;
 use reqwest::Client;
 use std::collections::HashMap;
-use std::sync::RwLock;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
+use super::cache::{ICON_CACHE, SEARCH_NAMESPACE, SVG_NAMESPACE};
+use super::local;
 use super::types::{
-    IconItem, IconSearchRequest, IconSearchResult,
+    IconConfig, IconItem, IconProvider, IconSearchRequest, IconSearchResult,
     IconfontApiResponse, IconfontIcon,
 };
 use crate::log_debug;
@@ -19,33 +19,12 @@ use crate::log_debug;
 /// Iconfont 搜索 API 端点
 const ICONFONT_SEARCH_API: &str = "https://www.iconfont.cn/api/icon/search.json";
 
-/// 默认缓存过期时间（30分钟）
-const DEFAULT_CACHE_EXPIRY_SECS: u64 = 30 * 60;
-
 /// HTTP 请求超时时间
 const REQUEST_TIMEOUT_SECS: u64 = 30;
 
 /// 最大重试次数
 const MAX_RETRIES: usize = 3;
 
-// ============ 缓存结构 ============
-
-/// 缓存条目
-struct CacheEntry {
-    /// 缓存的搜索结果
-    result: IconSearchResult,
-    /// 缓存创建时间
-    created_at: Instant,
-}
-
-/// 全局搜索结果缓存
-static SEARCH_CACHE: Lazy<RwLock<HashMap<String, CacheEntry>>> =
-    Lazy::new(|| RwLock::new(HashMap::new()));
-
-/// 缓存过期时间配置（秒）
-static CACHE_EXPIRY_SECS: Lazy<RwLock<u64>> =
-    Lazy::new(|| RwLock::new(DEFAULT_CACHE_EXPIRY_SECS));
-
 // ============ HTTP 客户端 ============
 
 /// 创建带有默认配置的 HTTP 客户端
@@ -79,94 +58,84 @@ fn generate_cache_key(request: &IconSearchRequest) -> String {
 
 /// 从缓存获取结果
 fn get_from_cache(key: &str) -> Option<IconSearchResult> {
-    let cache = SEARCH_CACHE.read().ok()?;
-    let entry = cache.get(key)?;
-    
-    // 检查是否过期
-    let expiry = CACHE_EXPIRY_SECS.read().ok().map(|e| *e).unwrap_or(DEFAULT_CACHE_EXPIRY_SECS);
-    if entry.created_at.elapsed() > Duration::from_secs(expiry) {
-        return None;
-    }
-    
-    Some(entry.result.clone())
+    ICON_CACHE.get(SEARCH_NAMESPACE, key)
 }
 
-/// 存入缓存
+/// 存入缓存（搜索结果中的 SVG 同时按图标 ID 缓存，供保存时取用）
 fn put_to_cache(key: String, result: IconSearchResult) {
-    if let Ok(mut cache) = SEARCH_CACHE.write() {
-        cache.insert(key, CacheEntry {
-            result,
-            created_at: Instant::now(),
-        });
+    if let Err(e) = ICON_CACHE.put(SEARCH_NAMESPACE, &key, &result) {
+        log_debug!("写入图标搜索缓存失败: {}", e);
+    }
+    for icon in &result.icons {
+        if let Some(svg) = icon.svg_content.as_ref().filter(|svg| !svg.is_empty()) {
+            cache_svg(icon.id, svg);
+        }
+    }
+}
+
+/// 按图标 ID 缓存 SVG
+fn cache_svg(id: u64, svg: &str) {
+    if let Err(e) = ICON_CACHE.put(SVG_NAMESPACE, &id.to_string(), &svg) {
+        log_debug!("写入图标 SVG 缓存失败: {}", e);
     }
 }
 
 /// 获取缓存统计
 pub fn get_cache_stats() -> super::types::IconCacheStats {
-    let cache = SEARCH_CACHE.read().ok();
-    let expiry = CACHE_EXPIRY_SECS.read().ok().map(|e| *e).unwrap_or(DEFAULT_CACHE_EXPIRY_SECS);
-    
-    match cache {
-        Some(cache) => {
-            let total = cache.len();
-            let valid = cache.values()
-                .filter(|e| e.created_at.elapsed() < Duration::from_secs(expiry))
-                .count();
-            
-            super::types::IconCacheStats {
-                total_entries: total,
-                valid_entries: valid,
-                expired_entries: total.saturating_sub(valid),
-                cache_expiry_minutes: expiry / 60,
-                memory_usage_bytes: None,
-            }
-        }
-        None => super::types::IconCacheStats {
-            total_entries: 0,
-            valid_entries: 0,
-            expired_entries: 0,
-            cache_expiry_minutes: expiry / 60,
-            memory_usage_bytes: None,
-        }
+    let stats = ICON_CACHE.stats();
+    super::types::IconCacheStats {
+        total_entries: stats.total_entries,
+        valid_entries: stats.total_entries.saturating_sub(stats.expired_entries),
+        expired_entries: stats.expired_entries,
+        cache_expiry_minutes: ICON_CACHE.ttl_secs() / 60,
+        memory_usage_bytes: None,
+        disk_usage_bytes: Some(stats.total_bytes),
     }
 }
 
 /// 清空缓存
 pub fn clear_cache(expired_only: bool) -> super::types::ClearCacheResult {
-    let mut cleared = 0;
-    let mut remaining = 0;
-    
-    if let Ok(mut cache) = SEARCH_CACHE.write() {
-        if expired_only {
-            let expiry = CACHE_EXPIRY_SECS.read().ok().map(|e| *e).unwrap_or(DEFAULT_CACHE_EXPIRY_SECS);
-            let original_len = cache.len();
-            cache.retain(|_, entry| {
-                entry.created_at.elapsed() < Duration::from_secs(expiry)
-            });
-            remaining = cache.len();
-            cleared = original_len.saturating_sub(remaining);
-        } else {
-            cleared = cache.len();
-            cache.clear();
-            remaining = 0;
-        }
+    let (cleared, remaining) = ICON_CACHE.clear(expired_only);
+    if !expired_only {
+        // 本地图标库索引一并重建
+        local::clear_index();
     }
-    
+
     super::types::ClearCacheResult {
         cleared_count: cleared,
         remaining_count: remaining,
     }
 }
 
+/// 从缓存中查找图标的 SVG 内容（无界面保存时调用方通常不回传 SVG）
+pub fn cached_svg(id: u64) -> Option<String> {
+    ICON_CACHE
+        .get::<String>(SVG_NAMESPACE, &id.to_string())
+        .filter(|svg| !svg.is_empty())
+}
+
 /// 设置缓存过期时间
 pub fn set_cache_expiry_minutes(minutes: u64) {
-    if let Ok(mut expiry) = CACHE_EXPIRY_SECS.write() {
-        *expiry = minutes * 60;
-    }
+    ICON_CACHE.set_ttl_secs(minutes.saturating_mul(60));
 }
 
 // ============ API 调用 ============
 
+/// 按来源搜索图标
+///
+/// 请求未指定来源时使用配置中的来源；本地来源在阻塞线程中索引与搜索
+pub async fn search_with_config(request: IconSearchRequest, config: &IconConfig) -> Result<IconSearchResult> {
+    match request.provider.unwrap_or(config.provider) {
+        IconProvider::Iconfont => search_icons(request).await,
+        IconProvider::Local => {
+            let sources = config.local_sources.clone();
+            tokio::task::spawn_blocking(move || local::search_icons(&sources, &request))
+                .await
+                .map_err(|e| anyhow!("本地图标搜索任务失败: {}", e))?
+        }
+    }
+}
+
 /// 搜索图标
 /// 
 /// 调用 Iconfont API 搜索图标，支持缓存
@@ -313,13 +282,25 @@ fn parse_search_response(
 /// 获取图标 SVG 内容
 /// 
 /// 根据图标 ID 获取 SVG 内容（如果搜索结果中已包含则直接返回）
-pub async fn get_icon_svg(id: u64, cached_svg: Option<String>) -> Result<String> {
-    // 如果已有缓存的 SVG 内容，直接返回
-    if let Some(svg) = cached_svg {
+pub async fn get_icon_svg(id: u64, known_svg: Option<String>) -> Result<String> {
+    // 如果调用方已有 SVG 内容，直接返回
+    if let Some(svg) = known_svg {
         if !svg.is_empty() {
             return Ok(svg);
         }
     }
+
+    // 本地图标直接从索引读取，不走网络
+    if local::is_local_id(id) {
+        return tokio::task::spawn_blocking(move || local::lookup_svg(id))
+            .await
+            .map_err(|e| anyhow!("读取本地图标任务失败: {}", e))?;
+    }
+
+    // 磁盘缓存（此前的搜索结果或详情请求）
+    if let Some(svg) = cached_svg(id) {
+        return Ok(svg);
+    }
     
     // 否则需要单独请求（Iconfont 的图标详情 API）
     // 注意：Iconfont 的搜索结果通常已包含 show_svg 字段，
@@ -361,10 +342,12 @@ pub async fn get_icon_svg(id: u64, cached_svg: Option<String>) -> Result<String>
         return Err(anyhow!("获取图标详情失败: API 返回错误"));
     }
     
-    detail.data
+    let svg = detail.data
         .and_then(|d| d.icon)
         .and_then(|i| i.show_svg)
-        .ok_or_else(|| anyhow!("图标 {} 没有 SVG 内容", id))
+        .ok_or_else(|| anyhow!("图标 {} 没有 SVG 内容", id))?;
+    cache_svg(id, &svg);
+    Ok(svg)
 }
 
 // ============ 辅助函数 ============
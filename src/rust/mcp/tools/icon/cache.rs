@@ -0,0 +1,34 @@
+// 图标磁盘缓存
+// 搜索结果与单个 SVG（按图标 ID）持久化到磁盘，跨进程复用；按最近访问时间 LRU 淘汰
+
+use once_cell::sync::Lazy;
+
+use crate::utils::disk_cache::DiskCache;
+
+// ============ 常量定义 ============
+
+/// 默认缓存过期时间（30分钟）
+pub const DEFAULT_CACHE_EXPIRY_SECS: u64 = 30 * 60;
+
+/// 缓存目录占用上限（超出后按 LRU 淘汰）
+const MAX_CACHE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// 搜索结果命名空间
+pub const SEARCH_NAMESPACE: &str = "search";
+
+/// 单个图标 SVG 命名空间
+pub const SVG_NAMESPACE: &str = "svg";
+
+/// 全局图标缓存，过期时间取自配置 `icon_cache_expiry_minutes`
+pub static ICON_CACHE: Lazy<DiskCache> = Lazy::new(|| {
+    let dir = dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("sanshu")
+        .join("icon_cache");
+    let expiry_secs = crate::config::load_standalone_config()
+        .ok()
+        .and_then(|c| c.mcp_config.icon_cache_expiry_minutes)
+        .map(|minutes| minutes.saturating_mul(60))
+        .unwrap_or(DEFAULT_CACHE_EXPIRY_SECS);
+    DiskCache::new(dir, MAX_CACHE_BYTES, expiry_secs)
+});
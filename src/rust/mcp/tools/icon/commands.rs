@@ -7,9 +7,12 @@ use std::path::PathBuf;
 use usvg::TreeParsing;
 
 use super::api;
+use super::export::{self, ProjectIconConfig};
+use super::font;
+use super::optimize::{self, SvgOptimizeOptions, SvgOptimizeReport};
 use super::types::{
     ClearCacheRequest, ClearCacheResult, IconCacheStats, IconConfig,
-    IconContentRequest, IconContentResult, IconFormat, IconItem,
+    IconContentRequest, IconContentResult, IconFormat, IconItem, IconProvider,
     IconSaveItem, IconSaveRequest, IconSaveResult, IconSearchRequest,
     IconSearchResult,
 };
@@ -20,12 +23,20 @@ use crate::log_important;
 
 /// 搜索图标
 /// 
-/// 根据关键词和筛选条件搜索 Iconfont 图标库
+/// 根据关键词和筛选条件搜索 Iconfont 或本地图标库（按配置的来源）
 #[tauri::command]
-pub async fn search_icons(request: IconSearchRequest) -> Result<IconSearchResult, String> {
+pub async fn search_icons(
+    state: tauri::State<'_, crate::config::AppState>,
+    request: IconSearchRequest,
+) -> Result<IconSearchResult, String> {
     log_debug!("搜索图标: query={}, page={:?}", request.query, request.page);
-    
-    api::search_icons(request)
+
+    let config = {
+        let app_config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        icon_config_from_mcp(&app_config.mcp_config)
+    };
+
+    api::search_with_config(request, &config)
         .await
         .map_err(|e| {
             log_important!(error, "图标搜索失败: {}", e);
@@ -48,23 +59,23 @@ pub async fn get_icon_content(request: IconContentRequest) -> Result<IconContent
         .map_err(|e| format!("获取图标内容失败: {}", e))?;
     
     match request.format {
-        IconFormat::Svg | IconFormat::Both => {
+        IconFormat::Png => {
+            // PNG 格式需要服务端转换，暂返回 SVG
+            log_debug!("PNG 格式暂不支持，返回 SVG");
             Ok(IconContentResult {
                 id: request.id,
                 name: format!("icon_{}", request.id),
                 svg_content: Some(svg_content),
-                png_base64: None, // PNG 转换暂未实现
+                png_base64: None,
                 mime_type: "image/svg+xml".to_string(),
             })
         }
-        IconFormat::Png => {
-            // PNG 格式需要服务端转换，暂返回 SVG
-            log_debug!("PNG 格式暂不支持，返回 SVG");
+        _ => {
             Ok(IconContentResult {
                 id: request.id,
                 name: format!("icon_{}", request.id),
                 svg_content: Some(svg_content),
-                png_base64: None,
+                png_base64: None, // PNG 转换暂未实现
                 mime_type: "image/svg+xml".to_string(),
             })
         }
@@ -95,16 +106,28 @@ pub async fn save_icons(request: IconSaveRequest) -> Result<IconSaveResult, Stri
     let mut items = Vec::new();
     let mut success_count = 0;
     let mut failed_count = 0;
+    let mut bytes_saved = 0u64;
+    // 合并进汇总文件的图标（名称, SVG），用于雪碧图与图标字体
+    let mut bundle_icons = Vec::new();
     
+    let overwrite = request.overwrite.unwrap_or(true);
+    let optimize_options = request.optimize.clone().unwrap_or_default();
     for icon in &request.icons {
-        match save_single_icon(icon, &save_dir, &request.format, request.png_size).await {
-            Ok(saved_paths) => {
+        match save_single_icon(icon, &save_dir, &request.format, request.png_size, overwrite, &optimize_options).await {
+            Ok(saved) => {
+                if matches!(request.format, IconFormat::Sprite | IconFormat::Font) {
+                    bundle_icons.push((export::sanitize_filename(&icon.name), saved.svg));
+                }
+                if let Some(report) = &saved.report {
+                    bytes_saved += report.saved_bytes() as u64;
+                }
                 items.push(IconSaveItem {
                     id: icon.id,
                     name: icon.name.clone(),
                     success: true,
-                    saved_paths,
+                    saved_paths: saved.paths,
                     error: None,
+                    optimize_report: saved.report,
                 });
                 success_count += 1;
             }
@@ -116,12 +139,21 @@ pub async fn save_icons(request: IconSaveRequest) -> Result<IconSaveResult, Stri
                     success: false,
                     saved_paths: vec![],
                     error: Some(e),
+                    optimize_report: None,
                 });
                 failed_count += 1;
             }
         }
     }
     
+    let bundle_files = write_bundle_files(&save_dir, &request.format, &bundle_icons)?;
+    if matches!(request.format, IconFormat::Sprite | IconFormat::Font) {
+        // 雪碧图与图标字体模式下各图标的产物即汇总文件本身
+        for item in items.iter_mut().filter(|item| item.success) {
+            item.saved_paths = bundle_files.clone();
+        }
+    }
+
     log_debug!("图标保存完成: 成功 {}, 失败 {}", success_count, failed_count);
     
     Ok(IconSaveResult {
@@ -129,17 +161,106 @@ pub async fn save_icons(request: IconSaveRequest) -> Result<IconSaveResult, Stri
         success_count,
         failed_count,
         save_path: request.save_path,
+        bundle_files,
+        bytes_saved,
     })
 }
 
+/// 列出保存时会被覆盖的已有文件，供界面保存前询问是否覆盖
+#[tauri::command]
+pub async fn find_existing_icons(request: IconSaveRequest) -> Result<Vec<String>, String> {
+    Ok(find_existing_icon_files(&request)
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect())
+}
+
+/// 写入汇总文件：雪碧图、图标字体（均与已有文件合并）或组件索引文件
+fn write_bundle_files(
+    save_dir: &PathBuf,
+    format: &IconFormat,
+    bundle_icons: &[(String, String)],
+) -> Result<Vec<String>, String> {
+    match format {
+        IconFormat::Sprite if !bundle_icons.is_empty() => {
+            let sprite_path = save_dir.join(export::SPRITE_FILE_NAME);
+            let existing = fs::read_to_string(&sprite_path).ok();
+            let sprite = export::build_sprite(existing.as_deref(), bundle_icons);
+            fs::write(&sprite_path, sprite).map_err(|e| format!("写入雪碧图失败: {}", e))?;
+            Ok(vec![sprite_path.to_string_lossy().to_string()])
+        }
+        IconFormat::Font if !bundle_icons.is_empty() => {
+            // 清单记录已分配的码位，再次保存时保持原有图标码位不变
+            let manifest_path = save_dir.join(font::FONT_MANIFEST_FILE_NAME);
+            let existing = fs::read_to_string(&manifest_path).ok();
+            let icon_font = font::build_icon_font(existing.as_deref(), bundle_icons)
+                .map_err(|e| format!("生成图标字体失败: {}", e))?;
+            let files = [
+                (save_dir.join(font::FONT_FILE_NAME), icon_font.ttf),
+                (save_dir.join(font::FONT_CSS_FILE_NAME), icon_font.css.into_bytes()),
+                (manifest_path, icon_font.manifest.into_bytes()),
+            ];
+            let mut paths = Vec::new();
+            for (path, data) in files {
+                fs::write(&path, data).map_err(|e| format!("写入图标字体失败: {}", e))?;
+                paths.push(path.to_string_lossy().to_string());
+            }
+            Ok(paths)
+        }
+        IconFormat::React | IconFormat::Vue | IconFormat::Svelte => {
+            let barrel = export::write_barrel(save_dir, format)
+                .map_err(|e| format!("写入索引文件失败: {}", e))?;
+            Ok(barrel.map(|p| p.to_string_lossy().to_string()).into_iter().collect())
+        }
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// 列出保存时会被覆盖的已有文件
+pub fn find_existing_icon_files(request: &IconSaveRequest) -> Vec<PathBuf> {
+    let save_dir = PathBuf::from(&request.save_path);
+    request
+        .icons
+        .iter()
+        .flat_map(|icon| icon_target_paths(icon, &save_dir, &request.format))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// 计算单个图标的目标文件路径
+fn icon_target_paths(icon: &IconItem, save_dir: &PathBuf, format: &IconFormat) -> Vec<PathBuf> {
+    export::icon_file_names(icon, format)
+        .into_iter()
+        .map(|name| save_dir.join(name))
+        .collect()
+}
+
+/// 单个图标的保存结果
+struct SavedIcon {
+    /// 写入的文件
+    paths: Vec<String>,
+    /// 处理后的 SVG（用于雪碧图）
+    svg: String,
+    report: Option<SvgOptimizeReport>,
+}
+
 /// 保存单个图标
 async fn save_single_icon(
     icon: &IconItem,
     save_dir: &PathBuf,
     format: &IconFormat,
     png_size: Option<u32>,
-) -> Result<Vec<String>, String> {
+    overwrite: bool,
+    optimize_options: &SvgOptimizeOptions,
+) -> Result<SavedIcon, String> {
     let mut saved_paths = Vec::new();
+
+    // 不允许覆盖时跳过已存在的图标
+    if !overwrite {
+        if let Some(existing) = icon_target_paths(icon, save_dir, format).iter().find(|p| p.exists()) {
+            return Err(format!("文件已存在，已跳过: {}", existing.display()));
+        }
+    }
     
     // 获取 SVG 内容
     let svg_content = api::get_icon_svg(icon.id, icon.svg_content.clone())
@@ -147,34 +268,91 @@ async fn save_single_icon(
         .map_err(|e| format!("获取 SVG 失败: {}", e))?;
     
     // 生成安全的文件名
-    let safe_name = sanitize_filename(&icon.name);
+    let safe_name = export::sanitize_filename(&icon.name);
+    // 校验并优化 SVG：无法解析时报错，不写入无效文件
+    let (processed_svg, report) = process_svg(&svg_content, optimize_options)?;
+    let mut write = |name: String, content: &[u8]| -> Result<(), String> {
+        let path = save_dir.join(name);
+        fs::write(&path, content).map_err(|e| format!("写入 {} 失败: {}", path.display(), e))?;
+        saved_paths.push(path.to_string_lossy().to_string());
+        Ok(())
+    };
     
     // 保存 SVG（清洗后）
     if *format == IconFormat::Svg || *format == IconFormat::Both {
-        let svg_path = save_dir.join(format!("{}.svg", safe_name));
-        // 清洗 SVG：移除 Iconfont 添加的内联样式限制
-        let cleaned_svg = clean_svg_for_save(&svg_content);
-        fs::write(&svg_path, &cleaned_svg)
-            .map_err(|e| format!("写入 SVG 文件失败: {}", e))?;
-        saved_paths.push(svg_path.to_string_lossy().to_string());
+        write(format!("{}.svg", safe_name), processed_svg.as_bytes())?;
     }
     
     // 保存 PNG（使用 resvg 转换）
     if *format == IconFormat::Png || *format == IconFormat::Both {
-        let size = png_size.unwrap_or(64);
-        let png_path = save_dir.join(format!("{}.png", safe_name));
-        
-        convert_svg_to_png(&svg_content, &png_path, size)
+        let png = render_svg_to_png(&svg_content, png_size.unwrap_or(64))
             .map_err(|e| format!("PNG 转换失败: {}", e))?;
-        
-        saved_paths.push(png_path.to_string_lossy().to_string());
+        write(format!("{}.png", safe_name), &png)?;
+    }
+
+    match format {
+        IconFormat::PngSet => {
+            let base_size = png_size.unwrap_or(64);
+            for scale in export::PNG_SET_SCALES {
+                let png = render_svg_to_png(&svg_content, base_size * scale)
+                    .map_err(|e| format!("PNG 转换失败: {}", e))?;
+                write(export::png_set_file_name(&safe_name, scale), &png)?;
+            }
+        }
+        IconFormat::Favicon => {
+            let mut ico_images = Vec::new();
+            for size in export::FAVICON_ICO_SIZES {
+                let png = render_svg_to_png(&svg_content, size)
+                    .map_err(|e| format!("PNG 转换失败: {}", e))?;
+                ico_images.push((size, png));
+            }
+            write(format!("{}.ico", safe_name), &export::encode_ico(&ico_images))?;
+            for size in export::FAVICON_PNG_SIZES {
+                let png = render_svg_to_png(&svg_content, size)
+                    .map_err(|e| format!("PNG 转换失败: {}", e))?;
+                write(format!("{}-{}x{}.png", safe_name, size, size), &png)?;
+            }
+        }
+        IconFormat::React | IconFormat::Vue | IconFormat::Svelte => {
+            let name = export::component_name(icon);
+            let source = export::render_component(format, &name, &processed_svg).unwrap_or_default();
+            // 文件名与 export::icon_file_names 保持一致
+            let file_name = export::icon_file_names(icon, format).into_iter().next().unwrap_or_default();
+            write(file_name, source.as_bytes())?;
+        }
+        _ => {}
     }
     
-    Ok(saved_paths)
+    Ok(SavedIcon {
+        paths: saved_paths,
+        svg: processed_svg,
+        report,
+    })
+}
+
+/// 保存前的 SVG 处理：启用优化时基于 usvg 重新输出，否则仅校验后清洗内联样式
+fn process_svg(
+    svg_content: &str,
+    options: &SvgOptimizeOptions,
+) -> Result<(String, Option<SvgOptimizeReport>), String> {
+    if options.enabled {
+        let (optimized, report) = optimize::optimize_svg(svg_content, options)
+            .map_err(|e| format!("SVG 校验失败: {}", e))?;
+        log_debug!(
+            "SVG 优化: {} -> {} 字节",
+            report.original_bytes,
+            report.optimized_bytes
+        );
+        return Ok((optimized, Some(report)));
+    }
+
+    optimize::validate_svg(svg_content).map_err(|e| format!("SVG 校验失败: {}", e))?;
+    // 清洗 SVG：移除 Iconfont 添加的内联样式限制
+    Ok((clean_svg_for_save(svg_content), None))
 }
 
-/// SVG 转 PNG（使用 resvg）
-fn convert_svg_to_png(svg_content: &str, output_path: &PathBuf, size: u32) -> Result<(), String> {
+/// SVG 渲染为 PNG 字节（使用 resvg）
+fn render_svg_to_png(svg_content: &str, size: u32) -> Result<Vec<u8>, String> {
     // 解析 SVG
     let tree = usvg::Tree::from_str(svg_content, &usvg::Options::default())
         .map_err(|e| format!("SVG 解析失败: {}", e))?;
@@ -185,45 +363,18 @@ fn convert_svg_to_png(svg_content: &str, output_path: &PathBuf, size: u32) -> Re
     let mut pixmap = tiny_skia::Pixmap::new(pixmap_size.width(), pixmap_size.height())
         .ok_or("创建画布失败")?;
     
-    // 计算缩放变换（保持比例居中）
+    // 计算缩放变换（保持比例居中，小尺寸 SVG 放大到目标尺寸以支持多倍图）
     let svg_size = tree.size;
-    let scale = (size as f32 / svg_size.width().max(svg_size.height())).min(1.0);
-    let transform = tiny_skia::Transform::from_scale(scale, scale);
+    let scale = size as f32 / svg_size.width().max(svg_size.height());
+    let offset_x = (size as f32 - svg_size.width() * scale) / 2.0;
+    let offset_y = (size as f32 - svg_size.height() * scale) / 2.0;
+    let transform = tiny_skia::Transform::from_scale(scale, scale).post_translate(offset_x, offset_y);
     
     // 渲染 SVG 到画布
     resvg::render(&tree, transform, &mut pixmap.as_mut());
     
-    // 保存为 PNG
-    pixmap.save_png(output_path)
-        .map_err(|e| format!("PNG 保存失败: {}", e))?;
-    
-    Ok(())
-}
-
-/// 清理文件名中的非法字符
-fn sanitize_filename(name: &str) -> String {
-    let mut safe_name = name
-        .chars()
-        .map(|c| {
-            if c.is_alphanumeric() || c == '-' || c == '_' {
-                c
-            } else {
-                '_'
-            }
-        })
-        .collect::<String>();
-    
-    // 确保文件名不为空
-    if safe_name.is_empty() {
-        safe_name = "icon".to_string();
-    }
-    
-    // 限制长度
-    if safe_name.len() > 64 {
-        safe_name = safe_name[..64].to_string();
-    }
-    
-    safe_name
+    pixmap.encode_png()
+        .map_err(|e| format!("PNG 编码失败: {}", e))
 }
 
 /// 清洗 SVG 内容，移除限制尺寸的内联样式
@@ -281,22 +432,41 @@ pub fn get_icon_config(
     state: tauri::State<crate::config::AppState>,
 ) -> Result<IconConfig, String> {
     let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
-    let mcp = &config.mcp_config;
-    
-    Ok(IconConfig {
+    Ok(icon_config_from_mcp(&config.mcp_config))
+}
+
+/// 从 MCP 配置中提取图标工坊配置（未设置的项使用默认值）
+pub fn icon_config_from_mcp(mcp: &crate::config::McpConfig) -> IconConfig {
+    IconConfig {
         default_save_path: mcp.icon_default_save_path.clone()
             .or_else(|| Some("assets/icons".to_string())),
-        default_format: mcp.icon_default_format.as_ref()
-            .and_then(|s| match s.as_str() {
-                "svg" => Some(IconFormat::Svg),
-                "png" => Some(IconFormat::Png),
-                "both" => Some(IconFormat::Both),
-                _ => Some(IconFormat::Svg),
-            })
+        default_format: mcp.icon_default_format.as_deref()
+            .map(IconFormat::from_config_value)
             .unwrap_or(IconFormat::Svg),
         default_png_size: mcp.icon_default_png_size.or(Some(64)),
         cache_expiry_minutes: mcp.icon_cache_expiry_minutes.or(Some(30)),
-    })
+        provider: IconProvider::from_config_value(mcp.icon_provider.as_deref()),
+        local_sources: mcp.icon_local_sources.clone().unwrap_or_default(),
+    }
+}
+
+/// 获取项目的图标工坊配置
+///
+/// 在全局配置之上应用项目根目录下 `.sanshu/icons.json` 中的保存路径、格式与 PNG 尺寸
+#[tauri::command]
+pub fn get_project_icon_config(
+    state: tauri::State<crate::config::AppState>,
+    project_root: String,
+) -> Result<IconConfig, String> {
+    let mut icon_config = {
+        let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        icon_config_from_mcp(&config.mcp_config)
+    };
+    if let Some(project_config) = ProjectIconConfig::load(&PathBuf::from(&project_root)) {
+        log_debug!("应用项目级图标配置: {}", project_root);
+        project_config.apply_to(&mut icon_config);
+    }
+    Ok(icon_config)
 }
 
 /// 保存图标工坊配置（持久化到配置文件）
@@ -318,13 +488,11 @@ pub async fn set_icon_config(
         let mut app_config = state.config.lock().map_err(|e| format!("锁定配置失败: {}", e))?;
         let mcp = &mut app_config.mcp_config;
         mcp.icon_default_save_path = config.default_save_path;
-        mcp.icon_default_format = Some(match config.default_format {
-            IconFormat::Svg => "svg".to_string(),
-            IconFormat::Png => "png".to_string(),
-            IconFormat::Both => "both".to_string(),
-        });
+        mcp.icon_default_format = Some(config.default_format.as_str().to_string());
         mcp.icon_default_png_size = config.default_png_size;
         mcp.icon_cache_expiry_minutes = config.cache_expiry_minutes;
+        mcp.icon_provider = Some(config.provider.as_str().to_string());
+        mcp.icon_local_sources = Some(config.local_sources);
     }
     
     // 持久化到配置文件
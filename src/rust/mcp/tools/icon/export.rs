@@ -0,0 +1,489 @@
+// 图标导出
+// 生成雪碧图、框架组件、索引文件与 favicon 等导出产物（纯内容生成，文件写入由 commands 负责）
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::types::{IconConfig, IconFormat, IconItem};
+
+// ============ 常量定义 ============
+
+/// 雪碧图文件名
+pub const SPRITE_FILE_NAME: &str = "sprite.svg";
+
+/// 多倍图倍率
+pub const PNG_SET_SCALES: [u32; 3] = [1, 2, 3];
+
+/// 写入 .ico 的尺寸
+pub const FAVICON_ICO_SIZES: [u32; 3] = [16, 32, 48];
+
+/// favicon 套件额外输出的 PNG 尺寸（apple-touch-icon 与 PWA 图标）
+pub const FAVICON_PNG_SIZES: [u32; 3] = [180, 192, 512];
+
+/// 项目级图标配置文件（相对于项目根目录）
+pub const PROJECT_CONFIG_PATH: &str = ".sanshu/icons.json";
+
+/// 组件默认尺寸
+const DEFAULT_COMPONENT_SIZE: u32 = 24;
+
+static SVG_OPEN_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<svg\b([^>]*)>").unwrap());
+static SVG_ATTR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"([\w:-]+)\s*=\s*"([^"]*)""#).unwrap());
+static COLOR_ATTR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\b(fill|stroke)="([^"]*)""#).unwrap());
+static SYMBOL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?s)<symbol\b[^>]*\bid="([^"]+)"[^>]*>.*?</symbol>"#).unwrap());
+
+/// 根元素上保留到组件中的呈现属性
+const ROOT_PRESENTATION_ATTRS: [&str; 5] = ["fill", "stroke", "stroke-width", "stroke-linecap", "stroke-linejoin"];
+
+// ============ 文件命名 ============
+
+/// 单个图标在指定格式下的输出文件（相对保存目录的文件名）
+///
+/// 雪碧图与图标字体为汇总文件，不产生单独的图标文件
+pub fn icon_file_names(icon: &IconItem, format: &IconFormat) -> Vec<String> {
+    let safe_name = sanitize_filename(&icon.name);
+    match format {
+        IconFormat::Svg => vec![format!("{}.svg", safe_name)],
+        IconFormat::Png => vec![format!("{}.png", safe_name)],
+        IconFormat::Both => vec![format!("{}.svg", safe_name), format!("{}.png", safe_name)],
+        IconFormat::Sprite | IconFormat::Font => Vec::new(),
+        IconFormat::PngSet => PNG_SET_SCALES
+            .iter()
+            .map(|scale| png_set_file_name(&safe_name, *scale))
+            .collect(),
+        IconFormat::Favicon => std::iter::once(format!("{}.ico", safe_name))
+            .chain(
+                FAVICON_PNG_SIZES
+                    .iter()
+                    .map(|size| format!("{}-{}x{}.png", safe_name, size, size)),
+            )
+            .collect(),
+        IconFormat::React => vec![format!("{}.tsx", component_name(icon))],
+        IconFormat::Vue => vec![format!("{}.vue", component_name(icon))],
+        IconFormat::Svelte => vec![format!("{}.svelte", component_name(icon))],
+    }
+}
+
+pub fn png_set_file_name(safe_name: &str, scale: u32) -> String {
+    if scale == 1 {
+        format!("{}.png", safe_name)
+    } else {
+        format!("{}@{}x.png", safe_name, scale)
+    }
+}
+
+/// 清理文件名中的非法字符
+pub fn sanitize_filename(name: &str) -> String {
+    let mut safe_name = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+
+    // 确保文件名不为空
+    if safe_name.is_empty() {
+        safe_name = "icon".to_string();
+    }
+
+    // 限制长度（按字符截断，避免切断多字节字符）
+    if safe_name.chars().count() > 64 {
+        safe_name = safe_name.chars().take(64).collect();
+    }
+
+    safe_name
+}
+
+/// 组件名：`Icon` + PascalCase
+///
+/// Iconfont 图标名常为中文，此时改用 font_class，仍不可用则退回 ID
+pub fn component_name(icon: &IconItem) -> String {
+    let base = [icon.name.as_str(), icon.font_class.as_str()]
+        .into_iter()
+        .find(|s| !s.is_empty() && s.is_ascii())
+        .map(str::to_string)
+        .unwrap_or_else(|| icon.id.to_string());
+
+    let pascal: String = base
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    format!("Icon{}", pascal)
+}
+
+// ============ 雪碧图 ============
+
+/// 生成 `<symbol>` 雪碧图，保留已有雪碧图中未被本次覆盖的图标
+pub fn build_sprite(existing: Option<&str>, icons: &[(String, String)]) -> String {
+    let new_ids: BTreeSet<&str> = icons.iter().map(|(id, _)| id.as_str()).collect();
+    let mut symbols: Vec<String> = existing
+        .map(|content| {
+            SYMBOL
+                .captures_iter(content)
+                .filter(|c| !new_ids.contains(&c[1]))
+                .map(|c| c[0].to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    for (id, svg) in icons {
+        let parts = split_svg(svg);
+        symbols.push(format!(
+            r#"<symbol id="{}" viewBox="{}">{}</symbol>"#,
+            id, parts.view_box, parts.body
+        ));
+    }
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" style=\"display: none\">\n{}\n</svg>\n",
+        symbols
+            .iter()
+            .map(|s| format!("  {}", s))
+            .collect::<Vec<_>>()
+            .join("\n")
+    )
+}
+
+// ============ 框架组件 ============
+
+/// 拆分后的 SVG：viewBox、根元素呈现属性与内部内容
+struct SvgParts {
+    view_box: String,
+    root_attrs: Vec<(String, String)>,
+    body: String,
+}
+
+fn split_svg(svg: &str) -> SvgParts {
+    let Some(open) = SVG_OPEN_TAG.captures(svg) else {
+        return SvgParts {
+            view_box: format!("0 0 {0} {0}", DEFAULT_COMPONENT_SIZE),
+            root_attrs: Vec::new(),
+            body: svg.trim().to_string(),
+        };
+    };
+
+    let attrs: Vec<(String, String)> = SVG_ATTR
+        .captures_iter(&open[1])
+        .map(|c| (c[1].to_string(), c[2].to_string()))
+        .collect();
+    let attr = |name: &str| attrs.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone());
+
+    let view_box = attr("viewBox").unwrap_or_else(|| {
+        let size = |name: &str| {
+            attr(name)
+                .map(|v| v.trim_end_matches("px").to_string())
+                .unwrap_or_else(|| DEFAULT_COMPONENT_SIZE.to_string())
+        };
+        format!("0 0 {} {}", size("width"), size("height"))
+    });
+
+    let body_start = open.get(0).map(|m| m.end()).unwrap_or(0);
+    let body_end = svg.rfind("</svg>").filter(|end| *end >= body_start).unwrap_or(svg.len());
+
+    SvgParts {
+        view_box,
+        root_attrs: attrs
+            .into_iter()
+            .filter(|(k, _)| ROOT_PRESENTATION_ATTRS.contains(&k.as_str()))
+            .collect(),
+        body: svg[body_start..body_end].trim().to_string(),
+    }
+}
+
+/// 单色图标的颜色替换为 currentColor，多色图标保留原色
+fn apply_current_color(svg: &str) -> String {
+    let colors: BTreeSet<String> = COLOR_ATTR
+        .captures_iter(svg)
+        .map(|c| c[2].trim().to_lowercase())
+        .filter(|v| v != "none" && v != "currentcolor" && !v.starts_with("url("))
+        .collect();
+    if colors.len() > 1 {
+        return svg.to_string();
+    }
+
+    COLOR_ATTR
+        .replace_all(svg, |c: &regex::Captures| {
+            let value = c[2].trim().to_lowercase();
+            if value == "none" || value.starts_with("url(") {
+                c[0].to_string()
+            } else {
+                format!(r#"{}="currentColor""#, &c[1])
+            }
+        })
+        .to_string()
+}
+
+/// 组件根元素的静态属性（不含尺寸），JSX 中连字符属性需转为驼峰
+fn component_root_attrs(parts: &SvgParts, jsx: bool) -> String {
+    let mut attrs = vec![
+        r#"xmlns="http://www.w3.org/2000/svg""#.to_string(),
+        format!(r#"viewBox="{}""#, parts.view_box),
+    ];
+    attrs.extend(parts.root_attrs.iter().map(|(k, v)| {
+        let name = if jsx { camel_case(k) } else { k.clone() };
+        format!(r#"{}="{}""#, name, v)
+    }));
+
+    // 根元素与内容都未声明颜色时，默认以 currentColor 填充
+    let has_root_color = parts.root_attrs.iter().any(|(k, _)| k == "fill" || k == "stroke");
+    if !has_root_color && !COLOR_ATTR.is_match(&parts.body) {
+        attrs.push(r#"fill="currentColor""#.to_string());
+    }
+    attrs.join(" ")
+}
+
+fn camel_case(name: &str) -> String {
+    let mut parts = name.split('-');
+    let first = parts.next().unwrap_or_default().to_string();
+    parts.fold(first, |mut acc, part| {
+        let mut chars = part.chars();
+        if let Some(c) = chars.next() {
+            acc.push(c.to_ascii_uppercase());
+            acc.push_str(chars.as_str());
+        }
+        acc
+    })
+}
+
+/// 生成组件源码
+pub fn render_component(format: &IconFormat, name: &str, svg: &str) -> Option<String> {
+    let parts = split_svg(&apply_current_color(svg));
+    let root = component_root_attrs(&parts, *format == IconFormat::React);
+    let size = DEFAULT_COMPONENT_SIZE;
+
+    let source = match format {
+        IconFormat::React => format!(
+            r#"import type {{ SVGProps }} from 'react'
+
+export interface {name}Props extends SVGProps<SVGSVGElement> {{
+  size?: number | string
+}}
+
+const body = {body}
+
+export default function {name}({{ size = {size}, ...props }}: {name}Props) {{
+  return (
+    <svg {root} width={{size}} height={{size}} {{...props}} dangerouslySetInnerHTML={{{{ __html: body }}}} />
+  )
+}}
+"#,
+            body = serde_json::to_string(&parts.body).unwrap_or_default(),
+        ),
+        IconFormat::Vue => format!(
+            r#"<script setup lang="ts">
+withDefaults(defineProps<{{ size?: number | string }}>(), {{ size: {size} }})
+</script>
+
+<template>
+  <svg {root} :width="size" :height="size">
+    {body}
+  </svg>
+</template>
+"#,
+            body = parts.body,
+        ),
+        IconFormat::Svelte => format!(
+            r#"<script lang="ts">
+  export let size: number | string = {size}
+</script>
+
+<svg {root} width={{size}} height={{size}} {{...$$restProps}}>
+  {body}
+</svg>
+"#,
+            body = parts.body,
+        ),
+        _ => return None,
+    };
+    Some(source)
+}
+
+/// 按目录中已有的组件文件重新生成索引文件（index.ts），多次保存的组件都会被导出
+pub fn write_barrel(save_dir: &Path, format: &IconFormat) -> std::io::Result<Option<PathBuf>> {
+    let extension = match format {
+        IconFormat::React => "tsx",
+        IconFormat::Vue => "vue",
+        IconFormat::Svelte => "svelte",
+        _ => return Ok(None),
+    };
+
+    let mut components: Vec<String> = fs::read_dir(save_dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some(extension))
+        .filter_map(|path| path.file_stem().map(|s| s.to_string_lossy().to_string()))
+        .filter(|stem| stem.starts_with("Icon"))
+        .collect();
+    components.sort();
+
+    let lines: Vec<String> = components
+        .iter()
+        .map(|name| match format {
+            // React 组件按 TS 模块解析，不带扩展名
+            IconFormat::React => format!("export {{ default as {} }} from './{}'", name, name),
+            _ => format!("export {{ default as {} }} from './{}.{}'", name, name, extension),
+        })
+        .collect();
+
+    let path = save_dir.join("index.ts");
+    fs::write(&path, lines.join("\n") + "\n")?;
+    Ok(Some(path))
+}
+
+// ============ favicon ============
+
+/// 将多张 PNG 打包为 .ico（PNG 压缩的图标项，Vista 及以上与所有现代浏览器支持）
+pub fn encode_ico(images: &[(u32, Vec<u8>)]) -> Vec<u8> {
+    let header_len = 6 + 16 * images.len();
+    let mut ico = Vec::with_capacity(header_len + images.iter().map(|(_, png)| png.len()).sum::<usize>());
+
+    ico.extend_from_slice(&0u16.to_le_bytes()); // 保留
+    ico.extend_from_slice(&1u16.to_le_bytes()); // 类型：图标
+    ico.extend_from_slice(&(images.len() as u16).to_le_bytes());
+
+    let mut offset = header_len as u32;
+    for (size, png) in images {
+        // 尺寸字段为单字节，256 记为 0
+        let dimension = if *size >= 256 { 0 } else { *size as u8 };
+        ico.push(dimension);
+        ico.push(dimension);
+        ico.push(0); // 调色板颜色数
+        ico.push(0); // 保留
+        ico.extend_from_slice(&1u16.to_le_bytes()); // 颜色平面
+        ico.extend_from_slice(&32u16.to_le_bytes()); // 位深
+        ico.extend_from_slice(&(png.len() as u32).to_le_bytes());
+        ico.extend_from_slice(&offset.to_le_bytes());
+        offset += png.len() as u32;
+    }
+
+    for (_, png) in images {
+        ico.extend_from_slice(png);
+    }
+    ico
+}
+
+// ============ 项目级默认配置 ============
+
+/// 项目级图标导出配置（`<项目根目录>/.sanshu/icons.json`），覆盖全局默认值
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProjectIconConfig {
+    #[serde(default)]
+    pub save_path: Option<String>,
+    #[serde(default)]
+    pub format: Option<IconFormat>,
+    #[serde(default)]
+    pub png_size: Option<u32>,
+}
+
+impl ProjectIconConfig {
+    /// 读取项目级配置，文件不存在或格式错误时返回 None
+    pub fn load(project_root: &Path) -> Option<Self> {
+        let content = fs::read_to_string(project_root.join(PROJECT_CONFIG_PATH)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// 合并到全局配置
+    pub fn apply_to(self, config: &mut IconConfig) {
+        if let Some(save_path) = self.save_path.filter(|p| !p.trim().is_empty()) {
+            config.default_save_path = Some(save_path);
+        }
+        if let Some(format) = self.format {
+            config.default_format = format;
+        }
+        if let Some(png_size) = self.png_size {
+            config.default_png_size = Some(png_size);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn icon(name: &str, font_class: &str) -> IconItem {
+        IconItem {
+            id: 42,
+            name: name.to_string(),
+            font_class: font_class.to_string(),
+            unicode: None,
+            svg_content: None,
+            preview_url: None,
+            author: None,
+            repository_name: None,
+            repository_id: None,
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn test_component_name() {
+        assert_eq!(component_name(&icon("arrow-up", "")), "IconArrowUp");
+        assert_eq!(component_name(&icon("首页", "home_fill")), "IconHomeFill");
+        assert_eq!(component_name(&icon("首页", "")), "Icon42");
+    }
+
+    #[test]
+    fn test_build_sprite_merges_existing() {
+        let existing = r#"<svg><symbol id="a" viewBox="0 0 1 1"><path/></symbol><symbol id="b" viewBox="0 0 1 1"><g/></symbol></svg>"#;
+        let sprite = build_sprite(
+            Some(existing),
+            &[("b".to_string(), r#"<svg viewBox="0 0 24 24"><circle/></svg>"#.to_string())],
+        );
+        assert!(sprite.contains(r#"<symbol id="a" viewBox="0 0 1 1"><path/></symbol>"#));
+        assert!(sprite.contains(r#"<symbol id="b" viewBox="0 0 24 24"><circle/></symbol>"#));
+        assert_eq!(sprite.matches("<symbol").count(), 2);
+    }
+
+    #[test]
+    fn test_render_component_uses_current_color() {
+        let svg = r##"<svg viewBox="0 0 1024 1024" width="200" height="200"><path fill="#333333" d="M0 0"/></svg>"##;
+        let vue = render_component(&IconFormat::Vue, "IconHome", svg).unwrap();
+        assert!(vue.contains(r#"<path fill="currentColor" d="M0 0"/>"#));
+        assert!(vue.contains(r#"viewBox="0 0 1024 1024" :width="size""#));
+        assert!(!vue.contains(r#"width="200""#));
+
+        // 多色图标保留原色
+        let multi = r##"<svg viewBox="0 0 24 24"><path fill="#f00"/><path fill="#0f0"/></svg>"##;
+        let react = render_component(&IconFormat::React, "IconFlag", multi).unwrap();
+        assert!(react.contains("#f00"));
+        assert!(react.contains("export default function IconFlag"));
+
+        let stroked = r#"<svg viewBox="0 0 24 24" stroke-width="2"><path/></svg>"#;
+        let react = render_component(&IconFormat::React, "IconLine", stroked).unwrap();
+        assert!(react.contains(r#"strokeWidth="2""#));
+
+        // 线性图标的根元素属性保留，不额外添加填充色
+        let line = r#"<svg viewBox="0 0 24 24" fill="none" stroke="currentColor"><path d="M1 1"/></svg>"#;
+        let svelte = render_component(&IconFormat::Svelte, "IconLine", line).unwrap();
+        assert!(svelte.contains(r#"fill="none" stroke="currentColor" width={size}"#));
+        assert_eq!(svelte.matches("fill=").count(), 1);
+    }
+
+    #[test]
+    fn test_encode_ico() {
+        let ico = encode_ico(&[(16, vec![1, 2, 3]), (256, vec![4, 5])]);
+        assert_eq!(&ico[0..6], &[0, 0, 1, 0, 2, 0]);
+        // 第一项：16x16，数据偏移在两个目录项之后
+        assert_eq!(ico[6], 16);
+        assert_eq!(u32::from_le_bytes(ico[18..22].try_into().unwrap()), 38);
+        // 256 记为 0
+        assert_eq!(ico[22], 0);
+        assert_eq!(u32::from_le_bytes(ico[34..38].try_into().unwrap()), 41);
+        assert_eq!(&ico[38..], &[1, 2, 3, 4, 5]);
+    }
+}
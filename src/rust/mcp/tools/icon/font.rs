@@ -0,0 +1,685 @@
+// 图标字体
+// 将 SVG 轮廓转换为 TrueType 字形，生成 iconfont.ttf 与配套的 iconfont.css（纯内容生成，文件写入由 commands 负责）
+//
+// 字形按私用区码位（U+E000 起）编号；清单文件记录名称、码位与源 SVG，后续保存时合并已有字形且码位不变
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use usvg::tiny_skia_path::{PathSegment, Point};
+use usvg::TreeParsing;
+
+// ============ 常量定义 ============
+
+/// 字体文件名
+pub const FONT_FILE_NAME: &str = "iconfont.ttf";
+
+/// 样式文件名
+pub const FONT_CSS_FILE_NAME: &str = "iconfont.css";
+
+/// 字形清单文件名（用于合并后续保存的图标）
+pub const FONT_MANIFEST_FILE_NAME: &str = "iconfont.json";
+
+/// 字体族名，同时用作 CSS 基础类名
+const FONT_FAMILY: &str = "iconfont";
+
+/// 首个字形码位（私用区）
+const FIRST_CODEPOINT: u32 = 0xE000;
+
+/// 私用区末尾码位
+const LAST_CODEPOINT: u32 = 0xF8FF;
+
+/// 与 Iconfont 导出的字体一致：1024 单位，基线以下 128
+const UNITS_PER_EM: u16 = 1024;
+const ASCENT: i16 = 896;
+const DESCENT: i16 = -128;
+
+/// 三次贝塞尔曲线拆分为二次曲线的段数
+const CUBIC_SPLITS: usize = 4;
+
+// ============ 类型定义 ============
+
+/// 字形清单
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FontManifest {
+    /// 名称 -> 字形
+    #[serde(default)]
+    glyphs: BTreeMap<String, ManifestGlyph>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ManifestGlyph {
+    codepoint: u32,
+    svg: String,
+}
+
+/// 生成的图标字体
+pub struct IconFont {
+    pub ttf: Vec<u8>,
+    pub css: String,
+    pub manifest: String,
+}
+
+/// 字形轮廓点（字体单位，y 轴向上）
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct OutlinePoint {
+    x: i16,
+    y: i16,
+    on_curve: bool,
+}
+
+struct Glyph {
+    codepoint: u32,
+    contours: Vec<Vec<OutlinePoint>>,
+}
+
+// ============ 生成入口 ============
+
+/// 生成图标字体，保留已有清单中未被本次覆盖的字形
+///
+/// `icons` 为（名称，SVG）；描边转换为轮廓，多色图标合并为单色。
+/// TrueType 按非零环绕规则填充，依赖 evenodd 规则镂空的图标可能显示为实心
+pub fn build_icon_font(existing_manifest: Option<&str>, icons: &[(String, String)]) -> Result<IconFont> {
+    let mut manifest: FontManifest = existing_manifest
+        .and_then(|content| serde_json::from_str(content).ok())
+        .unwrap_or_default();
+
+    for (name, svg) in icons {
+        let codepoint = match manifest.glyphs.get(name) {
+            Some(glyph) => glyph.codepoint,
+            None => next_codepoint(&manifest)?,
+        };
+        manifest.glyphs.insert(
+            name.clone(),
+            ManifestGlyph {
+                codepoint,
+                svg: svg.clone(),
+            },
+        );
+    }
+
+    let mut glyphs = Vec::new();
+    for (name, glyph) in &manifest.glyphs {
+        let contours = svg_outline(&glyph.svg).map_err(|e| anyhow!("图标 {} 转换为字形失败: {}", name, e))?;
+        glyphs.push(Glyph {
+            codepoint: glyph.codepoint,
+            contours,
+        });
+    }
+    glyphs.sort_by_key(|glyph| glyph.codepoint);
+
+    Ok(IconFont {
+        ttf: encode_ttf(&glyphs),
+        css: render_css(&manifest),
+        manifest: serde_json::to_string_pretty(&manifest)?,
+    })
+}
+
+fn next_codepoint(manifest: &FontManifest) -> Result<u32> {
+    let next = manifest
+        .glyphs
+        .values()
+        .map(|glyph| glyph.codepoint + 1)
+        .max()
+        .unwrap_or(FIRST_CODEPOINT)
+        .max(FIRST_CODEPOINT);
+    if next > LAST_CODEPOINT {
+        return Err(anyhow!("图标字体的字形数量已达上限"));
+    }
+    Ok(next)
+}
+
+fn render_css(manifest: &FontManifest) -> String {
+    let mut css = format!(
+        r#"@font-face {{
+  font-family: "{family}";
+  src: url("{file}") format("truetype");
+}}
+
+.{family} {{
+  font-family: "{family}" !important;
+  font-size: 16px;
+  font-style: normal;
+  line-height: 1;
+  -webkit-font-smoothing: antialiased;
+  -moz-osx-font-smoothing: grayscale;
+}}
+"#,
+        family = FONT_FAMILY,
+        file = FONT_FILE_NAME
+    );
+    for (name, glyph) in &manifest.glyphs {
+        css.push_str(&format!(
+            "\n.icon-{}::before {{\n  content: \"\\{:x}\";\n}}\n",
+            name, glyph.codepoint
+        ));
+    }
+    css
+}
+
+// ============ SVG 轮廓 ============
+
+/// 将 SVG 的所有填充与描边转换为字形轮廓
+///
+/// viewBox 等比缩放到 em 方框并居中，再翻转 y 轴
+fn svg_outline(svg: &str) -> Result<Vec<Vec<OutlinePoint>>> {
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).map_err(|e| anyhow!("SVG 解析失败: {}", e))?;
+    let rect = tree.view_box.rect;
+    let side = rect.width().max(rect.height());
+    let scale = UNITS_PER_EM as f32 / side;
+    let to_em = usvg::Transform::from_translate(
+        -rect.x() + (side - rect.width()) / 2.0,
+        -rect.y() + (side - rect.height()) / 2.0,
+    )
+    .post_scale(scale, -scale)
+    .post_translate(0.0, ASCENT as f32);
+
+    let mut contours = Vec::new();
+    collect_outlines(&tree.root, to_em, scale, &mut contours);
+    Ok(contours)
+}
+
+fn collect_outlines(group: &usvg::Group, parent: usvg::Transform, scale: f32, out: &mut Vec<Vec<OutlinePoint>>) {
+    let transform = parent.pre_concat(group.transform);
+    for node in &group.children {
+        match node {
+            usvg::Node::Group(child) => collect_outlines(child, transform, scale, out),
+            usvg::Node::Path(path) if path.visibility == usvg::Visibility::Visible => {
+                let mut shapes = Vec::new();
+                if path.fill.is_some() {
+                    shapes.push((*path.data).clone());
+                }
+                // 描边在用户坐标系中展开为轮廓，再随变换缩放
+                if let Some(stroke) = &path.stroke {
+                    shapes.extend(path.data.stroke(&stroke.to_tiny_skia(), scale));
+                }
+                for shape in shapes.into_iter().filter_map(|shape| shape.transform(transform)) {
+                    out.extend(path_contours(&shape));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 路径转换为 TrueType 轮廓：三次曲线近似为多段二次曲线
+fn path_contours(path: &usvg::tiny_skia_path::Path) -> Vec<Vec<OutlinePoint>> {
+    let mut contours = Vec::new();
+    let mut current: Vec<OutlinePoint> = Vec::new();
+    let mut last = Point::zero();
+
+    let point = |p: Point, on_curve: bool| OutlinePoint {
+        x: p.x.round().clamp(i16::MIN as f32, i16::MAX as f32) as i16,
+        y: p.y.round().clamp(i16::MIN as f32, i16::MAX as f32) as i16,
+        on_curve,
+    };
+
+    for segment in path.segments() {
+        match segment {
+            PathSegment::MoveTo(p) => {
+                finish_contour(&mut current, &mut contours);
+                current.push(point(p, true));
+                last = p;
+            }
+            PathSegment::LineTo(p) => {
+                current.push(point(p, true));
+                last = p;
+            }
+            PathSegment::QuadTo(c, p) => {
+                current.push(point(c, false));
+                current.push(point(p, true));
+                last = p;
+            }
+            PathSegment::CubicTo(c1, c2, p) => {
+                for (control, end) in cubic_to_quads(last, c1, c2, p) {
+                    current.push(point(control, false));
+                    current.push(point(end, true));
+                }
+                last = p;
+            }
+            PathSegment::Close => finish_contour(&mut current, &mut contours),
+        }
+    }
+    finish_contour(&mut current, &mut contours);
+    contours
+}
+
+/// 结束当前轮廓：去掉重复的相邻点与回到起点的终点，少于三个点的轮廓丢弃
+fn finish_contour(current: &mut Vec<OutlinePoint>, contours: &mut Vec<Vec<OutlinePoint>>) {
+    let mut points = std::mem::take(current);
+    points.dedup_by(|b, a| a.on_curve && b.on_curve && a.x == b.x && a.y == b.y);
+    if points.len() > 1 {
+        let (first, last) = (points[0], points[points.len() - 1]);
+        if last.on_curve && first.x == last.x && first.y == last.y {
+            points.pop();
+        }
+    }
+    if points.len() >= 3 {
+        contours.push(points);
+    }
+}
+
+/// 将三次曲线均分为若干段，每段以二次曲线近似，返回（控制点，终点）
+fn cubic_to_quads(p0: Point, c1: Point, c2: Point, p3: Point) -> Vec<(Point, Point)> {
+    let at = |t: f32| {
+        let mt = 1.0 - t;
+        let x = mt * mt * mt * p0.x + 3.0 * mt * mt * t * c1.x + 3.0 * mt * t * t * c2.x + t * t * t * p3.x;
+        let y = mt * mt * mt * p0.y + 3.0 * mt * mt * t * c1.y + 3.0 * mt * t * t * c2.y + t * t * t * p3.y;
+        Point::from_xy(x, y)
+    };
+    let derivative = |t: f32| {
+        let mt = 1.0 - t;
+        let x = 3.0 * mt * mt * (c1.x - p0.x) + 6.0 * mt * t * (c2.x - c1.x) + 3.0 * t * t * (p3.x - c2.x);
+        let y = 3.0 * mt * mt * (c1.y - p0.y) + 6.0 * mt * t * (c2.y - c1.y) + 3.0 * t * t * (p3.y - c2.y);
+        Point::from_xy(x, y)
+    };
+
+    let step = 1.0 / CUBIC_SPLITS as f32;
+    (0..CUBIC_SPLITS)
+        .map(|i| {
+            let (t0, t1) = (i as f32 * step, (i + 1) as f32 * step);
+            let (start, end) = (at(t0), if i + 1 == CUBIC_SPLITS { p3 } else { at(t1) });
+            let (d0, d1) = (derivative(t0), derivative(t1));
+            // 子曲线的两个控制点，二次控制点取 (3(c1 + c2) - p0 - p3) / 4
+            let sub_c1 = Point::from_xy(start.x + d0.x * step / 3.0, start.y + d0.y * step / 3.0);
+            let sub_c2 = Point::from_xy(end.x - d1.x * step / 3.0, end.y - d1.y * step / 3.0);
+            let control = Point::from_xy(
+                (3.0 * (sub_c1.x + sub_c2.x) - start.x - end.x) / 4.0,
+                (3.0 * (sub_c1.y + sub_c2.y) - start.y - end.y) / 4.0,
+            );
+            (control, end)
+        })
+        .collect()
+}
+
+// ============ TrueType 编码 ============
+
+/// 编码 TrueType 字体：字形 0 为 .notdef，其余按码位顺序排列
+fn encode_ttf(glyphs: &[Glyph]) -> Vec<u8> {
+    let empty = Glyph {
+        codepoint: 0,
+        contours: Vec::new(),
+    };
+    let all: Vec<&Glyph> = std::iter::once(&empty).chain(glyphs).collect();
+
+    let mut glyf = Vec::new();
+    let mut loca = Vec::new();
+    let mut hmtx = Vec::new();
+    let mut bounds = (i16::MAX, i16::MAX, i16::MIN, i16::MIN);
+    let (mut max_points, mut max_contours) = (0u16, 0u16);
+    for glyph in &all {
+        loca.extend_from_slice(&(glyf.len() as u32).to_be_bytes());
+        let glyph_bounds = encode_glyph(&glyph.contours, &mut glyf);
+        push_u16(&mut hmtx, UNITS_PER_EM);
+        push_i16(&mut hmtx, glyph_bounds.map(|b| b.0).unwrap_or(0));
+        if let Some((x_min, y_min, x_max, y_max)) = glyph_bounds {
+            bounds = (bounds.0.min(x_min), bounds.1.min(y_min), bounds.2.max(x_max), bounds.3.max(y_max));
+        }
+        max_points = max_points.max(glyph.contours.iter().map(Vec::len).sum::<usize>() as u16);
+        max_contours = max_contours.max(glyph.contours.len() as u16);
+    }
+    loca.extend_from_slice(&(glyf.len() as u32).to_be_bytes());
+    if bounds.0 > bounds.2 {
+        bounds = (0, 0, 0, 0);
+    }
+
+    let num_glyphs = all.len() as u16;
+    let first_char = glyphs.first().map(|g| g.codepoint as u16).unwrap_or(0);
+    let last_char = glyphs.last().map(|g| g.codepoint as u16).unwrap_or(0);
+
+    let mut head = Vec::new();
+    push_u32(&mut head, 0x0001_0000); // 版本
+    push_u32(&mut head, 0x0001_0000); // 字体修订号
+    push_u32(&mut head, 0); // checkSumAdjustment，最后回填
+    push_u32(&mut head, 0x5F0F_3CF5); // magic
+    push_u16(&mut head, 0x000B); // 基线 y=0、左边距 x=0、整数缩放
+    push_u16(&mut head, UNITS_PER_EM);
+    head.extend_from_slice(&[0; 16]); // 创建与修改时间
+    for value in [bounds.0, bounds.1, bounds.2, bounds.3] {
+        push_i16(&mut head, value);
+    }
+    push_u16(&mut head, 0); // macStyle
+    push_u16(&mut head, 8); // lowestRecPPEM
+    push_i16(&mut head, 2); // fontDirectionHint
+    push_i16(&mut head, 1); // indexToLocFormat：长偏移
+    push_i16(&mut head, 0); // glyphDataFormat
+
+    let mut hhea = Vec::new();
+    push_u32(&mut hhea, 0x0001_0000);
+    push_i16(&mut hhea, ASCENT);
+    push_i16(&mut hhea, DESCENT);
+    push_i16(&mut hhea, 0); // lineGap
+    push_u16(&mut hhea, UNITS_PER_EM); // advanceWidthMax
+    push_i16(&mut hhea, bounds.0); // minLeftSideBearing
+    push_i16(&mut hhea, UNITS_PER_EM as i16 - bounds.2); // minRightSideBearing
+    push_i16(&mut hhea, bounds.2); // xMaxExtent
+    push_i16(&mut hhea, 1); // caretSlopeRise
+    hhea.extend_from_slice(&[0; 12]); // caretSlopeRun、caretOffset 与保留字段
+    push_i16(&mut hhea, 0); // metricDataFormat
+    push_u16(&mut hhea, num_glyphs);
+
+    let mut maxp = Vec::new();
+    push_u32(&mut maxp, 0x0001_0000);
+    push_u16(&mut maxp, num_glyphs);
+    push_u16(&mut maxp, max_points);
+    push_u16(&mut maxp, max_contours);
+    push_u16(&mut maxp, 0); // maxCompositePoints
+    push_u16(&mut maxp, 0); // maxCompositeContours
+    push_u16(&mut maxp, 2); // maxZones
+    maxp.extend_from_slice(&[0; 16]); // 无字形指令
+
+    let mut os2 = Vec::new();
+    push_u16(&mut os2, 4); // 版本
+    push_i16(&mut os2, UNITS_PER_EM as i16); // xAvgCharWidth
+    push_u16(&mut os2, 400); // usWeightClass
+    push_u16(&mut os2, 5); // usWidthClass
+    push_u16(&mut os2, 0); // fsType：可安装嵌入
+    os2.extend_from_slice(&[0; 22]); // 上下标与删除线度量、sFamilyClass
+    os2.extend_from_slice(&[0; 10]); // panose
+    os2.extend_from_slice(&[0; 16]); // ulUnicodeRange
+    os2.extend_from_slice(b"SNSH"); // achVendID
+    push_u16(&mut os2, 0x0040); // fsSelection：REGULAR
+    push_u16(&mut os2, first_char);
+    push_u16(&mut os2, last_char);
+    push_i16(&mut os2, ASCENT);
+    push_i16(&mut os2, DESCENT);
+    push_i16(&mut os2, 0); // sTypoLineGap
+    push_u16(&mut os2, ASCENT as u16); // usWinAscent
+    push_u16(&mut os2, (-DESCENT) as u16); // usWinDescent
+    push_u32(&mut os2, 1); // ulCodePageRange1：Latin 1
+    push_u32(&mut os2, 0);
+    os2.extend_from_slice(&[0; 4]); // sxHeight、sCapHeight
+    push_u16(&mut os2, 0); // usDefaultChar
+    push_u16(&mut os2, 0x20); // usBreakChar
+    push_u16(&mut os2, 0); // usMaxContext
+
+    let mut post = Vec::new();
+    push_u32(&mut post, 0x0003_0000); // 版本 3：不含字形名
+    push_u32(&mut post, 0); // italicAngle
+    push_i16(&mut post, -100); // underlinePosition
+    push_i16(&mut post, 50); // underlineThickness
+    post.extend_from_slice(&[0; 20]); // isFixedPitch 与内存占用提示
+
+    let tables: Vec<(&[u8; 4], Vec<u8>)> = vec![
+        (b"OS/2", os2),
+        (b"cmap", encode_cmap(glyphs)),
+        (b"glyf", glyf),
+        (b"head", head),
+        (b"hhea", hhea),
+        (b"hmtx", hmtx),
+        (b"loca", loca),
+        (b"maxp", maxp),
+        (b"name", encode_name()),
+        (b"post", post),
+    ];
+    assemble_sfnt(tables)
+}
+
+/// 写入简单字形，返回包围盒（xMin, yMin, xMax, yMax）；无轮廓的字形不占数据
+fn encode_glyph(contours: &[Vec<OutlinePoint>], glyf: &mut Vec<u8>) -> Option<(i16, i16, i16, i16)> {
+    let points: Vec<&OutlinePoint> = contours.iter().flatten().collect();
+    if points.is_empty() {
+        return None;
+    }
+    let x_min = points.iter().map(|p| p.x).min()?;
+    let y_min = points.iter().map(|p| p.y).min()?;
+    let x_max = points.iter().map(|p| p.x).max()?;
+    let y_max = points.iter().map(|p| p.y).max()?;
+
+    push_i16(glyf, contours.len() as i16);
+    for value in [x_min, y_min, x_max, y_max] {
+        push_i16(glyf, value);
+    }
+    let mut end = 0usize;
+    for contour in contours {
+        end += contour.len();
+        push_u16(glyf, (end - 1) as u16);
+    }
+    push_u16(glyf, 0); // 无指令
+
+    // 坐标统一按 16 位差值存储
+    glyf.extend(points.iter().map(|p| u8::from(p.on_curve)));
+    let mut previous = 0i16;
+    for point in &points {
+        push_i16(glyf, point.x.wrapping_sub(previous));
+        previous = point.x;
+    }
+    previous = 0;
+    for point in &points {
+        push_i16(glyf, point.y.wrapping_sub(previous));
+        previous = point.y;
+    }
+    while !glyf.len().is_multiple_of(4) {
+        glyf.push(0);
+    }
+    Some((x_min, y_min, x_max, y_max))
+}
+
+/// cmap：Windows Unicode BMP（格式 4），每个字形一个分段
+fn encode_cmap(glyphs: &[Glyph]) -> Vec<u8> {
+    let mut segments: Vec<(u16, u16)> = glyphs
+        .iter()
+        .enumerate()
+        .map(|(index, glyph)| (glyph.codepoint as u16, (index + 1) as u16))
+        .collect();
+    segments.push((0xFFFF, 0));
+
+    let seg_count = segments.len() as u16;
+    let search_range = 2 * (1u16 << (15 - seg_count.leading_zeros()));
+    let entry_selector = (search_range / 2).trailing_zeros() as u16;
+
+    let mut subtable = Vec::new();
+    push_u16(&mut subtable, 4); // 格式
+    push_u16(&mut subtable, 16 + 8 * seg_count); // 长度
+    push_u16(&mut subtable, 0); // 语言
+    push_u16(&mut subtable, seg_count * 2);
+    push_u16(&mut subtable, search_range);
+    push_u16(&mut subtable, entry_selector);
+    push_u16(&mut subtable, seg_count * 2 - search_range);
+    for (code, _) in &segments {
+        push_u16(&mut subtable, *code); // endCode
+    }
+    push_u16(&mut subtable, 0); // 保留
+    for (code, _) in &segments {
+        push_u16(&mut subtable, *code); // startCode
+    }
+    for (code, glyph_id) in &segments {
+        // 末尾分段映射到 .notdef
+        let delta = if *code == 0xFFFF { 1 } else { glyph_id.wrapping_sub(*code) };
+        push_u16(&mut subtable, delta);
+    }
+    subtable.extend(std::iter::repeat_n(0, 2 * segments.len())); // idRangeOffset
+
+    let mut cmap = Vec::new();
+    push_u16(&mut cmap, 0); // 版本
+    push_u16(&mut cmap, 1); // 子表数量
+    push_u16(&mut cmap, 3); // 平台：Windows
+    push_u16(&mut cmap, 1); // 编码：Unicode BMP
+    push_u32(&mut cmap, 12); // 子表偏移
+    cmap.extend(subtable);
+    cmap
+}
+
+/// name：族名、样式、唯一标识、全名、版本与 PostScript 名（Windows 平台，UTF-16BE）
+fn encode_name() -> Vec<u8> {
+    let records = [
+        (1u16, FONT_FAMILY),
+        (2, "Regular"),
+        (3, FONT_FAMILY),
+        (4, FONT_FAMILY),
+        (5, "Version 1.0"),
+        (6, FONT_FAMILY),
+    ];
+    let strings: Vec<Vec<u8>> = records
+        .iter()
+        .map(|(_, value)| value.encode_utf16().flat_map(|unit| unit.to_be_bytes()).collect())
+        .collect();
+
+    let mut name = Vec::new();
+    push_u16(&mut name, 0); // 格式
+    push_u16(&mut name, records.len() as u16);
+    push_u16(&mut name, 6 + 12 * records.len() as u16); // 字符串区偏移
+    let mut offset = 0u16;
+    for ((name_id, _), string) in records.iter().zip(&strings) {
+        push_u16(&mut name, 3); // 平台：Windows
+        push_u16(&mut name, 1); // 编码：Unicode BMP
+        push_u16(&mut name, 0x0409); // 语言：en-US
+        push_u16(&mut name, *name_id);
+        push_u16(&mut name, string.len() as u16);
+        push_u16(&mut name, offset);
+        offset += string.len() as u16;
+    }
+    for string in strings {
+        name.extend(string);
+    }
+    name
+}
+
+/// 组装表目录与表数据（表按标签排序、4 字节对齐），并回填 head 的校验和调整值
+fn assemble_sfnt(mut tables: Vec<(&[u8; 4], Vec<u8>)>) -> Vec<u8> {
+    tables.sort_by_key(|(tag, _)| **tag);
+    let num_tables = tables.len() as u16;
+    let entry_selector = 15 - num_tables.leading_zeros() as u16;
+    let search_range = 16 * (1u16 << entry_selector);
+
+    let mut font = Vec::new();
+    push_u32(&mut font, 0x0001_0000);
+    push_u16(&mut font, num_tables);
+    push_u16(&mut font, search_range);
+    push_u16(&mut font, entry_selector);
+    push_u16(&mut font, num_tables * 16 - search_range);
+
+    let mut offset = 12 + 16 * tables.len();
+    let mut head_offset = None;
+    for (tag, data) in &tables {
+        if *tag == b"head" {
+            head_offset = Some(offset);
+        }
+        font.extend_from_slice(*tag);
+        push_u32(&mut font, checksum(data));
+        push_u32(&mut font, offset as u32);
+        push_u32(&mut font, data.len() as u32);
+        offset += data.len().div_ceil(4) * 4;
+    }
+    for (_, data) in &tables {
+        font.extend_from_slice(data);
+        while font.len() % 4 != 0 {
+            font.push(0);
+        }
+    }
+
+    if let Some(head_offset) = head_offset {
+        let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&font));
+        font[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    font
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+fn push_u16(buffer: &mut Vec<u8>, value: u16) {
+    buffer.extend_from_slice(&value.to_be_bytes());
+}
+
+fn push_i16(buffer: &mut Vec<u8>, value: i16) {
+    buffer.extend_from_slice(&value.to_be_bytes());
+}
+
+fn push_u32(buffer: &mut Vec<u8>, value: u32) {
+    buffer.extend_from_slice(&value.to_be_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_u16(data: &[u8], offset: usize) -> u16 {
+        u16::from_be_bytes([data[offset], data[offset + 1]])
+    }
+
+    fn read_u32(data: &[u8], offset: usize) -> u32 {
+        u32::from_be_bytes(data[offset..offset + 4].try_into().unwrap())
+    }
+
+    /// 表目录中查找表，返回（偏移，长度）
+    fn find_table(font: &[u8], tag: &[u8; 4]) -> (usize, usize) {
+        let count = read_u16(font, 4) as usize;
+        (0..count)
+            .map(|i| 12 + 16 * i)
+            .find(|record| &font[*record..*record + 4] == tag)
+            .map(|record| (read_u32(font, record + 8) as usize, read_u32(font, record + 12) as usize))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_svg_outline() {
+        // 24 网格的方块映射到 em 方框：y 轴翻转，基线下移 128
+        let square = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24"><path d="M0 0H24V24H0Z"/></svg>"#;
+        let contours = svg_outline(square).unwrap();
+        assert_eq!(contours.len(), 1);
+        let xs: Vec<i16> = contours[0].iter().map(|p| p.x).collect();
+        let ys: Vec<i16> = contours[0].iter().map(|p| p.y).collect();
+        assert_eq!((xs.iter().min(), xs.iter().max()), (Some(&0), Some(&1024)));
+        assert_eq!((ys.iter().min(), ys.iter().max()), (Some(&-128), Some(&896)));
+        assert!(contours[0].iter().all(|p| p.on_curve));
+
+        // 描边展开为轮廓，曲线产生离线控制点
+        let stroked = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24"><circle cx="12" cy="12" r="8" fill="none" stroke="#000" stroke-width="2"/></svg>"##;
+        let contours = svg_outline(stroked).unwrap();
+        assert_eq!(contours.len(), 2);
+        assert!(contours.iter().flatten().any(|p| !p.on_curve));
+    }
+
+    #[test]
+    fn test_build_icon_font_merges_manifest() {
+        let svg = |d: &str| format!(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24"><path d="{}"/></svg>"#, d);
+        let first = build_icon_font(None, &[("home".to_string(), svg("M2 2H22V22H2Z"))]).unwrap();
+        let second = build_icon_font(
+            Some(&first.manifest),
+            &[
+                ("user".to_string(), svg("M12 2C17 2 22 7 22 12S17 22 12 22 2 17 2 12 7 2 12 2Z")),
+                ("home".to_string(), svg("M4 4H20V20H4Z")),
+            ],
+        )
+        .unwrap();
+
+        // 已有字形码位不变，新字形顺延
+        assert!(second.css.contains(".icon-home::before {\n  content: \"\\e000\";"));
+        assert!(second.css.contains(".icon-user::before {\n  content: \"\\e001\";"));
+        assert!(second.manifest.contains("M4 4H20V20H4Z"));
+
+        let font = &second.ttf;
+        assert_eq!(read_u32(font, 0), 0x0001_0000);
+        assert_eq!(read_u16(font, 4), 10);
+        assert_eq!(font.len() % 4, 0);
+        // 整个字体的校验和为固定值
+        assert_eq!(checksum(font), 0xB1B0_AFBA);
+
+        let (maxp, _) = find_table(font, b"maxp");
+        assert_eq!(read_u16(font, maxp + 4), 3);
+
+        // cmap：U+E000 -> 字形 1，U+E001 -> 字形 2
+        let (cmap, _) = find_table(font, b"cmap");
+        let subtable = cmap + read_u32(font, cmap + 8) as usize;
+        assert_eq!(read_u16(font, subtable), 4);
+        let seg_count = read_u16(font, subtable + 6) as usize / 2;
+        assert_eq!(seg_count, 3);
+        let start_codes = subtable + 16 + 2 * seg_count;
+        let deltas = start_codes + 2 * seg_count;
+        assert_eq!(read_u16(font, start_codes), 0xE000);
+        assert_eq!(read_u16(font, start_codes).wrapping_add(read_u16(font, deltas)), 1);
+        assert_eq!(read_u16(font, start_codes + 2).wrapping_add(read_u16(font, deltas + 2)), 2);
+
+        // loca：.notdef 为空字形，其余字形有数据
+        let (loca, _) = find_table(font, b"loca");
+        let offsets: Vec<u32> = (0..4).map(|i| read_u32(font, loca + 4 * i)).collect();
+        assert_eq!(offsets[0], offsets[1]);
+        assert!(offsets[1] < offsets[2] && offsets[2] < offsets[3]);
+    }
+}
@@ -0,0 +1,616 @@
+// 本地图标库
+// 索引本地 SVG 目录或已安装的 Iconify JSON 图标集，离线提供与 Iconfont 相同结构的搜索结果
+
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use super::types::{IconItem, IconSearchRequest, IconSearchResult};
+use crate::log_debug;
+
+// ============ 常量定义 ============
+
+/// 本地图标 ID 标记位
+///
+/// 本地图标 ID 由哈希生成，置位第 52 位以区别于 Iconfont 的数字 ID，
+/// 同时保持在 JS 安全整数范围（2^53）内，前端选中状态不会丢失精度
+const LOCAL_ID_FLAG: u64 = 1 << 52;
+
+/// SVG 目录最大遍历深度
+const MAX_WALK_DEPTH: usize = 8;
+
+/// Iconify 图标默认尺寸
+const ICONIFY_DEFAULT_SIZE: f64 = 16.0;
+
+// ============ 索引结构 ============
+
+/// SVG 内容来源
+#[derive(Debug, Clone)]
+enum SvgSource {
+    /// SVG 文件（搜索命中时才读取）
+    File(PathBuf),
+    /// Iconify 图标集中拼装好的 SVG
+    Inline(String),
+}
+
+/// 单个本地图标
+#[derive(Debug, Clone)]
+struct LocalIcon {
+    id: u64,
+    name: String,
+    /// 图标集名称（Iconify 的 info.name 或目录名）
+    collection: String,
+    /// 图标集前缀（Iconify prefix 或目录名），用作 font_class 前缀
+    prefix: String,
+    /// 搜索用标签（分类、子目录、名称分词），均为小写
+    tags: Vec<String>,
+    author: Option<String>,
+    svg: SvgSource,
+}
+
+/// 单个本地来源的索引
+struct SourceIndex {
+    /// 来源指纹（含子目录中文件的修改时间与大小），变化时重建索引
+    fingerprint: u64,
+    icons: Vec<LocalIcon>,
+}
+
+/// 本地来源索引缓存（按来源路径）
+static LOCAL_INDEXES: Lazy<RwLock<HashMap<PathBuf, Arc<SourceIndex>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+// ============ Iconify JSON 结构 ============
+
+/// Iconify 图标集（IconifyJSON 格式，只取用到的字段）
+#[derive(Debug, Deserialize)]
+struct IconifyCollection {
+    prefix: String,
+    #[serde(default)]
+    info: Option<IconifyInfo>,
+    icons: BTreeMap<String, IconifyIcon>,
+    #[serde(default)]
+    aliases: BTreeMap<String, IconifyAlias>,
+    /// 分类名 -> 图标名列表
+    #[serde(default)]
+    categories: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    left: Option<f64>,
+    #[serde(default)]
+    top: Option<f64>,
+    #[serde(default)]
+    width: Option<f64>,
+    #[serde(default)]
+    height: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IconifyInfo {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    author: Option<IconifyAuthor>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IconifyAuthor {
+    #[serde(default)]
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IconifyIcon {
+    body: String,
+    #[serde(default)]
+    left: Option<f64>,
+    #[serde(default)]
+    top: Option<f64>,
+    #[serde(default)]
+    width: Option<f64>,
+    #[serde(default)]
+    height: Option<f64>,
+    #[serde(default)]
+    hidden: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct IconifyAlias {
+    parent: String,
+    #[serde(default)]
+    width: Option<f64>,
+    #[serde(default)]
+    height: Option<f64>,
+    #[serde(default)]
+    hidden: bool,
+}
+
+// ============ 搜索 ============
+
+/// 在本地图标库中搜索
+///
+/// 名称完全匹配 > 前缀匹配 > 包含匹配 > 标签匹配，关键词以空白分隔且需全部命中
+pub fn search_icons(sources: &[String], request: &IconSearchRequest) -> Result<IconSearchResult> {
+    if request.query.trim().is_empty() {
+        return Err(anyhow!("搜索关键词不能为空"));
+    }
+    if sources.iter().all(|s| s.trim().is_empty()) {
+        return Err(anyhow!("未配置本地图标库路径"));
+    }
+
+    let terms: Vec<String> = request
+        .query
+        .split_whitespace()
+        .map(|t| t.to_lowercase())
+        .collect();
+    let style = request.style.as_deref().unwrap_or("all");
+
+    let mut matched: Vec<(u32, LocalIcon)> = Vec::new();
+    for source in sources.iter().map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        let index = match load_index(Path::new(source)) {
+            Ok(index) => index,
+            Err(e) => {
+                log_debug!("加载本地图标库 {} 失败: {}", source, e);
+                continue;
+            }
+        };
+        for icon in &index.icons {
+            if !matches_style(&icon.name, style) {
+                continue;
+            }
+            if let Some(score) = score_icon(icon, &terms) {
+                matched.push((score, icon.clone()));
+            }
+        }
+    }
+
+    // 同分按名称排序，保证分页稳定
+    matched.sort_by(|(sa, a), (sb, b)| sb.cmp(sa).then_with(|| a.name.cmp(&b.name)));
+
+    let page = request.page.unwrap_or(1).max(1);
+    let page_size = request.page_size.unwrap_or(50).max(1);
+    let total = matched.len() as u32;
+    let icons = matched
+        .into_iter()
+        .skip(((page - 1) * page_size) as usize)
+        .take(page_size as usize)
+        .map(|(_, icon)| to_icon_item(icon))
+        .collect();
+
+    Ok(IconSearchResult {
+        icons,
+        total,
+        page,
+        page_size,
+        has_more: page * page_size < total,
+    })
+}
+
+/// 按 ID 读取本地图标 SVG
+///
+/// 先查已加载的索引；未命中时（如新进程中直接保存图标）按配置的本地来源建立索引后再查找
+pub fn lookup_svg(id: u64) -> Result<String> {
+    let indexed = LOCAL_INDEXES.read().ok().and_then(|indexes| {
+        indexes
+            .values()
+            .flat_map(|index| index.icons.iter())
+            .find(|icon| icon.id == id)
+            .cloned()
+    });
+    if let Some(icon) = indexed {
+        return read_svg(&icon);
+    }
+
+    let sources = crate::config::load_standalone_config()
+        .ok()
+        .and_then(|c| c.mcp_config.icon_local_sources)
+        .unwrap_or_default();
+    lookup_in_sources(&sources, id)
+}
+
+fn lookup_in_sources(sources: &[String], id: u64) -> Result<String> {
+    for source in sources.iter().map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        let Ok(index) = load_index(Path::new(source)) else {
+            continue;
+        };
+        if let Some(icon) = index.icons.iter().find(|icon| icon.id == id) {
+            return read_svg(icon);
+        }
+    }
+    Err(anyhow!("本地图标不存在（图标库路径或文件可能已变更）: {}", id))
+}
+
+/// 是否为本地图标 ID
+pub fn is_local_id(id: u64) -> bool {
+    id & LOCAL_ID_FLAG != 0
+}
+
+/// 清空本地来源索引（下次搜索时重建）
+pub fn clear_index() -> usize {
+    match LOCAL_INDEXES.write() {
+        Ok(mut indexes) => {
+            let count = indexes.len();
+            indexes.clear();
+            count
+        }
+        Err(_) => 0,
+    }
+}
+
+fn score_icon(icon: &LocalIcon, terms: &[String]) -> Option<u32> {
+    let name = icon.name.to_lowercase();
+    let mut score = 0;
+    for term in terms {
+        score += if name == *term {
+            100
+        } else if name.starts_with(term.as_str()) {
+            60
+        } else if name.contains(term.as_str()) {
+            40
+        } else if icon.tags.iter().any(|tag| tag == term) {
+            30
+        } else if icon.tags.iter().any(|tag| tag.contains(term.as_str())) {
+            15
+        } else {
+            return None;
+        };
+    }
+    Some(score)
+}
+
+/// 按名称约定过滤风格：面性图标通常以 fill/filled/solid 结尾，线性为其余
+fn matches_style(name: &str, style: &str) -> bool {
+    let name = name.to_lowercase();
+    let is_fill = ["fill", "filled", "solid"]
+        .iter()
+        .any(|marker| name.split(['-', '_']).any(|part| part == *marker));
+    match style {
+        "fill" => is_fill,
+        "line" => !is_fill,
+        _ => true,
+    }
+}
+
+fn to_icon_item(icon: LocalIcon) -> IconItem {
+    let svg_content = read_svg(&icon).ok();
+    IconItem {
+        id: icon.id,
+        font_class: format!("{}-{}", icon.prefix, icon.name),
+        name: icon.name,
+        unicode: None,
+        svg_content,
+        preview_url: None,
+        author: icon.author,
+        repository_name: Some(icon.collection),
+        repository_id: None,
+        created_at: None,
+    }
+}
+
+fn read_svg(icon: &LocalIcon) -> Result<String> {
+    match &icon.svg {
+        SvgSource::Inline(svg) => Ok(svg.clone()),
+        SvgSource::File(path) => {
+            fs::read_to_string(path).map_err(|e| anyhow!("读取 {} 失败: {}", path.display(), e))
+        }
+    }
+}
+
+// ============ 索引构建 ============
+
+/// 加载来源索引，来源未变化时复用缓存
+fn load_index(path: &Path) -> Result<Arc<SourceIndex>> {
+    let fingerprint = source_fingerprint(path)?;
+
+    if let Some(index) = LOCAL_INDEXES.read().ok().and_then(|i| i.get(path).cloned()) {
+        if index.fingerprint == fingerprint {
+            return Ok(index);
+        }
+    }
+
+    let icons = build_index(path)?;
+    log_debug!("本地图标库已索引: {} ({} 个图标)", path.display(), icons.len());
+    let index = Arc::new(SourceIndex { fingerprint, icons });
+    if let Ok(mut indexes) = LOCAL_INDEXES.write() {
+        indexes.insert(path.to_path_buf(), index.clone());
+    }
+    Ok(index)
+}
+
+/// 来源指纹：文件取自身的修改时间与大小，目录递归汇总其中所有条目的路径、修改时间与大小
+///
+/// 只读取元数据，子目录中新增、删除或修改 SVG 都会改变指纹
+fn source_fingerprint(path: &Path) -> Result<u64> {
+    let metadata = fs::metadata(path).map_err(|e| anyhow!("路径不可访问: {}", e))?;
+    let mut fingerprint = entry_fingerprint(path, &metadata);
+    if metadata.is_dir() {
+        for entry in source_walker(path).filter_map(|e| e.ok()) {
+            if let Ok(metadata) = entry.metadata() {
+                // 与遍历顺序无关
+                fingerprint = fingerprint.wrapping_add(entry_fingerprint(entry.path(), &metadata));
+            }
+        }
+    }
+    Ok(fingerprint)
+}
+
+fn entry_fingerprint(path: &Path, metadata: &fs::Metadata) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    metadata.len().hash(&mut hasher);
+    metadata.modified().ok().hash(&mut hasher);
+    hasher.finish()
+}
+
+fn source_walker(root: &Path) -> ignore::Walk {
+    ignore::WalkBuilder::new(root)
+        .max_depth(Some(MAX_WALK_DEPTH))
+        .git_ignore(false)
+        .build()
+}
+
+/// 识别来源类型并建立索引
+///
+/// - `*.json` 文件：Iconify 图标集
+/// - 包含 `icons.json` 的目录：`@iconify-json/*` 包
+/// - 其他目录：递归索引其中的 `*.svg`
+fn build_index(path: &Path) -> Result<Vec<LocalIcon>> {
+    if path.is_file() {
+        if path.extension().and_then(|e| e.to_str()) == Some("json") {
+            return parse_iconify_file(path);
+        }
+        return Err(anyhow!("不支持的图标库文件: {}", path.display()));
+    }
+
+    let package_json = path.join("icons.json");
+    if package_json.is_file() {
+        return parse_iconify_file(&package_json);
+    }
+
+    index_svg_dir(path)
+}
+
+fn parse_iconify_file(path: &Path) -> Result<Vec<LocalIcon>> {
+    let content = fs::read_to_string(path).map_err(|e| anyhow!("读取 {} 失败: {}", path.display(), e))?;
+    parse_iconify(&content, &path.to_string_lossy())
+}
+
+/// 解析 Iconify JSON 图标集，别名作为独立图标展开
+fn parse_iconify(content: &str, source_key: &str) -> Result<Vec<LocalIcon>> {
+    let collection: IconifyCollection =
+        serde_json::from_str(content).map_err(|e| anyhow!("Iconify JSON 解析失败: {}", e))?;
+
+    let collection_name = collection
+        .info
+        .as_ref()
+        .and_then(|i| i.name.clone())
+        .unwrap_or_else(|| collection.prefix.clone());
+    let author = collection
+        .info
+        .as_ref()
+        .and_then(|i| i.author.as_ref())
+        .and_then(|a| a.name.clone());
+
+    // 图标名 -> 所属分类
+    let mut categories: HashMap<&str, Vec<String>> = HashMap::new();
+    for (category, names) in &collection.categories {
+        for name in names {
+            categories.entry(name.as_str()).or_default().push(category.to_lowercase());
+        }
+    }
+
+    let make_icon = |name: &str, svg: String, parent: Option<&str>| {
+        let mut tags = name_tokens(name);
+        let lookup = parent.unwrap_or(name);
+        if let Some(found) = categories.get(lookup) {
+            tags.extend(found.iter().cloned());
+        }
+        if let Some(parent) = parent {
+            tags.push(parent.to_lowercase());
+        }
+        LocalIcon {
+            id: local_icon_id(&format!("{}:{}:{}", source_key, collection.prefix, name)),
+            name: name.to_string(),
+            collection: collection_name.clone(),
+            prefix: collection.prefix.clone(),
+            tags,
+            author: author.clone(),
+            svg: SvgSource::Inline(svg),
+        }
+    };
+
+    let mut icons = Vec::new();
+    for (name, icon) in collection.icons.iter().filter(|(_, i)| !i.hidden) {
+        icons.push(make_icon(name, iconify_svg(&collection, icon, None, None), None));
+    }
+
+    for (name, alias) in collection.aliases.iter().filter(|(_, a)| !a.hidden) {
+        // 只展开直接指向图标的别名，别名链在实际图标集中极少出现
+        if let Some(parent) = collection.icons.get(&alias.parent) {
+            let svg = iconify_svg(&collection, parent, alias.width, alias.height);
+            icons.push(make_icon(name, svg, Some(&alias.parent)));
+        }
+    }
+
+    Ok(icons)
+}
+
+/// 将 Iconify 图标数据拼装为完整 SVG
+fn iconify_svg(collection: &IconifyCollection, icon: &IconifyIcon, width: Option<f64>, height: Option<f64>) -> String {
+    let left = icon.left.or(collection.left).unwrap_or(0.0);
+    let top = icon.top.or(collection.top).unwrap_or(0.0);
+    let width = width
+        .or(icon.width)
+        .or(collection.width)
+        .unwrap_or(ICONIFY_DEFAULT_SIZE);
+    let height = height
+        .or(icon.height)
+        .or(collection.height)
+        .unwrap_or(ICONIFY_DEFAULT_SIZE);
+
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="{l} {t} {w} {h}">{body}</svg>"#,
+        w = format_number(width),
+        h = format_number(height),
+        l = format_number(left),
+        t = format_number(top),
+        body = icon.body
+    )
+}
+
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
+
+/// 递归索引目录中的 SVG 文件，子目录名作为标签
+fn index_svg_dir(root: &Path) -> Result<Vec<LocalIcon>> {
+    if !root.is_dir() {
+        return Err(anyhow!("不是目录: {}", root.display()));
+    }
+
+    let collection = root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "local".to_string());
+    let root_key = root.to_string_lossy();
+
+    let mut icons = Vec::new();
+    for entry in source_walker(root).filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let is_svg = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.eq_ignore_ascii_case("svg"))
+            .unwrap_or(false);
+        if !is_svg || !path.is_file() {
+            continue;
+        }
+
+        let Some(name) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let mut tags = name_tokens(&name);
+        if let Some(parent) = relative.parent() {
+            tags.extend(
+                parent
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().to_lowercase()),
+            );
+        }
+
+        icons.push(LocalIcon {
+            id: local_icon_id(&format!("{}:{}", root_key, relative.to_string_lossy())),
+            name,
+            collection: collection.clone(),
+            prefix: collection.clone(),
+            tags,
+            author: None,
+            svg: SvgSource::File(path.to_path_buf()),
+        });
+    }
+
+    Ok(icons)
+}
+
+/// 名称按 - _ 空格分词作为标签
+fn name_tokens(name: &str) -> Vec<String> {
+    name.split(['-', '_', ' '])
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .collect()
+}
+
+/// 由来源与图标名生成稳定 ID（FNV-1a）
+fn local_icon_id(key: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in key.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    LOCAL_ID_FLAG | (hash & (LOCAL_ID_FLAG - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"{
+        "prefix": "demo",
+        "info": { "name": "Demo Icons", "author": { "name": "Demo" } },
+        "width": 24,
+        "height": 24,
+        "icons": {
+            "arrow-up": { "body": "<path d=\"M12 4l8 8\"/>" },
+            "heart-fill": { "body": "<path d=\"M1 1\"/>", "width": 20 },
+            "old": { "body": "<path/>", "hidden": true }
+        },
+        "aliases": { "up": { "parent": "arrow-up" } },
+        "categories": { "Arrows": ["arrow-up"] }
+    }"#;
+
+    fn request(query: &str) -> IconSearchRequest {
+        IconSearchRequest {
+            query: query.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_iconify() {
+        let icons = parse_iconify(SAMPLE, "sample").unwrap();
+        let names: Vec<&str> = icons.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, vec!["arrow-up", "heart-fill", "up"]);
+
+        let heart = read_svg(&icons[1]).unwrap();
+        assert!(heart.contains(r#"viewBox="0 0 20 24""#));
+        assert!(icons[0].tags.contains(&"arrows".to_string()));
+        // 别名继承父图标的分类
+        assert!(icons[2].tags.contains(&"arrows".to_string()));
+        assert!(icons.iter().all(|i| is_local_id(i.id) && i.id < (1 << 53)));
+    }
+
+    #[test]
+    fn test_search_svg_dir() {
+        let dir = std::env::temp_dir().join(format!("sanshu-local-icons-{}", std::process::id()));
+        fs::create_dir_all(dir.join("arrows")).unwrap();
+        fs::write(dir.join("arrows").join("arrow-left.svg"), "<svg/>").unwrap();
+        fs::write(dir.join("home.svg"), "<svg id=\"home\"/>").unwrap();
+        fs::write(dir.join("home-solid.svg"), "<svg/>").unwrap();
+        let sources = vec![dir.to_string_lossy().to_string()];
+
+        let result = search_icons(&sources, &request("home")).unwrap();
+        assert_eq!(result.total, 2);
+        assert_eq!(result.icons[0].name, "home");
+        assert_eq!(result.icons[0].svg_content.as_deref(), Some("<svg id=\"home\"/>"));
+        let home_id = result.icons[0].id;
+        assert_eq!(lookup_svg(home_id).unwrap(), "<svg id=\"home\"/>");
+
+        // 子目录名作为标签参与搜索
+        let result = search_icons(&sources, &request("arrows")).unwrap();
+        assert_eq!(result.icons[0].name, "arrow-left");
+
+        let mut line_only = request("home");
+        line_only.style = Some("line".to_string());
+        assert_eq!(search_icons(&sources, &line_only).unwrap().total, 1);
+
+        // 子目录中新增文件也会触发重建索引
+        fs::write(dir.join("arrows").join("home-arrow.svg"), "<svg/>").unwrap();
+        assert_eq!(search_icons(&sources, &request("home")).unwrap().total, 3);
+
+        // 未搜索过（索引已清空）时按来源重建索引查找
+        LOCAL_INDEXES.write().unwrap().remove(dir.as_path());
+        assert_eq!(lookup_in_sources(&sources, home_id).unwrap(), "<svg id=\"home\"/>");
+        assert!(lookup_in_sources(&sources, local_icon_id("missing")).is_err());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
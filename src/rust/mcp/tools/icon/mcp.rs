@@ -5,15 +5,298 @@ use rmcp::model::{Tool, CallToolResult, ErrorData as McpError};
 use std::borrow::Cow;
 use std::sync::Arc;
 
-use crate::mcp::types::TuRequest;
-use crate::mcp::handlers::create_icon_popup;
+use rmcp::model::{Content, ElicitationAction};
+use rmcp::{Peer, RoleServer};
+use std::path::PathBuf;
+
+use super::api;
+use super::commands::{find_existing_icon_files, icon_config_from_mcp, save_icons};
+use super::export::ProjectIconConfig;
+use super::types::{IconConfig, IconFormat, IconProvider, IconSaveRequest, IconSaveResult, IconSearchRequest};
+use crate::config::load_standalone_config;
+use crate::mcp::types::{IconSaveToolRequest, IconSearchToolRequest, TuRequest};
+use crate::mcp::handlers::{create_icon_popup, elicit, Elicitation};
+use crate::mcp::tools::interaction::ZhiDangerLevel;
+
+/// 无界面搜索的默认每页数量
+const HEADLESS_PAGE_SIZE: u32 = 20;
 
 /// 图标工坊 MCP 工具
 /// 
-/// 提供交互式图标选择功能，通过弹窗让用户搜索、预览、选择并保存图标
+/// 提供交互式图标选择功能（tu 弹窗），以及供 AI 直接调用的无界面搜索与保存
 pub struct IconTool;
 
 impl IconTool {
+    /// 获取图标工坊的全部工具定义（tu、icon_search、icon_save）
+    pub fn get_tool_definitions() -> Vec<Tool> {
+        let mut tools = vec![Self::get_tool_definition()];
+
+        let search_schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "query": { "type": "string", "description": "搜索关键词" },
+                "style": {
+                    "type": "string",
+                    "enum": ["line", "fill", "flat", "all"],
+                    "description": "图标风格：line(线性)、fill(面性)、flat(扁平)、all(全部)"
+                },
+                "fills": {
+                    "type": "string",
+                    "enum": ["single", "multi", "all"],
+                    "description": "填充类型：single(单色)、multi(多色)、all(全部)"
+                },
+                "page": { "type": "number", "description": "页码，默认 1" },
+                "page_size": { "type": "number", "description": "每页数量，默认 20" },
+                "provider": {
+                    "type": "string",
+                    "enum": ["iconfont", "local"],
+                    "description": "图标来源（可选，默认使用配置中的来源）"
+                }
+            },
+            "required": ["query"]
+        });
+
+        if let serde_json::Value::Object(schema_map) = search_schema {
+            tools.push(Tool {
+                name: Cow::Borrowed("icon_search"),
+                description: Some(Cow::Borrowed(
+                    "无界面图标搜索。返回图标列表（id、name、font_class 等），可将结果直接传给 icon_save 保存；需要用户挑选时请使用 tu。"
+                )),
+                input_schema: Arc::new(schema_map),
+                annotations: None,
+                icons: None,
+                meta: None,
+                output_schema: None,
+                title: Some("图标搜索".to_string()),
+            });
+        }
+
+        let save_schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "icons": {
+                    "type": "array",
+                    "description": "要保存的图标（icon_search 返回的条目，至少包含 id 与 name）",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "id": { "type": "number" },
+                            "name": { "type": "string" },
+                            "font_class": { "type": "string" }
+                        },
+                        "required": ["id", "name"]
+                    }
+                },
+                "save_path": {
+                    "type": "string",
+                    "description": "保存目录（相对路径基于 project_root，默认使用配置）"
+                },
+                "project_root": { "type": "string", "description": "项目根目录路径" },
+                "format": {
+                    "type": "string",
+                    "enum": ["svg", "png", "both", "sprite", "font", "png_set", "favicon", "react", "vue", "svelte"],
+                    "description": "保存格式（默认使用配置）"
+                },
+                "png_size": { "type": "number", "description": "PNG 尺寸（PNG 相关格式有效）" },
+                "overwrite": {
+                    "type": "boolean",
+                    "description": "是否覆盖已存在的文件（未指定时询问用户）"
+                },
+                "optimize": {
+                    "type": "object",
+                    "description": "SVG 优化选项（未指定时默认优化）",
+                    "properties": {
+                        "enabled": { "type": "boolean", "description": "是否优化（关闭时仅校验 SVG 能否解析），默认 true" },
+                        "grid_size": { "type": "number", "description": "归一化后的正方形网格尺寸（如 24），未指定时取原 viewBox 的长边" },
+                        "current_color": { "type": "boolean", "description": "单色图标的颜色转换为 currentColor，默认 false" },
+                        "precision": { "type": "integer", "minimum": 0, "description": "坐标保留的小数位数，默认 2" }
+                    }
+                }
+            },
+            "required": ["icons"]
+        });
+
+        if let serde_json::Value::Object(schema_map) = save_schema {
+            tools.push(Tool {
+                name: Cow::Borrowed("icon_save"),
+                description: Some(Cow::Borrowed(
+                    "无界面图标保存。将 icon_search 返回的图标保存到项目目录，返回写入的文件路径。"
+                )),
+                input_schema: Arc::new(schema_map),
+                annotations: None,
+                icons: None,
+                meta: None,
+                output_schema: None,
+                title: Some("图标保存".to_string()),
+            });
+        }
+
+        tools
+    }
+
+    /// 无界面工具入口（icon_search / icon_save）
+    pub async fn call_tool(
+        tool_name: &str,
+        arguments: serde_json::Value,
+        peer: Option<&Peer<RoleServer>>,
+    ) -> Result<CallToolResult, McpError> {
+        match tool_name {
+            "icon_search" => {
+                let request: IconSearchToolRequest = serde_json::from_value(arguments)
+                    .map_err(|e| McpError::invalid_params(format!("参数解析失败: {}", e), None))?;
+                Self::search(request).await
+            }
+            "icon_save" => {
+                let request: IconSaveToolRequest = serde_json::from_value(arguments)
+                    .map_err(|e| McpError::invalid_params(format!("参数解析失败: {}", e), None))?;
+                Self::save(peer, request).await
+            }
+            _ => Err(McpError::invalid_params(format!("未知的工具: {}", tool_name), None)),
+        }
+    }
+
+    /// 执行 "icon_search"：按配置的来源搜索，结果不含 SVG 内容
+    async fn search(request: IconSearchToolRequest) -> Result<CallToolResult, McpError> {
+        let provider = match request.provider.as_deref() {
+            Some("iconfont") => Some(IconProvider::Iconfont),
+            Some("local") => Some(IconProvider::Local),
+            Some(other) => {
+                return Err(McpError::invalid_params(format!("未知的图标来源: {}", other), None));
+            }
+            None => None,
+        };
+        let search_request = IconSearchRequest {
+            query: request.query,
+            style: request.style,
+            fills: request.fills,
+            page: request.page,
+            page_size: Some(request.page_size.unwrap_or(HEADLESS_PAGE_SIZE)),
+            provider,
+            ..Default::default()
+        };
+
+        let config = load_icon_config(None);
+        let mut result = api::search_with_config(search_request, &config)
+            .await
+            .map_err(|e| McpError::internal_error(format!("图标搜索失败: {}", e), None))?;
+        // SVG 内容已缓存，保存时按 ID 取回，避免占用上下文
+        for icon in &mut result.icons {
+            icon.svg_content = None;
+        }
+
+        let lines = result
+            .icons
+            .iter()
+            .map(|icon| {
+                let source = icon.repository_name.as_deref().unwrap_or("-");
+                format!("• {} {} ({}) [{}]", icon.id, icon.name, icon.font_class, source)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let message = format!(
+            "找到 {} 个图标（第 {} 页{}）：\n{}",
+            result.total,
+            result.page,
+            if result.has_more { "，还有更多" } else { "" },
+            lines
+        );
+
+        let mut call_result = CallToolResult::success(vec![Content::text(message)]);
+        call_result.structured_content = serde_json::to_value(&result).ok();
+        Ok(call_result)
+    }
+
+    /// 执行 "icon_save"：保存到项目目录，已存在的文件按 overwrite 或询问用户处理
+    async fn save(peer: Option<&Peer<RoleServer>>, request: IconSaveToolRequest) -> Result<CallToolResult, McpError> {
+        if request.icons.is_empty() {
+            return Err(McpError::invalid_params("icons 不能为空".to_string(), None));
+        }
+
+        let config = load_icon_config(request.project_root.as_deref());
+        let save_path = resolve_save_path(
+            request.save_path.as_deref().or(config.default_save_path.as_deref()).unwrap_or("assets/icons"),
+            request.project_root.as_deref(),
+        );
+        let format = request
+            .format
+            .as_deref()
+            .map(IconFormat::from_config_value)
+            .unwrap_or(config.default_format);
+
+        let save_request = IconSaveRequest {
+            icons: request.icons,
+            save_path: save_path.to_string_lossy().to_string(),
+            format,
+            png_size: request.png_size.or(config.default_png_size),
+            overwrite: request.overwrite,
+            optimize: request.optimize,
+        };
+
+        let result = match Self::save_with_overwrite_confirm(peer, save_request).await {
+            Ok(result) => result,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+        let mut lines = Vec::new();
+        for item in &result.items {
+            if item.success {
+                lines.extend(item.saved_paths.iter().map(|p| format!("• {}", p)));
+            } else {
+                lines.push(format!("✗ {}: {}", item.name, item.error.as_deref().unwrap_or("未知错误")));
+            }
+        }
+        lines.extend(result.bundle_files.iter().map(|p| format!("• {}", p)));
+        let message = format!(
+            "已保存 {} 个图标到 {}（失败 {} 个）：\n{}",
+            result.success_count,
+            result.save_path,
+            result.failed_count,
+            lines.join("\n")
+        );
+
+        let mut call_result = CallToolResult::success(vec![Content::text(message)]);
+        call_result.structured_content = serde_json::to_value(&result).ok();
+        Ok(call_result)
+    }
+
+    /// 保存图标，目标文件已存在时先询问是否覆盖
+    ///
+    /// 接受则覆盖，拒绝则跳过已存在的文件，取消则不保存
+    async fn save_with_overwrite_confirm(
+        peer: Option<&Peer<RoleServer>>,
+        mut request: IconSaveRequest,
+    ) -> Result<IconSaveResult, String> {
+        let existing = find_existing_icon_files(&request);
+        if !existing.is_empty() && request.overwrite.is_none() {
+            let list = existing
+                .iter()
+                .take(10)
+                .map(|p| format!("- `{}`", p.display()))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let more = if existing.len() > 10 {
+                format!("\n- …… 另有 {} 个文件", existing.len() - 10)
+            } else {
+                String::new()
+            };
+            let elicitation = Elicitation::confirm(
+                format!("以下 {} 个文件已存在，是否覆盖？\n\n{}{}", existing.len(), list, more),
+                "覆盖已存在的文件",
+                ZhiDangerLevel::Medium,
+            );
+
+            let outcome = elicit(peer, &elicitation)
+                .await
+                .map_err(|e| format!("询问是否覆盖失败: {}", e))?;
+            match outcome.action {
+                ElicitationAction::Cancel => return Err("用户取消了图标保存".to_string()),
+                _ => request.overwrite = Some(outcome.is_confirmed("confirm")),
+            }
+        }
+
+        save_icons(request).await
+    }
+
     /// 获取 "tu" 工具定义（交互式图标选择）
     /// 
     /// 返回 MCP 协议规范的工具定义
@@ -45,7 +328,7 @@ impl IconTool {
             Tool {
                 name: Cow::Borrowed("tu"),
                 description: Some(Cow::Borrowed(
-                    "交互式图标选择工具。打开可视化界面让用户搜索、预览、选择并保存 Iconfont 图标。支持筛选风格、分页浏览和批量保存。"
+                    "交互式图标选择工具。打开可视化界面让用户搜索、预览、选择并保存 Iconfont 或本地图标库（SVG 目录、Iconify 图标集）中的图标。支持筛选风格、分页浏览和批量保存。无需用户挑选时请使用 icon_search 与 icon_save。"
                 )),
                 input_schema: Arc::new(schema_map),
                 annotations: None,
@@ -138,3 +421,49 @@ pub struct IconToolInfo {
     pub has_config: bool,
 }
 
+/// 读取图标工坊配置，并应用项目级配置
+fn load_icon_config(project_root: Option<&str>) -> IconConfig {
+    let mut config = load_standalone_config()
+        .map(|c| icon_config_from_mcp(&c.mcp_config))
+        .unwrap_or_default();
+    if let Some(project_config) = project_root.and_then(|root| ProjectIconConfig::load(&PathBuf::from(root))) {
+        project_config.apply_to(&mut config);
+    }
+    config
+}
+
+/// 解析保存目录：相对路径基于项目根目录（未提供时基于当前目录）
+fn resolve_save_path(save_path: &str, project_root: Option<&str>) -> PathBuf {
+    let path = PathBuf::from(save_path);
+    if path.is_absolute() {
+        return path;
+    }
+    match project_root {
+        Some(root) => PathBuf::from(root).join(path),
+        None => std::env::current_dir().map(|dir| dir.join(&path)).unwrap_or(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_save_path() {
+        let root = std::env::temp_dir();
+        let resolved = resolve_save_path("assets/icons", Some(root.to_str().unwrap()));
+        assert_eq!(resolved, root.join("assets/icons"));
+
+        let absolute = root.join("icons");
+        assert_eq!(resolve_save_path(absolute.to_str().unwrap(), None), absolute);
+    }
+
+    #[test]
+    fn test_tool_definitions() {
+        let names: Vec<_> = IconTool::get_tool_definitions()
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .collect();
+        assert_eq!(names, vec!["tu", "icon_search", "icon_save"]);
+    }
+}
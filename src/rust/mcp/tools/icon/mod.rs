@@ -2,8 +2,13 @@
 // 用于图标搜索和管理的 MCP 工具
 
 pub mod api;
+pub mod cache;
 pub mod commands;
+pub mod export;
+pub mod font;
+pub mod local;
 pub mod mcp;
+pub mod optimize;
 pub mod types;
 
 // 重新导出工具以便访问
@@ -0,0 +1,413 @@
+// SVG 优化
+// 基于 usvg 解析树重新输出 SVG：去除元数据与注释、合并变换、归一化 viewBox、坐标取整
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use usvg::{TreeParsing, TreeWriting};
+
+// ============ 常量定义 ============
+
+/// 默认坐标精度（小数位数）
+const DEFAULT_PRECISION: u8 = 2;
+
+/// 最大坐标精度
+const MAX_PRECISION: u8 = 6;
+
+// ============ 类型定义 ============
+
+/// SVG 优化选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SvgOptimizeOptions {
+    /// 是否优化（关闭时仅校验 SVG 能否解析）
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// 归一化后的正方形网格尺寸（如 24），未指定时取原 viewBox 的长边
+    #[serde(default)]
+    pub grid_size: Option<f32>,
+    /// 单色图标的颜色转换为 currentColor
+    #[serde(default)]
+    pub current_color: bool,
+    /// 坐标保留的小数位数
+    #[serde(default = "default_precision")]
+    pub precision: u8,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_precision() -> u8 {
+    DEFAULT_PRECISION
+}
+
+impl Default for SvgOptimizeOptions {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            grid_size: None,
+            current_color: false,
+            precision: default_precision(),
+        }
+    }
+}
+
+/// 单个图标的优化结果统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SvgOptimizeReport {
+    /// 原始字节数
+    pub original_bytes: usize,
+    /// 优化后字节数
+    pub optimized_bytes: usize,
+    /// 是否已合并变换并归一化 viewBox
+    ///
+    /// 含裁剪、蒙版、滤镜、渐变、图片或文字的 SVG 仅做精简输出
+    pub flattened: bool,
+}
+
+impl SvgOptimizeReport {
+    /// 节省的字节数
+    pub fn saved_bytes(&self) -> usize {
+        self.original_bytes.saturating_sub(self.optimized_bytes)
+    }
+}
+
+/// 展平后的路径
+struct FlatPath {
+    data: String,
+    attrs: Vec<(&'static str, String)>,
+    /// 用到的纯色（用于判断是否单色）
+    colors: Vec<usvg::Color>,
+}
+
+// ============ 优化入口 ============
+
+/// 校验 SVG 能否解析，失败时返回错误而不是写入无效文件
+pub fn validate_svg(svg: &str) -> Result<usvg::Tree> {
+    usvg::Tree::from_str(svg, &usvg::Options::default()).map_err(|e| anyhow!("SVG 解析失败: {}", e))
+}
+
+/// 优化 SVG 并返回优化结果与统计
+pub fn optimize_svg(svg: &str, options: &SvgOptimizeOptions) -> Result<(String, SvgOptimizeReport)> {
+    // usvg 会把 currentColor 解析为黑色：解析前替换为占位色，输出时还原
+    let placeholder = current_color_placeholder(svg);
+    let tree = match &placeholder {
+        Some(placeholder) => validate_svg(&replace_ignore_ascii_case(svg, "currentcolor", placeholder))?,
+        None => validate_svg(svg)?,
+    };
+    let precision = options.precision.min(MAX_PRECISION);
+
+    let (mut optimized, flattened) = match flatten_tree(&tree, options, precision) {
+        Some(flattened) => (flattened, true),
+        None => {
+            // 复杂 SVG 交由 usvg 输出：注释、元数据与未使用的定义同样会被去除
+            let xml_options = usvg::XmlOptions {
+                coordinates_precision: precision,
+                transforms_precision: precision.max(DEFAULT_PRECISION + 2),
+                ..Default::default()
+            };
+            (tree.to_string(&xml_options), false)
+        }
+    };
+    if let Some(placeholder) = &placeholder {
+        optimized = optimized.replace(placeholder.as_str(), "currentColor");
+    }
+
+    let report = SvgOptimizeReport {
+        original_bytes: svg.len(),
+        optimized_bytes: optimized.len(),
+        flattened,
+    };
+    Ok((optimized, report))
+}
+
+// ============ currentColor 保留 ============
+
+/// 源 SVG 使用 currentColor 时，选一个源中未出现的颜色作为占位色
+fn current_color_placeholder(svg: &str) -> Option<String> {
+    let lower = svg.to_ascii_lowercase();
+    if !lower.contains("currentcolor") {
+        return None;
+    }
+    (0..=u16::MAX)
+        .map(|n| format!("#fe{:04x}", n))
+        .find(|candidate| !lower.contains(candidate.as_str()))
+}
+
+/// 忽略 ASCII 大小写替换全部匹配（pattern 须为小写）
+fn replace_ignore_ascii_case(text: &str, pattern: &str, replacement: &str) -> String {
+    let lower = text.to_ascii_lowercase();
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, _) in lower.match_indices(pattern) {
+        result.push_str(&text[last..start]);
+        result.push_str(replacement);
+        last = start + pattern.len();
+    }
+    result.push_str(&text[last..]);
+    result
+}
+
+// ============ 展平输出 ============
+
+/// 将所有路径的变换与 viewBox 归一化合并进路径数据，输出无分组的 SVG
+///
+/// 遇到无法展平的特性时返回 None
+fn flatten_tree(tree: &usvg::Tree, options: &SvgOptimizeOptions, precision: u8) -> Option<String> {
+    let rect = tree.view_box.rect;
+    let side = rect.width().max(rect.height());
+    let target = options.grid_size.filter(|size| *size > 0.0).unwrap_or(side);
+    let scale = target / side;
+
+    // 内容平移到原点并在正方形内居中，再缩放到目标网格
+    let normalize = usvg::Transform::from_translate(
+        -rect.x() + (side - rect.width()) / 2.0,
+        -rect.y() + (side - rect.height()) / 2.0,
+    )
+    .post_scale(scale, scale);
+
+    let mut paths = Vec::new();
+    collect_paths(&tree.root, normalize, precision, &mut paths)?;
+
+    // 只有一种颜色时才转换为 currentColor，多色图标保留原色
+    let colors: Vec<usvg::Color> = paths.iter().flat_map(|p| p.colors.iter().copied()).collect();
+    let single_color = colors.windows(2).all(|pair| pair[0] == pair[1]);
+    let use_current_color = options.current_color && single_color && !colors.is_empty();
+
+    let mut output = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {0} {0}">"#,
+        format_number(target, precision)
+    );
+    for path in paths {
+        let _ = write!(output, r#"<path d="{}""#, path.data);
+        for (name, value) in path.attrs {
+            let value = if use_current_color && value.starts_with('#') {
+                "currentColor".to_string()
+            } else {
+                value
+            };
+            let _ = write!(output, r#" {}="{}""#, name, value);
+        }
+        output.push_str("/>");
+    }
+    output.push_str("</svg>");
+    Some(output)
+}
+
+/// 递归收集路径，沿途累积各分组的变换
+fn collect_paths(
+    group: &usvg::Group,
+    parent_transform: usvg::Transform,
+    precision: u8,
+    out: &mut Vec<FlatPath>,
+) -> Option<()> {
+    if group.clip_path.is_some()
+        || group.mask.is_some()
+        || !group.filters.is_empty()
+        || group.opacity.get() < 1.0
+        || group.blend_mode != usvg::BlendMode::Normal
+    {
+        return None;
+    }
+
+    let transform = parent_transform.pre_concat(group.transform);
+    for node in &group.children {
+        match node {
+            usvg::Node::Group(child) => collect_paths(child, transform, precision, out)?,
+            usvg::Node::Path(path) => {
+                if path.visibility != usvg::Visibility::Visible {
+                    continue;
+                }
+                out.push(flatten_path(path, transform, precision)?);
+            }
+            usvg::Node::Image(_) | usvg::Node::Text(_) => return None,
+        }
+    }
+    Some(())
+}
+
+fn flatten_path(path: &usvg::Path, transform: usvg::Transform, precision: u8) -> Option<FlatPath> {
+    let data = (*path.data).clone().transform(transform)?;
+
+    let mut attrs = Vec::new();
+    let mut colors = Vec::new();
+
+    match &path.fill {
+        Some(fill) => {
+            let color = solid_color(&fill.paint)?;
+            colors.push(color);
+            attrs.push(("fill", hex_color(color)));
+            if fill.rule == usvg::FillRule::EvenOdd {
+                attrs.push(("fill-rule", "evenodd".to_string()));
+            }
+            if fill.opacity.get() < 1.0 {
+                attrs.push(("fill-opacity", format_number(fill.opacity.get(), 2)));
+            }
+        }
+        None => attrs.push(("fill", "none".to_string())),
+    }
+
+    if let Some(stroke) = &path.stroke {
+        let color = solid_color(&stroke.paint)?;
+        colors.push(color);
+        // 描边宽度随变换等比缩放（取面积比的平方根）
+        let stroke_scale = (transform.sx * transform.sy - transform.kx * transform.ky).abs().sqrt();
+        attrs.push(("stroke", hex_color(color)));
+        attrs.push(("stroke-width", format_number(stroke.width.get() * stroke_scale, precision)));
+        match stroke.linecap {
+            usvg::LineCap::Butt => {}
+            usvg::LineCap::Round => attrs.push(("stroke-linecap", "round".to_string())),
+            usvg::LineCap::Square => attrs.push(("stroke-linecap", "square".to_string())),
+        }
+        match stroke.linejoin {
+            usvg::LineJoin::Miter => {}
+            usvg::LineJoin::MiterClip => attrs.push(("stroke-linejoin", "miter-clip".to_string())),
+            usvg::LineJoin::Round => attrs.push(("stroke-linejoin", "round".to_string())),
+            usvg::LineJoin::Bevel => attrs.push(("stroke-linejoin", "bevel".to_string())),
+        }
+        if stroke.miterlimit.get() != 4.0 {
+            attrs.push(("stroke-miterlimit", format_number(stroke.miterlimit.get(), precision)));
+        }
+        if let Some(dasharray) = &stroke.dasharray {
+            let values: Vec<String> = dasharray
+                .iter()
+                .map(|v| format_number(v * stroke_scale, precision))
+                .collect();
+            attrs.push(("stroke-dasharray", values.join(" ")));
+        }
+        if stroke.opacity.get() < 1.0 {
+            attrs.push(("stroke-opacity", format_number(stroke.opacity.get(), 2)));
+        }
+    }
+
+    Some(FlatPath {
+        data: path_data(&data, precision),
+        attrs,
+        colors,
+    })
+}
+
+/// 只支持纯色，渐变与图案无法安全展平
+fn solid_color(paint: &usvg::Paint) -> Option<usvg::Color> {
+    match paint {
+        usvg::Paint::Color(color) => Some(*color),
+        _ => None,
+    }
+}
+
+fn hex_color(color: usvg::Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.red, color.green, color.blue)
+}
+
+/// 输出绝对坐标的路径数据
+fn path_data(path: &usvg::tiny_skia_path::Path, precision: u8) -> String {
+    use usvg::tiny_skia_path::PathSegment;
+
+    let point = |p: usvg::tiny_skia_path::Point| {
+        format!("{} {}", format_number(p.x, precision), format_number(p.y, precision))
+    };
+    let mut data = String::new();
+    for segment in path.segments() {
+        match segment {
+            PathSegment::MoveTo(p) => data.push_str(&format!("M{}", point(p))),
+            PathSegment::LineTo(p) => data.push_str(&format!("L{}", point(p))),
+            PathSegment::QuadTo(p1, p) => data.push_str(&format!("Q{} {}", point(p1), point(p))),
+            PathSegment::CubicTo(p1, p2, p) => {
+                data.push_str(&format!("C{} {} {}", point(p1), point(p2), point(p)))
+            }
+            PathSegment::Close => data.push('Z'),
+        }
+    }
+    data
+}
+
+/// 按精度取整并去掉多余的 0
+fn format_number(value: f32, precision: u8) -> String {
+    let formatted = format!("{:.*}", precision as usize, value);
+    let trimmed = if formatted.contains('.') {
+        formatted.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        formatted.as_str()
+    };
+    match trimmed {
+        "-0" | "" => "0".to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_optimize_flattens_and_normalizes() {
+        let svg = r##"<?xml version="1.0"?>
+<!-- 注释 -->
+<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 10" width="200" height="100">
+  <metadata>editor data</metadata>
+  <g transform="translate(5 0)">
+    <rect x="0" y="0" width="10" height="10" fill="#333333"/>
+  </g>
+</svg>"##;
+        let options = SvgOptimizeOptions {
+            grid_size: Some(24.0),
+            current_color: true,
+            ..Default::default()
+        };
+        let (optimized, report) = optimize_svg(svg, &options).unwrap();
+
+        assert!(report.flattened);
+        assert!(report.saved_bytes() > 0);
+        assert!(!optimized.contains("metadata") && !optimized.contains("<!--"));
+        assert!(optimized.contains(r#"viewBox="0 0 24 24""#));
+        // 平移 5 后为 (5, 0) - (15, 10)，在 20x20 内垂直居中再缩放 1.2：(6, 6) - (18, 18)
+        assert!(optimized.contains(r#"d="M6 6L18 6L18 18L6 18Z""#), "{}", optimized);
+        assert!(optimized.contains(r#"fill="currentColor""#));
+    }
+
+    #[test]
+    fn test_optimize_keeps_multicolor_and_falls_back() {
+        let multi = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10"><path d="M0 0h5v5z" fill="#f00"/><path d="M5 5h5v5z" fill="#00f"/></svg>"##;
+        let options = SvgOptimizeOptions {
+            current_color: true,
+            ..Default::default()
+        };
+        let (optimized, _) = optimize_svg(multi, &options).unwrap();
+        assert!(optimized.contains("#ff0000") && optimized.contains("#0000ff"));
+
+        // 渐变无法展平，退回 usvg 输出
+        let gradient = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10"><defs><linearGradient id="g"><stop offset="0" stop-color="#fff"/><stop offset="1" stop-color="#000"/></linearGradient></defs><rect width="10" height="10" fill="url(#g)"/></svg>"##;
+        let (optimized, report) = optimize_svg(gradient, &SvgOptimizeOptions::default()).unwrap();
+        assert!(!report.flattened);
+        assert!(optimized.contains("linearGradient"));
+    }
+
+    #[test]
+    fn test_optimize_keeps_current_color() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24"><path d="M0 0h24v24z" fill="currentColor"/><path d="M2 2h4" stroke="CurrentColor" fill="none"/></svg>"#;
+        let (optimized, report) = optimize_svg(svg, &SvgOptimizeOptions::default()).unwrap();
+        assert!(report.flattened);
+        assert!(optimized.contains(r#"fill="currentColor""#), "{}", optimized);
+        assert!(optimized.contains(r#"stroke="currentColor""#), "{}", optimized);
+        assert!(!optimized.contains("#000000"));
+
+        // 与其他颜色混用时只还原 currentColor 部分
+        let mixed = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10"><path d="M0 0h5v5z" fill="currentColor"/><path d="M5 5h5v5z" fill="#f00"/></svg>"##;
+        let (optimized, _) = optimize_svg(mixed, &SvgOptimizeOptions::default()).unwrap();
+        assert!(optimized.contains(r#"fill="currentColor""#) && optimized.contains("#ff0000"));
+    }
+
+    #[test]
+    fn test_invalid_svg_is_rejected() {
+        assert!(optimize_svg("<svg", &SvgOptimizeOptions::default()).is_err());
+        assert!(optimize_svg("not svg", &SvgOptimizeOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(format_number(1.2345, 2), "1.23");
+        assert_eq!(format_number(2.0, 2), "2");
+        assert_eq!(format_number(-0.001, 2), "0");
+        assert_eq!(format_number(10.0, 0), "10");
+    }
+}
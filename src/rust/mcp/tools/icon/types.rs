@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::optimize::{SvgOptimizeOptions, SvgOptimizeReport};
+
 // ============ 图标搜索相关类型 ============
 
 /// 图标搜索请求参数
@@ -25,6 +27,9 @@ pub struct IconSearchRequest {
     /// 是否仅从精选集搜索
     #[serde(default)]
     pub from_collection: Option<bool>,
+    /// 图标来源（未指定时使用配置中的来源）
+    #[serde(default)]
+    pub provider: Option<IconProvider>,
 }
 
 impl Default for IconSearchRequest {
@@ -37,6 +42,7 @@ impl Default for IconSearchRequest {
             page: Some(1),
             page_size: Some(50),
             from_collection: None,
+            provider: None,
         }
     }
 }
@@ -49,6 +55,7 @@ pub struct IconItem {
     /// 图标名称
     pub name: String,
     /// 图标标识（用于 CSS 类名）
+    #[serde(default)]
     pub font_class: String,
     /// 图标 Unicode 编码
     #[serde(default)]
@@ -100,6 +107,39 @@ impl Default for IconSearchResult {
     }
 }
 
+/// 图标来源
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IconProvider {
+    /// iconfont.cn 在线搜索
+    Iconfont,
+    /// 本地 SVG 目录或 Iconify JSON 图标集（离线可用）
+    Local,
+}
+
+impl Default for IconProvider {
+    fn default() -> Self {
+        Self::Iconfont
+    }
+}
+
+impl IconProvider {
+    /// 从配置字符串解析，未知值回退到 iconfont
+    pub fn from_config_value(value: Option<&str>) -> Self {
+        match value {
+            Some("local") => Self::Local,
+            _ => Self::Iconfont,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Iconfont => "iconfont",
+            Self::Local => "local",
+        }
+    }
+}
+
 // ============ 图标下载/保存相关类型 ============
 
 /// 图标格式枚举
@@ -112,6 +152,59 @@ pub enum IconFormat {
     Png,
     /// 同时保存 SVG 和 PNG
     Both,
+    /// 合并为 `<symbol>` 雪碧图（sprite.svg）
+    Sprite,
+    /// 图标字体（iconfont.ttf 与 iconfont.css）
+    Font,
+    /// 多倍图 PNG（1x/2x/3x）
+    #[serde(rename = "png_set")]
+    PngSet,
+    /// favicon 套件（.ico 与常用尺寸 PNG）
+    Favicon,
+    /// React 组件（.tsx）及索引文件
+    React,
+    /// Vue 单文件组件（.vue）及索引文件
+    Vue,
+    /// Svelte 组件（.svelte）及索引文件
+    Svelte,
+}
+
+impl IconFormat {
+    /// 从配置字符串解析，未知值回退到 SVG
+    pub fn from_config_value(value: &str) -> Self {
+        match value {
+            "png" => Self::Png,
+            "both" => Self::Both,
+            "sprite" => Self::Sprite,
+            "font" => Self::Font,
+            "png_set" => Self::PngSet,
+            "favicon" => Self::Favicon,
+            "react" => Self::React,
+            "vue" => Self::Vue,
+            "svelte" => Self::Svelte,
+            _ => Self::Svg,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            Self::Png => "png",
+            Self::Both => "both",
+            Self::Sprite => "sprite",
+            Self::Font => "font",
+            Self::PngSet => "png_set",
+            Self::Favicon => "favicon",
+            Self::React => "react",
+            Self::Vue => "vue",
+            Self::Svelte => "svelte",
+        }
+    }
+
+    /// 是否需要按 PNG 尺寸渲染位图
+    pub fn uses_png_size(&self) -> bool {
+        matches!(self, Self::Png | Self::Both | Self::PngSet)
+    }
 }
 
 impl Default for IconFormat {
@@ -130,9 +223,15 @@ pub struct IconSaveRequest {
     /// 保存格式
     #[serde(default)]
     pub format: IconFormat,
-    /// PNG 尺寸（仅当 format 包含 PNG 时有效）
+    /// PNG 尺寸（PNG 格式为输出尺寸，多倍图为 1x 尺寸）
     #[serde(default)]
     pub png_size: Option<u32>,
+    /// 目标文件已存在时是否覆盖（未指定时覆盖，保持旧行为）
+    #[serde(default)]
+    pub overwrite: Option<bool>,
+    /// SVG 优化选项（未指定时使用默认优化）
+    #[serde(default)]
+    pub optimize: Option<SvgOptimizeOptions>,
 }
 
 /// 单个图标保存结果
@@ -149,6 +248,9 @@ pub struct IconSaveItem {
     /// 错误信息（如果失败）
     #[serde(default)]
     pub error: Option<String>,
+    /// SVG 优化统计
+    #[serde(default)]
+    pub optimize_report: Option<SvgOptimizeReport>,
 }
 
 /// 图标保存结果
@@ -162,6 +264,12 @@ pub struct IconSaveResult {
     pub failed_count: u32,
     /// 保存目录
     pub save_path: String,
+    /// 汇总生成的文件（雪碧图、组件索引文件）
+    #[serde(default)]
+    pub bundle_files: Vec<String>,
+    /// SVG 优化共节省的字节数
+    #[serde(default)]
+    pub bytes_saved: u64,
 }
 
 // ============ 图标内容获取相关类型 ============
@@ -212,6 +320,9 @@ pub struct IconCacheStats {
     /// 预估内存占用（字节）
     #[serde(default)]
     pub memory_usage_bytes: Option<u64>,
+    /// 磁盘缓存占用（字节）
+    #[serde(default)]
+    pub disk_usage_bytes: Option<u64>,
 }
 
 /// 清空缓存请求
@@ -248,6 +359,12 @@ pub struct IconConfig {
     /// 缓存过期时间（分钟）
     #[serde(default)]
     pub cache_expiry_minutes: Option<u64>,
+    /// 图标来源
+    #[serde(default)]
+    pub provider: IconProvider,
+    /// 本地图标库路径（SVG 目录、Iconify JSON 文件或 @iconify-json/* 包目录）
+    #[serde(default)]
+    pub local_sources: Vec<String>,
 }
 
 impl Default for IconConfig {
@@ -257,6 +374,8 @@ impl Default for IconConfig {
             default_format: IconFormat::Svg,
             default_png_size: Some(64),
             cache_expiry_minutes: Some(30),
+            provider: IconProvider::Iconfont,
+            local_sources: Vec::new(),
         }
     }
 }
@@ -45,3 +45,39 @@ pub async fn clear_zhi_history(
 
     manager.clear().map_err(|e| format!("清空历史失败: {}", e))
 }
+
+/// 为 zhi 表单的 file_path 字段选择文件或目录
+#[tauri::command]
+pub async fn select_zhi_form_path(
+    app_handle: tauri::AppHandle,
+    directory: bool,
+    default_path: Option<String>,
+) -> Result<Option<String>, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let mut builder = app_handle.dialog().file();
+
+    if let Some(path) = default_path {
+        let path_buf = std::path::PathBuf::from(&path);
+        if path_buf.exists() {
+            builder = builder.set_directory(&path_buf);
+        }
+    }
+
+    // 使用 tokio oneshot channel 接收回调结果
+    let (tx, rx) = tokio::sync::oneshot::channel();
+
+    if directory {
+        builder.pick_folder(move |path| {
+            let _ = tx.send(path);
+        });
+    } else {
+        builder.pick_file(move |path| {
+            let _ = tx.send(path);
+        });
+    }
+
+    let result = rx.await.map_err(|_| "对话框选择被取消".to_string())?;
+
+    Ok(result.map(|path| path.to_string()))
+}
@@ -0,0 +1,399 @@
+// zhi 结构化表单
+// 定义字段规格、校验用户提交的表单值，并生成可读摘要
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+/// 表单字段类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ZhiFieldKind {
+    /// 单选（从 options 中选择一个）
+    SingleSelect,
+    /// 多选（从 options 中选择多个）
+    MultiSelect,
+    /// 文本输入
+    Text,
+    /// 数字输入
+    Number,
+    /// 开关
+    Boolean,
+    /// 文件/目录路径选择
+    FilePath,
+    /// 操作确认（可附带危险等级）
+    Confirm,
+}
+
+/// 确认字段的危险等级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ZhiDangerLevel {
+    Low,
+    Medium,
+    High,
+}
+
+/// 单个表单字段规格
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ZhiField {
+    #[schemars(description = "字段键名（返回结果中的 key，需唯一）")]
+    pub key: String,
+    #[schemars(description = "字段显示名称")]
+    pub label: String,
+    #[schemars(description = "字段类型：single_select|multi_select|text|number|boolean|file_path|confirm")]
+    #[serde(rename = "type")]
+    pub kind: ZhiFieldKind,
+    #[schemars(description = "字段说明（可选）")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[schemars(description = "是否必填，默认 false")]
+    #[serde(default)]
+    pub required: bool,
+    #[schemars(description = "可选项（single_select/multi_select 必填）")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+    #[schemars(description = "默认值（可选）")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+    #[schemars(description = "下限：number 为数值下限，text 为最小长度，multi_select 为最少选择数")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[schemars(description = "上限：number 为数值上限，text 为最大长度，multi_select 为最多选择数")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[schemars(description = "text 字段的正则校验（可选）")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[schemars(description = "file_path 字段是否选择目录，默认 false")]
+    #[serde(default)]
+    pub directory: bool,
+    #[schemars(description = "confirm 字段的危险等级：low|medium|high")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub danger_level: Option<ZhiDangerLevel>,
+}
+
+impl ZhiField {
+    /// 构造一个必填的确认字段（服务端发起的确认询问使用）
+    pub fn confirm(key: &str, label: &str, danger_level: ZhiDangerLevel) -> Self {
+        Self {
+            key: key.to_string(),
+            label: label.to_string(),
+            kind: ZhiFieldKind::Confirm,
+            description: None,
+            required: true,
+            options: Vec::new(),
+            default: None,
+            min: None,
+            max: None,
+            pattern: None,
+            directory: false,
+            danger_level: Some(danger_level),
+        }
+    }
+}
+
+/// 单个字段的校验错误
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FieldError {
+    pub key: String,
+    pub message: String,
+}
+
+impl FieldError {
+    fn new(key: &str, message: impl Into<String>) -> Self {
+        Self {
+            key: key.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+/// 校验字段规格本身是否合法（由 AI 提交的请求）
+pub fn validate_field_specs(fields: &[ZhiField]) -> Result<(), String> {
+    let mut keys = HashSet::new();
+
+    for field in fields {
+        let key = field.key.trim();
+        if key.is_empty() {
+            return Err("表单字段 key 不能为空".to_string());
+        }
+        if !keys.insert(key.to_string()) {
+            return Err(format!("表单字段 key 重复: {}", key));
+        }
+        if field.label.trim().is_empty() {
+            return Err(format!("表单字段 {} 缺少 label", key));
+        }
+
+        match field.kind {
+            ZhiFieldKind::SingleSelect | ZhiFieldKind::MultiSelect => {
+                if field.options.is_empty() {
+                    return Err(format!("选择字段 {} 必须提供 options", key));
+                }
+            }
+            ZhiFieldKind::Text => {
+                if let Some(pattern) = &field.pattern {
+                    regex::Regex::new(pattern)
+                        .map_err(|e| format!("字段 {} 的 pattern 无效: {}", key, e))?;
+                }
+            }
+            _ => {}
+        }
+
+        if let (Some(min), Some(max)) = (field.min, field.max) {
+            if min > max {
+                return Err(format!("字段 {} 的 min 大于 max", key));
+            }
+        }
+
+        // 默认值也需要满足字段约束，避免弹窗初始即为非法状态
+        if let Some(default) = &field.default {
+            if field.kind == ZhiFieldKind::Confirm && field.danger_level == Some(ZhiDangerLevel::High) {
+                return Err(format!("高危确认字段 {} 不允许设置默认值", key));
+            }
+            validate_value(field, default).map_err(|e| format!("字段 {} 的默认值无效: {}", key, e.message))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 校验用户提交的表单值
+///
+/// 返回按字段规格归一化后的值（补齐默认值、数字字符串转数字），
+/// 未在规格中声明的 key 会被丢弃
+pub fn validate_form_values(
+    fields: &[ZhiField],
+    values: &Map<String, Value>,
+) -> Result<Map<String, Value>, Vec<FieldError>> {
+    let mut normalized = Map::new();
+    let mut errors = Vec::new();
+
+    for field in fields {
+        let value = match values.get(&field.key) {
+            Some(value) if !is_empty_value(value) => Some(value.clone()),
+            _ => field.default.clone(),
+        };
+
+        match value {
+            Some(value) => match validate_value(field, &value) {
+                Ok(value) => {
+                    normalized.insert(field.key.clone(), value);
+                }
+                Err(e) => errors.push(e),
+            },
+            None => {
+                if field.required {
+                    errors.push(FieldError::new(&field.key, "必填字段未填写"));
+                } else {
+                    normalized.insert(field.key.clone(), Value::Null);
+                }
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(normalized)
+    } else {
+        Err(errors)
+    }
+}
+
+/// 校验并归一化单个字段的值
+fn validate_value(field: &ZhiField, value: &Value) -> Result<Value, FieldError> {
+    let key = field.key.as_str();
+
+    match field.kind {
+        ZhiFieldKind::SingleSelect => {
+            let choice = value
+                .as_str()
+                .ok_or_else(|| FieldError::new(key, "应为字符串"))?;
+            if !field.options.iter().any(|o| o == choice) {
+                return Err(FieldError::new(key, format!("不在可选项中: {}", choice)));
+            }
+            Ok(Value::String(choice.to_string()))
+        }
+        ZhiFieldKind::MultiSelect => {
+            let items = value
+                .as_array()
+                .ok_or_else(|| FieldError::new(key, "应为字符串数组"))?;
+            let mut choices = Vec::new();
+            for item in items {
+                let choice = item
+                    .as_str()
+                    .ok_or_else(|| FieldError::new(key, "应为字符串数组"))?;
+                if !field.options.iter().any(|o| o == choice) {
+                    return Err(FieldError::new(key, format!("不在可选项中: {}", choice)));
+                }
+                if !choices.contains(&choice) {
+                    choices.push(choice);
+                }
+            }
+            check_range(field, choices.len() as f64, "选择数量")?;
+            Ok(Value::Array(
+                choices.into_iter().map(|c| Value::String(c.to_string())).collect(),
+            ))
+        }
+        ZhiFieldKind::Text | ZhiFieldKind::FilePath => {
+            let text = value
+                .as_str()
+                .ok_or_else(|| FieldError::new(key, "应为字符串"))?;
+            if field.kind == ZhiFieldKind::Text {
+                check_range(field, text.chars().count() as f64, "长度")?;
+                if let Some(pattern) = &field.pattern {
+                    let re = regex::Regex::new(pattern)
+                        .map_err(|e| FieldError::new(key, format!("pattern 无效: {}", e)))?;
+                    if !re.is_match(text) {
+                        return Err(FieldError::new(key, format!("不符合格式要求: {}", pattern)));
+                    }
+                }
+            }
+            Ok(Value::String(text.to_string()))
+        }
+        ZhiFieldKind::Number => {
+            let number = match value {
+                Value::Number(n) => n.as_f64(),
+                Value::String(s) => s.trim().parse::<f64>().ok(),
+                _ => None,
+            }
+            .ok_or_else(|| FieldError::new(key, "应为数字"))?;
+            check_range(field, number, "数值")?;
+            // 整数保持整数表示，便于调用方直接使用
+            if number.fract() == 0.0 && number.abs() < i64::MAX as f64 {
+                return Ok(Value::from(number as i64));
+            }
+            serde_json::Number::from_f64(number)
+                .map(Value::Number)
+                .ok_or_else(|| FieldError::new(key, "应为有限数字"))
+        }
+        ZhiFieldKind::Boolean | ZhiFieldKind::Confirm => {
+            let flag = match value {
+                Value::Bool(b) => Some(*b),
+                Value::String(s) => match s.trim().to_lowercase().as_str() {
+                    "true" | "yes" | "y" | "1" | "是" | "确认" => Some(true),
+                    "false" | "no" | "n" | "0" | "否" | "取消" => Some(false),
+                    _ => None,
+                },
+                _ => None,
+            }
+            .ok_or_else(|| FieldError::new(key, "应为布尔值"))?;
+            // 必填的确认字段必须明确确认
+            if field.kind == ZhiFieldKind::Confirm && field.required && !flag {
+                return Err(FieldError::new(key, "需要确认后才能提交"));
+            }
+            Ok(Value::Bool(flag))
+        }
+    }
+}
+
+/// 检查数值是否在 min/max 范围内
+fn check_range(field: &ZhiField, actual: f64, what: &str) -> Result<(), FieldError> {
+    if let Some(min) = field.min {
+        if actual < min {
+            return Err(FieldError::new(&field.key, format!("{}不能小于 {}", what, min)));
+        }
+    }
+    if let Some(max) = field.max {
+        if actual > max {
+            return Err(FieldError::new(&field.key, format!("{}不能大于 {}", what, max)));
+        }
+    }
+    Ok(())
+}
+
+/// 判断值是否视为“未填写”
+fn is_empty_value(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.trim().is_empty(),
+        Value::Array(a) => a.is_empty(),
+        _ => false,
+    }
+}
+
+/// 生成表单结果的可读摘要（用于文本内容与历史记录）
+pub fn format_form_summary(fields: &[ZhiField], values: &Map<String, Value>) -> String {
+    let mut lines = Vec::new();
+
+    for field in fields {
+        let display = match values.get(&field.key) {
+            None | Some(Value::Null) => "（未填写）".to_string(),
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Bool(b)) => if *b { "是" } else { "否" }.to_string(),
+            Some(Value::Array(items)) => items
+                .iter()
+                .map(|v| v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string()))
+                .collect::<Vec<_>>()
+                .join(", "),
+            Some(other) => other.to_string(),
+        };
+        lines.push(format!("• {} ({}): {}", field.label, field.key, display));
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fields() -> Vec<ZhiField> {
+        serde_json::from_value(json!([
+            {"key": "db", "label": "数据库", "type": "single_select", "options": ["pg", "mysql"], "required": true},
+            {"key": "features", "label": "功能", "type": "multi_select", "options": ["auth", "cache"], "max": 1},
+            {"key": "port", "label": "端口", "type": "number", "min": 1, "max": 65535, "default": 8080},
+            {"key": "drop", "label": "删除旧表", "type": "confirm", "danger_level": "high", "required": true}
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validate_form_values() {
+        let fields = fields();
+        assert!(validate_field_specs(&fields).is_ok());
+
+        let values = json!({"db": "pg", "features": ["auth"], "port": "3000", "drop": true});
+        let normalized = validate_form_values(&fields, values.as_object().unwrap()).unwrap();
+        assert_eq!(normalized["db"], json!("pg"));
+        assert_eq!(normalized["port"], json!(3000));
+        assert_eq!(normalized["drop"], json!(true));
+
+        // 未填写端口时使用默认值
+        let values = json!({"db": "mysql", "drop": "yes"});
+        let normalized = validate_form_values(&fields, values.as_object().unwrap()).unwrap();
+        assert_eq!(normalized["port"], json!(8080));
+        assert_eq!(normalized["features"], Value::Null);
+    }
+
+    #[test]
+    fn test_validate_form_errors() {
+        let fields = fields();
+        let values = json!({"db": "sqlite", "features": ["auth", "cache"], "port": 0, "drop": false});
+        let errors = validate_form_values(&fields, values.as_object().unwrap()).unwrap_err();
+        let keys: Vec<&str> = errors.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(keys, vec!["db", "features", "port", "drop"]);
+    }
+
+    #[test]
+    fn test_validate_field_specs_rejects_invalid() {
+        let dup: Vec<ZhiField> = serde_json::from_value(json!([
+            {"key": "a", "label": "A", "type": "text"},
+            {"key": "a", "label": "B", "type": "text"}
+        ]))
+        .unwrap();
+        assert!(validate_field_specs(&dup).is_err());
+
+        let no_options: Vec<ZhiField> = serde_json::from_value(json!([
+            {"key": "a", "label": "A", "type": "single_select"}
+        ]))
+        .unwrap();
+        assert!(validate_field_specs(&no_options).is_err());
+    }
+}
@@ -1,7 +1,8 @@
 use anyhow::Result;
-use rmcp::model::{ErrorData as McpError, CallToolResult};
+use rmcp::model::{ErrorData as McpError, CallToolResult, Content};
 
-use crate::mcp::{ZhiRequest, PopupRequest};
+use super::form::{format_form_summary, validate_field_specs, validate_form_values, FieldError, ZhiField};
+use crate::mcp::{ZhiRequest, PopupRequest, McpResponse};
 use crate::mcp::handlers::{create_tauri_popup, parse_mcp_response};
 use crate::mcp::utils::{generate_request_id, popup_error};
 
@@ -24,6 +25,11 @@ impl InteractionTool {
                 request.uiux_reason.as_deref()
             );
         }
+        // 表单字段规格不合法时直接拒绝，避免弹出无法提交的表单
+        validate_field_specs(&request.fields)
+            .map_err(|e| McpError::invalid_params(format!("表单字段无效: {}", e), None))?;
+        let fields = request.fields.clone();
+
         let popup_request = PopupRequest {
             id: generate_request_id(),
             message: request.message,
@@ -38,13 +44,34 @@ impl InteractionTool {
             uiux_intent: request.uiux_intent,
             uiux_context_policy: request.uiux_context_policy,
             uiux_reason: request.uiux_reason,
+            fields: request.fields,
         };
 
         match create_tauri_popup(&popup_request) {
             Ok(response) => {
                 // 解析响应内容，支持文本和图片
                 let content = parse_mcp_response(&response)?;
-                Ok(CallToolResult::success(content))
+                let mut result = CallToolResult::success(content);
+
+                // 表单结果以结构化 JSON 返回，便于 AI 直接读取
+                match extract_form_values(&fields, &response) {
+                    Some(Ok(values)) => {
+                        result.structured_content = Some(serde_json::json!({
+                            "form_values": values,
+                            "summary": format_form_summary(&fields, &values),
+                        }));
+                    }
+                    Some(Err(errors)) => {
+                        let detail = errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("\n");
+                        return Ok(CallToolResult::error(vec![Content::text(format!(
+                            "表单校验失败:\n{}",
+                            detail
+                        ))]));
+                    }
+                    None => {}
+                }
+
+                Ok(result)
             }
             Err(e) => {
                 Err(popup_error(e.to_string()).into())
@@ -52,3 +79,19 @@ impl InteractionTool {
         }
     }
 }
+
+/// 从弹窗响应中提取并校验表单值
+///
+/// 请求不含表单、用户取消或选择“继续”时返回 None
+fn extract_form_values(
+    fields: &[ZhiField],
+    response: &str,
+) -> Option<Result<serde_json::Map<String, serde_json::Value>, Vec<FieldError>>> {
+    if fields.is_empty() {
+        return None;
+    }
+
+    let parsed = serde_json::from_str::<McpResponse>(response).ok()?;
+    let values = parsed.form_values?;
+    Some(validate_form_values(fields, &values))
+}
@@ -19,3 +19,8 @@ pub use icon::IconTool;
 pub use skills::SkillsTool;
 pub use uiux::UiuxTool;
 pub use enhance::EnhanceTool;
+
+/// 登记各模块提供的内置 Rust 技能
+pub fn register_builtin_skills(registry: &mut skills::builtin::BuiltinRegistry) {
+    uiux::register_skills(registry);
+}
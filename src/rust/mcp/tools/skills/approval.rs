@@ -0,0 +1,215 @@
+// 技能首次运行审批
+// 受信任密钥签名的技能直接放行；未签名技能首次运行（或内容变化后）请求用户确认
+// 客户端支持 elicitation 时由客户端询问，否则回退到 zhi 弹窗；两者都不可用时直接报错
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use base64::Engine as _;
+use ring::digest::{Context, SHA256};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use rmcp::{Peer, RoleServer};
+use serde::{Deserialize, Serialize};
+
+use crate::config::McpConfig;
+use crate::log_important;
+use crate::mcp::handlers::{elicit, popup_available, Elicitation};
+use crate::mcp::tools::interaction::ZhiDangerLevel;
+
+use super::permissions::SkillPermissions;
+
+/// 技能目录内的签名文件（对内容摘要的 Ed25519 签名，base64）
+pub const SIGNATURE_FILE: &str = "skill.sig";
+const APPROVALS_FILE: &str = "skill_approvals.json";
+
+/// 待审批的技能信息
+pub struct ApprovalSubject<'a> {
+    pub name: &'a str,
+    pub root: &'a Path,
+    pub runtime: &'a str,
+    pub entry: &'a str,
+    pub permissions: &'a SkillPermissions,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ApprovalRecord {
+    digest: String,
+    approved_at: String,
+}
+
+/// 技能内容摘要：按相对路径排序的全部文件（跳过 .git 与签名文件）
+/// __pycache__ 计入摘要：植入或修改字节码同样需要重新审批
+pub fn skill_digest(root: &Path) -> Result<String, String> {
+    let mut files: Vec<PathBuf> = ignore::WalkBuilder::new(root)
+        .standard_filters(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build()
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .map(|entry| entry.into_path())
+        .filter(|path| path.file_name().and_then(|n| n.to_str()) != Some(SIGNATURE_FILE))
+        .collect();
+    files.sort();
+
+    let mut context = Context::new(&SHA256);
+    for path in files {
+        let rel = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+        let content = std::fs::read(&path).map_err(|e| format!("读取技能文件失败: {} ({})", rel, e))?;
+        context.update(rel.as_bytes());
+        context.update(&(content.len() as u64).to_le_bytes());
+        context.update(&content);
+    }
+    Ok(hex::encode(context.finish().as_ref()))
+}
+
+/// 校验 skill.sig 是否为任一受信任公钥对摘要的签名
+pub fn verify_signature(root: &Path, digest: &str, trusted_keys: &[String]) -> bool {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let Some(signature) = std::fs::read_to_string(root.join(SIGNATURE_FILE))
+        .ok()
+        .and_then(|text| engine.decode(text.trim()).ok())
+    else {
+        return false;
+    };
+    trusted_keys
+        .iter()
+        .filter_map(|key| engine.decode(key.trim()).ok())
+        .any(|key| {
+            UnparsedPublicKey::new(&ED25519, key)
+                .verify(digest.as_bytes(), &signature)
+                .is_ok()
+        })
+}
+
+fn approvals_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".sanshu")
+        .join(APPROVALS_FILE)
+}
+
+fn load_approvals(path: &Path) -> BTreeMap<String, ApprovalRecord> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save_approvals(path: &Path, approvals: &BTreeMap<String, ApprovalRecord>) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("创建审批记录目录失败: {}", e))?;
+    }
+    let text = serde_json::to_string_pretty(approvals).map_err(|e| format!("序列化审批记录失败: {}", e))?;
+    std::fs::write(path, text).map_err(|e| format!("写入审批记录失败: {}", e))
+}
+
+/// 确认技能可以运行：签名受信任、已审批过当前内容，或用户在询问中同意
+pub async fn ensure_approved(
+    subject: &ApprovalSubject<'_>,
+    config: Option<&McpConfig>,
+    peer: Option<&Peer<RoleServer>>,
+) -> Result<(), String> {
+    if !config.and_then(|c| c.skill_require_approval).unwrap_or(true) {
+        return Ok(());
+    }
+
+    let digest = skill_digest(subject.root)?;
+    let trusted_keys = config.and_then(|c| c.skill_trusted_keys.clone()).unwrap_or_default();
+    if verify_signature(subject.root, &digest, &trusted_keys) {
+        return Ok(());
+    }
+
+    let store_path = approvals_path();
+    let mut approvals = load_approvals(&store_path);
+    let key = subject.root.to_string_lossy().to_string();
+    if approvals.get(&key).is_some_and(|record| record.digest == digest) {
+        return Ok(());
+    }
+
+    let changed = approvals.contains_key(&key);
+    let message = format!(
+        "## 运行未签名技能？\n\n{}技能 **{}** 未经受信任密钥签名，请确认是否允许运行。\n\n\
+         - 目录：`{}`\n- 运行时：{}\n- 入口：`{}`\n\n**权限清单**\n\n{}\n",
+        if changed { "技能内容自上次批准后已变化。" } else { "" },
+        subject.name,
+        subject.root.display(),
+        subject.runtime,
+        subject.entry,
+        subject.permissions.summary()
+    );
+
+    // 无界面环境（客户端不支持 elicitation 且找不到 UI）立即失败，不等待审批
+    let can_elicit = peer.is_some_and(|p| p.supports_elicitation());
+    if !can_elicit && !tokio::task::spawn_blocking(popup_available).await.unwrap_or(false) {
+        return Err(format!(
+            "技能 {} 未签名，首次运行需要审批，但客户端不支持 elicitation 且找不到等一下 UI。\
+             请在有界面的环境中运行一次完成审批，或用受信任密钥签名并将公钥加入 skill_trusted_keys，\
+             或在配置中设置 skill_require_approval = false",
+            subject.name
+        ));
+    }
+
+    let request = Elicitation::confirm(message, "允许运行此技能", ZhiDangerLevel::High);
+    let outcome = elicit(peer, &request)
+        .await
+        .map_err(|e| format!("技能审批询问失败: {}（可在配置中关闭 skill_require_approval）", e))?;
+    if !outcome.is_confirmed("confirm") {
+        return Err(format!("用户未批准运行技能: {}", subject.name));
+    }
+
+    log_important!(info, "技能已批准: name={}, digest={}", subject.name, digest);
+    approvals.insert(
+        key,
+        ApprovalRecord {
+            digest,
+            approved_at: chrono::Local::now().to_rfc3339(),
+        },
+    );
+    save_approvals(&store_path, &approvals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    #[test]
+    fn test_digest_and_signature() {
+        let root = std::env::temp_dir().join(format!("sanshu-skill-approval-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("scripts").join("__pycache__")).unwrap();
+        std::fs::write(root.join("SKILL.md"), "---\nname: demo\n---\n").unwrap();
+        std::fs::write(root.join("scripts").join("main.py"), "print('hi')\n").unwrap();
+
+        let digest = skill_digest(&root).unwrap();
+        // 签名文件不计入摘要
+        std::fs::write(root.join(SIGNATURE_FILE), "bogus").unwrap();
+        assert_eq!(skill_digest(&root).unwrap(), digest);
+
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let engine = base64::engine::general_purpose::STANDARD;
+        let trusted = vec![engine.encode(pair.public_key().as_ref())];
+        assert!(!verify_signature(&root, &digest, &trusted));
+
+        std::fs::write(root.join(SIGNATURE_FILE), engine.encode(pair.sign(digest.as_bytes()).as_ref())).unwrap();
+        assert!(verify_signature(&root, &digest, &trusted));
+
+        // 植入字节码后摘要变化，签名与审批失效
+        let pyc = root.join("scripts").join("__pycache__").join("main.cpython-312.pyc");
+        std::fs::write(&pyc, "planted").unwrap();
+        let planted = skill_digest(&root).unwrap();
+        assert_ne!(planted, digest);
+        assert!(!verify_signature(&root, &planted, &trusted));
+        std::fs::remove_file(&pyc).unwrap();
+        assert_eq!(skill_digest(&root).unwrap(), digest);
+
+        // 内容变化后签名失效
+        std::fs::write(root.join("scripts").join("main.py"), "print('changed')\n").unwrap();
+        let changed = skill_digest(&root).unwrap();
+        assert_ne!(changed, digest);
+        assert!(!verify_signature(&root, &changed, &trusted));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
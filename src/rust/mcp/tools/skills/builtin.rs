@@ -0,0 +1,53 @@
+// 内置 Rust 技能注册表
+// 各工具模块通过 register_skills 登记自身的技能处理器，技能配置以 runtime = "builtin" 引用
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+
+use once_cell::sync::Lazy;
+use rmcp::model::{CallToolResult, ErrorData as McpError};
+
+use crate::mcp::types::SkillRunRequest;
+
+pub type BuiltinFuture<'a> = Pin<Box<dyn Future<Output = Result<CallToolResult, McpError>> + Send + 'a>>;
+
+/// 内置技能处理器
+pub trait BuiltinSkill: Send + Sync {
+    fn call<'a>(&'a self, action: &'a str, request: &'a SkillRunRequest) -> BuiltinFuture<'a>;
+}
+
+/// 处理器名称 -> 处理器
+#[derive(Default)]
+pub struct BuiltinRegistry {
+    skills: HashMap<String, Arc<dyn BuiltinSkill>>,
+}
+
+impl BuiltinRegistry {
+    /// 同名处理器后注册者覆盖先注册者
+    pub fn register(&mut self, name: &str, skill: Arc<dyn BuiltinSkill>) {
+        self.skills.insert(name.to_ascii_lowercase(), skill);
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn BuiltinSkill>> {
+        self.skills.get(&name.to_ascii_lowercase()).cloned()
+    }
+}
+
+static REGISTRY: Lazy<RwLock<BuiltinRegistry>> = Lazy::new(|| {
+    let mut registry = BuiltinRegistry::default();
+    crate::mcp::tools::register_builtin_skills(&mut registry);
+    RwLock::new(registry)
+});
+
+/// 运行期追加内置技能
+pub fn register_builtin_skill(name: &str, skill: Arc<dyn BuiltinSkill>) {
+    if let Ok(mut registry) = REGISTRY.write() {
+        registry.register(name, skill);
+    }
+}
+
+pub fn builtin_skill(name: &str) -> Option<Arc<dyn BuiltinSkill>> {
+    REGISTRY.read().ok().and_then(|registry| registry.get(name))
+}
@@ -0,0 +1,182 @@
+// 技能包管理命令行：三术 skill <install|update|sync|remove|list|doctor> ...
+
+use super::package::{InstallOptions, IntegrityStatus, SkillPackageManager, UpdateOutcome};
+use super::registry::{doctor, IssueLevel};
+
+/// 执行 `skill` 子命令，返回进程退出码
+pub async fn run(args: &[String]) -> i32 {
+    let manager = SkillPackageManager::global();
+    match execute(&manager, args).await {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            1
+        }
+    }
+}
+
+async fn execute(manager: &SkillPackageManager, args: &[String]) -> Result<i32, String> {
+    let Some(command) = args.first() else {
+        print_help();
+        return Ok(1);
+    };
+    let rest = &args[1..];
+    match command.as_str() {
+        "install" | "add" => {
+            // install --locked 等价于 sync
+            if rest.iter().any(|a| a == "--locked") {
+                let outcomes = manager.sync(None).await?;
+                return Ok(print_outcomes(outcomes, "已还原", "与锁文件一致"));
+            }
+            let mut options = InstallOptions::default();
+            let mut spec = None;
+            let mut iter = rest.iter();
+            while let Some(arg) = iter.next() {
+                match arg.as_str() {
+                    "--name" => options.name = Some(next_value(&mut iter, "--name")?),
+                    "--sha256" => options.sha256 = Some(next_value(&mut iter, "--sha256")?),
+                    "--force" | "-f" => options.force = true,
+                    other if other.starts_with('-') => return Err(format!("未知参数: {}", other)),
+                    other => spec = Some(other.to_string()),
+                }
+            }
+            let spec = spec.ok_or("缺少技能来源（git 地址、.zip 归档或本地目录）")?;
+            let (name, locked) = manager.install(&spec, &options).await?;
+            println!("✅ 已安装技能 {} -> {}", name, manager.root().join(&name).display());
+            if let Some(commit) = locked.commit {
+                println!("   提交: {}", commit);
+            }
+            if let Some(sha256) = locked.archive_sha256 {
+                println!("   归档 SHA-256: {}", sha256);
+            }
+            Ok(0)
+        }
+        "update" | "upgrade" => {
+            let allow_hash_change = rest.iter().any(|a| a == "--allow-hash-change");
+            let name = rest.iter().find(|a| !a.starts_with('-')).map(String::as_str);
+            let outcomes = manager.update(name, allow_hash_change).await?;
+            Ok(print_outcomes(outcomes, "已更新", "已是最新"))
+        }
+        "sync" => {
+            let outcomes = manager.sync(rest.first().map(String::as_str)).await?;
+            Ok(print_outcomes(outcomes, "已还原", "与锁文件一致"))
+        }
+        "remove" | "uninstall" | "rm" => {
+            let name = rest.first().ok_or("缺少技能名")?;
+            manager.remove(name)?;
+            println!("✅ 已移除技能 {}", name);
+            Ok(0)
+        }
+        "list" | "ls" => {
+            let skills = manager.list();
+            if skills.is_empty() {
+                println!("没有通过包管理器安装的技能（目录: {}）", manager.root().display());
+                return Ok(0);
+            }
+            let mut code = 0;
+            for skill in skills {
+                if skill.status != IntegrityStatus::Ok {
+                    code = 1;
+                }
+                let pin = skill
+                    .locked
+                    .commit
+                    .as_deref()
+                    .or(skill.locked.archive_sha256.as_deref())
+                    .map(|hash| format!(" @{}", &hash[..hash.len().min(12)]))
+                    .unwrap_or_default();
+                println!(
+                    "{:<24} [{}] {}{}",
+                    skill.name,
+                    skill.status.label(),
+                    skill.locked.source.describe(),
+                    pin
+                );
+            }
+            // 存在被修改或缺失的技能时返回非零，便于在 CI 中校验
+            Ok(code)
+        }
+        "doctor" => {
+            let project_root = match rest.first() {
+                Some(path) => std::path::PathBuf::from(path),
+                None => std::env::current_dir().map_err(|e| format!("获取当前目录失败: {}", e))?,
+            };
+            let report = doctor(&project_root);
+            println!("扫描目录（按优先级）:");
+            for root in report.roots.iter().filter(|r| r.exists) {
+                println!("  [{}] {}", root.scope.label(), root.path.display());
+            }
+            println!();
+            if report.skills.is_empty() {
+                println!("未发现技能");
+            }
+            for skill in &report.skills {
+                let state = if skill.active { "" } else { "（未加载）" };
+                println!("{} [{}]{} {}", skill.name, skill.scope.label(), state, skill.path.display());
+                for issue in &skill.issues {
+                    let mark = match issue.level {
+                        IssueLevel::Error => "❌",
+                        IssueLevel::Warning => "⚠️ ",
+                    };
+                    println!("  {} {}", mark, issue.message);
+                }
+            }
+            let errors = report.error_count();
+            println!();
+            println!("共 {} 个技能，{} 个错误", report.skills.len(), errors);
+            Ok(if errors > 0 { 1 } else { 0 })
+        }
+        "help" | "--help" | "-h" => {
+            print_help();
+            Ok(0)
+        }
+        other => Err(format!("未知的 skill 子命令: {}", other)),
+    }
+}
+
+/// 打印更新 / 还原结果，存在失败时返回非零退出码
+fn print_outcomes(outcomes: Vec<UpdateOutcome>, changed: &str, unchanged: &str) -> i32 {
+    if outcomes.is_empty() {
+        println!("没有通过包管理器安装的技能");
+    }
+    let short = |pin: &Option<String>| pin.as_deref().map(|p| p[..p.len().min(12)].to_string());
+    let mut code = 0;
+    for outcome in outcomes {
+        match outcome.error {
+            Some(e) => {
+                code = 1;
+                println!("❌ {}: {}", outcome.name, e);
+            }
+            None if outcome.changed => match (short(&outcome.from), short(&outcome.to)) {
+                (Some(from), Some(to)) if from != to => println!("✅ {}: {} ({} -> {})", outcome.name, changed, from, to),
+                _ => println!("✅ {}: {}", outcome.name, changed),
+            },
+            None => println!("✅ {}: {}", outcome.name, unchanged),
+        }
+    }
+    code
+}
+
+fn next_value<'a>(iter: &mut impl Iterator<Item = &'a String>, flag: &str) -> Result<String, String> {
+    iter.next().cloned().ok_or_else(|| format!("{} 需要一个值", flag))
+}
+
+fn print_help() {
+    println!("三术 skill - 技能包管理");
+    println!();
+    println!("用法:");
+    println!("  三术 skill install <来源> [选项]     安装技能（git 地址、.zip 归档或本地目录）");
+    println!("  三术 skill update [技能名] [--allow-hash-change]  按锁定来源更新到最新（缺省更新全部）");
+    println!("  三术 skill sync [技能名]             按锁文件精确还原（锁定提交 / 归档哈希 / 内容摘要）");
+    println!("  三术 skill install --locked         同 sync，还原锁文件中的全部技能");
+    println!("  三术 skill remove <技能名>           移除技能");
+    println!("  三术 skill list                     列出已安装技能及完整性状态");
+    println!("  三术 skill doctor [项目路径]         校验技能 front matter 与配置，报告同名遮蔽");
+    println!();
+    println!("安装选项:");
+    println!("  --name <名称>         覆盖 SKILL.md 中的技能名");
+    println!("  --sha256 <哈希>       校验归档 SHA-256");
+    println!("  --force, -f           覆盖已安装的同名技能");
+    println!();
+    println!("git 地址可用 #<分支|标签|提交> 固定版本，例如 https://github.com/acme/skills.git#v1.2");
+}
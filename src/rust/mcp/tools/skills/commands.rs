@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use super::package::{InstallOptions, InstalledSkill, LockedSkill, SkillPackageManager, UpdateOutcome};
+use super::registry::{doctor, DoctorReport};
+
+/// 列出通过包管理器安装的技能（含完整性状态）
+#[tauri::command]
+pub async fn list_installed_skills() -> Result<Vec<InstalledSkill>, String> {
+    Ok(SkillPackageManager::global().list())
+}
+
+/// 从 git 地址、zip 归档或本地目录安装技能
+#[tauri::command]
+pub async fn install_skill(
+    source: String,
+    name: Option<String>,
+    sha256: Option<String>,
+    force: Option<bool>,
+) -> Result<LockedSkill, String> {
+    let options = InstallOptions {
+        name,
+        sha256,
+        force: force.unwrap_or(false),
+        ..Default::default()
+    };
+    let (_, locked) = SkillPackageManager::global().install(&source, &options).await?;
+    Ok(locked)
+}
+
+/// 按锁定来源更新技能，name 为空时更新全部；归档哈希变化时需 allow_hash_change
+#[tauri::command]
+pub async fn update_skills(name: Option<String>, allow_hash_change: Option<bool>) -> Result<Vec<UpdateOutcome>, String> {
+    SkillPackageManager::global()
+        .update(name.as_deref(), allow_hash_change.unwrap_or(false))
+        .await
+}
+
+/// 按锁文件精确还原技能（锁定提交、归档哈希与内容摘要）
+#[tauri::command]
+pub async fn sync_skills(name: Option<String>) -> Result<Vec<UpdateOutcome>, String> {
+    SkillPackageManager::global().sync(name.as_deref()).await
+}
+
+/// 移除已安装的技能
+#[tauri::command]
+pub async fn remove_skill(name: String) -> Result<(), String> {
+    SkillPackageManager::global().remove(&name).map(|_| ())
+}
+
+/// 诊断技能：校验 front matter 与 skill.config.json，报告同名技能的遮蔽与重名
+#[tauri::command]
+pub async fn skills_doctor(project_root: String) -> Result<DoctorReport, String> {
+    let root = PathBuf::from(&project_root);
+    if !root.is_dir() {
+        return Err(format!("项目目录不存在: {}", project_root));
+    }
+    Ok(doctor(&root))
+}
@@ -1,20 +1,40 @@
+pub mod approval;
+pub mod builtin;
+pub mod cli;
+pub mod commands;
+pub mod package;
+pub mod permissions;
+pub mod registry;
+pub mod runtime;
+mod sandbox;
+pub mod schema;
+#[cfg(feature = "skill-wasm")]
+mod wasm;
+
 use std::borrow::Cow;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Result;
 use rmcp::model::{CallToolResult, Content, ErrorData as McpError, Tool};
+use rmcp::{Peer, RoleServer};
 use serde::{Deserialize, Serialize};
-use tokio::process::Command;
 
 use crate::config::load_standalone_config;
 use crate::{log_debug, log_important};
 use crate::mcp::types::SkillRunRequest;
-use crate::mcp::tools::UiuxTool;
+
+use self::approval::{ensure_approved, ApprovalSubject};
+use self::builtin::builtin_skill;
+use self::permissions::SkillPermissions;
+use self::registry::SkillScope;
+use self::schema::{default_input_schema, object_schema, render_template, validate_arguments};
+use self::runtime::{build_env, run_process, run_wasm, ProcessSpec, SkillRuntime, DEFAULT_TIMEOUT_SECS};
 
 /// 技能运行时工具
-/// 负责发现 skills、动态注册 MCP 工具并执行 Python 入口
+/// 负责发现 skills、动态注册 MCP 工具并按声明的运行时执行入口
 pub struct SkillsTool;
 
 #[derive(Debug, Clone)]
@@ -22,6 +42,7 @@ struct SkillInfo {
     name: String,
     description: Option<String>,
     path: PathBuf,
+    scope: SkillScope,
     config: Option<SkillConfig>,
 }
 
@@ -29,6 +50,16 @@ struct SkillInfo {
 struct SkillConfig {
     #[serde(default)]
     default_action: Option<String>,
+    /// 技能级默认运行时，动作可单独覆盖
+    #[serde(default)]
+    runtime: Option<SkillRuntime>,
+    #[serde(default)]
+    timeout_secs: Option<u64>,
+    #[serde(default)]
+    env_allow: Vec<String>,
+    /// 权限清单（沙箱与审批依据），未声明时按最小权限执行
+    #[serde(default)]
+    permissions: Option<SkillPermissions>,
     #[serde(default)]
     actions: Vec<SkillActionConfig>,
 }
@@ -36,38 +67,131 @@ struct SkillConfig {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 struct SkillActionConfig {
     name: String,
+    /// 入口路径（相对技能目录），builtin 运行时可省略
+    #[serde(default)]
     entry: String,
     #[serde(default)]
+    runtime: Option<SkillRuntime>,
+    /// builtin 处理器名称，默认与技能同名
+    #[serde(default)]
+    builtin: Option<String>,
+    /// 参数模板，`{field}` 引用请求中的命名参数
+    #[serde(default)]
     args_template: Option<Vec<String>>,
     #[serde(default)]
     allow_args: Option<bool>,
+    /// 参数 JSON Schema（作为 skill_<name>_<action> 工具的 input_schema）
+    #[serde(default)]
+    input_schema: Option<serde_json::Value>,
+    /// 输出 JSON Schema；声明后 stdout 需为 JSON 对象，并作为结构化结果返回
+    #[serde(default)]
+    output_schema: Option<serde_json::Value>,
+    #[serde(default)]
+    timeout_secs: Option<u64>,
+    /// 额外透传的环境变量（与技能级列表合并，支持 `PREFIX_*`）
+    #[serde(default)]
+    env_allow: Vec<String>,
+    /// 是否通过 stdin 传入 JSON 请求 {skill, action, query, args, arguments}
+    #[serde(default)]
+    stdin: Option<bool>,
     #[serde(default)]
     description: Option<String>,
 }
 
+/// 解析后的动作执行计划
+#[derive(Debug)]
+struct ResolvedAction {
+    runtime: SkillRuntime,
+    entry: String,
+    builtin: String,
+    args: Vec<String>,
+    timeout: Duration,
+    env_allow: Vec<String>,
+    stdin: bool,
+    output_schema: Option<serde_json::Value>,
+}
+
 impl SkillsTool {
-    /// 获取动态工具列表（包含 skill_run 与各个 skill_<name>）
-    pub fn list_dynamic_tools(project_root: &Path) -> Vec<Tool> {
+    /// 获取动态工具列表（包含 skill_run、各个 skill_<name> 与声明过的 skill_<name>_<action>）
+    ///
+    /// 多个项目根目录按顺序合并，同名技能取排在前面的项目，并在描述中注明被隐藏的项目
+    /// （调用时可用 project_root_path 指定项目）
+    pub fn list_dynamic_tools(project_roots: &[PathBuf]) -> Vec<Tool> {
         let mut tools = Vec::new();
         tools.push(Self::get_skill_run_tool_definition());
 
-        let skills = scan_skills(project_root);
         let input_schema = skills_input_schema();
-
-        // 兼容 Antigravity：动态技能工具名使用下划线分隔
-        for skill in skills {
-            let tool_name = format!("skill_{}", skill.name);
-            let description = skill.description.clone().unwrap_or_else(|| "技能工具".to_string());
-            tools.push(Tool {
-                name: Cow::Owned(tool_name),
-                description: Some(Cow::Owned(description)),
-                input_schema: Arc::new(input_schema.clone()),
-                annotations: None,
-                icons: None,
-                meta: None,
-                output_schema: None,
-                title: None,
-            });
+        let scans: Vec<_> = project_roots.iter().map(|root| (root, registry::skills(root))).collect();
+        let mut seen = HashSet::new();
+        for (index, (project_root, scan)) in scans.iter().enumerate() {
+            // 兼容 Antigravity：动态技能工具名使用下划线分隔
+            for skill in &scan.skills {
+                if !seen.insert(skill.name.clone()) {
+                    continue;
+                }
+                let tool_name = format!("skill_{}", skill.name);
+                let mut description = skill.description.clone().unwrap_or_else(|| "技能工具".to_string());
+                // 同名技能按优先级遮蔽，在描述中注明生效来源
+                let shadowed: Vec<&str> = scan.shadowed_by(skill).map(|s| s.scope.label()).collect();
+                if !shadowed.is_empty() {
+                    description.push_str(&format!(
+                        "（{}目录版本，覆盖了{}目录中的同名技能）",
+                        skill.scope.label(),
+                        shadowed.join("、")
+                    ));
+                }
+                // 多个项目时注明技能所属项目（全局技能各项目共享）
+                if project_roots.len() > 1 && skill.scope != SkillScope::Global {
+                    if let Some(project) = project_root.file_name() {
+                        description.push_str(&format!("（项目: {}）", project.to_string_lossy()));
+                    }
+                }
+                // 后面的项目中不同的同名技能无法单独列出，注明所在项目以便按 project_root_path 调用
+                let hidden: Vec<String> = scans[index + 1..]
+                    .iter()
+                    .filter(|(_, other)| other.find(&skill.name).is_some_and(|s| s.path != skill.path))
+                    .map(|(root, _)| root.display().to_string())
+                    .collect();
+                if !hidden.is_empty() {
+                    log_debug!("技能 {} 在多个项目中重名，已隐藏: {:?}", skill.name, hidden);
+                    description.push_str(&format!(
+                        "（同名技能另见 {}，调用时传入 project_root_path 指定项目）",
+                        hidden.join("、")
+                    ));
+                }
+                tools.push(Tool {
+                    name: Cow::Owned(tool_name),
+                    description: Some(Cow::Owned(description)),
+                    input_schema: Arc::new(input_schema.clone()),
+                    annotations: None,
+                    icons: None,
+                    meta: None,
+                    output_schema: None,
+                    title: None,
+                });
+
+                // 每个动作一个工具，参数来自动作声明的 schema
+                for action in skill.config.iter().flat_map(|c| c.actions.iter()) {
+                    let Some(action_schema) = action_input_schema(action) else {
+                        log_debug!("忽略非对象类型的 input_schema: skill={}, action={}", skill.name, action.name);
+                        continue;
+                    };
+                    let description = action
+                        .description
+                        .clone()
+                        .unwrap_or_else(|| format!("{} 技能的 {} 动作", skill.name, action.name));
+                    tools.push(Tool {
+                        name: Cow::Owned(action_tool_name(&skill.name, &action.name)),
+                        description: Some(Cow::Owned(description)),
+                        input_schema: Arc::new(action_schema),
+                        annotations: None,
+                        icons: None,
+                        meta: None,
+                        output_schema: action.output_schema.as_ref().and_then(object_schema).map(Arc::new),
+                        title: None,
+                    });
+                }
+            }
         }
 
         tools
@@ -77,41 +201,73 @@ impl SkillsTool {
     pub async fn call_tool(
         tool_name: &str,
         mut request: SkillRunRequest,
-        project_root: &Path,
+        project_roots: &[PathBuf],
+        peer: Option<&Peer<RoleServer>>,
     ) -> Result<CallToolResult, McpError> {
-        let skill_name = if tool_name == "skill_run" {
-            request.skill_name.clone().unwrap_or_default()
+        // 技能名已规范化为不含下划线，skill_<name>_<action> 在第一个下划线处拆分
+        let (skill_name, tool_action) = if tool_name == "skill_run" {
+            (request.skill_name.clone().unwrap_or_default(), None)
         } else {
-            tool_name.trim_start_matches("skill_").to_string()
+            let rest = tool_name.trim_start_matches("skill_");
+            match rest.split_once('_') {
+                Some((skill, action)) => (skill.to_string(), Some(action.to_string())),
+                None => (rest.to_string(), None),
+            }
         };
 
         if skill_name.trim().is_empty() {
             return Err(McpError::invalid_params("缺少 skill_name".to_string(), None));
         }
 
-        // 读取技能清单（按需加载，避免启动时全量解析）
-        let skills = scan_skills(project_root);
-        let skill = skills
-            .into_iter()
-            .find(|s| s.name.eq_ignore_ascii_case(&skill_name))
+        // 显式传入的 project_root_path 优先，否则取第一个包含该技能的项目
+        let explicit_root = request
+            .project_root_path
+            .as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from);
+        let candidates: Vec<PathBuf> = match explicit_root {
+            Some(root) => vec![root],
+            None => project_roots.to_vec(),
+        };
+        // 读取技能清单（注册表缓存，技能目录变化时重新扫描）
+        let (project_root, scan) = candidates
+            .iter()
+            .map(|root| (root, registry::skills(root)))
+            .find(|(_, scan)| scan.find(&skill_name).is_some())
             .ok_or_else(|| McpError::invalid_params(format!("未找到技能: {}", skill_name), None))?;
+        let project_root = project_root.as_path();
+        let skill = scan.find(&skill_name).expect("已确认技能存在");
+
+        // 动作工具名中的 action 优先，其次请求里的 action、配置默认 action，最后兜底 search
+        let action_name = match tool_action {
+            Some(tool_action) => skill
+                .config
+                .iter()
+                .flat_map(|c| c.actions.iter())
+                .find(|a| action_tool_name(&skill.name, &a.name) == tool_name)
+                .map(|a| a.name.clone())
+                .ok_or_else(|| McpError::invalid_params(format!("未知 action: {}", tool_action), None))?,
+            None => request
+                .action
+                .clone()
+                .or_else(|| skill.config.as_ref().and_then(|c| c.default_action.clone()))
+                .unwrap_or_else(|| "search".to_string()),
+        };
 
-        // 优先请求里的 action，其次使用配置默认 action，最后兜底 search
-        let action_name = request
-            .action
-            .clone()
-            .or_else(|| skill.config.as_ref().and_then(|c| c.default_action.clone()))
-            .unwrap_or_else(|| "search".to_string());
+        let action = resolve_action(skill, &action_name, &mut request)
+            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
 
-        if skill.name == "ui-ux-pro-max" {
-            return UiuxTool::call_from_skill(&action_name, &request).await;
+        if action.runtime == SkillRuntime::Builtin {
+            let handler = builtin_skill(&action.builtin).ok_or_else(|| {
+                McpError::invalid_params(format!("未注册的内置技能: {}", action.builtin), None)
+            })?;
+            log_important!(info, "技能执行: skill={}, action={}, runtime=builtin", skill.name, action_name);
+            return handler.call(&action_name, &request).await;
         }
 
-        let (entry_rel, args) = resolve_action_args(&skill, &action_name, &mut request)
-            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
-
         // 构建入口路径，并限制在技能目录内执行（防止路径穿透）
-        let entry_path = skill.path.join(&entry_rel);
+        let entry_path = skill.path.join(&action.entry);
         let entry_path = entry_path
             .canonicalize()
             .map_err(|e| McpError::invalid_params(format!("入口路径解析失败: {}", e), None))?;
@@ -123,43 +279,99 @@ impl SkillsTool {
             return Err(McpError::invalid_params("入口路径不在技能目录内".to_string(), None));
         }
 
-        // 选择 Python 执行器：配置优先，其次 PATH
-        let python_bin = load_standalone_config()
-            .ok()
-            .and_then(|c| c.mcp_config.skill_python_path.clone())
-            .unwrap_or_else(|| "python".to_string());
-
         log_important!(
             info,
-            "技能执行: skill={}, action={}, entry={}, args={:?}",
+            "技能执行: skill={}, action={}, runtime={}, entry={}, args={:?}",
             skill.name,
             action_name,
+            action.runtime.label(),
             entry_path.display(),
-            args
+            action.args
         );
 
-        let output = Command::new(python_bin)
-            .arg(entry_path)
-            .args(args)
-            .current_dir(&skill.path)
-            // 确保 Python 输出统一编码，避免控制台乱码
-            .env("PYTHONIOENCODING", "utf-8")
-            .output()
-            .await
-            .map_err(|e| McpError::internal_error(format!("Python 执行失败: {}", e), None))?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-
-        if !output.status.success() {
-            let err_text = if stderr.is_empty() { stdout } else { stderr };
+        let config = load_standalone_config().ok().map(|c| c.mcp_config);
+        let permissions = skill
+            .config
+            .as_ref()
+            .and_then(|c| c.permissions.clone())
+            .unwrap_or_default();
+        ensure_approved(
+            &ApprovalSubject {
+                name: &skill.name,
+                root: &skill_root,
+                runtime: action.runtime.label(),
+                entry: &action.entry,
+                permissions: &permissions,
+            },
+            config.as_ref(),
+            peer,
+        )
+        .await
+        .map_err(|e| McpError::invalid_request(e, None))?;
+
+        let mut env_allow = action.env_allow;
+        env_allow.extend(permissions.env.iter().cloned());
+        let timeout = permissions
+            .max_runtime_secs
+            .map_or(action.timeout, |secs| action.timeout.min(Duration::from_secs(secs)));
+
+        let stdin = action.stdin.then(|| {
+            serde_json::json!({
+                "skill": skill.name,
+                "action": action_name,
+                "query": request.query,
+                "args": request.args.clone().unwrap_or_default(),
+                "arguments": request_arguments(&request),
+            })
+            .to_string()
+            .into_bytes()
+        });
+        let spec = ProcessSpec {
+            runtime: action.runtime,
+            entry: entry_path,
+            args: action.args,
+            cwd: skill_root.clone(),
+            env: build_env(std::env::vars(), &env_allow),
+            stdin,
+            timeout,
+            sandbox: permissions.resolve(&skill_root, project_root),
+        };
+        let output = if spec.runtime == SkillRuntime::Wasm {
+            run_wasm(spec).await
+        } else {
+            run_process(spec, config.as_ref()).await
+        }
+        .map_err(|e| McpError::internal_error(e, None))?;
+
+        if !output.success {
+            let err_text = if output.stderr.is_empty() { output.stdout } else { output.stderr };
             return Err(McpError::internal_error(format!("技能执行失败: {}", err_text), None));
         }
 
-        let final_text = if stdout.is_empty() {
+        if let Some(output_schema) = &action.output_schema {
+            let structured = match serde_json::from_str::<serde_json::Value>(&output.stdout) {
+                Ok(value @ serde_json::Value::Object(_)) => value,
+                _ => {
+                    return Err(McpError::internal_error(
+                        "技能输出不符合 output_schema：stdout 不是 JSON 对象".to_string(),
+                        None,
+                    ))
+                }
+            };
+            if let Some(object) = structured.as_object() {
+                validate_arguments(output_schema, object).map_err(|e| {
+                    McpError::internal_error(format!("技能输出不符合 output_schema：{}", e), None)
+                })?;
+            }
+            let mut result = CallToolResult::success(vec![Content::text(output.stdout)]);
+            result.structured_content = Some(structured);
+            return Ok(result);
+        }
+
+        let final_text = if output.stdout.is_empty() {
             "技能执行完成，但无输出".to_string()
         } else {
-            stdout
+            output.stdout
         };
 
         Ok(CallToolResult::success(vec![Content::text(final_text)]))
@@ -180,6 +392,46 @@ impl SkillsTool {
     }
 }
 
+const PROJECT_ROOT_DESCRIPTION: &str = "项目根目录绝对路径（可选，默认使用客户端 roots）";
+
+/// 动作工具名：skill_<name>_<action>，action 中的非法字符替换为下划线
+fn action_tool_name(skill_name: &str, action_name: &str) -> String {
+    let action: String = action_name
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() || ch == '-' { ch.to_ascii_lowercase() } else { '_' })
+        .collect();
+    format!("skill_{}_{}", skill_name, action)
+}
+
+fn action_input_schema(action: &SkillActionConfig) -> Option<serde_json::Map<String, serde_json::Value>> {
+    let mut schema = match &action.input_schema {
+        Some(declared) => object_schema(declared)?,
+        None => default_input_schema(),
+    };
+    // 所有动作工具都可指定项目目录
+    if let Some(serde_json::Value::Object(properties)) = schema.get_mut("properties") {
+        properties
+            .entry("project_root_path")
+            .or_insert_with(|| serde_json::json!({ "type": "string", "description": PROJECT_ROOT_DESCRIPTION }));
+    }
+    Some(schema)
+}
+
+/// 请求中的命名参数（query、args 与 schema 声明的其他字段）
+fn request_arguments(request: &SkillRunRequest) -> serde_json::Map<String, serde_json::Value> {
+    let mut arguments = request.params.clone();
+    if let Some(query) = &request.query {
+        arguments.insert("query".to_string(), serde_json::Value::String(query.clone()));
+    }
+    if let Some(args) = &request.args {
+        arguments.insert("args".to_string(), serde_json::json!(args));
+    }
+    if let Some(root) = &request.project_root_path {
+        arguments.insert("project_root_path".to_string(), serde_json::Value::String(root.clone()));
+    }
+    arguments
+}
+
 fn skills_input_schema() -> serde_json::Map<String, serde_json::Value> {
     let schema = serde_json::json!({
         "type": "object",
@@ -187,7 +439,8 @@ fn skills_input_schema() -> serde_json::Map<String, serde_json::Value> {
             "skill_name": { "type": "string", "description": "技能名称（仅 skill_run 需要）" },
             "action": { "type": "string", "description": "动作名称（如 search/design_system/custom）" },
             "query": { "type": "string", "description": "查询或输入（可选）" },
-            "args": { "type": "array", "items": { "type": "string" }, "description": "追加参数（可选）" }
+            "args": { "type": "array", "items": { "type": "string" }, "description": "追加参数（可选）" },
+            "project_root_path": { "type": "string", "description": PROJECT_ROOT_DESCRIPTION }
         }
     });
 
@@ -197,93 +450,6 @@ fn skills_input_schema() -> serde_json::Map<String, serde_json::Value> {
     }
 }
 
-fn scan_skills(project_root: &Path) -> Vec<SkillInfo> {
-    let mut skills_map: HashMap<String, SkillInfo> = HashMap::new();
-    let mut seen_paths: HashSet<PathBuf> = HashSet::new();
-
-    for root in build_skill_roots(project_root) {
-        if !root.exists() {
-            continue;
-        }
-        if seen_paths.contains(&root) {
-            continue;
-        }
-        seen_paths.insert(root.clone());
-
-        if let Ok(entries) = std::fs::read_dir(&root) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if !path.is_dir() {
-                    continue;
-                }
-                let skill_md = path.join("SKILL.md");
-                if !skill_md.exists() {
-                    continue;
-                }
-                if let Ok(content) = std::fs::read_to_string(&skill_md) {
-                    let (name_opt, desc_opt) = parse_skill_front_matter(&content);
-                    let name = name_opt
-                        .or_else(|| path.file_name().and_then(|s| s.to_str()).map(|s| s.to_string()))
-                        .unwrap_or_else(|| "unknown-skill".to_string());
-                    let normalized = normalize_skill_name(&name);
-                    if skills_map.contains_key(&normalized) {
-                        continue;
-                    }
-
-                    let config = load_skill_config(&path);
-                    skills_map.insert(
-                        normalized.clone(),
-                        SkillInfo {
-                            name: normalized,
-                            description: desc_opt,
-                            path: path.clone(),
-                            config,
-                        },
-                    );
-                }
-            }
-        }
-    }
-
-    let mut skills: Vec<SkillInfo> = skills_map.into_values().collect();
-    skills.sort_by(|a, b| a.name.cmp(&b.name));
-    skills
-}
-
-fn build_skill_roots(project_root: &Path) -> Vec<PathBuf> {
-    let mut roots = Vec::new();
-
-    // 项目内多生态目录
-    let rel_paths = [
-        ".codex/skills",
-        ".claude/skills",
-        ".continue/skills",
-        ".opencode/skills",
-        ".trae/skills",
-        ".windsurf/skills",
-        ".cursor/skills",
-        ".gemini/skills",
-        ".roo/skills",
-        ".kiro/skills",
-        ".qoder/skills",
-        ".codebuddy/skills",
-        ".agent/skills",
-        ".shared/skills",
-        "skills",
-    ];
-
-    for rel in rel_paths {
-        roots.push(project_root.join(rel));
-    }
-
-    // 用户全局（Codex）
-    if let Some(home) = dirs::home_dir() {
-        roots.push(home.join(".codex").join("skills"));
-    }
-
-    roots
-}
-
 fn parse_skill_front_matter(content: &str) -> (Option<String>, Option<String>) {
     let mut lines = content.lines();
     let first = lines.next().unwrap_or("");
@@ -323,78 +489,103 @@ fn strip_quotes(input: &str) -> String {
     }
 }
 
-fn load_skill_config(skill_path: &Path) -> Option<SkillConfig> {
+/// 读取 skill.config.json；文件不存在时返回 None，读取或解析失败时返回错误说明
+fn read_skill_config(skill_path: &Path) -> Result<Option<SkillConfig>, String> {
     let config_path = skill_path.join("skill.config.json");
     if !config_path.exists() {
-        return None;
-    }
-    match std::fs::read_to_string(&config_path) {
-        Ok(text) => match serde_json::from_str::<SkillConfig>(&text) {
-            Ok(cfg) => Some(cfg),
-            Err(e) => {
-                log_debug!("解析 skill.config.json 失败: {}, path={}", e, config_path.display());
-                None
-            }
-        },
-        Err(e) => {
-            log_debug!("读取 skill.config.json 失败: {}, path={}", e, config_path.display());
-            None
-        }
+        return Ok(None);
     }
+    let text = std::fs::read_to_string(&config_path)
+        .map_err(|e| format!("读取 skill.config.json 失败: {}, path={}", e, config_path.display()))?;
+    serde_json::from_str::<SkillConfig>(&text)
+        .map(Some)
+        .map_err(|e| format!("解析 skill.config.json 失败: {}, path={}", e, config_path.display()))
 }
 
-fn resolve_action_args(
+fn resolve_action(
     skill: &SkillInfo,
     action_name: &str,
     request: &mut SkillRunRequest,
-) -> Result<(String, Vec<String>)> {
+) -> Result<ResolvedAction> {
+    let skill_runtime = skill.config.as_ref().and_then(|c| c.runtime);
+    let skill_timeout = skill.config.as_ref().and_then(|c| c.timeout_secs);
+    let skill_env = skill.config.as_ref().map(|c| c.env_allow.clone()).unwrap_or_default();
+
     // 先尝试使用显式清单
-    if let Some(config) = &skill.config {
-        if let Some(action) = config.actions.iter().find(|a| a.name == action_name) {
-            let mut args = Vec::new();
-            if let Some(template) = &action.args_template {
-                for token in template {
-                    if token.contains("{query}") {
-                        let q = request
-                            .query
-                            .clone()
-                            .ok_or_else(|| anyhow::anyhow!("缺少 query 参数"))?;
-                        args.push(token.replace("{query}", &q));
-                    } else {
-                        args.push(token.clone());
-                    }
-                }
-            }
-            if action.allow_args.unwrap_or(false) {
-                if let Some(extra) = &request.args {
-                    args.extend(extra.clone());
-                }
+    if let Some(action) = skill
+        .config
+        .as_ref()
+        .and_then(|c| c.actions.iter().find(|a| a.name == action_name))
+    {
+        // 声明了 schema 时先校验，模板中缺失的可选字段跳过；未声明时引用的字段均为必填
+        let arguments = request_arguments(request);
+        if let Some(input_schema) = &action.input_schema {
+            validate_arguments(input_schema, &arguments).map_err(|e| anyhow::anyhow!(e))?;
+        }
+        let mut args = match &action.args_template {
+            Some(template) => render_template(template, &arguments, action.input_schema.is_none())
+                .map_err(|e| anyhow::anyhow!(e))?,
+            None => Vec::new(),
+        };
+        if action.allow_args.unwrap_or(false) {
+            if let Some(extra) = &request.args {
+                args.extend(extra.clone());
             }
+        }
 
-            return Ok((action.entry.clone(), args));
+        // 运行时优先级：动作声明 > 技能声明 > 入口扩展名 > python
+        // 内置技能只能显式声明 runtime = "builtin"，同名的磁盘技能仍运行自己的脚本
+        let runtime = action
+            .runtime
+            .or(skill_runtime)
+            .or_else(|| SkillRuntime::infer(&action.entry))
+            .unwrap_or(SkillRuntime::Python);
+        if runtime != SkillRuntime::Builtin && action.entry.trim().is_empty() {
+            return Err(anyhow::anyhow!("动作 {} 未声明 entry", action.name));
         }
+
+        let mut env_allow = skill_env;
+        env_allow.extend(action.env_allow.iter().cloned());
+        return Ok(ResolvedAction {
+            runtime,
+            entry: action.entry.clone(),
+            builtin: action.builtin.clone().unwrap_or_else(|| skill.name.clone()),
+            args,
+            timeout: Duration::from_secs(action.timeout_secs.or(skill_timeout).unwrap_or(DEFAULT_TIMEOUT_SECS)),
+            env_allow,
+            stdin: action.stdin.unwrap_or(false),
+            output_schema: action.output_schema.clone(),
+        });
     }
 
-    // 兜底：约定式入口
-    let search_entry = skill.path.join("scripts").join("search.py");
-    let main_entry = skill.path.join("scripts").join("main.py");
+    let conventional = |runtime: SkillRuntime, entry: &str, args: Vec<String>| ResolvedAction {
+        runtime,
+        entry: entry.to_string(),
+        builtin: skill.name.clone(),
+        args,
+        timeout: Duration::from_secs(skill_timeout.unwrap_or(DEFAULT_TIMEOUT_SECS)),
+        env_allow: skill_env.clone(),
+        stdin: false,
+        output_schema: None,
+    };
+
+    // 未声明的动作交给内置技能自行分派
+    if skill_runtime == Some(SkillRuntime::Builtin) {
+        return Ok(conventional(SkillRuntime::Builtin, "", Vec::new()));
+    }
 
-    if search_entry.exists() {
-        let mut args = Vec::new();
-        if let Some(q) = &request.query {
-            args.push(q.clone());
-        }
-        if let Some(extra) = &request.args {
-            args.extend(extra.clone());
-        }
-        return Ok(("scripts/search.py".to_string(), args));
+    // 兜底：约定式入口
+    let extra = request.args.clone().unwrap_or_default();
+    if skill.path.join("scripts").join("search.py").exists() {
+        let mut args: Vec<String> = request.query.iter().cloned().collect();
+        args.extend(extra);
+        return Ok(conventional(SkillRuntime::Python, "scripts/search.py", args));
     }
-    if main_entry.exists() {
-        let mut args = Vec::new();
-        if let Some(extra) = &request.args {
-            args.extend(extra.clone());
+    for entry in ["scripts/main.py", "scripts/main.js", "scripts/main.sh", "scripts/main.wasm"] {
+        if skill.path.join(entry).exists() {
+            let runtime = SkillRuntime::infer(entry).unwrap_or(SkillRuntime::Python);
+            return Ok(conventional(runtime, entry, extra));
         }
-        return Ok(("scripts/main.py".to_string(), args));
     }
 
     log_debug!("技能未找到可执行入口: {}", skill.name);
@@ -419,3 +610,151 @@ fn normalize_skill_name(name: &str) -> String {
         out
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skill(name: &str, config: serde_json::Value) -> SkillInfo {
+        SkillInfo {
+            name: name.to_string(),
+            description: None,
+            path: std::env::temp_dir().join("sanshu-missing-skill"),
+            scope: SkillScope::Project,
+            config: Some(serde_json::from_value(config).unwrap()),
+        }
+    }
+
+    fn request(action: &str) -> SkillRunRequest {
+        SkillRunRequest {
+            skill_name: None,
+            action: Some(action.to_string()),
+            query: Some("fintech".to_string()),
+            args: Some(vec!["--extra".to_string()]),
+            project_root_path: None,
+            params: serde_json::Map::new(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_action_runtime() {
+        let lint = skill(
+            "lint",
+            serde_json::json!({
+                "runtime": "node",
+                "timeout_secs": 5,
+                "env_allow": ["NODE_*"],
+                "actions": [
+                    { "name": "check", "entry": "bin/check.js", "args_template": ["{query}"], "stdin": true },
+                    { "name": "fmt", "entry": "fmt.sh", "runtime": "bash", "allow_args": true,
+                      "timeout_secs": 30, "env_allow": ["GITHUB_TOKEN"] },
+                    { "name": "broken" }
+                ]
+            }),
+        );
+
+        let check = resolve_action(&lint, "check", &mut request("check")).unwrap();
+        assert_eq!(check.runtime, SkillRuntime::Node);
+        assert_eq!(check.args, vec!["fintech"]);
+        assert_eq!(check.timeout, Duration::from_secs(5));
+        assert!(check.stdin);
+
+        let fmt = resolve_action(&lint, "fmt", &mut request("fmt")).unwrap();
+        assert_eq!(fmt.runtime, SkillRuntime::Bash);
+        assert_eq!(fmt.args, vec!["--extra"]);
+        assert_eq!(fmt.timeout, Duration::from_secs(30));
+        assert_eq!(fmt.env_allow, vec!["NODE_*", "GITHUB_TOKEN"]);
+
+        assert!(resolve_action(&lint, "broken", &mut request("broken")).is_err());
+
+        // 未声明运行时：按扩展名推断
+        let inferred = skill("tool", serde_json::json!({ "actions": [{ "name": "run", "entry": "main.wasm" }] }));
+        assert_eq!(resolve_action(&inferred, "run", &mut request("run")).unwrap().runtime, SkillRuntime::Wasm);
+
+        let uiux = skill("ui-ux-pro-max", serde_json::json!({ "runtime": "builtin", "actions": [] }));
+        let resolved = resolve_action(&uiux, "audit", &mut request("audit")).unwrap();
+        assert_eq!(resolved.runtime, SkillRuntime::Builtin);
+        assert_eq!(resolved.builtin, "ui-ux-pro-max");
+
+        // 与内置技能同名但未声明 builtin 的磁盘技能运行自己的入口
+        let impostor = skill(
+            "ui-ux-pro-max",
+            serde_json::json!({ "actions": [{ "name": "search", "entry": "scripts/search.py" }] }),
+        );
+        let resolved = resolve_action(&impostor, "search", &mut request("search")).unwrap();
+        assert_eq!(resolved.runtime, SkillRuntime::Python);
+        assert_eq!(resolved.entry, "scripts/search.py");
+        assert!(resolve_action(&impostor, "audit", &mut request("audit")).is_err());
+    }
+
+    #[test]
+    fn test_action_tools_and_named_arguments() {
+        let palette = skill(
+            "palette",
+            serde_json::json!({
+                "actions": [{
+                    "name": "Export Tokens",
+                    "entry": "main.js",
+                    "args_template": ["--format", "{format}", "--scale={scale}", "{files}"],
+                    "input_schema": {
+                        "properties": {
+                            "format": { "type": "string", "enum": ["css", "json"] },
+                            "scale": { "type": "integer" },
+                            "files": { "type": "array" }
+                        },
+                        "required": ["files"]
+                    },
+                    "output_schema": { "type": "object", "required": ["path"] }
+                }]
+            }),
+        );
+        let action = &palette.config.as_ref().unwrap().actions[0];
+        assert_eq!(action_tool_name(&palette.name, &action.name), "skill_palette_export_tokens");
+        let schema = action_input_schema(action).unwrap();
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["required"][0], "files");
+
+        let mut typed: SkillRunRequest = serde_json::from_value(serde_json::json!({
+            "format": "css",
+            "files": ["a.json", "b.json"]
+        }))
+        .unwrap();
+        let resolved = resolve_action(&palette, "Export Tokens", &mut typed).unwrap();
+        assert_eq!(resolved.args, vec!["--format", "css", "a.json", "b.json"]);
+        assert!(resolved.output_schema.is_some());
+
+        let mut invalid: SkillRunRequest = serde_json::from_value(serde_json::json!({ "format": "xml" })).unwrap();
+        let err = resolve_action(&palette, "Export Tokens", &mut invalid).err().unwrap().to_string();
+        assert!(err.contains("缺少 files 参数"));
+        assert!(err.contains("参数 format 不在可选值范围内"));
+    }
+
+    #[test]
+    fn test_dynamic_tools_report_cross_root_collisions() {
+        let base = std::env::temp_dir().join(format!("sanshu-skill-tools-{}", std::process::id()));
+        let roots = [base.join("app"), base.join("lib"), base.join("docs")];
+        for (root, description) in roots.iter().zip(["app 版本", "lib 版本", ""]) {
+            if description.is_empty() {
+                std::fs::create_dir_all(root).unwrap();
+                continue;
+            }
+            let dir = root.join("skills").join("collide-demo");
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(
+                dir.join("SKILL.md"),
+                format!("---\nname: collide-demo\ndescription: {}\n---\n", description),
+            )
+            .unwrap();
+        }
+
+        let tools = SkillsTool::list_dynamic_tools(&roots);
+        let demo: Vec<&Tool> = tools.iter().filter(|t| t.name == "skill_collide-demo").collect();
+        assert_eq!(demo.len(), 1);
+        let description = demo[0].description.as_deref().unwrap();
+        assert!(description.starts_with("app 版本"));
+        assert!(description.contains(&roots[1].display().to_string()));
+        assert!(!description.contains(&roots[2].display().to_string()));
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
@@ -0,0 +1,752 @@
+// 技能包管理
+// 从 git 仓库、zip 归档或本地目录安装技能到全局技能目录（~/.sanshu/skills）
+// 锁文件记录来源、固定的提交 / 归档哈希与安装后的内容摘要，用于团队间一致分发与完整性校验
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use ring::digest::{digest, SHA256};
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use crate::log_important;
+
+use super::approval::skill_digest;
+use super::registry;
+use super::{normalize_skill_name, parse_skill_front_matter};
+
+/// 全局技能目录下的锁文件
+pub const LOCK_FILE: &str = "skills.lock.json";
+const LOCK_VERSION: u32 = 1;
+/// 安装过程中的暂存目录（不含 SKILL.md，扫描时自然跳过）
+const STAGING_DIR: &str = ".staging";
+const DOWNLOAD_TIMEOUT_SECS: u64 = 120;
+
+/// 全局技能目录
+pub fn global_skills_root() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".sanshu")
+        .join("skills")
+}
+
+/// 技能来源
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SkillSource {
+    /// git 仓库，reference 为分支 / 标签 / 提交
+    Git {
+        url: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reference: Option<String>,
+    },
+    /// zip 归档（本地路径或 http(s) URL）
+    Archive { location: String },
+    /// 本地目录
+    Dir { path: String },
+}
+
+impl SkillSource {
+    /// 解析安装来源；git 地址可用 `#<分支|标签|提交>` 固定版本
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        if spec.is_empty() {
+            return Err("技能来源不能为空".to_string());
+        }
+        // 以 - 开头的来源会被 git 当作选项解析（如 --upload-pack=<cmd>）
+        if spec.starts_with('-') {
+            return Err(format!("技能来源不能以 - 开头: {}", spec));
+        }
+        let is_remote = spec.starts_with("http://") || spec.starts_with("https://");
+        let path_part = spec.split(['?', '#']).next().unwrap_or(spec);
+
+        if path_part.to_ascii_lowercase().ends_with(".zip") {
+            let location = if is_remote { spec.to_string() } else { absolute(spec) };
+            return Ok(Self::Archive { location });
+        }
+
+        let git_like = spec.starts_with("git@")
+            || spec.starts_with("ssh://")
+            || spec.starts_with("git://")
+            || spec.starts_with("git+")
+            || path_part.ends_with(".git")
+            || is_remote;
+        if git_like {
+            let (url, reference) = match spec.split_once('#') {
+                Some((url, reference)) if !reference.trim().is_empty() => {
+                    (url, Some(reference.trim().to_string()))
+                }
+                Some((url, _)) => (url, None),
+                None => (spec, None),
+            };
+            let url = url.strip_prefix("git+").unwrap_or(url).to_string();
+            if url.starts_with('-') || reference.as_deref().is_some_and(|r| r.starts_with('-')) {
+                return Err(format!("技能来源不能以 - 开头: {}", spec));
+            }
+            return Ok(Self::Git { url, reference });
+        }
+
+        let path = Path::new(spec);
+        if path.is_dir() {
+            return Ok(Self::Dir { path: absolute(spec) });
+        }
+        Err(format!("无法识别的技能来源: {}（支持 git 地址、.zip 归档或本地目录）", spec))
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Git { url, reference: Some(reference) } => format!("{}#{}", url, reference),
+            Self::Git { url, reference: None } => url.clone(),
+            Self::Archive { location } => location.clone(),
+            Self::Dir { path } => path.clone(),
+        }
+    }
+}
+
+fn absolute(path: &str) -> String {
+    std::fs::canonicalize(path)
+        .unwrap_or_else(|_| PathBuf::from(path))
+        .to_string_lossy()
+        .to_string()
+}
+
+/// 锁文件中的一条技能记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedSkill {
+    pub source: SkillSource,
+    /// git 来源实际安装的提交
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// 归档来源的 SHA-256
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_sha256: Option<String>,
+    /// 安装后技能目录的内容摘要（与审批摘要算法一致）
+    pub integrity: String,
+    pub installed_at: String,
+}
+
+impl LockedSkill {
+    /// 固定的提交或归档哈希
+    pub fn pin(&self) -> Option<&str> {
+        self.commit.as_deref().or(self.archive_sha256.as_deref())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillLock {
+    pub version: u32,
+    #[serde(default)]
+    pub skills: BTreeMap<String, LockedSkill>,
+}
+
+impl Default for SkillLock {
+    fn default() -> Self {
+        Self {
+            version: LOCK_VERSION,
+            skills: BTreeMap::new(),
+        }
+    }
+}
+
+/// 安装选项
+#[derive(Debug, Clone, Default)]
+pub struct InstallOptions {
+    /// 覆盖 SKILL.md 中的技能名
+    pub name: Option<String>,
+    /// 归档期望的 SHA-256，不一致时拒绝安装
+    pub sha256: Option<String>,
+    /// 覆盖已安装的同名技能
+    pub force: bool,
+    /// git 来源检出的固定提交（优先于来源中的分支 / 标签）
+    pub commit: Option<String>,
+    /// 安装后内容摘要的期望值，不一致时拒绝安装
+    pub integrity: Option<String>,
+}
+
+/// 已安装技能的完整性状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityStatus {
+    Ok,
+    Modified,
+    Missing,
+}
+
+impl IntegrityStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Ok => "完整",
+            Self::Modified => "已修改",
+            Self::Missing => "缺失",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstalledSkill {
+    pub name: String,
+    pub path: String,
+    pub status: IntegrityStatus,
+    #[serde(flatten)]
+    pub locked: LockedSkill,
+}
+
+/// 更新结果
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateOutcome {
+    pub name: String,
+    pub changed: bool,
+    /// 更新前后固定的提交或归档哈希
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl UpdateOutcome {
+    fn failed(name: String, locked: &LockedSkill, error: String) -> Self {
+        Self {
+            name,
+            changed: false,
+            from: locked.pin().map(str::to_string),
+            to: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// 技能包管理器
+pub struct SkillPackageManager {
+    root: PathBuf,
+}
+
+impl SkillPackageManager {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// 管理全局技能目录
+    pub fn global() -> Self {
+        Self::new(global_skills_root())
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn lock_path(&self) -> PathBuf {
+        self.root.join(LOCK_FILE)
+    }
+
+    pub fn load_lock(&self) -> SkillLock {
+        std::fs::read_to_string(self.lock_path())
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_lock(&self, lock: &SkillLock) -> Result<(), String> {
+        std::fs::create_dir_all(&self.root).map_err(|e| format!("创建技能目录失败: {}", e))?;
+        let text = serde_json::to_string_pretty(lock).map_err(|e| format!("序列化锁文件失败: {}", e))?;
+        std::fs::write(self.lock_path(), text).map_err(|e| format!("写入锁文件失败: {}", e))
+    }
+
+    /// 安装技能并写入锁文件，返回技能名与锁定记录
+    pub async fn install(&self, spec: &str, options: &InstallOptions) -> Result<(String, LockedSkill), String> {
+        let source = SkillSource::parse(spec)?;
+        self.install_source(source, options).await
+    }
+
+    async fn install_source(
+        &self,
+        source: SkillSource,
+        options: &InstallOptions,
+    ) -> Result<(String, LockedSkill), String> {
+        let staging = self
+            .root
+            .join(STAGING_DIR)
+            .join(format!("{}-{}", std::process::id(), chrono::Local::now().timestamp_nanos_opt().unwrap_or_default()));
+        let _ = std::fs::remove_dir_all(&staging);
+        std::fs::create_dir_all(&staging).map_err(|e| format!("创建暂存目录失败: {}", e))?;
+
+        let result = self.stage_and_commit(&source, options, &staging).await;
+        let _ = std::fs::remove_dir_all(&staging);
+        if let Some(parent) = staging.parent() {
+            // 仅在暂存目录为空时删除
+            let _ = std::fs::remove_dir(parent);
+        }
+        result
+    }
+
+    async fn stage_and_commit(
+        &self,
+        source: &SkillSource,
+        options: &InstallOptions,
+        staging: &Path,
+    ) -> Result<(String, LockedSkill), String> {
+        let fetched = staging.join("src");
+        let mut commit = None;
+        let mut archive_sha256 = None;
+        match source {
+            SkillSource::Git { url, reference } => {
+                let reference = options.commit.as_deref().or(reference.as_deref());
+                let fetched_commit = fetch_git(url, reference, &fetched).await?;
+                if let Some(expected) = options.commit.as_deref() {
+                    if !fetched_commit.starts_with(expected) {
+                        return Err(format!("检出的提交 {} 与锁定提交 {} 不一致", fetched_commit, expected));
+                    }
+                }
+                commit = Some(fetched_commit);
+                let _ = std::fs::remove_dir_all(fetched.join(".git"));
+            }
+            SkillSource::Archive { location } => {
+                let bytes = read_archive(location).await?;
+                let actual = hex::encode(digest(&SHA256, &bytes).as_ref());
+                if let Some(expected) = options.sha256.as_deref() {
+                    if !expected.trim().eq_ignore_ascii_case(&actual) {
+                        return Err(format!("归档完整性校验失败: 期望 {}，实际 {}", expected.trim(), actual));
+                    }
+                }
+                extract_archive(&bytes, &fetched)?;
+                archive_sha256 = Some(actual);
+            }
+            SkillSource::Dir { path } => {
+                copy_dir(Path::new(path), &fetched)?;
+            }
+        }
+
+        let skill_dir = locate_skill_dir(&fetched)?;
+        let name = match options.name.as_deref().filter(|n| !n.trim().is_empty()) {
+            Some(name) => normalize_skill_name(name),
+            None => {
+                let content = std::fs::read_to_string(skill_dir.join("SKILL.md"))
+                    .map_err(|e| format!("读取 SKILL.md 失败: {}", e))?;
+                let fallback = skill_dir.file_name().and_then(|n| n.to_str()).map(str::to_string);
+                normalize_skill_name(
+                    &parse_skill_front_matter(&content)
+                        .0
+                        .or(fallback)
+                        .unwrap_or_else(|| "skill".to_string()),
+                )
+            }
+        };
+
+        let target = self.root.join(&name);
+        if target.exists() && !options.force {
+            return Err(format!("技能 {} 已安装，如需覆盖请使用 --force", name));
+        }
+        // 先校验再替换，校验失败时保留已安装的版本
+        let integrity = skill_digest(&skill_dir)?;
+        if let Some(expected) = options.integrity.as_deref() {
+            if expected != integrity {
+                return Err(format!("技能内容与锁文件记录不一致: 期望摘要 {}，实际 {}", expected, integrity));
+            }
+        }
+        replace_dir(&skill_dir, &target, &staging.join("previous"))?;
+
+        let locked = LockedSkill {
+            source: source.clone(),
+            commit,
+            archive_sha256,
+            integrity,
+            installed_at: chrono::Local::now().to_rfc3339(),
+        };
+        let mut lock = self.load_lock();
+        lock.skills.insert(name.clone(), locked.clone());
+        self.save_lock(&lock)?;
+        registry::invalidate_all();
+        log_important!(info, "技能已安装: name={}, source={}", name, source.describe());
+        Ok((name, locked))
+    }
+
+    /// 按锁文件中的来源更新到最新版本；name 为空时更新全部
+    /// git 来源移动到分支 / 标签的最新提交（结果中给出前后提交）；
+    /// 归档来源须与锁定哈希一致，归档内容确有变化时需显式传入 allow_hash_change
+    pub async fn update(&self, name: Option<&str>, allow_hash_change: bool) -> Result<Vec<UpdateOutcome>, String> {
+        let mut outcomes = Vec::new();
+        for (name, locked) in self.locked_targets(name)? {
+            let options = InstallOptions {
+                name: Some(name.clone()),
+                sha256: if allow_hash_change { None } else { locked.archive_sha256.clone() },
+                force: true,
+                commit: None,
+                integrity: None,
+            };
+            let outcome = match self.install_source(locked.source.clone(), &options).await {
+                Ok((_, updated)) => UpdateOutcome {
+                    name,
+                    changed: updated.integrity != locked.integrity,
+                    from: locked.pin().map(str::to_string),
+                    to: updated.pin().map(str::to_string),
+                    error: None,
+                },
+                Err(e) if matches!(locked.source, SkillSource::Archive { .. }) && options.sha256.is_some() => {
+                    let hint = format!("{}（归档内容已变化，确认可信后使用 --allow-hash-change 更新）", e);
+                    UpdateOutcome::failed(name, &locked, hint)
+                }
+                Err(e) => UpdateOutcome::failed(name, &locked, e),
+            };
+            outcomes.push(outcome);
+        }
+        Ok(outcomes)
+    }
+
+    /// 按锁文件精确还原技能（git 检出锁定提交，归档校验锁定哈希，内容校验锁定摘要）
+    /// 已完整安装的技能跳过；name 为空时还原全部
+    pub async fn sync(&self, name: Option<&str>) -> Result<Vec<UpdateOutcome>, String> {
+        let mut outcomes = Vec::new();
+        for (name, locked) in self.locked_targets(name)? {
+            let path = self.root.join(&name);
+            if path.join("SKILL.md").exists() && skill_digest(&path).is_ok_and(|d| d == locked.integrity) {
+                outcomes.push(UpdateOutcome {
+                    name,
+                    changed: false,
+                    from: locked.pin().map(str::to_string),
+                    to: locked.pin().map(str::to_string),
+                    error: None,
+                });
+                continue;
+            }
+            let options = InstallOptions {
+                name: Some(name.clone()),
+                sha256: locked.archive_sha256.clone(),
+                force: true,
+                commit: locked.commit.clone(),
+                integrity: Some(locked.integrity.clone()),
+            };
+            let outcome = match self.install_source(locked.source.clone(), &options).await {
+                Ok((_, restored)) => UpdateOutcome {
+                    name,
+                    changed: true,
+                    from: locked.pin().map(str::to_string),
+                    to: restored.pin().map(str::to_string),
+                    error: None,
+                },
+                Err(e) => UpdateOutcome::failed(name, &locked, e),
+            };
+            outcomes.push(outcome);
+        }
+        Ok(outcomes)
+    }
+
+    fn locked_targets(&self, name: Option<&str>) -> Result<Vec<(String, LockedSkill)>, String> {
+        let lock = self.load_lock();
+        match name {
+            Some(name) => {
+                let name = normalize_skill_name(name);
+                let locked = lock
+                    .skills
+                    .get(&name)
+                    .cloned()
+                    .ok_or_else(|| format!("技能未通过包管理器安装: {}", name))?;
+                Ok(vec![(name, locked)])
+            }
+            None => Ok(lock.skills.into_iter().collect()),
+        }
+    }
+
+    /// 删除技能目录与锁定记录
+    pub fn remove(&self, name: &str) -> Result<LockedSkill, String> {
+        let name = normalize_skill_name(name);
+        let mut lock = self.load_lock();
+        let locked = lock
+            .skills
+            .remove(&name)
+            .ok_or_else(|| format!("技能未通过包管理器安装: {}", name))?;
+        let target = self.root.join(&name);
+        if target.exists() {
+            std::fs::remove_dir_all(&target).map_err(|e| format!("删除技能目录失败: {}", e))?;
+        }
+        self.save_lock(&lock)?;
+        registry::invalidate_all();
+        log_important!(info, "技能已移除: name={}", name);
+        Ok(locked)
+    }
+
+    /// 列出已安装技能，并重新计算摘要校验完整性
+    pub fn list(&self) -> Vec<InstalledSkill> {
+        self.load_lock()
+            .skills
+            .into_iter()
+            .map(|(name, locked)| {
+                let path = self.root.join(&name);
+                let status = if !path.join("SKILL.md").exists() {
+                    IntegrityStatus::Missing
+                } else if skill_digest(&path).is_ok_and(|d| d == locked.integrity) {
+                    IntegrityStatus::Ok
+                } else {
+                    IntegrityStatus::Modified
+                };
+                InstalledSkill {
+                    name,
+                    path: path.to_string_lossy().to_string(),
+                    status,
+                    locked,
+                }
+            })
+            .collect()
+    }
+}
+
+async fn git(args: &[&str], cwd: Option<&Path>) -> Result<String, String> {
+    let mut command = Command::new("git");
+    command.args(args).env("GIT_TERMINAL_PROMPT", "0");
+    if let Some(cwd) = cwd {
+        command.current_dir(cwd);
+    }
+    let output = command.output().await.map_err(|e| format!("执行 git 失败（请确认已安装 git）: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} 失败: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// 克隆仓库并检出指定版本，返回实际提交
+async fn fetch_git(url: &str, reference: Option<&str>, dest: &Path) -> Result<String, String> {
+    let dest_str = dest.to_string_lossy().to_string();
+    let is_commit = reference.is_some_and(|r| r.len() >= 7 && r.chars().all(|c| c.is_ascii_hexdigit()));
+    match reference {
+        // 提交哈希无法浅克隆，完整克隆后检出
+        Some(commit) if is_commit => {
+            git(&["clone", "--quiet", "--no-checkout", "--", url, &dest_str], None).await?;
+            git(&["checkout", "--quiet", "--detach", commit], Some(dest)).await?;
+        }
+        Some(branch) => {
+            git(&["clone", "--quiet", "--depth", "1", "--branch", branch, "--", url, &dest_str], None).await?;
+        }
+        None => {
+            git(&["clone", "--quiet", "--depth", "1", "--", url, &dest_str], None).await?;
+        }
+    }
+    git(&["rev-parse", "HEAD"], Some(dest)).await
+}
+
+async fn read_archive(location: &str) -> Result<Vec<u8>, String> {
+    if location.starts_with("http://") || location.starts_with("https://") {
+        let client = crate::network::create_http_client(None, DOWNLOAD_TIMEOUT_SECS)?;
+        let response = client
+            .get(location)
+            .send()
+            .await
+            .map_err(|e| format!("下载技能归档失败: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("下载技能归档失败: HTTP {}", response.status()));
+        }
+        let bytes = response.bytes().await.map_err(|e| format!("下载技能归档失败: {}", e))?;
+        Ok(bytes.to_vec())
+    } else {
+        std::fs::read(location).map_err(|e| format!("读取技能归档失败: {} ({})", location, e))
+    }
+}
+
+fn extract_archive(bytes: &[u8], dest: &Path) -> Result<(), String> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))
+        .map_err(|e| format!("无法读取 ZIP 归档: {}", e))?;
+    for i in 0..archive.len() {
+        let mut file = archive
+            .by_index(i)
+            .map_err(|e| format!("无法读取 ZIP 条目 {}: {}", i, e))?;
+        // 拒绝包含 .. 或绝对路径的条目
+        let Some(relative) = file.enclosed_name() else {
+            return Err(format!("ZIP 条目路径不安全: {}", file.name()));
+        };
+        let out_path = dest.join(relative);
+        if file.is_dir() {
+            std::fs::create_dir_all(&out_path).map_err(|e| format!("创建目录失败 {}: {}", out_path.display(), e))?;
+            continue;
+        }
+        if let Some(parent) = out_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("创建目录失败 {}: {}", parent.display(), e))?;
+        }
+        let mut out = std::fs::File::create(&out_path)
+            .map_err(|e| format!("创建文件失败 {}: {}", out_path.display(), e))?;
+        std::io::copy(&mut file, &mut out).map_err(|e| format!("解压文件失败 {}: {}", out_path.display(), e))?;
+    }
+    Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(to).map_err(|e| format!("创建目录失败 {}: {}", to.display(), e))?;
+    let entries = std::fs::read_dir(from).map_err(|e| format!("读取目录失败 {}: {}", from.display(), e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        if name == ".git" {
+            continue;
+        }
+        let target = to.join(&name);
+        if path.is_dir() {
+            copy_dir(&path, &target)?;
+        } else {
+            std::fs::copy(&path, &target).map_err(|e| format!("复制文件失败 {}: {}", path.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// 定位包含 SKILL.md 的技能目录：来源根目录，或其唯一的子目录（如 GitHub 归档的顶层目录）
+fn locate_skill_dir(root: &Path) -> Result<PathBuf, String> {
+    if root.join("SKILL.md").exists() {
+        return Ok(root.to_path_buf());
+    }
+    let candidates: Vec<PathBuf> = std::fs::read_dir(root)
+        .map_err(|e| format!("读取来源目录失败: {}", e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && path.join("SKILL.md").exists())
+        .collect();
+    match candidates.as_slice() {
+        [single] => Ok(single.clone()),
+        [] => Err("来源中未找到 SKILL.md".to_string()),
+        _ => Err("来源中包含多个技能，请分别指定目录安装".to_string()),
+    }
+}
+
+/// 以新目录替换目标目录：旧版本先移到 backup，新目录就位后才删除，失败时移回
+fn replace_dir(source: &Path, target: &Path, backup: &Path) -> Result<(), String> {
+    let had_previous = target.exists();
+    if had_previous {
+        std::fs::rename(target, backup).map_err(|e| format!("移除旧版本失败: {}", e))?;
+    }
+    if let Err(e) = std::fs::rename(source, target) {
+        if had_previous {
+            let _ = std::fs::rename(backup, target);
+        }
+        return Err(format!("安装技能失败: {}", e));
+    }
+    if had_previous {
+        let _ = std::fs::remove_dir_all(backup);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_source() {
+        assert_eq!(
+            SkillSource::parse("https://github.com/acme/skills.git#v1.2").unwrap(),
+            SkillSource::Git {
+                url: "https://github.com/acme/skills.git".to_string(),
+                reference: Some("v1.2".to_string()),
+            }
+        );
+        assert_eq!(
+            SkillSource::parse("git+ssh://git@host/team/skill").unwrap(),
+            SkillSource::Git {
+                url: "ssh://git@host/team/skill".to_string(),
+                reference: None,
+            }
+        );
+        assert_eq!(
+            SkillSource::parse("https://example.com/skill.zip?token=1").unwrap(),
+            SkillSource::Archive {
+                location: "https://example.com/skill.zip?token=1".to_string(),
+            }
+        );
+        assert!(matches!(SkillSource::parse("./skill.zip").unwrap(), SkillSource::Archive { .. }));
+        assert!(SkillSource::parse("/definitely/not/here").is_err());
+        // 以 - 开头的来源不能作为 git 选项注入
+        assert!(SkillSource::parse("--upload-pack=touch /tmp/pwned.git").is_err());
+        assert!(SkillSource::parse("git+--upload-pack=x").is_err());
+        assert!(SkillSource::parse("https://github.com/acme/skills.git#--orphan").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_install_dir_and_verify() {
+        let base = std::env::temp_dir().join(format!("sanshu-skill-package-{}", std::process::id()));
+        let source = base.join("source").join("demo");
+        std::fs::create_dir_all(source.join("scripts")).unwrap();
+        std::fs::write(source.join("SKILL.md"), "---\nname: Demo Skill\n---\n").unwrap();
+        std::fs::write(source.join("scripts").join("main.py"), "print('hi')\n").unwrap();
+
+        let manager = SkillPackageManager::new(base.join("installed"));
+        let spec = base.join("source").to_string_lossy().to_string();
+        let (name, locked) = manager.install(&spec, &InstallOptions::default()).await.unwrap();
+        assert_eq!(name, "demo-skill");
+        assert!(matches!(locked.source, SkillSource::Dir { .. }));
+        assert!(manager.root().join("demo-skill").join("scripts").join("main.py").exists());
+        assert!(!manager.root().join(STAGING_DIR).exists());
+
+        // 重复安装需 --force；锁文件可往返
+        assert!(manager.install(&spec, &InstallOptions::default()).await.is_err());
+        assert_eq!(manager.load_lock().skills.get("demo-skill"), Some(&locked));
+
+        assert_eq!(manager.list()[0].status, IntegrityStatus::Ok);
+        std::fs::write(manager.root().join("demo-skill").join("scripts").join("main.py"), "print('x')\n").unwrap();
+        assert_eq!(manager.list()[0].status, IntegrityStatus::Modified);
+
+        // sync 按锁定摘要还原被修改的技能
+        let outcomes = manager.sync(None).await.unwrap();
+        assert!(outcomes[0].error.is_none() && outcomes[0].changed);
+        assert_eq!(manager.list()[0].status, IntegrityStatus::Ok);
+        assert!(!manager.sync(None).await.unwrap()[0].changed);
+
+        // 来源内容变化：sync 拒绝安装且保留锁定记录，update 显式接受新内容
+        std::fs::write(source.join("scripts").join("main.py"), "print('v2')\n").unwrap();
+        std::fs::write(manager.root().join("demo-skill").join("scripts").join("main.py"), "print('x')\n").unwrap();
+        let outcomes = manager.sync(Some("demo-skill")).await.unwrap();
+        assert!(outcomes[0].error.as_deref().is_some_and(|e| e.contains("锁文件")));
+        assert_eq!(manager.load_lock().skills["demo-skill"].integrity, locked.integrity);
+        // 校验失败不影响已安装的版本
+        assert_eq!(
+            std::fs::read_to_string(manager.root().join("demo-skill").join("scripts").join("main.py")).unwrap(),
+            "print('x')\n"
+        );
+        let outcomes = manager.update(Some("demo-skill"), false).await.unwrap();
+        assert!(outcomes[0].error.is_none() && outcomes[0].changed);
+        assert_eq!(manager.list()[0].status, IntegrityStatus::Ok);
+
+        manager.remove("demo-skill").unwrap();
+        assert!(manager.list().is_empty());
+        assert!(!manager.root().join("demo-skill").exists());
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    fn write_zip(path: &Path, body: &str) {
+        use std::io::Write;
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+        writer.start_file("SKILL.md", zip::write::SimpleFileOptions::default()).unwrap();
+        writer.write_all(format!("---\nname: zipped\n---\n{}\n", body).as_bytes()).unwrap();
+        writer.finish().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_archive_update_checks_locked_hash() {
+        let base = std::env::temp_dir().join(format!("sanshu-skill-archive-{}", std::process::id()));
+        std::fs::create_dir_all(&base).unwrap();
+        let archive = base.join("skill.zip");
+        write_zip(&archive, "v1");
+
+        let manager = SkillPackageManager::new(base.join("installed"));
+        let (name, locked) = manager
+            .install(&archive.to_string_lossy(), &InstallOptions::default())
+            .await
+            .unwrap();
+        assert!(locked.archive_sha256.is_some());
+
+        // 归档被替换：update 默认拒绝，锁定记录不变
+        write_zip(&archive, "swapped");
+        let outcomes = manager.update(Some(&name), false).await.unwrap();
+        assert!(outcomes[0].error.as_deref().is_some_and(|e| e.contains("--allow-hash-change")));
+        assert_eq!(manager.load_lock().skills.get(&name), Some(&locked));
+        assert!(manager.sync(None).await.unwrap()[0].error.is_none());
+
+        // 显式允许后重新锁定新哈希
+        let outcomes = manager.update(Some(&name), true).await.unwrap();
+        assert!(outcomes[0].error.is_none());
+        assert_ne!(outcomes[0].to, outcomes[0].from);
+        assert_ne!(manager.load_lock().skills[&name].archive_sha256, locked.archive_sha256);
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
@@ -0,0 +1,148 @@
+// 技能权限清单
+// skill.config.json 中的 permissions 字段：文件系统读写根目录、网络、环境变量与资源上限
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// 权限清单（未声明时按最小权限：只读技能目录与项目目录，禁止网络）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SkillPermissions {
+    /// 额外的只读路径（支持 {skill}/{project}/~ 前缀，相对路径基于项目根目录）
+    #[serde(default)]
+    pub fs_read: Vec<String>,
+    /// 可写路径
+    #[serde(default)]
+    pub fs_write: Vec<String>,
+    /// 是否允许 IPv4/IPv6 网络访问
+    #[serde(default)]
+    pub network: bool,
+    /// 透传给技能进程的环境变量（支持 `PREFIX_*`）
+    #[serde(default)]
+    pub env: Vec<String>,
+    #[serde(default)]
+    pub max_runtime_secs: Option<u64>,
+    #[serde(default)]
+    pub max_memory_mb: Option<u64>,
+}
+
+/// 解析后的沙箱策略
+#[derive(Debug, Clone, Default)]
+pub struct SandboxPolicy {
+    pub read_roots: Vec<PathBuf>,
+    pub write_roots: Vec<PathBuf>,
+    pub network: bool,
+    pub max_cpu_secs: Option<u64>,
+    pub max_memory_bytes: Option<u64>,
+}
+
+impl SkillPermissions {
+    /// 展开路径占位符并规范化，忽略不存在的路径
+    pub fn resolve(&self, skill_root: &Path, project_root: &Path) -> SandboxPolicy {
+        let resolve_into = |roots: &mut Vec<PathBuf>, paths: &[String]| {
+            for path in paths
+                .iter()
+                .filter_map(|raw| expand_path(raw, skill_root, project_root))
+                .filter_map(|path| path.canonicalize().ok())
+            {
+                if !roots.contains(&path) {
+                    roots.push(path);
+                }
+            }
+        };
+
+        let mut read_roots = vec![skill_root.to_path_buf()];
+        if let Ok(project) = project_root.canonicalize() {
+            if !read_roots.contains(&project) {
+                read_roots.push(project);
+            }
+        }
+        resolve_into(&mut read_roots, &self.fs_read);
+        let mut write_roots = Vec::new();
+        resolve_into(&mut write_roots, &self.fs_write);
+
+        SandboxPolicy {
+            read_roots,
+            write_roots,
+            network: self.network,
+            max_cpu_secs: self.max_runtime_secs,
+            max_memory_bytes: self.max_memory_mb.map(|mb| mb.saturating_mul(1024 * 1024)),
+        }
+    }
+
+    /// 审批弹窗中展示的权限摘要（Markdown 列表）
+    pub fn summary(&self) -> String {
+        let list = |paths: &[String]| {
+            if paths.is_empty() {
+                "无".to_string()
+            } else {
+                paths.iter().map(|p| format!("`{}`", p)).collect::<Vec<_>>().join("、")
+            }
+        };
+        let mut lines = vec![
+            format!("- 额外只读路径：{}", list(&self.fs_read)),
+            format!("- 可写路径：{}", list(&self.fs_write)),
+            format!("- 网络访问：{}", if self.network { "允许" } else { "禁止" }),
+            format!("- 环境变量：{}", list(&self.env)),
+        ];
+        if let Some(secs) = self.max_runtime_secs {
+            lines.push(format!("- 最长运行：{} 秒", secs));
+        }
+        if let Some(mb) = self.max_memory_mb {
+            lines.push(format!("- 内存上限：{} MB", mb));
+        }
+        lines.join("\n")
+    }
+}
+
+fn expand_path(raw: &str, skill_root: &Path, project_root: &Path) -> Option<PathBuf> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
+    let (base, rest) = if let Some(rest) = raw.strip_prefix("{skill}") {
+        (skill_root.to_path_buf(), rest)
+    } else if let Some(rest) = raw.strip_prefix("{project}") {
+        (project_root.to_path_buf(), rest)
+    } else if let Some(rest) = raw.strip_prefix('~') {
+        (dirs::home_dir()?, rest)
+    } else if Path::new(raw).is_absolute() {
+        return Some(PathBuf::from(raw));
+    } else {
+        (project_root.to_path_buf(), raw)
+    };
+    let rest = rest.trim_start_matches(['/', '\\']);
+    Some(if rest.is_empty() { base } else { base.join(rest) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_permission_paths() {
+        let base = std::env::temp_dir().join(format!("sanshu-skill-perms-{}", std::process::id()));
+        let skill = base.join("skill");
+        let project = base.join("project");
+        std::fs::create_dir_all(skill.join("data")).unwrap();
+        std::fs::create_dir_all(project.join("dist")).unwrap();
+
+        let permissions: SkillPermissions = serde_json::from_value(serde_json::json!({
+            "fs_read": ["{skill}/data", "missing"],
+            "fs_write": ["dist", "{project}/dist"],
+            "max_memory_mb": 256
+        }))
+        .unwrap();
+        let skill = skill.canonicalize().unwrap();
+        let policy = permissions.resolve(&skill, &project);
+
+        let project = project.canonicalize().unwrap();
+        assert_eq!(policy.read_roots, vec![skill.clone(), project.clone(), skill.join("data")]);
+        assert_eq!(policy.write_roots, vec![project.join("dist")]);
+        assert!(!policy.network);
+        assert_eq!(policy.max_memory_bytes, Some(256 * 1024 * 1024));
+        assert!(permissions.summary().contains("网络访问：禁止"));
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
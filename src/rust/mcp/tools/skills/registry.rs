@@ -0,0 +1,585 @@
+// 技能注册表
+// 按项目缓存技能扫描结果，技能目录变化（文件监听）或目录增删时失效重扫
+// 优先级：项目目录（skills/、.shared/skills）> 各生态目录（.claude/skills 等）> 全局目录，同名技能保留优先级高者并报告被遮蔽者
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use notify_debouncer_full::{
+    new_debouncer,
+    notify::{RecommendedWatcher, RecursiveMode, Watcher},
+    DebounceEventResult, Debouncer, FileIdMap,
+};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use crate::{log_debug, log_important};
+use crate::mcp::types::SkillRunRequest;
+
+use super::builtin::builtin_skill;
+use super::runtime::SkillRuntime;
+use super::schema::object_schema;
+use super::{
+    action_tool_name, normalize_skill_name, package, parse_skill_front_matter, read_skill_config,
+    resolve_action, SkillInfo,
+};
+
+/// 技能目录变化的防抖延迟
+const WATCH_DEBOUNCE_MS: u64 = 300;
+
+/// 项目内共享目录（优先级最高）
+const PROJECT_DIRS: &[&str] = &["skills", ".shared/skills"];
+
+/// 项目内各生态的技能目录
+const ECOSYSTEM_DIRS: &[&str] = &[
+    ".codex/skills",
+    ".claude/skills",
+    ".continue/skills",
+    ".opencode/skills",
+    ".trae/skills",
+    ".windsurf/skills",
+    ".cursor/skills",
+    ".gemini/skills",
+    ".roo/skills",
+    ".kiro/skills",
+    ".qoder/skills",
+    ".codebuddy/skills",
+    ".agent/skills",
+];
+
+/// 技能来源层级，越靠前优先级越高
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillScope {
+    Project,
+    Ecosystem,
+    Global,
+}
+
+impl SkillScope {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Project => "项目",
+            Self::Ecosystem => "生态",
+            Self::Global => "全局",
+        }
+    }
+}
+
+/// 技能扫描根目录
+#[derive(Debug, Clone)]
+pub struct SkillRoot {
+    pub path: PathBuf,
+    pub scope: SkillScope,
+}
+
+/// 被同名技能遮蔽（跨层级）或重名（同层级）而未加载的技能
+#[derive(Debug, Clone, Serialize)]
+pub struct ShadowedSkill {
+    pub name: String,
+    pub path: PathBuf,
+    pub scope: SkillScope,
+    pub shadowed_by: PathBuf,
+    pub shadowed_by_scope: SkillScope,
+}
+
+impl ShadowedSkill {
+    /// 同一层级内重名（通常是误配置）
+    pub fn is_collision(&self) -> bool {
+        self.scope == self.shadowed_by_scope
+    }
+}
+
+/// 一次扫描的结果
+#[derive(Debug, Default)]
+pub(super) struct SkillScan {
+    pub skills: Vec<SkillInfo>,
+    pub shadowed: Vec<ShadowedSkill>,
+}
+
+impl SkillScan {
+    pub fn find(&self, name: &str) -> Option<&SkillInfo> {
+        self.skills.iter().find(|s| s.name.eq_ignore_ascii_case(name))
+    }
+
+    /// 被指定技能遮蔽的其他来源
+    pub fn shadowed_by<'a>(&'a self, skill: &'a SkillInfo) -> impl Iterator<Item = &'a ShadowedSkill> {
+        self.shadowed.iter().filter(move |s| s.shadowed_by == skill.path)
+    }
+}
+
+struct CachedScan {
+    scan: Arc<SkillScan>,
+    /// 各根目录是否存在；目录新建或删除时无法靠已有监听感知，需重扫
+    existing: Vec<bool>,
+    dirty: Arc<AtomicBool>,
+    watcher: Option<Debouncer<RecommendedWatcher, FileIdMap>>,
+}
+
+static CACHE: Lazy<Mutex<HashMap<PathBuf, CachedScan>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 按优先级排列的扫描根目录
+pub fn build_skill_roots(project_root: &Path) -> Vec<SkillRoot> {
+    let mut roots = Vec::new();
+    for (dirs, scope) in [(PROJECT_DIRS, SkillScope::Project), (ECOSYSTEM_DIRS, SkillScope::Ecosystem)] {
+        roots.extend(dirs.iter().map(|rel| SkillRoot {
+            path: project_root.join(rel),
+            scope,
+        }));
+    }
+
+    // 包管理器安装目录优先于其他工具的全局目录
+    roots.push(SkillRoot {
+        path: package::global_skills_root(),
+        scope: SkillScope::Global,
+    });
+    if let Some(home) = dirs::home_dir() {
+        roots.push(SkillRoot {
+            path: home.join(".codex").join("skills"),
+            scope: SkillScope::Global,
+        });
+    }
+    roots
+}
+
+/// 获取项目的技能列表（命中缓存时不访问技能文件）
+pub(super) fn skills(project_root: &Path) -> Arc<SkillScan> {
+    let roots = build_skill_roots(project_root);
+    let existing: Vec<bool> = roots.iter().map(|r| r.path.is_dir()).collect();
+    let key = project_root
+        .canonicalize()
+        .unwrap_or_else(|_| project_root.to_path_buf());
+
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(cached) = cache.get_mut(&key) {
+        if cached.watcher.is_some() && cached.existing == existing {
+            if !cached.dirty.swap(false, Ordering::AcqRel) {
+                return cached.scan.clone();
+            }
+            log_debug!("技能目录有变化，重新扫描: {}", key.display());
+            cached.scan = Arc::new(scan_roots(&roots));
+            return cached.scan.clone();
+        }
+    }
+
+    // 先建立监听再扫描，扫描期间的变化会标记为 dirty
+    let dirty = Arc::new(AtomicBool::new(false));
+    let watcher = watch_roots(&roots, dirty.clone());
+    let scan = Arc::new(scan_roots(&roots));
+    cache.insert(
+        key,
+        CachedScan {
+            scan: scan.clone(),
+            existing,
+            dirty,
+            watcher,
+        },
+    );
+    scan
+}
+
+/// 清空缓存（如安装 / 移除技能后）
+pub fn invalidate_all() {
+    CACHE.lock().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+fn watch_roots(roots: &[SkillRoot], dirty: Arc<AtomicBool>) -> Option<Debouncer<RecommendedWatcher, FileIdMap>> {
+    let mut debouncer = match new_debouncer(
+        Duration::from_millis(WATCH_DEBOUNCE_MS),
+        None,
+        move |result: DebounceEventResult| {
+            if result.is_ok_and(|events| !events.is_empty()) {
+                dirty.store(true, Ordering::Release);
+            }
+        },
+    ) {
+        Ok(debouncer) => debouncer,
+        Err(e) => {
+            // 无法监听时不缓存，每次调用都重新扫描
+            log_debug!("创建技能目录监听失败: {}", e);
+            return None;
+        }
+    };
+
+    let mut watched = HashSet::new();
+    for root in roots.iter().filter(|r| r.path.is_dir()) {
+        if !watched.insert(root.path.clone()) {
+            continue;
+        }
+        if let Err(e) = debouncer.watcher().watch(&root.path, RecursiveMode::Recursive) {
+            log_debug!("监听技能目录失败: {}, path={}", e, root.path.display());
+            return None;
+        }
+    }
+    Some(debouncer)
+}
+
+/// 扫描根目录；根目录按优先级排列，同名技能保留先出现者
+fn scan_roots(roots: &[SkillRoot]) -> SkillScan {
+    let mut scan = SkillScan::default();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut seen_roots: HashSet<PathBuf> = HashSet::new();
+
+    for root in roots {
+        if !seen_roots.insert(root.path.clone()) {
+            continue;
+        }
+        for path in skill_dirs(&root.path) {
+            let Some(info) = load_skill(&path, root.scope) else {
+                continue;
+            };
+            match index.get(&info.name) {
+                Some(&i) => {
+                    let winner = &scan.skills[i];
+                    let shadowed = ShadowedSkill {
+                        name: info.name.clone(),
+                        path: info.path.clone(),
+                        scope: info.scope,
+                        shadowed_by: winner.path.clone(),
+                        shadowed_by_scope: winner.scope,
+                    };
+                    if shadowed.is_collision() {
+                        log_important!(
+                            warn,
+                            "技能重名，已忽略: name={}, kept={}, ignored={}",
+                            shadowed.name,
+                            shadowed.shadowed_by.display(),
+                            shadowed.path.display()
+                        );
+                    } else {
+                        log_debug!(
+                            "技能被遮蔽: name={}, kept={}, shadowed={}",
+                            shadowed.name,
+                            shadowed.shadowed_by.display(),
+                            shadowed.path.display()
+                        );
+                    }
+                    scan.shadowed.push(shadowed);
+                }
+                None => {
+                    index.insert(info.name.clone(), scan.skills.len());
+                    scan.skills.push(info);
+                }
+            }
+        }
+    }
+
+    scan.skills.sort_by(|a, b| a.name.cmp(&b.name));
+    scan
+}
+
+/// 根目录下包含 SKILL.md 的子目录（排序保证重名时结果稳定）
+fn skill_dirs(root: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && path.join("SKILL.md").exists())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn load_skill(path: &Path, scope: SkillScope) -> Option<SkillInfo> {
+    let content = std::fs::read_to_string(path.join("SKILL.md")).ok()?;
+    let (name_opt, description) = parse_skill_front_matter(&content);
+    let name = name_opt
+        .or_else(|| path.file_name().and_then(|s| s.to_str()).map(|s| s.to_string()))
+        .unwrap_or_else(|| "unknown-skill".to_string());
+    let config = match read_skill_config(path) {
+        Ok(config) => config,
+        Err(e) => {
+            log_debug!("{}", e);
+            None
+        }
+    };
+    Some(SkillInfo {
+        name: normalize_skill_name(&name),
+        description,
+        path: path.to_path_buf(),
+        scope,
+        config,
+    })
+}
+
+/// 诊断问题级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueLevel {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorIssue {
+    pub level: IssueLevel,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorSkill {
+    pub name: String,
+    pub path: PathBuf,
+    pub scope: SkillScope,
+    /// 是否实际生效（未被遮蔽）
+    pub active: bool,
+    pub issues: Vec<DoctorIssue>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorRoot {
+    pub path: PathBuf,
+    pub scope: SkillScope,
+    pub exists: bool,
+}
+
+/// 技能诊断报告
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorReport {
+    pub roots: Vec<DoctorRoot>,
+    pub skills: Vec<DoctorSkill>,
+}
+
+impl DoctorReport {
+    pub fn error_count(&self) -> usize {
+        self.skills
+            .iter()
+            .flat_map(|s| s.issues.iter())
+            .filter(|i| i.level == IssueLevel::Error)
+            .count()
+    }
+}
+
+/// 校验各技能的 front matter 与 skill.config.json，并报告遮蔽与重名
+pub fn doctor(project_root: &Path) -> DoctorReport {
+    let roots = build_skill_roots(project_root);
+    let scan = scan_roots(&roots);
+
+    let mut skills = Vec::new();
+    for skill in &scan.skills {
+        let mut issues = check_skill(skill);
+        for shadowed in scan.shadowed_by(skill) {
+            issues.push(DoctorIssue {
+                level: IssueLevel::Warning,
+                message: format!(
+                    "覆盖了{}目录中的同名技能: {}",
+                    shadowed.scope.label(),
+                    shadowed.path.display()
+                ),
+            });
+        }
+        skills.push(DoctorSkill {
+            name: skill.name.clone(),
+            path: skill.path.clone(),
+            scope: skill.scope,
+            active: true,
+            issues,
+        });
+    }
+    for shadowed in &scan.shadowed {
+        let mut issues = load_skill(&shadowed.path, shadowed.scope)
+            .map(|skill| check_skill(&skill))
+            .unwrap_or_default();
+        let message = if shadowed.is_collision() {
+            format!("与同层级技能重名，未加载（生效的是 {}）", shadowed.shadowed_by.display())
+        } else {
+            format!(
+                "被{}目录中的同名技能遮蔽: {}",
+                shadowed.shadowed_by_scope.label(),
+                shadowed.shadowed_by.display()
+            )
+        };
+        issues.insert(
+            0,
+            DoctorIssue {
+                level: if shadowed.is_collision() { IssueLevel::Error } else { IssueLevel::Warning },
+                message,
+            },
+        );
+        skills.push(DoctorSkill {
+            name: shadowed.name.clone(),
+            path: shadowed.path.clone(),
+            scope: shadowed.scope,
+            active: false,
+            issues,
+        });
+    }
+
+    DoctorReport {
+        roots: roots
+            .into_iter()
+            .map(|root| DoctorRoot {
+                exists: root.path.is_dir(),
+                path: root.path,
+                scope: root.scope,
+            })
+            .collect(),
+        skills,
+    }
+}
+
+fn check_skill(skill: &SkillInfo) -> Vec<DoctorIssue> {
+    let mut issues = Vec::new();
+    let mut error = |message: String| issues.push(DoctorIssue { level: IssueLevel::Error, message });
+    let mut warnings = Vec::new();
+    let mut warn = |message: String| warnings.push(DoctorIssue { level: IssueLevel::Warning, message });
+
+    // front matter
+    let content = std::fs::read_to_string(skill.path.join("SKILL.md")).unwrap_or_default();
+    let mut lines = content.lines();
+    if lines.next().map(str::trim) != Some("---") {
+        warn("SKILL.md 缺少 front matter（以 --- 开头的 name / description）".to_string());
+    } else if !lines.any(|line| matches!(line.trim(), "---" | "...")) {
+        error("SKILL.md 的 front matter 未闭合（缺少结束的 ---）".to_string());
+    } else {
+        let (name, description) = parse_skill_front_matter(&content);
+        match name {
+            None => warn("front matter 未声明 name，使用目录名".to_string()),
+            Some(name) if normalize_skill_name(&name) != name => {
+                warn(format!("name \"{}\" 规范化为 {}", name, normalize_skill_name(&name)))
+            }
+            Some(_) => {}
+        }
+        if description.as_deref().is_none_or(|d| d.trim().is_empty()) {
+            warn("front matter 未声明 description，工具描述将显示默认文本".to_string());
+        }
+    }
+
+    // skill.config.json
+    let config = match read_skill_config(&skill.path) {
+        Ok(config) => config,
+        Err(e) => {
+            error(e);
+            None
+        }
+    };
+    match &config {
+        Some(config) => {
+            let mut names = HashSet::new();
+            let mut tool_names = HashSet::new();
+            for action in &config.actions {
+                if !names.insert(action.name.as_str()) {
+                    error(format!("动作 {} 重复声明", action.name));
+                } else if !tool_names.insert(action_tool_name(&skill.name, &action.name)) {
+                    error(format!("动作 {} 的工具名与其他动作冲突", action.name));
+                }
+
+                let runtime = action
+                    .runtime
+                    .or(config.runtime)
+                    .or_else(|| SkillRuntime::infer(&action.entry));
+                if runtime == Some(SkillRuntime::Builtin) {
+                    let handler = action.builtin.as_deref().unwrap_or(&skill.name);
+                    if builtin_skill(handler).is_none() {
+                        error(format!("动作 {} 引用了未注册的内置技能: {}", action.name, handler));
+                    }
+                } else if action.entry.trim().is_empty() {
+                    error(format!("动作 {} 缺少 entry", action.name));
+                } else {
+                    let entry = skill.path.join(&action.entry);
+                    match (entry.canonicalize(), skill.path.canonicalize()) {
+                        (Ok(entry), Ok(root)) if !entry.starts_with(&root) => {
+                            error(format!("动作 {} 的入口不在技能目录内: {}", action.name, action.entry))
+                        }
+                        (Err(_), _) => error(format!("动作 {} 的入口不存在: {}", action.name, action.entry)),
+                        _ => {}
+                    }
+                }
+
+                for (label, schema) in [("input_schema", &action.input_schema), ("output_schema", &action.output_schema)] {
+                    if schema.as_ref().is_some_and(|s| object_schema(s).is_none()) {
+                        error(format!("动作 {} 的 {} 不是对象类型的 JSON Schema", action.name, label));
+                    }
+                }
+            }
+            if let Some(default_action) = &config.default_action {
+                if !config.actions.is_empty() && !names.contains(default_action.as_str()) {
+                    error(format!("default_action {} 未在 actions 中声明", default_action));
+                }
+            }
+        }
+        None => {
+            // 无清单时确认约定式入口可用
+            let mut request = SkillRunRequest {
+                skill_name: None,
+                action: None,
+                query: None,
+                args: None,
+                project_root_path: None,
+                params: serde_json::Map::new(),
+            };
+            if resolve_action(skill, "search", &mut request).is_err() {
+                warn("未提供 skill.config.json，也未找到约定式入口（scripts/search.py、scripts/main.* 等）".to_string());
+            }
+        }
+    }
+
+    issues.extend(warnings);
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_skill(root: &Path, dir: &str, front_matter: &str, config: Option<&str>) {
+        let path = root.join(dir);
+        std::fs::create_dir_all(path.join("scripts")).unwrap();
+        std::fs::write(path.join("SKILL.md"), front_matter).unwrap();
+        std::fs::write(path.join("scripts").join("main.py"), "print('hi')\n").unwrap();
+        if let Some(config) = config {
+            std::fs::write(path.join("skill.config.json"), config).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_precedence_shadowing_and_doctor() {
+        let base = std::env::temp_dir().join(format!("sanshu-skill-registry-{}", std::process::id()));
+        let roots = [
+            SkillRoot { path: base.join("project"), scope: SkillScope::Project },
+            SkillRoot { path: base.join("claude"), scope: SkillScope::Ecosystem },
+            SkillRoot { path: base.join("global"), scope: SkillScope::Global },
+        ];
+        let ok = "---\nname: lint\ndescription: 项目 lint\n---\n";
+        write_skill(&roots[0].path, "lint", ok, None);
+        write_skill(&roots[2].path, "lint", "---\nname: lint\ndescription: 全局 lint\n---\n", None);
+        write_skill(&roots[1].path, "a-fmt", "---\nname: fmt\ndescription: x\n---\n", None);
+        write_skill(&roots[1].path, "b-fmt", "---\nname: FMT\n---\n", None);
+        write_skill(
+            &roots[1].path,
+            "broken",
+            "---\nname: broken\ndescription: x\n",
+            Some(r#"{ "default_action": "go", "actions": [{ "name": "run", "entry": "missing.py" }, { "name": "run", "entry": "scripts/main.py" }] }"#),
+        );
+
+        let scan = scan_roots(&roots);
+        let names: Vec<&str> = scan.skills.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["broken", "fmt", "lint"]);
+        // 项目目录优先于全局目录；同层级重名保留排序靠前者
+        let lint = scan.find("lint").unwrap();
+        assert_eq!(lint.scope, SkillScope::Project);
+        assert_eq!(lint.description.as_deref(), Some("项目 lint"));
+        assert_eq!(scan.shadowed_by(lint).count(), 1);
+        let fmt = scan.find("fmt").unwrap();
+        assert!(fmt.path.ends_with("a-fmt"));
+        assert!(scan.shadowed.iter().any(|s| s.is_collision() && s.path.ends_with("b-fmt")));
+
+        let issues: Vec<String> = check_skill(scan.find("broken").unwrap())
+            .into_iter()
+            .filter(|i| i.level == IssueLevel::Error)
+            .map(|i| i.message)
+            .collect();
+        assert!(issues.iter().any(|m| m.contains("未闭合")));
+        assert!(issues.iter().any(|m| m.contains("入口不存在")));
+        assert!(issues.iter().any(|m| m.contains("重复声明")));
+        assert!(issues.iter().any(|m| m.contains("default_action go")));
+        assert!(check_skill(lint).is_empty());
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
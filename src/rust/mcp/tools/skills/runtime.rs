@@ -0,0 +1,405 @@
+// 技能运行时
+// 按动作声明的 runtime 选择执行器：解释器子进程（python/node/bash/pwsh）或嵌入式 WASI
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::config::McpConfig;
+use crate::log_debug;
+
+use super::permissions::SandboxPolicy;
+use super::sandbox;
+
+/// 动作未声明超时时的默认值（秒）
+pub const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+/// 始终透传给技能进程的环境变量（定位解释器、临时目录与区域设置所需）
+const BASE_ENV_ALLOW: &[&str] = &[
+    "PATH", "PATHEXT", "HOME", "USERPROFILE", "SYSTEMROOT", "WINDIR", "COMSPEC", "TEMP", "TMP",
+    "TMPDIR", "LANG", "LC_ALL", "APPDATA", "LOCALAPPDATA",
+];
+
+/// 技能运行时
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SkillRuntime {
+    Python,
+    Node,
+    #[serde(alias = "sh", alias = "shell")]
+    Bash,
+    #[serde(alias = "powershell")]
+    Pwsh,
+    Wasm,
+    /// 编译进程序的 Rust 技能（见 builtin 注册表）
+    Builtin,
+}
+
+impl SkillRuntime {
+    /// 按入口扩展名推断运行时
+    pub fn infer(entry: &str) -> Option<Self> {
+        let ext = Path::new(entry).extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "py" => Some(Self::Python),
+            "js" | "mjs" | "cjs" => Some(Self::Node),
+            "sh" | "bash" => Some(Self::Bash),
+            "ps1" => Some(Self::Pwsh),
+            "wasm" => Some(Self::Wasm),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Python => "Python",
+            Self::Node => "Node.js",
+            Self::Bash => "Bash",
+            Self::Pwsh => "PowerShell",
+            Self::Wasm => "WASM",
+            Self::Builtin => "内置技能",
+        }
+    }
+
+    /// 解释器路径：配置优先，其次 PATH
+    fn interpreter(&self, config: Option<&McpConfig>) -> Option<String> {
+        let (configured, fallback) = match self {
+            Self::Python => (config.and_then(|c| c.skill_python_path.clone()), "python"),
+            Self::Node => (config.and_then(|c| c.skill_node_path.clone()), "node"),
+            Self::Bash => (config.and_then(|c| c.skill_bash_path.clone()), "bash"),
+            Self::Pwsh => (config.and_then(|c| c.skill_pwsh_path.clone()), "pwsh"),
+            Self::Wasm | Self::Builtin => return None,
+        };
+        Some(
+            configured
+                .filter(|p| !p.trim().is_empty())
+                .unwrap_or_else(|| fallback.to_string()),
+        )
+    }
+
+    /// 解释器自身需要的前置参数
+    fn leading_args(&self) -> &'static [&'static str] {
+        match self {
+            Self::Pwsh => &["-NoProfile", "-NonInteractive", "-File"],
+            // 不写入字节码缓存，避免运行后技能摘要变化
+            Self::Python => &["-B"],
+            _ => &[],
+        }
+    }
+}
+
+/// 一次技能执行的参数
+pub struct ProcessSpec {
+    pub runtime: SkillRuntime,
+    pub entry: PathBuf,
+    pub args: Vec<String>,
+    pub cwd: PathBuf,
+    pub env: Vec<(String, String)>,
+    pub stdin: Option<Vec<u8>>,
+    pub timeout: Duration,
+    pub sandbox: SandboxPolicy,
+}
+
+/// 技能执行结果
+pub struct SkillOutput {
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// 构建技能进程环境：仅透传基础变量与 allow-list 中的变量（支持 `PREFIX_*` 前缀匹配）
+pub fn build_env(
+    vars: impl IntoIterator<Item = (String, String)>,
+    allow: &[String],
+) -> Vec<(String, String)> {
+    let matches = |key: &str, pattern: &str| match pattern.strip_suffix('*') {
+        Some(prefix) => key.len() >= prefix.len() && key[..prefix.len()].eq_ignore_ascii_case(prefix),
+        None => key.eq_ignore_ascii_case(pattern),
+    };
+    let mut env: Vec<(String, String)> = vars
+        .into_iter()
+        .filter(|(key, _)| {
+            BASE_ENV_ALLOW.iter().any(|p| matches(key, p)) || allow.iter().any(|p| matches(key, p))
+        })
+        .collect();
+    env.sort();
+    env
+}
+
+/// 以子进程执行解释型技能，超时后终止进程
+pub async fn run_process(spec: ProcessSpec, config: Option<&McpConfig>) -> Result<SkillOutput, String> {
+    let program = spec
+        .runtime
+        .interpreter(config)
+        .ok_or_else(|| format!("{} 运行时不支持子进程执行", spec.runtime.label()))?;
+
+    let mut command = Command::new(&program);
+    command
+        .args(spec.runtime.leading_args())
+        .arg(&spec.entry)
+        .args(&spec.args)
+        .current_dir(&spec.cwd)
+        .env_clear()
+        .envs(spec.env.iter().map(|(k, v)| (k, v)))
+        .stdin(if spec.stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if spec.runtime == SkillRuntime::Python {
+        // 确保 Python 输出统一编码，避免控制台乱码
+        command.env("PYTHONIOENCODING", "utf-8");
+        // 不写入字节码；技能目录内的 __pycache__ 已计入审批摘要
+        command.env("PYTHONDONTWRITEBYTECODE", "1");
+    }
+
+    // 守卫持有到函数结束：子进程退出后再清理私有临时目录
+    let guard = sandbox::apply(&mut command, &spec.sandbox, &program)?;
+    log_debug!("技能沙箱机制: {:?}", guard.mechanisms);
+    ProcessTree::prepare(&mut command);
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("{} 执行失败: {}", spec.runtime.label(), e))?;
+    let tree = ProcessTree::attach(&child);
+
+    // 独立任务写入 stdin，避免子进程输出阻塞时互相等待
+    if let (Some(input), Some(mut pipe)) = (spec.stdin, child.stdin.take()) {
+        tokio::spawn(async move {
+            let _ = pipe.write_all(&input).await;
+        });
+    }
+
+    // 超时时先终止整个进程树（子进程尚未回收，进程组不会被复用），再丢弃 future
+    let wait = child.wait_with_output();
+    tokio::pin!(wait);
+    let output = match tokio::time::timeout(spec.timeout, &mut wait).await {
+        Ok(output) => output.map_err(|e| format!("{} 执行失败: {}", spec.runtime.label(), e))?,
+        Err(_) => {
+            tree.kill();
+            return Err(format!("技能执行超时（{} 秒）", spec.timeout.as_secs()));
+        }
+    };
+
+    Ok(SkillOutput {
+        success: output.status.success(),
+        stdout: String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    })
+}
+
+/// 技能进程树：Unix 上子进程自成进程组，Windows 上加入作业对象，超时时连同孙进程一起终止
+struct ProcessTree {
+    #[cfg(unix)]
+    pgid: Option<i32>,
+    #[cfg(windows)]
+    job: Option<job::Job>,
+}
+
+impl ProcessTree {
+    /// spawn 前调用
+    fn prepare(command: &mut Command) {
+        #[cfg(unix)]
+        command.process_group(0);
+        #[cfg(not(unix))]
+        let _ = command;
+    }
+
+    /// spawn 后调用；Windows 上在加入作业对象前已启动的孙进程不受控制
+    fn attach(child: &tokio::process::Child) -> Self {
+        Self {
+            #[cfg(unix)]
+            pgid: child.id().and_then(|id| i32::try_from(id).ok()),
+            #[cfg(windows)]
+            job: child.raw_handle().and_then(job::Job::assign),
+        }
+    }
+
+    fn kill(&self) {
+        #[cfg(unix)]
+        if let Some(pgid) = self.pgid {
+            // 负数 pid 表示向整个进程组发送信号
+            unsafe {
+                libc::kill(-pgid, libc::SIGKILL);
+            }
+        }
+        #[cfg(windows)]
+        if let Some(job) = &self.job {
+            job.terminate();
+        }
+    }
+}
+
+#[cfg(windows)]
+mod job {
+    use std::os::windows::io::RawHandle;
+
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation, SetInformationJobObject,
+        TerminateJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+
+    /// 作业对象：关闭句柄时终止其中仍在运行的进程
+    pub struct Job(HANDLE);
+
+    // 句柄只在本结构内使用，可跨线程移动
+    unsafe impl Send for Job {}
+
+    impl Job {
+        pub fn assign(process: RawHandle) -> Option<Self> {
+            unsafe {
+                let handle = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+                if handle.is_null() {
+                    return None;
+                }
+                let job = Self(handle);
+                let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+                info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+                let configured = SetInformationJobObject(
+                    handle,
+                    JobObjectExtendedLimitInformation,
+                    &info as *const JOBOBJECT_EXTENDED_LIMIT_INFORMATION as *const core::ffi::c_void,
+                    std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+                ) != 0;
+                (configured && AssignProcessToJobObject(handle, process as HANDLE) != 0).then_some(job)
+            }
+        }
+
+        pub fn terminate(&self) {
+            unsafe {
+                TerminateJobObject(self.0, 1);
+            }
+        }
+    }
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            unsafe {
+                CloseHandle(self.0);
+            }
+        }
+    }
+}
+
+/// 在嵌入式 WASI 运行时中执行 .wasm 技能（技能目录只读挂载）
+#[cfg(feature = "skill-wasm")]
+pub async fn run_wasm(spec: ProcessSpec) -> Result<SkillOutput, String> {
+    tokio::task::spawn_blocking(move || super::wasm::run(spec))
+        .await
+        .map_err(|e| format!("WASM 执行失败: {}", e))?
+}
+
+#[cfg(not(feature = "skill-wasm"))]
+pub async fn run_wasm(_spec: ProcessSpec) -> Result<SkillOutput, String> {
+    Err("当前构建未启用 WASM 技能运行时（skill-wasm 特性）".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_runtime_and_env_allow_list() {
+        assert_eq!(SkillRuntime::infer("scripts/search.py"), Some(SkillRuntime::Python));
+        assert_eq!(SkillRuntime::infer("bin/main.MJS"), Some(SkillRuntime::Node));
+        assert_eq!(SkillRuntime::infer("run.ps1"), Some(SkillRuntime::Pwsh));
+        assert_eq!(SkillRuntime::infer("skill.wasm"), Some(SkillRuntime::Wasm));
+        assert_eq!(SkillRuntime::infer("Makefile"), None);
+        assert_eq!(
+            serde_json::from_str::<SkillRuntime>("\"sh\"").unwrap(),
+            SkillRuntime::Bash
+        );
+
+        let vars = [
+            ("PATH", "/usr/bin"),
+            ("Path", "C:\\Windows"),
+            ("AWS_SECRET_ACCESS_KEY", "secret"),
+            ("GITHUB_TOKEN", "token"),
+            ("SKILL_MODE", "fast"),
+            ("SKILL_LEVEL", "2"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+        let env = build_env(vars, &["GITHUB_TOKEN".to_string(), "skill_*".to_string()]);
+        let keys: Vec<&str> = env.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, vec!["GITHUB_TOKEN", "PATH", "Path", "SKILL_LEVEL", "SKILL_MODE"]);
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_process_timeout_and_stdin() {
+        let dir = std::env::temp_dir().join(format!("sanshu-skill-runtime-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let entry = dir.join("echo.sh");
+        std::fs::write(&entry, "read line\necho \"got:$line\"\n[ \"$1\" = slow ] && sleep 5\nexit 0\n").unwrap();
+
+        let spec = |arg: &str, timeout: u64| ProcessSpec {
+            runtime: SkillRuntime::Bash,
+            entry: entry.clone(),
+            args: vec![arg.to_string()],
+            cwd: dir.clone(),
+            env: build_env(std::env::vars(), &[]),
+            stdin: Some(b"{\"query\":\"hi\"}\n".to_vec()),
+            timeout: Duration::from_secs(timeout),
+            sandbox: SandboxPolicy {
+                read_roots: vec![dir.clone()],
+                ..Default::default()
+            },
+        };
+
+        let output = run_process(spec("fast", 10), None).await.unwrap();
+        assert!(output.success);
+        assert_eq!(output.stdout, "got:{\"query\":\"hi\"}");
+
+        let err = run_process(spec("slow", 1), None).await.err().unwrap();
+        assert!(err.contains("超时"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    #[cfg(target_os = "linux")]
+    async fn test_timeout_kills_grandchildren() {
+        let dir = std::env::temp_dir().join(format!("sanshu-skill-tree-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let entry = dir.join("spawn.sh");
+        let pid_file = dir.join("grandchild.pid");
+        std::fs::write(&entry, "sleep 30 &\necho $! > grandchild.pid\nwait\n").unwrap();
+
+        let spec = ProcessSpec {
+            runtime: SkillRuntime::Bash,
+            entry: entry.clone(),
+            args: Vec::new(),
+            cwd: dir.clone(),
+            env: build_env(std::env::vars(), &[]),
+            stdin: None,
+            timeout: Duration::from_secs(1),
+            sandbox: SandboxPolicy {
+                read_roots: vec![dir.clone()],
+                write_roots: vec![dir.clone()],
+                ..Default::default()
+            },
+        };
+        let err = run_process(spec, None).await.err().unwrap();
+        assert!(err.contains("超时"));
+
+        // 孙进程随进程组一起终止（已退出或仅剩僵尸进程）
+        let pid = std::fs::read_to_string(&pid_file).unwrap().trim().to_string();
+        let alive = || {
+            std::fs::read_to_string(format!("/proc/{}/stat", pid))
+                .map(|stat| !stat.rsplit(')').next().unwrap_or_default().trim_start().starts_with('Z'))
+                .unwrap_or(false)
+        };
+        for _ in 0..50 {
+            if !alive() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(!alive());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
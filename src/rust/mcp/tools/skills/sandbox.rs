@@ -0,0 +1,399 @@
+// 技能进程沙箱
+// Unix 上以 rlimit 限制 CPU 时间与内存；Linux 上额外以 landlock 限制文件系统访问、seccomp 禁止 IPv4/IPv6 套接字
+// 内核不支持的机制自动跳过，实际生效的机制记录在 SandboxGuard::mechanisms 中
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tokio::process::Command;
+
+use super::permissions::SandboxPolicy;
+
+/// 解释器运行所需的系统只读路径
+#[cfg(target_os = "linux")]
+const SYSTEM_READ_ROOTS: &[&str] = &[
+    "/usr", "/lib", "/lib64", "/lib32", "/bin", "/sbin", "/etc", "/opt", "/nix", "/proc", "/sys", "/dev",
+];
+
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// 沙箱守卫：需保持到子进程结束，释放时清理私有临时目录
+pub struct SandboxGuard {
+    pub mechanisms: Vec<&'static str>,
+    temp_dir: Option<PathBuf>,
+    #[cfg(target_os = "linux")]
+    _ruleset: Option<std::os::fd::OwnedFd>,
+}
+
+impl Drop for SandboxGuard {
+    fn drop(&mut self) {
+        if let Some(dir) = self.temp_dir.take() {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+}
+
+/// 在 spawn 前为命令配置沙箱（私有临时目录、资源限制与访问控制）
+pub fn apply(command: &mut Command, policy: &SandboxPolicy, program: &str) -> Result<SandboxGuard, String> {
+    let temp_dir = std::env::temp_dir().join(format!(
+        "sanshu-skill-{}-{}",
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::create_dir_all(&temp_dir).map_err(|e| format!("创建技能临时目录失败: {}", e))?;
+    for key in ["TMPDIR", "TMP", "TEMP"] {
+        command.env(key, &temp_dir);
+    }
+
+    let mut write_roots = policy.write_roots.clone();
+    write_roots.push(temp_dir.clone());
+    let mut read_roots = policy.read_roots.clone();
+    read_roots.extend(interpreter_root(program));
+
+    let mut guard = SandboxGuard {
+        mechanisms: Vec::new(),
+        temp_dir: Some(temp_dir),
+        #[cfg(target_os = "linux")]
+        _ruleset: None,
+    };
+
+    #[cfg(unix)]
+    {
+        let limits = unix::Limits {
+            cpu_secs: policy.max_cpu_secs,
+            memory_bytes: policy.max_memory_bytes,
+        };
+        if limits.cpu_secs.is_some() || limits.memory_bytes.is_some() {
+            guard.mechanisms.push("rlimit");
+        }
+
+        #[cfg(target_os = "linux")]
+        let (ruleset_fd, filter) = {
+            use std::os::fd::AsRawFd;
+
+            read_roots.extend(SYSTEM_READ_ROOTS.iter().map(PathBuf::from));
+            write_roots.push(PathBuf::from("/dev/null"));
+            let ruleset = linux::landlock_ruleset(&read_roots, &write_roots);
+            if ruleset.is_some() {
+                guard.mechanisms.push("landlock");
+            }
+            let filter = if policy.network { None } else { linux::network_filter() };
+            if filter.is_some() {
+                guard.mechanisms.push("seccomp");
+            }
+            let fd = ruleset.as_ref().map(|fd| fd.as_raw_fd());
+            guard._ruleset = ruleset;
+            (fd, filter)
+        };
+
+        // pre_exec 在 fork 后的子进程中执行，只能调用异步信号安全的系统调用
+        unsafe {
+            command.pre_exec(move || {
+                unix::apply_limits(&limits)?;
+                #[cfg(target_os = "linux")]
+                linux::restrict_self(ruleset_fd, filter.as_deref())?;
+                Ok(())
+            });
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    let _ = (read_roots, write_roots);
+
+    Ok(guard)
+}
+
+/// 解释器所在的安装目录（bin 目录取其上级），用于放行 ~/.nvm、~/.pyenv 等用户级安装
+fn interpreter_root(program: &str) -> Option<PathBuf> {
+    let path = if Path::new(program).components().count() > 1 {
+        PathBuf::from(program)
+    } else {
+        std::env::split_paths(&std::env::var_os("PATH")?)
+            .map(|dir| dir.join(program))
+            .find(|candidate| candidate.is_file())?
+    };
+    let real = path.canonicalize().ok()?;
+    let parent = real.parent()?;
+    if parent.file_name().is_some_and(|name| name == "bin") {
+        parent.parent().map(Path::to_path_buf)
+    } else {
+        Some(parent.to_path_buf())
+    }
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::io;
+
+    #[derive(Clone, Copy)]
+    pub struct Limits {
+        pub cpu_secs: Option<u64>,
+        pub memory_bytes: Option<u64>,
+    }
+
+    pub fn apply_limits(limits: &Limits) -> io::Result<()> {
+        // CPU 时间多留 1 秒，让墙钟超时优先给出可读的错误
+        let cpu = limits.cpu_secs.map(|secs| secs.saturating_add(1));
+        // 以数据段（含私有匿名映射）计内存，避免 V8 等运行时的地址空间预留被误伤
+        for (resource, value) in [(libc::RLIMIT_CPU, cpu), (libc::RLIMIT_DATA, limits.memory_bytes)] {
+            if let Some(value) = value {
+                let limit = libc::rlimit {
+                    rlim_cur: value as libc::rlim_t,
+                    rlim_max: value as libc::rlim_t,
+                };
+                if unsafe { libc::setrlimit(resource, &limit) } != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::ffi::CString;
+    use std::io;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::{Path, PathBuf};
+
+    const LANDLOCK_CREATE_RULESET_VERSION: u32 = 1;
+    const LANDLOCK_RULE_PATH_BENEATH: u32 = 1;
+    const ACCESS_EXECUTE: u64 = 1 << 0;
+    const ACCESS_WRITE_FILE: u64 = 1 << 1;
+    const ACCESS_READ_FILE: u64 = 1 << 2;
+    const ACCESS_READ_DIR: u64 = 1 << 3;
+    /// landlock ABI v1 的全部文件系统权限
+    const ACCESS_FS_ALL: u64 = (1 << 13) - 1;
+    /// ABI v3 新增：截断文件（truncate/ftruncate/O_TRUNC）
+    const ACCESS_TRUNCATE: u64 = 1 << 14;
+    const ACCESS_FS_READ: u64 = ACCESS_EXECUTE | ACCESS_READ_FILE | ACCESS_READ_DIR;
+    /// 普通文件只能授予文件级权限
+    const ACCESS_FILE: u64 = ACCESS_EXECUTE | ACCESS_WRITE_FILE | ACCESS_READ_FILE | ACCESS_TRUNCATE;
+
+    #[repr(C)]
+    struct RulesetAttr {
+        handled_access_fs: u64,
+    }
+
+    #[repr(C, packed)]
+    struct PathBeneathAttr {
+        allowed_access: u64,
+        parent_fd: i32,
+    }
+
+    /// BPF_LD | BPF_W | BPF_ABS
+    const BPF_LD_W_ABS: u16 = 0x20;
+    /// BPF_JMP | BPF_JEQ | BPF_K
+    const BPF_JEQ_K: u16 = 0x15;
+    /// BPF_JMP | BPF_JGE | BPF_K
+    const BPF_JGE_K: u16 = 0x35;
+    /// x32 ABI 系统调用号标志位（与 x86_64 共用 AUDIT_ARCH）
+    const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+    /// BPF_RET | BPF_K
+    const BPF_RET_K: u16 = 0x06;
+    const SECCOMP_DATA_NR: u32 = 0;
+    const SECCOMP_DATA_ARCH: u32 = 4;
+    const SECCOMP_DATA_ARG0: u32 = 16;
+
+    #[cfg(target_arch = "x86_64")]
+    const AUDIT_ARCH: Option<u32> = Some(0xC000_003E);
+    #[cfg(target_arch = "aarch64")]
+    const AUDIT_ARCH: Option<u32> = Some(0xC000_00B7);
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    const AUDIT_ARCH: Option<u32> = None;
+
+    /// 创建 landlock 规则集；内核不支持时返回 None
+    pub fn landlock_ruleset(read_roots: &[PathBuf], write_roots: &[PathBuf]) -> Option<OwnedFd> {
+        let abi = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                std::ptr::null::<RulesetAttr>(),
+                0usize,
+                LANDLOCK_CREATE_RULESET_VERSION,
+            )
+        };
+        if abi < 1 {
+            return None;
+        }
+
+        // 未声明处理的权限不受限制：内核支持时一并处理截断权限
+        let handled = if abi >= 3 { ACCESS_FS_ALL | ACCESS_TRUNCATE } else { ACCESS_FS_ALL };
+        let attr = RulesetAttr { handled_access_fs: handled };
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                &attr as *const RulesetAttr,
+                std::mem::size_of::<RulesetAttr>(),
+                0u32,
+            )
+        };
+        if fd < 0 {
+            return None;
+        }
+        let ruleset = unsafe { OwnedFd::from_raw_fd(fd as RawFd) };
+
+        for (roots, access) in [(read_roots, ACCESS_FS_READ), (write_roots, handled)] {
+            for root in roots {
+                add_path_rule(&ruleset, root, access);
+            }
+        }
+        Some(ruleset)
+    }
+
+    fn add_path_rule(ruleset: &OwnedFd, path: &Path, access: u64) -> bool {
+        let Ok(c_path) = CString::new(path.as_os_str().as_bytes()) else {
+            return false;
+        };
+        let fd = unsafe { libc::open(c_path.as_ptr(), libc::O_PATH | libc::O_CLOEXEC) };
+        if fd < 0 {
+            return false;
+        }
+        let target = unsafe { OwnedFd::from_raw_fd(fd) };
+        let allowed_access = if path.is_dir() { access } else { access & ACCESS_FILE };
+        let attr = PathBeneathAttr {
+            allowed_access,
+            parent_fd: target.as_raw_fd(),
+        };
+        let ret = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_add_rule,
+                ruleset.as_raw_fd(),
+                LANDLOCK_RULE_PATH_BENEATH,
+                std::ptr::addr_of!(attr),
+                0u32,
+            )
+        };
+        ret == 0
+    }
+
+    /// 拒绝创建 AF_INET/AF_INET6 套接字的 seccomp 过滤器（本地 Unix 套接字不受影响）
+    /// 同时拒绝 x32 系统调用号（可绕过按调用号匹配）与 io_uring_setup（IORING_OP_SOCKET 可创建套接字）
+    pub fn network_filter() -> Option<Vec<libc::sock_filter>> {
+        let arch = AUDIT_ARCH?;
+        if unsafe { libc::prctl(libc::PR_GET_SECCOMP, 0, 0, 0, 0) } < 0 {
+            return None;
+        }
+        let stmt = |code: u16, k: u32| libc::sock_filter { code, jt: 0, jf: 0, k };
+        let jeq = |k: u32, jt: u8, jf: u8| libc::sock_filter { code: BPF_JEQ_K, jt, jf, k };
+        let jge = |k: u32, jt: u8, jf: u8| libc::sock_filter { code: BPF_JGE_K, jt, jf, k };
+        let deny = libc::SECCOMP_RET_ERRNO | libc::EACCES as u32;
+        // 跳转偏移相对下一条指令：ALLOW 位于 9，DENY 位于 10
+        Some(vec![
+            stmt(BPF_LD_W_ABS, SECCOMP_DATA_ARCH),
+            jeq(arch, 0, 8),
+            stmt(BPF_LD_W_ABS, SECCOMP_DATA_NR),
+            jge(X32_SYSCALL_BIT, 6, 0),
+            jeq(libc::SYS_io_uring_setup as u32, 5, 0),
+            jeq(libc::SYS_socket as u32, 0, 3),
+            stmt(BPF_LD_W_ABS, SECCOMP_DATA_ARG0),
+            jeq(libc::AF_INET as u32, 2, 0),
+            jeq(libc::AF_INET6 as u32, 1, 0),
+            stmt(BPF_RET_K, libc::SECCOMP_RET_ALLOW),
+            stmt(BPF_RET_K, deny),
+        ])
+    }
+
+    /// 子进程中启用 landlock 与 seccomp（仅调用系统调用，不分配内存）
+    pub fn restrict_self(ruleset_fd: Option<RawFd>, filter: Option<&[libc::sock_filter]>) -> io::Result<()> {
+        if ruleset_fd.is_none() && filter.is_none() {
+            return Ok(());
+        }
+        unsafe {
+            if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            if let Some(fd) = ruleset_fd {
+                if libc::syscall(libc::SYS_landlock_restrict_self, fd, 0u32) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+            if let Some(filter) = filter {
+                let program = libc::sock_fprog {
+                    len: filter.len() as u16,
+                    filter: filter.as_ptr() as *mut libc::sock_filter,
+                };
+                if libc::prctl(
+                    libc::PR_SET_SECCOMP,
+                    libc::SECCOMP_MODE_FILTER,
+                    &program as *const libc::sock_fprog,
+                ) != 0
+                {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_sandbox_restricts_filesystem_and_network() {
+        let base = std::env::temp_dir().join(format!("sanshu-skill-sandbox-{}", std::process::id()));
+        let allowed = base.join("allowed");
+        let denied = base.join("denied");
+        std::fs::create_dir_all(&allowed).unwrap();
+        std::fs::create_dir_all(&denied).unwrap();
+
+        let policy = SandboxPolicy {
+            read_roots: vec![base.clone()],
+            write_roots: vec![allowed.clone()],
+            network: false,
+            max_cpu_secs: Some(5),
+            max_memory_bytes: Some(512 * 1024 * 1024),
+        };
+        let script = format!(
+            "echo ok > {allowed}/a; echo no > {denied}/b; echo > /dev/tcp/127.0.0.1/9 && echo net; echo done",
+            allowed = allowed.display(),
+            denied = denied.display()
+        );
+        let mut command = Command::new("bash");
+        command.arg("-c").arg(script).stdout(std::process::Stdio::piped()).stderr(std::process::Stdio::piped());
+        let guard = apply(&mut command, &policy, "bash").unwrap();
+        let output = tokio::time::timeout(Duration::from_secs(10), command.output())
+            .await
+            .unwrap()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stdout.contains("done"));
+        assert!(allowed.join("a").exists());
+
+        if guard.mechanisms.contains(&"landlock") {
+            assert!(!denied.join("b").exists());
+        }
+        if guard.mechanisms.contains(&"seccomp") {
+            assert!(!stdout.contains("net"));
+            assert!(!stderr.contains("Connection refused"));
+        }
+
+        drop(guard);
+
+        // x32 调用号与 io_uring_setup 被拒绝（EACCES），不能绕过套接字过滤
+        let probe = "import ctypes; c = ctypes.CDLL(None, use_errno=True); \
+            r = [c.syscall(0x40000000 | 41, 2, 1, 0), ctypes.get_errno(), c.syscall(425, 1, None), ctypes.get_errno()]; \
+            print(*r)";
+        let python = "/usr/bin/python3";
+        let mut command = Command::new(python);
+        command.arg("-c").arg(probe).stdout(std::process::Stdio::piped());
+        let guard = apply(&mut command, &policy, python).unwrap();
+        if let Ok(Ok(output)) = tokio::time::timeout(Duration::from_secs(10), command.output()).await {
+            if guard.mechanisms.contains(&"seccomp") && output.status.success() {
+                let results: Vec<i64> = String::from_utf8_lossy(&output.stdout)
+                    .split_whitespace()
+                    .filter_map(|v| v.parse().ok())
+                    .collect();
+                assert_eq!(results, vec![-1, libc::EACCES as i64, -1, libc::EACCES as i64]);
+            }
+        }
+
+        drop(guard);
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
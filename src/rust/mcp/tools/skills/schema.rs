@@ -0,0 +1,205 @@
+// 技能动作参数
+// 动作可声明 input_schema / output_schema（JSON Schema），args_template 按命名字段渲染命令行参数
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{Map, Value};
+
+static PLACEHOLDER_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{([A-Za-z0-9_]+)\}").unwrap());
+
+/// 未声明 input_schema 的动作使用的参数
+pub fn default_input_schema() -> Map<String, Value> {
+    let schema = serde_json::json!({
+        "type": "object",
+        "properties": {
+            "query": { "type": "string", "description": "查询或输入（可选）" },
+            "args": { "type": "array", "items": { "type": "string" }, "description": "追加参数（可选）" }
+        }
+    });
+    match schema {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    }
+}
+
+/// 声明的 schema 需为对象类型，缺省 type 时补全
+pub fn object_schema(declared: &Value) -> Option<Map<String, Value>> {
+    let mut schema = declared.as_object()?.clone();
+    match schema.get("type") {
+        None => {
+            schema.insert("type".to_string(), Value::String("object".to_string()));
+        }
+        Some(Value::String(kind)) if kind == "object" => {}
+        Some(_) => return None,
+    }
+    Some(schema)
+}
+
+/// 校验必填字段与顶层字段的类型 / 枚举值
+pub fn validate_arguments(schema: &Value, arguments: &Map<String, Value>) -> Result<(), String> {
+    let mut errors = Vec::new();
+    if let Some(required) = schema.get("required").and_then(Value::as_array) {
+        for name in required.iter().filter_map(Value::as_str) {
+            if arguments.get(name).is_none_or(Value::is_null) {
+                errors.push(format!("缺少 {} 参数", name));
+            }
+        }
+    }
+
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        for (name, value) in arguments {
+            let Some(property) = properties.get(name) else {
+                continue;
+            };
+            if value.is_null() {
+                continue;
+            }
+            if let Some(kind) = property.get("type").and_then(Value::as_str) {
+                if !matches_type(kind, value) {
+                    errors.push(format!("参数 {} 应为 {} 类型", name, kind));
+                    continue;
+                }
+            }
+            if let Some(options) = property.get("enum").and_then(Value::as_array) {
+                if !options.contains(value) {
+                    errors.push(format!("参数 {} 不在可选值范围内", name));
+                }
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+fn matches_type(kind: &str, value: &Value) -> bool {
+    match kind {
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+/// 按命名字段渲染参数模板
+///
+/// - 整个参数恰为 `{field}` 且值为数组时展开为多个参数
+/// - 引用字段缺失时：strict 模式报错，否则跳过该参数（连同紧邻其前的 `--flag` 参数）
+pub fn render_template(
+    template: &[String],
+    arguments: &Map<String, Value>,
+    strict: bool,
+) -> Result<Vec<String>, String> {
+    let mut rendered = Vec::new();
+    // 上一个参数是否为原样输出的 flag（如 "--domain"），其取值参数被跳过时一并移除
+    let mut pending_flag = false;
+    'tokens: for token in template {
+        let was_flag = std::mem::replace(&mut pending_flag, false);
+        if !PLACEHOLDER_RE.is_match(token) {
+            pending_flag = token.starts_with('-');
+            rendered.push(token.clone());
+            continue;
+        }
+        if let Some(caps) = PLACEHOLDER_RE.captures(token) {
+            if caps.get(0).map(|m| m.as_str()) == Some(token.as_str()) {
+                if let Some(Value::Array(items)) = arguments.get(&caps[1]) {
+                    rendered.extend(items.iter().map(value_to_arg));
+                    continue;
+                }
+            }
+        }
+
+        let mut output = String::new();
+        let mut last = 0;
+        for caps in PLACEHOLDER_RE.captures_iter(token) {
+            let whole = caps.get(0).expect("捕获组 0 总是存在");
+            let name = &caps[1];
+            let value = match arguments.get(name) {
+                Some(value) if !value.is_null() => value,
+                _ if strict => return Err(format!("缺少 {} 参数", name)),
+                _ => {
+                    if was_flag {
+                        rendered.pop();
+                    }
+                    continue 'tokens;
+                }
+            };
+            output.push_str(&token[last..whole.start()]);
+            output.push_str(&value_to_arg(value));
+            last = whole.end();
+        }
+        output.push_str(&token[last..]);
+        rendered.push(output);
+    }
+    Ok(rendered)
+}
+
+fn value_to_arg(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn args(value: Value) -> Map<String, Value> {
+        value.as_object().cloned().unwrap()
+    }
+
+    #[test]
+    fn test_render_named_template() {
+        let template: Vec<String> = ["{query}", "--domain", "{domain}", "--max={limit}", "{tags}", "--json"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let full = args(json!({ "query": "fintech", "domain": "color", "limit": 5, "tags": ["a", "b"] }));
+        assert_eq!(
+            render_template(&template, &full, false).unwrap(),
+            vec!["fintech", "--domain", "color", "--max=5", "a", "b", "--json"]
+        );
+
+        // 非严格模式跳过缺失字段所在的参数；严格模式报错
+        let partial = args(json!({ "query": "fintech" }));
+        assert_eq!(
+            render_template(&template, &partial, false).unwrap(),
+            vec!["fintech", "--json"]
+        );
+        assert_eq!(
+            render_template(&template, &partial, true).unwrap_err(),
+            "缺少 domain 参数"
+        );
+    }
+
+    #[test]
+    fn test_validate_arguments() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "query": { "type": "string" },
+                "limit": { "type": "integer" },
+                "domain": { "type": "string", "enum": ["color", "style"] }
+            },
+            "required": ["query"]
+        });
+        assert!(validate_arguments(&schema, &args(json!({ "query": "a", "limit": 3, "domain": "color" }))).is_ok());
+
+        let err = validate_arguments(&schema, &args(json!({ "limit": "3", "domain": "font" }))).unwrap_err();
+        assert!(err.contains("缺少 query 参数"));
+        assert!(err.contains("参数 limit 应为 integer 类型"));
+        assert!(err.contains("参数 domain 不在可选值范围内"));
+
+        assert!(object_schema(&json!({ "properties": {} })).is_some());
+        assert!(object_schema(&json!({ "type": "string" })).is_none());
+    }
+}
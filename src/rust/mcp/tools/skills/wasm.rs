@@ -0,0 +1,113 @@
+// 嵌入式 WASI 技能运行时（skill-wasm 特性）
+// 每次调用独立的 Store：技能目录只读挂载为 "."，权限清单中的路径按原路径挂载，stdin/stdout 走内存管道
+// 超时通过 epoch 中断，内存上限通过 StoreLimits 约束；WASI preview1 本身不提供网络能力
+
+use std::sync::mpsc::{self, RecvTimeoutError};
+
+use wasmtime::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Trap};
+use wasmtime_wasi::pipe::{MemoryInputPipe, MemoryOutputPipe};
+use wasmtime_wasi::preview1::{self, WasiP1Ctx};
+use wasmtime_wasi::{DirPerms, FilePerms, I32Exit, WasiCtxBuilder};
+
+use super::runtime::{ProcessSpec, SkillOutput};
+
+/// stdout/stderr 各自的缓冲上限
+const OUTPUT_CAPACITY: usize = 4 * 1024 * 1024;
+
+struct WasmState {
+    wasi: WasiP1Ctx,
+    limits: StoreLimits,
+}
+
+pub fn run(spec: ProcessSpec) -> Result<SkillOutput, String> {
+    let mut config = Config::new();
+    config.epoch_interruption(true);
+    let engine = Engine::new(&config).map_err(|e| format!("初始化 WASM 引擎失败: {}", e))?;
+    let module = Module::from_file(&engine, &spec.entry)
+        .map_err(|e| format!("加载 WASM 模块失败: {}", e))?;
+
+    let mut linker: Linker<WasmState> = Linker::new(&engine);
+    preview1::add_to_linker_sync(&mut linker, |state| &mut state.wasi)
+        .map_err(|e| format!("初始化 WASI 失败: {}", e))?;
+
+    let program = spec
+        .entry
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "skill.wasm".to_string());
+    let mut argv = vec![program];
+    argv.extend(spec.args.iter().cloned());
+
+    let stdout = MemoryOutputPipe::new(OUTPUT_CAPACITY);
+    let stderr = MemoryOutputPipe::new(OUTPUT_CAPACITY);
+    let mut builder = WasiCtxBuilder::new();
+    builder
+        .args(&argv)
+        .envs(&spec.env)
+        .stdin(MemoryInputPipe::new(spec.stdin.unwrap_or_default()))
+        .stdout(stdout.clone())
+        .stderr(stderr.clone());
+    builder
+        .preopened_dir(&spec.cwd, ".", DirPerms::READ, FilePerms::READ)
+        .map_err(|e| format!("挂载技能目录失败: {}", e))?;
+    let policy = &spec.sandbox;
+    for (roots, dir_perms, file_perms) in [
+        (&policy.read_roots, DirPerms::READ, FilePerms::READ),
+        (&policy.write_roots, DirPerms::all(), FilePerms::all()),
+    ] {
+        for root in roots.iter().filter(|root| root.is_dir()) {
+            let guest = root.to_string_lossy().to_string();
+            builder
+                .preopened_dir(root, guest, dir_perms, file_perms)
+                .map_err(|e| format!("挂载目录失败: {} ({})", root.display(), e))?;
+        }
+    }
+
+    let mut limits = StoreLimitsBuilder::new();
+    if let Some(bytes) = policy.max_memory_bytes {
+        limits = limits.memory_size(bytes as usize);
+    }
+    let state = WasmState {
+        wasi: builder.build_p1(),
+        limits: limits.build(),
+    };
+    let mut store = Store::new(&engine, state);
+    store.limiter(|state| &mut state.limits);
+    store.set_epoch_deadline(1);
+
+    // 超时后推进 epoch，模块在下一个检查点中断；正常结束时 done_tx 被丢弃，计时线程直接退出
+    let (done_tx, done_rx) = mpsc::channel::<()>();
+    let ticker = engine.clone();
+    let timeout = spec.timeout;
+    std::thread::spawn(move || {
+        if let Err(RecvTimeoutError::Timeout) = done_rx.recv_timeout(timeout) {
+            ticker.increment_epoch();
+        }
+    });
+
+    let result = (|| -> wasmtime::Result<()> {
+        let instance = linker.instantiate(&mut store, &module)?;
+        let start = instance.get_typed_func::<(), ()>(&mut store, "_start")?;
+        start.call(&mut store, ())
+    })();
+    drop(done_tx);
+
+    let exit_code = match result {
+        Ok(()) => 0,
+        Err(err) => {
+            if let Some(exit) = err.downcast_ref::<I32Exit>() {
+                exit.0
+            } else if err.downcast_ref::<Trap>() == Some(&Trap::Interrupt) {
+                return Err(format!("技能执行超时（{} 秒）", timeout.as_secs()));
+            } else {
+                return Err(format!("WASM 执行失败: {}", err));
+            }
+        }
+    };
+
+    Ok(SkillOutput {
+        success: exit_code == 0,
+        stdout: String::from_utf8_lossy(&stdout.contents()).trim().to_string(),
+        stderr: String::from_utf8_lossy(&stderr.contents()).trim().to_string(),
+    })
+}
@@ -0,0 +1,663 @@
+// 设计系统审计
+// 按结构化 DesignSystem 扫描项目样式与组件源码，报告色板外颜色、未声明字体、非标准间距与反模式
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+use super::engine::{DesignSystem, DESIGN_SYSTEM_JSON};
+use super::sanitize::sanitize_slug;
+
+/// 间距基准（px），取值须为其整数倍
+const SPACING_BASE_PX: f64 = 4.0;
+
+/// 与色板颜色的最大 RGB 欧氏距离（允许轻微的悬停/按下变体）
+const COLOR_TOLERANCE: f64 = 24.0;
+
+/// 单个文件大小上限，超出跳过（多为打包产物）
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// 最多扫描的文件数
+const MAX_FILES: usize = 5000;
+
+/// 样式表扩展名：所有十六进制颜色都参与检查
+const STYLE_EXTENSIONS: &[&str] = &["css", "scss", "sass", "less"];
+
+/// 组件与模板扩展名：仅检查样式上下文中的颜色
+const MARKUP_EXTENSIONS: &[&str] = &["vue", "jsx", "tsx", "html", "svelte"];
+
+/// 不扫描的目录
+const SKIP_DIRS: &[&str] = &["node_modules", "dist", "build", "target", "coverage", "design-system"];
+
+/// 通用字体族与关键字（不视为未声明字体）
+const GENERIC_FONTS: &[&str] = &[
+    "serif", "sans-serif", "monospace", "cursive", "fantasy", "system-ui", "ui-sans-serif",
+    "ui-serif", "ui-monospace", "ui-rounded", "-apple-system", "blinkmacsystemfont", "emoji",
+    "math", "inherit", "initial", "unset", "revert", "sans", "mono",
+];
+
+/// 非灰阶的 Tailwind 默认色系
+const TAILWIND_CHROMATIC: &str =
+    "red|orange|amber|yellow|lime|green|emerald|teal|cyan|sky|blue|indigo|violet|purple|fuchsia|pink|rose";
+
+/// 审计规则类别
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditRule {
+    Color,
+    Font,
+    Spacing,
+    AntiPattern,
+}
+
+impl AuditRule {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Color => "color",
+            Self::Font => "font",
+            Self::Spacing => "spacing",
+            Self::AntiPattern => "anti_pattern",
+        }
+    }
+
+    /// 计分权重
+    fn weight(&self) -> u64 {
+        match self {
+            Self::Color => 2,
+            Self::Font => 3,
+            Self::Spacing => 1,
+            Self::AntiPattern => 5,
+        }
+    }
+}
+
+/// 单条审计发现
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditFinding {
+    pub rule: AuditRule,
+    /// 相对项目根目录的路径
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub value: String,
+    pub message: String,
+}
+
+/// 审计报告
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditReport {
+    pub project_name: String,
+    /// 设计系统来源（design-system.json 路径或 query）
+    pub design_system_source: String,
+    pub files_scanned: usize,
+    /// 已检查的值数量
+    pub checks: usize,
+    /// 0~100，按规则权重计算的通过率
+    pub score: u32,
+    pub total_findings: usize,
+    /// 各规则的发现数量
+    pub counts: BTreeMap<AuditRule, usize>,
+    pub findings: Vec<AuditFinding>,
+    pub truncated: bool,
+    /// 无法自动检测的反模式（需人工复核）
+    pub unchecked_anti_patterns: Vec<String>,
+}
+
+/// 可检测的反模式：设计系统中的反模式描述包含任一关键词时启用
+struct AntiPatternCheck {
+    keywords: &'static [&'static str],
+    regex: Regex,
+    message: &'static str,
+}
+
+static ANTI_PATTERN_CHECKS: Lazy<Vec<AntiPatternCheck>> = Lazy::new(|| {
+    let check = |keywords, pattern: &str, message| AntiPatternCheck {
+        keywords,
+        regex: Regex::new(pattern).expect("invalid anti-pattern regex"),
+        message,
+    };
+    vec![
+        check(
+            &["gradient"],
+            r"(?i)\b(?:linear|radial|conic)-gradient\(|\bbg-gradient-to-|\bbg-linear-to-",
+            "使用了渐变",
+        ),
+        check(
+            &["animation", "motion"],
+            r"(?i)\banimation\s*:|@keyframes\b|\banimate-(?:spin|ping|pulse|bounce|\[)",
+            "使用了动画",
+        ),
+        check(
+            &["neon", "glow"],
+            r"(?i)\btext-shadow\s*:|\bdrop-shadow-\[|\bshadow-\[0_0_",
+            "使用了发光效果",
+        ),
+        check(
+            &["dark mode"],
+            r#"(?i)\bcolor-scheme\s*:\s*dark\b|<html[^>]*class="[^"]*\bdark\b"#,
+            "默认启用深色模式",
+        ),
+        check(
+            &["small text"],
+            r"(?i)\bfont-size\s*:\s*(?:[0-9]|1[01])(?:\.\d+)?px|\btext-\[(?:[0-9]|1[01])px\]",
+            "字号小于 12px",
+        ),
+    ]
+});
+
+static HEX_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"#([0-9a-fA-F]{3,8})\b").unwrap());
+static RGB_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\brgba?\(\s*(\d{1,3})[\s,]+(\d{1,3})[\s,]+(\d{1,3})").unwrap());
+static TAILWIND_COLOR_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"\b(?:bg|text|border|ring|fill|stroke|from|via|to|outline|decoration|divide|accent|caret|shadow)-(?:{})-(?:50|[1-9]00|950)\b",
+        TAILWIND_CHROMATIC
+    ))
+    .unwrap()
+});
+static FONT_FAMILY_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\bfont-family\s*:\s*([^;{}]+)").unwrap());
+static TAILWIND_FONT_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\bfont-\[([^\]]+)\]").unwrap());
+static SPACING_DECL_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:margin|padding|gap|row-gap|column-gap)(?:-(?:top|right|bottom|left|inline|block)(?:-start|-end)?)?\s*:\s*([^;{}]+)").unwrap()
+});
+static SPACING_VALUE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(-?\d*\.?\d+)(px|rem)\b").unwrap());
+static TAILWIND_SPACING_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:^|[\s:'\x22])-?(?:p[xytrblse]?|m[xytrblse]?|gap(?:-[xy])?|space-[xy])-\[(-?\d*\.?\d+)(px|rem)\]").unwrap()
+});
+
+/// 从设计系统提取的审计规则
+struct AuditRules {
+    palette: Vec<[u8; 3]>,
+    palette_hex: Vec<String>,
+    fonts: Vec<String>,
+    font_names: Vec<String>,
+    anti_patterns: Vec<(String, &'static AntiPatternCheck)>,
+    unchecked_anti_patterns: Vec<String>,
+}
+
+impl AuditRules {
+    fn from_design_system(design_system: &DesignSystem) -> Self {
+        let colors = &design_system.colors;
+        let mut palette = Vec::new();
+        let mut palette_hex = Vec::new();
+        for value in [&colors.primary, &colors.secondary, &colors.cta, &colors.background, &colors.text] {
+            if let Some(rgb) = HEX_RE.captures(value).and_then(|c| parse_hex(&c[1])) {
+                palette.push(rgb);
+                palette_hex.push(format!("#{:02X}{:02X}{:02X}", rgb[0], rgb[1], rgb[2]));
+            }
+        }
+
+        let typography = &design_system.typography;
+        let font_names: Vec<String> = [&typography.heading, &typography.body]
+            .into_iter()
+            .map(|font| font.trim().to_string())
+            .filter(|font| !font.is_empty())
+            .collect();
+        let fonts = font_names.iter().map(|font| font.to_lowercase()).collect();
+
+        let mut anti_patterns = Vec::new();
+        let mut unchecked_anti_patterns = Vec::new();
+        for phrase in design_system
+            .anti_patterns
+            .split('+')
+            .map(str::trim)
+            .filter(|phrase| !phrase.is_empty())
+        {
+            let lower = phrase.to_lowercase();
+            match ANTI_PATTERN_CHECKS
+                .iter()
+                .find(|check| check.keywords.iter().any(|keyword| lower.contains(keyword)))
+            {
+                Some(check) => anti_patterns.push((phrase.to_string(), check)),
+                None => unchecked_anti_patterns.push(phrase.to_string()),
+            }
+        }
+
+        Self {
+            palette,
+            palette_hex,
+            fonts,
+            font_names,
+            anti_patterns,
+            unchecked_anti_patterns,
+        }
+    }
+
+    fn color_allowed(&self, rgb: [u8; 3]) -> bool {
+        // 色板为空时不检查；灰阶作为中性色放行
+        if self.palette.is_empty() || is_neutral(rgb) {
+            return true;
+        }
+        self.palette.iter().any(|color| color_distance(*color, rgb) <= COLOR_TOLERANCE)
+    }
+
+    fn font_allowed(&self, family: &str) -> bool {
+        let lower = family.to_lowercase();
+        self.fonts.is_empty()
+            || GENERIC_FONTS.contains(&lower.as_str())
+            || lower.starts_with("var(")
+            || self.fonts.contains(&lower)
+    }
+}
+
+/// 审计过程中的计数与发现
+#[derive(Default)]
+struct AuditCollector {
+    findings: Vec<AuditFinding>,
+    counts: BTreeMap<AuditRule, usize>,
+    checks: usize,
+    total_weight: u64,
+    failed_weight: u64,
+}
+
+impl AuditCollector {
+    fn pass(&mut self, rule: AuditRule) {
+        self.checks += 1;
+        self.total_weight += rule.weight();
+    }
+
+    fn fail(&mut self, finding: AuditFinding) {
+        self.checks += 1;
+        self.total_weight += finding.rule.weight();
+        self.failed_weight += finding.rule.weight();
+        *self.counts.entry(finding.rule).or_insert(0) += 1;
+        self.findings.push(finding);
+    }
+
+    fn score(&self) -> u32 {
+        if self.total_weight == 0 {
+            return 100;
+        }
+        let passed = self.total_weight - self.failed_weight;
+        ((passed as f64 / self.total_weight as f64) * 100.0).round() as u32
+    }
+}
+
+/// 读取已持久化的设计系统：指定项目名时读取对应目录，否则要求仅存在一个
+pub fn load_design_system(base_dir: &Path, project_name: Option<&str>) -> Result<(DesignSystem, PathBuf), String> {
+    let root = base_dir.join("design-system");
+    let path = match project_name {
+        Some(name) => root.join(sanitize_slug(name)).join(DESIGN_SYSTEM_JSON),
+        None => {
+            let mut candidates: Vec<PathBuf> = fs::read_dir(&root)
+                .map(|entries| {
+                    entries
+                        .flatten()
+                        .map(|entry| entry.path().join(DESIGN_SYSTEM_JSON))
+                        .filter(|path| path.is_file())
+                        .collect()
+                })
+                .unwrap_or_default();
+            candidates.sort();
+            match candidates.len() {
+                0 => return Err(format!("未找到 {}，请先以 persist=true 调用 uiux_design_system，或提供 query", DESIGN_SYSTEM_JSON)),
+                1 => candidates.remove(0),
+                _ => {
+                    let names = candidates
+                        .iter()
+                        .filter_map(|path| path.parent()?.file_name()?.to_str().map(str::to_string))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    return Err(format!("存在多个设计系统，请通过 project_name 指定: {}", names));
+                }
+            }
+        }
+    };
+
+    let content = fs::read_to_string(&path).map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+    let design_system = serde_json::from_str(&content).map_err(|e| format!("解析 {} 失败: {}", path.display(), e))?;
+    Ok((design_system, path))
+}
+
+/// 扫描项目根目录（遵循 .gitignore）并生成审计报告
+pub fn audit_project(
+    design_system: &DesignSystem,
+    design_system_source: String,
+    project_root: &Path,
+    max_findings: usize,
+) -> AuditReport {
+    let rules = AuditRules::from_design_system(design_system);
+    let mut collector = AuditCollector::default();
+    let mut files_scanned = 0;
+
+    let walker = ignore::WalkBuilder::new(project_root)
+        .filter_entry(|entry| {
+            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            !(is_dir && entry.file_name().to_str().map(|name| SKIP_DIRS.contains(&name)).unwrap_or(false))
+        })
+        .build();
+
+    for entry in walker.flatten() {
+        if files_scanned >= MAX_FILES {
+            break;
+        }
+        let path = entry.path();
+        let Some(is_stylesheet) = source_kind(path) else {
+            continue;
+        };
+        if entry.metadata().map(|m| m.len() > MAX_FILE_BYTES).unwrap_or(true) {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        let relative = path
+            .strip_prefix(project_root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        audit_source(&rules, &relative, &content, is_stylesheet, &mut collector);
+        files_scanned += 1;
+    }
+
+    build_report(design_system, design_system_source, &rules, collector, files_scanned, max_findings)
+}
+
+fn build_report(
+    design_system: &DesignSystem,
+    design_system_source: String,
+    rules: &AuditRules,
+    collector: AuditCollector,
+    files_scanned: usize,
+    max_findings: usize,
+) -> AuditReport {
+    let score = collector.score();
+    let total_findings = collector.findings.len();
+    let mut findings = collector.findings;
+    findings.truncate(max_findings);
+
+    AuditReport {
+        project_name: design_system.project_name.clone(),
+        design_system_source,
+        files_scanned,
+        checks: collector.checks,
+        score,
+        total_findings,
+        counts: collector.counts,
+        truncated: total_findings > findings.len(),
+        findings,
+        unchecked_anti_patterns: rules.unchecked_anti_patterns.clone(),
+    }
+}
+
+/// 返回 Some(是否为样式表)，不支持的文件返回 None
+fn source_kind(path: &Path) -> Option<bool> {
+    let name = path.file_name()?.to_str()?;
+    if name.ends_with(".min.css") {
+        return None;
+    }
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    if STYLE_EXTENSIONS.contains(&ext.as_str()) {
+        Some(true)
+    } else if MARKUP_EXTENSIONS.contains(&ext.as_str()) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn audit_source(rules: &AuditRules, file: &str, content: &str, is_stylesheet: bool, collector: &mut AuditCollector) {
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("//") || trimmed.starts_with("/*") || trimmed.starts_with('*') {
+            continue;
+        }
+        let mut report = |rule: AuditRule, start: usize, value: &str, message: String| {
+            collector.fail(AuditFinding {
+                rule,
+                file: file.to_string(),
+                line: index + 1,
+                column: line[..start].chars().count() + 1,
+                value: value.to_string(),
+                message,
+            });
+        };
+        let mut passed = Vec::new();
+
+        // 颜色
+        if is_stylesheet || is_style_context(line) {
+            for caps in HEX_RE.captures_iter(line) {
+                let whole = caps.get(0).unwrap();
+                let preceded_by_entity = line[..whole.start()].ends_with('&');
+                let Some(rgb) = parse_hex(&caps[1]).filter(|_| !preceded_by_entity) else {
+                    continue;
+                };
+                if rules.color_allowed(rgb) {
+                    passed.push(AuditRule::Color);
+                } else {
+                    report(AuditRule::Color, whole.start(), whole.as_str(), color_message(rules));
+                }
+            }
+            for caps in RGB_RE.captures_iter(line) {
+                let whole = caps.get(0).unwrap();
+                let channels: Vec<u8> = (1..=3).filter_map(|i| caps[i].parse().ok()).collect();
+                let [r, g, b] = channels[..] else {
+                    continue;
+                };
+                if rules.color_allowed([r, g, b]) {
+                    passed.push(AuditRule::Color);
+                } else {
+                    report(AuditRule::Color, whole.start(), whole.as_str(), color_message(rules));
+                }
+            }
+        }
+        for found in TAILWIND_COLOR_RE.find_iter(line) {
+            report(
+                AuditRule::Color,
+                found.start(),
+                found.as_str(),
+                "使用了 Tailwind 默认色板，建议改用设计系统颜色（如 bg-primary）".to_string(),
+            );
+        }
+
+        // 字体
+        let font_matches = FONT_FAMILY_RE
+            .captures_iter(line)
+            .map(|caps| (caps.get(1).unwrap(), false))
+            .chain(TAILWIND_FONT_RE.captures_iter(line).map(|caps| (caps.get(1).unwrap(), true)));
+        for (found, is_tailwind) in font_matches {
+            let stack = if is_tailwind { found.as_str().replace('_', " ") } else { found.as_str().to_string() };
+            let Some(family) = primary_font_family(&stack) else {
+                continue;
+            };
+            if rules.font_allowed(&family) {
+                passed.push(AuditRule::Font);
+            } else {
+                let message = format!("字体不在设计系统中（应为 {}）", rules.font_names.join(" / "));
+                report(AuditRule::Font, found.start(), &family, message);
+            }
+        }
+
+        // 间距
+        for caps in SPACING_DECL_RE.captures_iter(line) {
+            let value = caps.get(1).unwrap();
+            for spacing in SPACING_VALUE_RE.captures_iter(value.as_str()) {
+                let whole = spacing.get(0).unwrap();
+                if spacing_on_scale(&spacing[1], &spacing[2]) {
+                    passed.push(AuditRule::Spacing);
+                } else {
+                    report(AuditRule::Spacing, value.start() + whole.start(), whole.as_str(), spacing_message());
+                }
+            }
+        }
+        for caps in TAILWIND_SPACING_RE.captures_iter(line) {
+            let whole = caps.get(0).unwrap();
+            let value = format!("{}{}", &caps[1], &caps[2]);
+            if spacing_on_scale(&caps[1], &caps[2]) {
+                passed.push(AuditRule::Spacing);
+            } else {
+                report(AuditRule::Spacing, whole.start(), &value, spacing_message());
+            }
+        }
+
+        // 反模式
+        for (phrase, check) in &rules.anti_patterns {
+            if let Some(found) = check.regex.find(line) {
+                let message = format!("{}（设计系统反模式：{}）", check.message, phrase);
+                report(AuditRule::AntiPattern, found.start(), found.as_str(), message);
+            }
+        }
+
+        for rule in passed {
+            collector.pass(rule);
+        }
+    }
+}
+
+fn color_message(rules: &AuditRules) -> String {
+    format!("颜色不在设计系统色板中（{}）", rules.palette_hex.join(", "))
+}
+
+fn spacing_message() -> String {
+    format!("间距不在 {}px 栅格上", SPACING_BASE_PX)
+}
+
+/// 组件文件中仅在样式相关的行检查颜色，避免误报锚点等
+fn is_style_context(line: &str) -> bool {
+    let lower = line.to_lowercase();
+    ["style", "color", "background", "border", "fill", "stroke", "shadow", "outline", "[#"]
+        .iter()
+        .any(|hint| lower.contains(hint))
+}
+
+fn parse_hex(hex: &str) -> Option<[u8; 3]> {
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+    match hex.len() {
+        3 | 4 => {
+            let expand = |i: usize| channel(&hex[i..i + 1].repeat(2));
+            Some([expand(0)?, expand(1)?, expand(2)?])
+        }
+        6 | 8 => Some([channel(&hex[0..2])?, channel(&hex[2..4])?, channel(&hex[4..6])?]),
+        _ => None,
+    }
+}
+
+fn is_neutral(rgb: [u8; 3]) -> bool {
+    let max = rgb.iter().max().copied().unwrap_or(0);
+    let min = rgb.iter().min().copied().unwrap_or(0);
+    max - min <= 8
+}
+
+fn color_distance(a: [u8; 3], b: [u8; 3]) -> f64 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(x, y)| (*x as f64 - *y as f64).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// 取字体栈中第一个字体名（去除引号）
+fn primary_font_family(stack: &str) -> Option<String> {
+    let first = stack.split(',').next()?.trim().trim_matches(|c| c == '\'' || c == '"').trim();
+    let first = first.trim_end_matches("!important").trim();
+    (!first.is_empty()).then(|| first.to_string())
+}
+
+/// 0~2px 视为细线，其余须为基准的整数倍（rem 按 16px 换算）
+fn spacing_on_scale(number: &str, unit: &str) -> bool {
+    let Ok(value) = number.parse::<f64>() else {
+        return true;
+    };
+    let px = if unit.eq_ignore_ascii_case("rem") { value * 16.0 } else { value }.abs();
+    px <= 2.0 || (px / SPACING_BASE_PX).fract().abs() < f64::EPSILON
+}
+
+/// 文本格式的审计报告
+pub fn format_report(report: &AuditReport) -> String {
+    let mut lines = vec![format!(
+        "UI/UX Audit: {} — score {}/100 (files {}, checks {}, findings {})",
+        report.project_name, report.score, report.files_scanned, report.checks, report.total_findings
+    )];
+    for finding in &report.findings {
+        lines.push(format!(
+            "{}:{}:{} [{}] {} — {}",
+            finding.file,
+            finding.line,
+            finding.column,
+            finding.rule.as_str(),
+            finding.value,
+            finding.message
+        ));
+    }
+    if report.truncated {
+        lines.push(format!("... {} more", report.total_findings - report.findings.len()));
+    }
+    if !report.unchecked_anti_patterns.is_empty() {
+        lines.push(format!("Manual review: {}", report.unchecked_anti_patterns.join(", ")));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> DesignSystem {
+        let json = serde_json::json!({
+            "project_name": "Acme",
+            "category": "SaaS",
+            "pattern": { "name": "", "sections": "", "cta_placement": "", "color_strategy": "", "conversion": "" },
+            "style": { "name": "", "style_type": "", "effects": "", "keywords": "", "best_for": "", "performance": "", "accessibility": "" },
+            "colors": { "primary": "#2563EB", "secondary": "#3B82F6", "cta": "#F97316", "background": "#F8FAFC", "text": "#1E293B", "notes": "" },
+            "typography": { "heading": "Poppins", "body": "Open Sans", "mood": "", "best_for": "", "google_fonts_url": "", "css_import": "" },
+            "key_effects": "",
+            "anti_patterns": "AI purple/pink gradients + Cluttered layout",
+            "decision_rules": null,
+            "severity": "HIGH"
+        });
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_audit_source_findings() {
+        let ds = sample();
+        let rules = AuditRules::from_design_system(&ds);
+        assert_eq!(rules.unchecked_anti_patterns, vec!["Cluttered layout".to_string()]);
+
+        let css = ".btn {\n  color: #2563eb;\n  background: #ff00aa;\n  border: 1px solid #e5e5e5;\n  font-family: 'Open Sans', sans-serif;\n  padding: 8px 13px;\n  margin: 0.5rem;\n}\n.hero { background: linear-gradient(#2563EB, #3B82F6); font-family: Comic Sans MS; }\n";
+        let mut collector = AuditCollector::default();
+        audit_source(&rules, "src/app.css", css, true, &mut collector);
+        let summary: Vec<(AuditRule, usize, &str)> = collector
+            .findings
+            .iter()
+            .map(|f| (f.rule, f.line, f.value.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (AuditRule::Color, 3, "#ff00aa"),
+                (AuditRule::Spacing, 6, "13px"),
+                (AuditRule::Font, 9, "Comic Sans MS"),
+                (AuditRule::AntiPattern, 9, "linear-gradient("),
+            ]
+        );
+        assert_eq!(collector.findings[1].column, 16);
+
+        // 组件中的 Tailwind 类与锚点
+        let vue = "<a href=\"#add\" class=\"bg-pink-500 p-[10px] font-['Poppins']\">Add</a>\n";
+        let mut collector = AuditCollector::default();
+        audit_source(&rules, "src/App.vue", vue, false, &mut collector);
+        let rules_hit: Vec<AuditRule> = collector.findings.iter().map(|f| f.rule).collect();
+        assert_eq!(rules_hit, vec![AuditRule::Color, AuditRule::Spacing]);
+        assert!(collector.score() < 100);
+    }
+
+    #[test]
+    fn test_load_persisted_design_system() {
+        let root = std::env::temp_dir().join(format!("sanshu-uiux-audit-{}", std::process::id()));
+        let dir = root.join("design-system").join("acme");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(DESIGN_SYSTEM_JSON), serde_json::to_string(&sample()).unwrap()).unwrap();
+
+        let (ds, path) = load_design_system(&root, None).unwrap();
+        assert_eq!(ds.project_name, "Acme");
+        assert_eq!(path, dir.join(DESIGN_SYSTEM_JSON));
+        assert!(load_design_system(&root, Some("Other")).is_err());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
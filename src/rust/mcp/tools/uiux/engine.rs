@@ -7,16 +7,21 @@ use std::path::{Path, PathBuf};
 
 use chrono::Local;
 use once_cell::sync::Lazy;
-use regex::Regex;
 use rust_embed::RustEmbed;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::log_debug;
+use super::export::{export_design_system, ExportFile, ExportTarget};
+use super::index_cache::{Fingerprint, IndexCache};
+use super::packs::{self, KnowledgePack, PackData};
 use super::sanitize::{sanitize_path_segment, sanitize_slug};
+use super::tokenizer;
 
 const MAX_RESULTS: usize = 3;
 const BOX_WIDTH: usize = 90;
+/// 单个字段输出的最大字符数
+const MAX_VALUE_CHARS: usize = 300;
 
 #[derive(RustEmbed)]
 #[folder = "skills/ui-ux-pro-max/data"]
@@ -333,7 +338,8 @@ static DOMAIN_KEYWORDS: Lazy<Vec<(&'static str, &'static [&'static str])>> = Laz
     ]
 });
 
-static TOKEN_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"[^\w\s]").unwrap());
+/// 模糊匹配命中的词按此折扣计分
+const FUZZY_WEIGHT: f64 = 0.7;
 
 static KEYWORD_STOPWORDS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
     [
@@ -344,15 +350,16 @@ static KEYWORD_STOPWORDS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
     .collect()
 });
 
-#[derive(Clone)]
+/// BM25 索引：构建时生成倒排表，查询只遍历命中词的文档
+#[derive(Clone, Serialize, Deserialize)]
 struct BM25 {
     k1: f64,
     b: f64,
-    corpus: Vec<Vec<String>>,
-    doc_lengths: Vec<usize>,
+    doc_lengths: Vec<u32>,
     avgdl: f64,
     idf: HashMap<String, f64>,
-    doc_freqs: HashMap<String, usize>,
+    /// 倒排表：词 -> [(文档序号, 词频)]
+    postings: HashMap<String, Vec<(u32, u32)>>,
 }
 
 impl BM25 {
@@ -360,123 +367,166 @@ impl BM25 {
         Self {
             k1,
             b,
-            corpus: Vec::new(),
             doc_lengths: Vec::new(),
             avgdl: 0.0,
             idf: HashMap::new(),
-            doc_freqs: HashMap::new(),
+            postings: HashMap::new(),
         }
     }
 
-    fn tokenize(text: &str) -> Vec<String> {
-        let lower = text.to_lowercase();
-        let cleaned = TOKEN_RE.replace_all(&lower, " ");
-        cleaned
-            .split_whitespace()
-            .filter(|w| w.len() > 2)
-            .map(|w| w.to_string())
-            .collect()
-    }
-
     fn fit(&mut self, documents: &[String]) {
-        self.corpus = documents.iter().map(|doc| Self::tokenize(doc)).collect();
-        self.doc_lengths = self.corpus.iter().map(|doc| doc.len()).collect();
+        self.doc_lengths.clear();
         self.idf.clear();
-        self.doc_freqs.clear();
+        self.postings.clear();
 
-        let n = self.corpus.len();
+        for (idx, doc) in documents.iter().enumerate() {
+            let tokens = tokenizer::tokenize(doc);
+            self.doc_lengths.push(tokens.len() as u32);
+
+            let mut term_freqs: HashMap<String, u32> = HashMap::new();
+            for token in tokens {
+                *term_freqs.entry(token).or_insert(0) += 1;
+            }
+            for (token, tf) in term_freqs {
+                self.postings.entry(token).or_default().push((idx as u32, tf));
+            }
+        }
+
+        let n = self.doc_lengths.len();
         if n == 0 {
             self.avgdl = 0.0;
             return;
         }
 
-        self.avgdl = self.doc_lengths.iter().sum::<usize>() as f64 / n as f64;
-
-        for doc in &self.corpus {
-            let mut seen: HashSet<&str> = HashSet::new();
-            for token in doc {
-                if seen.insert(token) {
-                    *self.doc_freqs.entry(token.clone()).or_insert(0) += 1;
-                }
-            }
-        }
+        self.avgdl = self.doc_lengths.iter().map(|len| *len as f64).sum::<f64>() / n as f64;
 
-        for (token, freq) in &self.doc_freqs {
-            let freq = *freq as f64;
+        for (token, postings) in &mut self.postings {
+            postings.sort_unstable();
+            let freq = postings.len() as f64;
             let n = n as f64;
             let idf = ((n - freq + 0.5) / (freq + 0.5) + 1.0).ln();
             self.idf.insert(token.clone(), idf);
         }
     }
 
-    fn score(&self, query: &str) -> Vec<(usize, f64)> {
-        let query_tokens = Self::tokenize(query);
-        let mut scores = Vec::with_capacity(self.corpus.len());
-
-        for (idx, doc) in self.corpus.iter().enumerate() {
-            let mut term_freqs: HashMap<&str, usize> = HashMap::new();
-            for token in doc {
-                *term_freqs.entry(token).or_insert(0) += 1;
-            }
+    /// 查询词映射到词表：精确命中权重 1，未收录的词回退到拼写最接近的词
+    fn resolve_query(&self, query: &str) -> Vec<(String, f64)> {
+        tokenizer::tokenize_query(query)
+            .into_iter()
+            .filter_map(|token| {
+                if self.idf.contains_key(&token) {
+                    Some((token, 1.0))
+                } else {
+                    tokenizer::fuzzy_match(&token, self.idf.keys()).map(|term| (term.to_string(), FUZZY_WEIGHT))
+                }
+            })
+            .collect()
+    }
 
-            let doc_len = self.doc_lengths.get(idx).copied().unwrap_or(0) as f64;
-            let mut score = 0.0;
+    /// 返回得分大于 0 的文档，按得分降序（同分按文档顺序）
+    fn score(&self, query: &str) -> Vec<(usize, f64)> {
+        if self.avgdl == 0.0 {
+            return Vec::new();
+        }
 
-            for token in &query_tokens {
-                if let Some(idf) = self.idf.get(token) {
-                    let tf = *term_freqs.get(token.as_str()).unwrap_or(&0) as f64;
-                    if tf == 0.0 || self.avgdl == 0.0 {
-                        continue;
-                    }
-                    let numerator = tf * (self.k1 + 1.0);
-                    let denominator = tf + self.k1 * (1.0 - self.b + self.b * doc_len / self.avgdl);
-                    score += idf * numerator / denominator;
-                }
+        let mut scores: HashMap<usize, f64> = HashMap::new();
+        for (token, weight) in self.resolve_query(query) {
+            let (Some(idf), Some(postings)) = (self.idf.get(&token), self.postings.get(&token)) else {
+                continue;
+            };
+            for &(idx, tf) in postings {
+                let tf = tf as f64;
+                let doc_len = self.doc_lengths.get(idx as usize).copied().unwrap_or(0) as f64;
+                let numerator = tf * (self.k1 + 1.0);
+                let denominator = tf + self.k1 * (1.0 - self.b + self.b * doc_len / self.avgdl);
+                *scores.entry(idx as usize).or_insert(0.0) += weight * idf * numerator / denominator;
             }
-
-            scores.push((idx, score));
         }
 
-        scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-        scores
+        let mut ranked: Vec<(usize, f64)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.0.cmp(&b.0))
+        });
+        ranked
     }
 }
 
+#[derive(Serialize, Deserialize)]
 struct DomainIndex {
-    file: &'static str,
-    search_cols: &'static [&'static str],
-    output_cols: &'static [&'static str],
+    file: String,
+    output_cols: Vec<String>,
     rows: Vec<HashMap<String, String>>,
     bm25: BM25,
 }
 
-impl DomainIndex {
-    fn new(config: &DomainConfig) -> Result<Self, String> {
-        let rows = load_csv(config.file)?;
-        let documents: Vec<String> = rows
+/// 构建索引前的领域数据：内嵌 CSV 与知识包数据在此合并
+#[derive(Default)]
+struct DomainSource {
+    files: Vec<String>,
+    search_cols: Vec<String>,
+    output_cols: Vec<String>,
+    rows: Vec<HashMap<String, String>>,
+}
+
+impl DomainSource {
+    fn embedded(config: &DomainConfig) -> Result<Self, String> {
+        Ok(Self {
+            files: vec![config.file.to_string()],
+            search_cols: config.search_cols.iter().map(|c| c.to_string()).collect(),
+            output_cols: config.output_cols.iter().map(|c| c.to_string()).collect(),
+            rows: load_csv(config.file)?,
+        })
+    }
+
+    /// 并入知识包数据：列取并集，行追加在后
+    fn merge(&mut self, data: &PackData) {
+        self.files.push(data.source.clone());
+        for col in &data.domain.search_cols {
+            if !self.search_cols.contains(col) {
+                self.search_cols.push(col.clone());
+            }
+        }
+        for col in &data.domain.output_cols {
+            if !self.output_cols.contains(col) {
+                self.output_cols.push(col.clone());
+            }
+        }
+        self.rows.extend(data.rows.iter().cloned());
+    }
+
+    fn into_index(self) -> DomainIndex {
+        let documents: Vec<String> = self
+            .rows
             .iter()
             .map(|row| {
-                config
-                    .search_cols
-                    .iter()
-                    .map(|col| row.get(*col).cloned().unwrap_or_default())
-                    .collect::<Vec<_>>()
-                    .join(" ")
+                if self.search_cols.is_empty() {
+                    // 未声明检索列时检索全部列
+                    row.values().cloned().collect::<Vec<_>>().join(" ")
+                } else {
+                    self.search_cols
+                        .iter()
+                        .map(|col| row.get(col).cloned().unwrap_or_default())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                }
             })
             .collect();
 
         let mut bm25 = BM25::new(1.5, 0.75);
         bm25.fit(&documents);
 
-        Ok(Self {
-            file: config.file,
-            search_cols: config.search_cols,
-            output_cols: config.output_cols,
-            rows,
+        DomainIndex {
+            file: self.files.join(" + "),
+            output_cols: self.output_cols,
+            rows: self.rows,
             bm25,
-        })
+        }
     }
+}
 
+impl DomainIndex {
     fn search(&self, query: &str, max_results: usize) -> Vec<HashMap<String, String>> {
         let ranked = self.bm25.score(query);
         let mut results = Vec::new();
@@ -489,10 +539,15 @@ impl DomainIndex {
                 continue;
             }
             if let Some(row) = self.rows.get(idx) {
+                // 未声明输出列时输出整行
+                if self.output_cols.is_empty() {
+                    results.push(row.clone());
+                    continue;
+                }
                 let mut out = HashMap::new();
-                for col in self.output_cols {
-                    if let Some(value) = row.get(*col) {
-                        out.insert((*col).to_string(), value.clone());
+                for col in &self.output_cols {
+                    if let Some(value) = row.get(col) {
+                        out.insert(col.clone(), value.clone());
                     }
                 }
                 results.push(out);
@@ -553,24 +608,49 @@ pub struct DesignSystemOutput {
     pub design_system: DesignSystem,
     pub persisted: Option<PersistSummary>,
     pub formatted: String,
+    /// 按请求生成的导出文件（持久化时同时写入设计系统目录）
+    pub exports: Vec<ExportFile>,
 }
 
+#[derive(Serialize, Deserialize)]
 struct UiuxStore {
-    domains: HashMap<&'static str, DomainIndex>,
-    stacks: HashMap<&'static str, DomainIndex>,
+    domains: HashMap<String, DomainIndex>,
+    stacks: HashMap<String, DomainIndex>,
     reasoning: Vec<HashMap<String, String>>,
     keyword_set: HashSet<String>,
+    /// 知识包为领域声明的识别关键词
+    pack_keywords: Vec<(String, Vec<String>)>,
 }
 
-static UIUX_STORE: Lazy<UiuxStore> = Lazy::new(UiuxStore::load);
+/// 内嵌数据 + 用户级与项目级（MCP 进程工作目录）知识包，优先读取磁盘索引快照
+static UIUX_STORE: Lazy<UiuxStore> = Lazy::new(|| {
+    let project_root = std::env::current_dir().ok();
+    let roots = packs::pack_roots(project_root.as_deref());
+    UiuxStore::load_cached(&IndexCache::new(IndexCache::default_dir()), &roots)
+});
 
 impl UiuxStore {
-    fn load() -> Self {
-        let mut domains = HashMap::new();
+    /// 指纹一致时直接读取快照，否则重新解析数据并写回缓存
+    fn load_cached(cache: &IndexCache, pack_roots: &[PathBuf]) -> Self {
+        let fingerprint = store_fingerprint(pack_roots);
+        if let Some(store) = cache.load::<Self>(&fingerprint) {
+            log_debug!("UIUX 索引命中缓存: {}", fingerprint);
+            return store;
+        }
+
+        let store = Self::load(&packs::load_packs(pack_roots));
+        if let Err(err) = cache.store(&fingerprint, &store) {
+            log_debug!("UIUX 索引缓存写入失败: {}", err);
+        }
+        store
+    }
+
+    fn load(packs: &[KnowledgePack]) -> Self {
+        let mut domain_sources = HashMap::new();
         for (name, config) in DOMAIN_CONFIGS.iter() {
-            match DomainIndex::new(config) {
-                Ok(index) => {
-                    domains.insert(*name, index);
+            match DomainSource::embedded(config) {
+                Ok(source) => {
+                    domain_sources.insert(name.to_string(), source);
                 }
                 Err(err) => {
                     log_debug!("UIUX 域数据加载失败: {} -> {}", name, err);
@@ -578,16 +658,16 @@ impl UiuxStore {
             }
         }
 
-        let mut stacks = HashMap::new();
+        let mut stack_sources = HashMap::new();
         for (name, file) in STACK_CONFIGS.iter() {
             let config = DomainConfig {
                 file,
                 search_cols: STACK_SEARCH_COLS,
                 output_cols: STACK_OUTPUT_COLS,
             };
-            match DomainIndex::new(&config) {
-                Ok(index) => {
-                    stacks.insert(*name, index);
+            match DomainSource::embedded(&config) {
+                Ok(source) => {
+                    stack_sources.insert(name.to_string(), source);
                 }
                 Err(err) => {
                     log_debug!("UIUX 栈数据加载失败: {} -> {}", name, err);
@@ -595,18 +675,68 @@ impl UiuxStore {
             }
         }
 
+        let mut keyword_set = build_keyword_set();
+        let mut pack_keywords = Vec::new();
+        for pack in packs {
+            for data in &pack.domains {
+                domain_sources
+                    .entry(data.domain.name.clone())
+                    .or_insert_with(DomainSource::default)
+                    .merge(data);
+                if !data.domain.keywords.is_empty() {
+                    pack_keywords.push((data.domain.name.clone(), data.domain.keywords.clone()));
+                }
+            }
+            for data in &pack.stacks {
+                stack_sources
+                    .entry(data.domain.name.clone())
+                    .or_insert_with(DomainSource::default)
+                    .merge(data);
+            }
+            for data in pack.domains.iter().chain(&pack.stacks) {
+                keyword_set.insert(data.domain.name.to_lowercase());
+                for keyword in &data.domain.keywords {
+                    add_keywords(keyword, &mut keyword_set);
+                }
+            }
+        }
+
+        let domains = domain_sources
+            .into_iter()
+            .map(|(name, source)| (name, source.into_index()))
+            .collect();
+        let stacks = stack_sources
+            .into_iter()
+            .map(|(name, source)| (name, source.into_index()))
+            .collect();
         let reasoning = load_csv("ui-reasoning.csv").unwrap_or_default();
-        let keyword_set = build_keyword_set();
 
         Self {
             domains,
             stacks,
             reasoning,
             keyword_set,
+            pack_keywords,
         }
     }
 }
 
+/// 内嵌数据内容 + 知识包文件元数据
+fn store_fingerprint(pack_roots: &[PathBuf]) -> String {
+    let mut fingerprint = Fingerprint::new();
+    let mut names: Vec<String> = EmbeddedUiuxData::iter().map(|name| name.to_string()).collect();
+    names.sort();
+    for name in names {
+        if let Some(file) = EmbeddedUiuxData::get(&name) {
+            fingerprint.update(&name, &file.data);
+        }
+    }
+    for path in packs::pack_files(pack_roots) {
+        fingerprint.update_file(&path);
+    }
+    fingerprint.finish()
+}
+
 fn read_embedded(path: &str) -> Result<Vec<u8>, String> {
     EmbeddedUiuxData::get(path)
         .map(|file| file.data.into_owned())
@@ -614,7 +744,11 @@ fn read_embedded(path: &str) -> Result<Vec<u8>, String> {
 }
 
 fn load_csv(path: &str) -> Result<Vec<HashMap<String, String>>, String> {
-    let bytes = read_embedded(path)?;
+    parse_csv(&read_embedded(path)?)
+}
+
+/// 解析带表头的 CSV，每行转为 列名 -> 值
+pub(super) fn parse_csv(bytes: &[u8]) -> Result<Vec<HashMap<String, String>>, String> {
     let mut reader = csv::ReaderBuilder::new()
         .has_headers(true)
         .from_reader(Cursor::new(bytes));
@@ -638,15 +772,25 @@ fn load_csv(path: &str) -> Result<Vec<HashMap<String, String>>, String> {
     Ok(rows)
 }
 
-fn detect_domain(query: &str) -> &'static str {
+fn detect_domain(store: &UiuxStore, query: &str) -> String {
     let query_lower = query.to_lowercase();
+    // 中文查询经对照表扩展出英文词，关键词按词干或拼写相近匹配
+    let query_terms = tokenizer::tokenize_query(query);
     let mut best_domain = "style";
     let mut best_score = 0;
 
-    for (domain, keywords) in DOMAIN_KEYWORDS.iter() {
+    let builtin = DOMAIN_KEYWORDS
+        .iter()
+        .map(|(domain, keywords)| (*domain, keywords.iter().map(|kw| kw.to_lowercase()).collect::<Vec<_>>()));
+    let custom = store
+        .pack_keywords
+        .iter()
+        .map(|(domain, keywords)| (domain.as_str(), keywords.iter().map(|kw| kw.to_lowercase()).collect()));
+
+    for (domain, keywords) in builtin.chain(custom) {
         let score = keywords
             .iter()
-            .filter(|kw| query_lower.contains(&kw.to_lowercase()))
+            .filter(|kw| query_lower.contains(kw.as_str()) || keyword_matches_terms(kw, &query_terms))
             .count();
         if score > best_score {
             best_score = score;
@@ -654,11 +798,18 @@ fn detect_domain(query: &str) -> &'static str {
         }
     }
 
-    if best_score == 0 {
-        "style"
-    } else {
-        best_domain
-    }
+    best_domain.to_string()
+}
+
+/// 关键词的每个词都出现在查询词中（允许拼写相近）
+fn keyword_matches_terms(keyword: &str, query_terms: &[String]) -> bool {
+    let keyword_terms = tokenizer::tokenize(keyword);
+    !keyword_terms.is_empty()
+        && keyword_terms.iter().all(|kw| {
+            query_terms
+                .iter()
+                .any(|term| term == kw || tokenizer::is_fuzzy_match(term, kw))
+        })
 }
 
 fn normalize_format(value: Option<&str>, default: &str) -> String {
@@ -677,28 +828,30 @@ pub fn cap_max_results(value: Option<u32>, cap: u32, default: u32) -> usize {
 
 pub fn search_domain(query: &str, domain: Option<&str>, max_results: Option<usize>) -> SearchResult {
     let store = &*UIUX_STORE;
-    let requested_domain = domain.unwrap_or_else(|| detect_domain(query));
-    let domain = if store.domains.contains_key(requested_domain) {
+    let requested_domain = domain
+        .map(str::to_string)
+        .unwrap_or_else(|| detect_domain(store, query));
+    let domain = if store.domains.contains_key(&requested_domain) {
         requested_domain
     } else {
-        "style"
+        "style".to_string()
     };
     let max_results = max_results.unwrap_or(MAX_RESULTS);
 
-    match store.domains.get(domain) {
+    match store.domains.get(&domain) {
         Some(index) => {
             let results = index.search(query, max_results);
             SearchResult {
-                domain: domain.to_string(),
+                domain: domain.clone(),
                 query: query.to_string(),
-                file: Some(index.file.to_string()),
+                file: Some(index.file.clone()),
                 count: results.len(),
                 results,
                 stack: None,
                 error: None,
             }
         }
-        None => SearchResult::error(domain, query, &format!("未知领域: {}", domain)),
+        None => SearchResult::error(&domain, query, &format!("未知领域: {}", domain)),
     }
 }
 
@@ -712,7 +865,7 @@ pub fn search_stack(query: &str, stack: &str, max_results: Option<usize>) -> Sea
             SearchResult {
                 domain: "stack".to_string(),
                 query: query.to_string(),
-                file: Some(index.file.to_string()),
+                file: Some(index.file.clone()),
                 count: results.len(),
                 results,
                 stack: Some(stack.to_string()),
@@ -722,13 +875,13 @@ pub fn search_stack(query: &str, stack: &str, max_results: Option<usize>) -> Sea
         None => SearchResult::error(
             "stack",
             query,
-            &format!("Unknown stack: {}. Available: {}", stack, available_stacks().join(", ")),
+            &format!("Unknown stack: {}. Available: {}", stack, available_stacks(store).join(", ")),
         ),
     }
 }
 
-fn available_stacks() -> Vec<&'static str> {
-    let mut stacks: Vec<&'static str> = STACK_CONFIGS.keys().copied().collect();
+fn available_stacks(store: &UiuxStore) -> Vec<&str> {
+    let mut stacks: Vec<&str> = store.stacks.keys().map(String::as_str).collect();
     stacks.sort();
     stacks
 }
@@ -752,40 +905,33 @@ pub fn format_search_output(result: &SearchResult) -> String {
         result.count
     ));
 
+    // 使用索引的输出列顺序保证稳定展示
+    let store = &*UIUX_STORE;
+    let index = match &result.stack {
+        Some(stack) => store.stacks.get(stack),
+        None => store.domains.get(&result.domain),
+    };
+    let ordered_cols = index
+        .map(|index| index.output_cols.as_slice())
+        .filter(|cols| !cols.is_empty());
+
     for (idx, row) in result.results.iter().enumerate() {
         output.push(format!("### Result {}", idx + 1));
-        // 使用输出列顺序保证稳定展示
-        if let Some(config) = DOMAIN_CONFIGS.get(result.domain.as_str()) {
-            for col in config.output_cols {
-                if let Some(value) = row.get(*col) {
-                    let mut value_str = value.clone();
-                    if value_str.len() > 300 {
-                        value_str.truncate(300);
-                        value_str.push_str("...");
+        match ordered_cols {
+            Some(cols) => {
+                for col in cols {
+                    if let Some(value) = row.get(col) {
+                        output.push(format!("- **{}:** {}", col, truncate_value(value)));
                     }
-                    output.push(format!("- **{}:** {}", col, value_str));
                 }
             }
-        } else if result.domain == "stack" {
-            for col in STACK_OUTPUT_COLS {
-                if let Some(value) = row.get(*col) {
-                    let mut value_str = value.clone();
-                    if value_str.len() > 300 {
-                        value_str.truncate(300);
-                        value_str.push_str("...");
-                    }
-                    output.push(format!("- **{}:** {}", col, value_str));
+            None => {
+                let mut keys: Vec<&String> = row.keys().collect();
+                keys.sort();
+                for key in keys {
+                    output.push(format!("- **{}:** {}", key, truncate_value(&row[key])));
                 }
             }
-        } else {
-            for (key, value) in row {
-                let mut value_str = value.clone();
-                if value_str.len() > 300 {
-                    value_str.truncate(300);
-                    value_str.push_str("...");
-                }
-                output.push(format!("- **{}:** {}", key, value_str));
-            }
         }
         output.push(String::new());
     }
@@ -793,6 +939,16 @@ pub fn format_search_output(result: &SearchResult) -> String {
     output.join("\n")
 }
 
+/// 截断过长字段（按字符，避免截断多字节字符）
+fn truncate_value(value: &str) -> String {
+    if value.chars().count() <= MAX_VALUE_CHARS {
+        return value.to_string();
+    }
+    let mut truncated: String = value.chars().take(MAX_VALUE_CHARS).collect();
+    truncated.push_str("...");
+    truncated
+}
+
 pub fn format_search_json(result: &SearchResult) -> Result<String, String> {
     serde_json::to_string_pretty(result).map_err(|e| format!("JSON 序列化失败: {}", e))
 }
@@ -829,7 +985,7 @@ fn dedupe_results(rows: Vec<HashMap<String, String>>) -> Vec<HashMap<String, Str
     output
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PatternInfo {
     pub name: String,
     pub sections: String,
@@ -838,7 +994,7 @@ pub struct PatternInfo {
     pub conversion: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StyleInfo {
     pub name: String,
     pub style_type: String,
@@ -849,7 +1005,7 @@ pub struct StyleInfo {
     pub accessibility: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ColorInfo {
     pub primary: String,
     pub secondary: String,
@@ -859,7 +1015,7 @@ pub struct ColorInfo {
     pub notes: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TypographyInfo {
     pub heading: String,
     pub body: String,
@@ -869,7 +1025,7 @@ pub struct TypographyInfo {
     pub css_import: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DesignSystem {
     pub project_name: String,
     pub category: String,
@@ -2053,11 +2209,15 @@ fn format_page_override_md(design_system: &DesignSystem, page_name: &str, page_q
     lines.join("\n")
 }
 
+/// 持久化的结构化设计系统文件名（与 MASTER.md 同目录）
+pub const DESIGN_SYSTEM_JSON: &str = "design-system.json";
+
 fn persist_design_system(
     design_system: &DesignSystem,
     page: Option<&str>,
     output_dir: Option<&Path>,
     page_query: Option<&str>,
+    exports: &[ExportFile],
 ) -> Result<PersistSummary, String> {
     let base_dir = output_dir
         .map(|p| p.to_path_buf())
@@ -2076,7 +2236,14 @@ fn persist_design_system(
     std::fs::write(&master_file, format_master_md(design_system))
         .map_err(|e| format!("写入 MASTER.md 失败: {}", e))?;
 
-    let mut created = vec![master_file];
+    // 结构化副本供 uiux_audit 等工具读取，避免解析 Markdown
+    let json_file = design_system_dir.join(DESIGN_SYSTEM_JSON);
+    let json = serde_json::to_string_pretty(design_system)
+        .map_err(|e| format!("序列化设计系统失败: {}", e))?;
+    std::fs::write(&json_file, json)
+        .map_err(|e| format!("写入 {} 失败: {}", DESIGN_SYSTEM_JSON, e))?;
+
+    let mut created = vec![master_file, json_file];
 
     if let Some(page_name) = page {
         let page_slug = sanitize_path_segment(page_name);
@@ -2086,6 +2253,13 @@ fn persist_design_system(
         created.push(page_file);
     }
 
+    for export in exports {
+        let export_file = design_system_dir.join(&export.file_name);
+        std::fs::write(&export_file, &export.content)
+            .map_err(|e| format!("写入 {} 失败: {}", export.file_name, e))?;
+        created.push(export_file);
+    }
+
     Ok(PersistSummary {
         design_system_dir: design_system_dir.to_string_lossy().to_string(),
         created_files: created
@@ -2102,12 +2276,20 @@ pub fn generate_design_system(
     persist: bool,
     page: Option<&str>,
     output_dir: Option<&Path>,
+    export_targets: &[ExportTarget],
 ) -> Result<DesignSystemOutput, String> {
     let generator = DesignSystemGenerator::new();
     let design_system = generator.generate(query, project_name);
 
+    let mut exports: Vec<ExportFile> = Vec::new();
+    for target in export_targets {
+        if !exports.iter().any(|export| export.target == *target) {
+            exports.push(export_design_system(&design_system, *target));
+        }
+    }
+
     let persisted = if persist {
-        Some(persist_design_system(&design_system, page, output_dir, Some(query))?)
+        Some(persist_design_system(&design_system, page, output_dir, Some(query), &exports)?)
     } else {
         None
     };
@@ -2130,6 +2312,10 @@ pub fn generate_design_system(
             "   📄 design-system/{}/MASTER.md (Global Source of Truth)\n",
             project_slug
         ));
+        formatted.push_str(&format!(
+            "   📄 design-system/{}/{} (Structured, used by uiux_audit)\n",
+            project_slug, DESIGN_SYSTEM_JSON
+        ));
         if let Some(page_name) = page {
             let page_slug = sanitize_path_segment(page_name);
             formatted.push_str(&format!(
@@ -2137,6 +2323,12 @@ pub fn generate_design_system(
                 project_slug, page_slug
             ));
         }
+        for export in &exports {
+            formatted.push_str(&format!(
+                "   📄 design-system/{}/{} (Export)\n",
+                project_slug, export.file_name
+            ));
+        }
         formatted.push_str("\n");
         formatted.push_str(&format!(
             "📖 Usage: When building a page, check design-system/{}/pages/[page].md first.\n",
@@ -2150,17 +2342,20 @@ pub fn generate_design_system(
         design_system,
         persisted,
         formatted,
+        exports,
     })
 }
 
 pub fn suggest(text: &str) -> SuggestResult {
     let store = &*UIUX_STORE;
-    let tokens = BM25::tokenize(text);
+    let tokens = tokenizer::tokenize_query(text);
     let mut matched: HashSet<String> = HashSet::new();
 
     for token in tokens {
         if store.keyword_set.contains(&token) {
             matched.insert(token);
+        } else if let Some(keyword) = tokenizer::fuzzy_match(&token, &store.keyword_set) {
+            matched.insert(keyword.to_string());
         }
     }
 
@@ -2217,15 +2412,121 @@ fn build_keyword_set() -> HashSet<String> {
 }
 
 fn add_keywords(text: &str, set: &mut HashSet<String>) {
-    let lower = text.to_lowercase();
-    let cleaned = TOKEN_RE.replace_all(&lower, " ");
-    for token in cleaned.split_whitespace() {
-        if token.len() <= 2 {
+    for token in tokenizer::tokenize(text) {
+        if KEYWORD_STOPWORDS.contains(token.as_str()) {
             continue;
         }
-        if KEYWORD_STOPWORDS.contains(token) {
-            continue;
+        set.insert(token);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::tools::uiux::packs::{PackDomain, PackManifest};
+
+    fn pack_data(name: &str, keywords: &[&str], rows: Vec<HashMap<String, String>>) -> PackData {
+        PackData {
+            domain: PackDomain {
+                name: name.to_string(),
+                file: format!("{}.json", name),
+                search_cols: Vec::new(),
+                output_cols: Vec::new(),
+                keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            },
+            source: format!("acme/{}.json", name),
+            rows,
         }
-        set.insert(token.to_string());
+    }
+
+    fn row(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_store_merges_knowledge_packs() {
+        let pack = KnowledgePack {
+            manifest: PackManifest {
+                name: "acme".to_string(),
+                description: None,
+                domains: Vec::new(),
+                stacks: Vec::new(),
+            },
+            dir: PathBuf::from("acme"),
+            domains: vec![
+                pack_data(
+                    "color",
+                    &[],
+                    vec![row(&[("Product Type", "Acme Console"), ("Keywords", "acmeconsole"), ("Primary (Hex)", "#FF5500")])],
+                ),
+                pack_data(
+                    "brand",
+                    &["acme"],
+                    vec![row(&[("Component", "AcmeButton"), ("Guideline", "rounded corners only")])],
+                ),
+            ],
+            stacks: Vec::new(),
+        };
+        let store = UiuxStore::load(&[pack]);
+
+        // 合并到内置领域：内嵌数据保留，新增行可被检索
+        let color = &store.domains["color"];
+        assert!(color.file.starts_with("colors.csv + acme/color.json"));
+        let hits = color.search("acmeconsole", 3);
+        assert_eq!(hits[0].get("Primary (Hex)").map(String::as_str), Some("#FF5500"));
+
+        // 新领域：检索并输出全部列，关键词参与领域识别
+        let brand = &store.domains["brand"];
+        assert_eq!(brand.search("rounded", 3)[0].get("Component").map(String::as_str), Some("AcmeButton"));
+        assert_eq!(detect_domain(&store, "acme button"), "brand");
+        assert!(store.keyword_set.contains("brand"));
+    }
+
+    #[test]
+    fn test_cjk_and_fuzzy_queries() {
+        let store = UiuxStore::load(&[]);
+
+        // 中文查询经对照表扩展后识别领域并命中英文数据
+        assert_eq!(detect_domain(&store, "深色模式的配色方案"), "color");
+        assert_eq!(detect_domain(&store, "折线图展示趋势"), "chart");
+        let hits = store.domains["product"].search("奢华电商", 1);
+        assert!(hits[0].values().any(|v| v.contains("E-commerce Luxury")));
+
+        // 拼写错误回退到最接近的词
+        assert_eq!(detect_domain(&store, "dashbord for fintech"), "product");
+        let hits = store.domains["product"].search("fintec", 1);
+        assert!(hits[0].values().any(|v| v.to_lowercase().contains("fintech")));
+    }
+
+    #[test]
+    fn test_store_snapshot_matches_fresh_index() {
+        let dir = std::env::temp_dir().join(format!("sanshu-uiux-store-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let cache = IndexCache::new(dir.clone());
+
+        let fresh = UiuxStore::load_cached(&cache, &[]);
+        let cached = UiuxStore::load_cached(&cache, &[]);
+        for query in ["fintech dashboard", "奢华电商", "dashbord"] {
+            assert_eq!(
+                fresh.domains["product"].search(query, 3),
+                cached.domains["product"].search(query, 3)
+            );
+        }
+        assert_eq!(fresh.keyword_set, cached.keyword_set);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_bm25_scores_only_matching_documents() {
+        let mut bm25 = BM25::new(1.5, 0.75);
+        bm25.fit(&[
+            "dark mode dashboard".to_string(),
+            "light landing page".to_string(),
+            "dashboard dashboard charts".to_string(),
+        ]);
+        let ranked: Vec<usize> = bm25.score("dashboard").into_iter().map(|(idx, _)| idx).collect();
+        assert_eq!(ranked, vec![2, 0]);
+        assert!(bm25.score("pricing").is_empty());
     }
 }
@@ -0,0 +1,313 @@
+// 设计系统导出
+// 将 DesignSystem 的颜色与字体导出为 Tailwind 主题扩展、CSS 变量、W3C 设计令牌与 Figma Tokens
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use super::engine::DesignSystem;
+use super::sanitize::sanitize_slug;
+
+/// 常见衬线字体（用于推断字体族回退）
+const SERIF_HINTS: &[&str] = &[
+    "serif", "playfair", "merriweather", "lora", "garamond", "georgia", "baskerville", "crimson",
+    "libre caslon", "cormorant", "noto serif", "source serif", "dm serif", "bodoni",
+];
+
+/// 导出目标
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportTarget {
+    /// tailwind.config.js 主题扩展（可作为 preset 引入）
+    Tailwind,
+    /// CSS 自定义属性
+    Css,
+    /// W3C Design Tokens 格式
+    Tokens,
+    /// Figma Tokens（Tokens Studio）格式
+    Figma,
+}
+
+impl ExportTarget {
+    /// 解析技能命令行参数中的导出目标（不区分大小写）
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "tailwind" => Some(Self::Tailwind),
+            "css" => Some(Self::Css),
+            "tokens" => Some(Self::Tokens),
+            "figma" => Some(Self::Figma),
+            _ => None,
+        }
+    }
+
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Self::Tailwind => "tailwind.config.js",
+            Self::Css => "tokens.css",
+            Self::Tokens => "tokens.json",
+            Self::Figma => "figma-tokens.json",
+        }
+    }
+}
+
+/// 导出结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportFile {
+    pub target: ExportTarget,
+    pub file_name: String,
+    pub content: String,
+}
+
+/// 颜色令牌（名称, 值），值非十六进制时跳过
+fn color_tokens(design_system: &DesignSystem) -> Vec<(&'static str, String)> {
+    let colors = &design_system.colors;
+    [
+        ("primary", &colors.primary),
+        ("secondary", &colors.secondary),
+        ("cta", &colors.cta),
+        ("background", &colors.background),
+        ("text", &colors.text),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| normalize_hex(value).map(|hex| (name, hex)))
+    .collect()
+}
+
+/// 字体令牌（名称, 字体族列表）
+fn font_tokens(design_system: &DesignSystem) -> Vec<(&'static str, Vec<String>)> {
+    let typography = &design_system.typography;
+    [("heading", &typography.heading), ("body", &typography.body)]
+        .into_iter()
+        .filter(|(_, font)| !font.trim().is_empty())
+        .map(|(name, font)| {
+            let font = font.trim().to_string();
+            let fallback = generic_family(&font).to_string();
+            (name, vec![font, fallback])
+        })
+        .collect()
+}
+
+/// 提取十六进制颜色（如 "#2563EB (Blue)" -> "#2563EB"）
+fn normalize_hex(value: &str) -> Option<String> {
+    let start = value.find('#')?;
+    let hex: String = value[start + 1..]
+        .chars()
+        .take_while(|c| c.is_ascii_hexdigit())
+        .collect();
+    matches!(hex.len(), 3 | 4 | 6 | 8).then(|| format!("#{}", hex.to_uppercase()))
+}
+
+fn generic_family(font: &str) -> &'static str {
+    let lower = font.to_lowercase();
+    if lower.contains("mono") || lower.contains("code") {
+        "monospace"
+    } else if lower.contains("sans") {
+        "sans-serif"
+    } else if SERIF_HINTS.iter().any(|hint| lower.contains(hint)) {
+        "serif"
+    } else {
+        "sans-serif"
+    }
+}
+
+/// CSS 中的字体族声明：含空格的字体名加引号
+fn css_font_stack(families: &[String]) -> String {
+    families
+        .iter()
+        .map(|family| {
+            if family.contains(' ') && !family.contains('-') {
+                format!("'{}'", family)
+            } else {
+                family.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn export_design_system(design_system: &DesignSystem, target: ExportTarget) -> ExportFile {
+    let content = match target {
+        ExportTarget::Tailwind => export_tailwind(design_system),
+        ExportTarget::Css => export_css(design_system),
+        ExportTarget::Tokens => export_w3c_tokens(design_system),
+        ExportTarget::Figma => export_figma_tokens(design_system),
+    };
+    ExportFile {
+        target,
+        file_name: target.file_name().to_string(),
+        content,
+    }
+}
+
+fn export_tailwind(design_system: &DesignSystem) -> String {
+    let mut lines = vec![
+        format!("// {} 设计系统（由三术 UI/UX 生成）", design_system.project_name),
+        // 导出文件写入 design-system/<slug>/，preset 路径相对 design-system 所在目录
+        format!(
+            "// 用法：在 design-system 目录同级的 tailwind.config.js 中加入 presets: [require('./design-system/{}/{}')]",
+            sanitize_slug(&design_system.project_name),
+            ExportTarget::Tailwind.file_name()
+        ),
+        "/** @type {import('tailwindcss').Config} */".to_string(),
+        "module.exports = {".to_string(),
+        "  theme: {".to_string(),
+        "    extend: {".to_string(),
+        "      colors: {".to_string(),
+    ];
+    for (name, hex) in color_tokens(design_system) {
+        lines.push(format!("        {}: '{}',", name, hex));
+    }
+    lines.push("      },".to_string());
+    lines.push("      fontFamily: {".to_string());
+    for (name, families) in font_tokens(design_system) {
+        let quoted = families
+            .iter()
+            .map(|family| format!("'{}'", family.replace('\'', "\\'")))
+            .collect::<Vec<_>>()
+            .join(", ");
+        lines.push(format!("        {}: [{}],", name, quoted));
+    }
+    lines.push("      },".to_string());
+    lines.push("    },".to_string());
+    lines.push("  },".to_string());
+    lines.push("}".to_string());
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+fn export_css(design_system: &DesignSystem) -> String {
+    let mut lines = vec![format!("/* {} 设计系统（由三术 UI/UX 生成） */", design_system.project_name)];
+    let css_import = design_system.typography.css_import.trim();
+    if css_import.starts_with("@import") {
+        lines.push(css_import.to_string());
+    }
+    lines.push(String::new());
+    lines.push(":root {".to_string());
+    for (name, hex) in color_tokens(design_system) {
+        lines.push(format!("  --color-{}: {};", name, hex));
+    }
+    for (name, families) in font_tokens(design_system) {
+        lines.push(format!("  --font-{}: {};", name, css_font_stack(&families)));
+    }
+    lines.push("}".to_string());
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// W3C Design Tokens（$type / $value）
+fn export_w3c_tokens(design_system: &DesignSystem) -> String {
+    let mut colors = Map::new();
+    for (name, hex) in color_tokens(design_system) {
+        colors.insert(name.to_string(), json!({ "$type": "color", "$value": hex }));
+    }
+    let mut fonts = Map::new();
+    for (name, families) in font_tokens(design_system) {
+        fonts.insert(name.to_string(), json!({ "$type": "fontFamily", "$value": families }));
+    }
+
+    let tokens = json!({
+        "$description": format!("{} design system", design_system.project_name),
+        "color": Value::Object(colors),
+        "font": Value::Object(fonts),
+    });
+    serde_json::to_string_pretty(&tokens).unwrap_or_default()
+}
+
+/// Figma Tokens（Tokens Studio）：value / type，集合名为 global
+fn export_figma_tokens(design_system: &DesignSystem) -> String {
+    let mut colors = Map::new();
+    for (name, hex) in color_tokens(design_system) {
+        colors.insert(name.to_string(), json!({ "value": hex, "type": "color" }));
+    }
+    let mut fonts = Map::new();
+    for (name, families) in font_tokens(design_system) {
+        fonts.insert(
+            name.to_string(),
+            json!({ "value": families.first().cloned().unwrap_or_default(), "type": "fontFamilies" }),
+        );
+    }
+
+    let tokens = json!({
+        "global": {
+            "color": Value::Object(colors),
+            "fontFamilies": Value::Object(fonts),
+        },
+        "$metadata": { "tokenSetOrder": ["global"] },
+    });
+    serde_json::to_string_pretty(&tokens).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::tools::uiux::engine::{ColorInfo, PatternInfo, StyleInfo, TypographyInfo};
+
+    fn sample() -> DesignSystem {
+        let text = |s: &str| s.to_string();
+        DesignSystem {
+            project_name: text("Acme"),
+            category: text("SaaS"),
+            pattern: PatternInfo {
+                name: text("Hero"),
+                sections: text(""),
+                cta_placement: text(""),
+                color_strategy: text(""),
+                conversion: text(""),
+            },
+            style: StyleInfo {
+                name: text("Minimalism"),
+                style_type: text(""),
+                effects: text(""),
+                keywords: text(""),
+                best_for: text(""),
+                performance: text(""),
+                accessibility: text(""),
+            },
+            colors: ColorInfo {
+                primary: text("#2563eb"),
+                secondary: text("#3B82F6"),
+                cta: text("#F97316 (orange)"),
+                background: text("#F8FAFC"),
+                text: text("n/a"),
+                notes: text(""),
+            },
+            typography: TypographyInfo {
+                heading: text("Playfair Display"),
+                body: text("Inter"),
+                mood: text(""),
+                best_for: text(""),
+                google_fonts_url: text(""),
+                css_import: text("@import url('https://fonts.googleapis.com/css2?family=Inter');"),
+            },
+            key_effects: text(""),
+            anti_patterns: text(""),
+            decision_rules: Value::Null,
+            severity: text(""),
+        }
+    }
+
+    #[test]
+    fn test_export_targets() {
+        let ds = sample();
+
+        let css = export_design_system(&ds, ExportTarget::Css).content;
+        assert!(css.starts_with("/* Acme"));
+        assert!(css.contains("@import url("));
+        assert!(css.contains("--color-primary: #2563EB;"));
+        assert!(css.contains("--color-cta: #F97316;"));
+        // 非颜色值不导出
+        assert!(!css.contains("--color-text"));
+        assert!(css.contains("--font-heading: 'Playfair Display', serif;"));
+
+        let tailwind = export_design_system(&ds, ExportTarget::Tailwind).content;
+        assert!(tailwind.contains("body: ['Inter', 'sans-serif'],"));
+        assert!(tailwind.contains("presets: [require('./design-system/acme/tailwind.config.js')]"));
+
+        let tokens: Value = serde_json::from_str(&export_design_system(&ds, ExportTarget::Tokens).content).unwrap();
+        assert_eq!(tokens["color"]["primary"]["$value"], "#2563EB");
+        assert_eq!(tokens["font"]["heading"]["$value"][1], "serif");
+
+        let figma: Value = serde_json::from_str(&export_design_system(&ds, ExportTarget::Figma).content).unwrap();
+        assert_eq!(figma["global"]["color"]["background"]["type"], "color");
+        assert_eq!(figma["global"]["fontFamilies"]["body"]["value"], "Inter");
+    }
+}
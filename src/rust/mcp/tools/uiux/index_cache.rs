@@ -0,0 +1,171 @@
+// UI/UX 检索索引磁盘缓存
+// 以数据指纹命名的 JSON 快照：命中时跳过 CSV 解析与 BM25 构建，数据或版本变化时自动重建
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ring::digest::{Context, SHA256};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::log_debug;
+
+/// 索引格式版本，分词或索引结构变化时递增
+const INDEX_FORMAT_VERSION: &str = "1";
+
+/// 保留的快照数量（不同项目的知识包组合各有一份）
+const MAX_SNAPSHOTS: usize = 4;
+
+/// 索引数据指纹
+pub struct Fingerprint(Context);
+
+impl Fingerprint {
+    /// 包含索引格式版本与程序版本
+    pub fn new() -> Self {
+        let mut context = Context::new(&SHA256);
+        context.update(INDEX_FORMAT_VERSION.as_bytes());
+        context.update(env!("CARGO_PKG_VERSION").as_bytes());
+        Self(context)
+    }
+
+    pub fn update(&mut self, name: &str, bytes: &[u8]) {
+        self.0.update(name.as_bytes());
+        self.0.update(&(bytes.len() as u64).to_le_bytes());
+        self.0.update(bytes);
+    }
+
+    /// 外部文件按路径、大小与修改时间计入，避免每次启动读取内容
+    pub fn update_file(&mut self, path: &Path) {
+        let (len, modified) = fs::metadata(path)
+            .map(|m| {
+                let modified = m
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_nanos())
+                    .unwrap_or(0);
+                (m.len(), modified)
+            })
+            .unwrap_or((0, 0));
+        self.0.update(path.to_string_lossy().as_bytes());
+        self.0.update(&len.to_le_bytes());
+        self.0.update(&modified.to_le_bytes());
+    }
+
+    pub fn finish(self) -> String {
+        hex::encode(&self.0.finish().as_ref()[..16])
+    }
+}
+
+impl Default for Fingerprint {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 索引缓存目录，按修改时间保留最近的若干快照
+pub struct IndexCache {
+    dir: PathBuf,
+}
+
+impl IndexCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn default_dir() -> PathBuf {
+        dirs::config_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("sanshu")
+            .join("uiux_index")
+    }
+
+    pub fn load<T: DeserializeOwned>(&self, fingerprint: &str) -> Option<T> {
+        let path = self.entry_path(fingerprint);
+        let content = fs::read(&path).ok()?;
+        match serde_json::from_slice(&content) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                log_debug!("UIUX 索引缓存损坏，将重建: {}", err);
+                let _ = fs::remove_file(&path);
+                None
+            }
+        }
+    }
+
+    /// 写入快照（临时文件 + 重命名），并清理超出数量的旧快照
+    pub fn store<T: Serialize>(&self, fingerprint: &str, value: &T) -> Result<(), String> {
+        fs::create_dir_all(&self.dir).map_err(|e| format!("创建索引缓存目录失败: {}", e))?;
+        let path = self.entry_path(fingerprint);
+        let content = serde_json::to_vec(value).map_err(|e| format!("序列化索引失败: {}", e))?;
+        let temp_path = path.with_extension(format!("tmp{}", std::process::id()));
+        fs::write(&temp_path, content).map_err(|e| format!("写入索引缓存失败: {}", e))?;
+        fs::rename(&temp_path, &path).map_err(|e| format!("写入索引缓存失败: {}", e))?;
+
+        self.prune();
+        Ok(())
+    }
+
+    fn prune(&self) {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut snapshots: Vec<(SystemTime, PathBuf)> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+            .map(|path| {
+                let modified = fs::metadata(&path).and_then(|m| m.modified()).unwrap_or(UNIX_EPOCH);
+                (modified, path)
+            })
+            .collect();
+        snapshots.sort_by(|a, b| b.cmp(a));
+        for (_, stale) in snapshots.into_iter().skip(MAX_SNAPSHOTS) {
+            let _ = fs::remove_file(stale);
+        }
+    }
+
+    fn entry_path(&self, fingerprint: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", fingerprint))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_and_load_snapshot() {
+        let dir = std::env::temp_dir().join(format!("sanshu-uiux-index-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let cache = IndexCache::new(dir.clone());
+
+        let fingerprint = |data: &[u8]| {
+            let mut fp = Fingerprint::new();
+            fp.update("styles.csv", data);
+            fp.finish()
+        };
+        let old = fingerprint(b"a,b\n1,2\n");
+        assert_ne!(old, fingerprint(b"a,b\n1,3\n"));
+
+        cache.store(&old, &vec!["old".to_string()]).unwrap();
+        assert_eq!(cache.load::<Vec<String>>(&old), Some(vec!["old".to_string()]));
+        fs::File::options()
+            .write(true)
+            .open(cache.entry_path(&old))
+            .unwrap()
+            .set_modified(UNIX_EPOCH + std::time::Duration::from_secs(1))
+            .unwrap();
+
+        // 超出保留数量时清理最旧的快照
+        for i in 0..MAX_SNAPSHOTS {
+            let fp = fingerprint(i.to_string().as_bytes());
+            cache.store(&fp, &vec![i.to_string()]).unwrap();
+        }
+        assert_eq!(cache.load::<Vec<String>>(&old), None);
+        assert_eq!(cache.load::<Vec<String>>(&fingerprint(b"0")), Some(vec!["0".to_string()]));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
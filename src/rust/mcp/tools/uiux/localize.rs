@@ -1,6 +1,7 @@
 // UI/UX Pro Max 文案本地化
 // 仅提供 zh/en 简洁文案，避免过度设计
 
+use super::audit::AuditReport;
 use super::engine::{SearchResult, SuggestResult};
 use super::types::{UiuxLang, UiuxMode};
 
@@ -56,6 +57,18 @@ pub fn design_system_summary(lang: UiuxLang, project_name: &str, persisted: bool
     }
 }
 
+pub fn audit_summary(lang: UiuxLang, report: &AuditReport) -> String {
+    let zh = format!(
+        "设计系统审计完成：{}，得分 {}/100，扫描 {} 个文件，发现 {} 处问题。",
+        report.project_name, report.score, report.files_scanned, report.total_findings
+    );
+    let en = format!(
+        "Design system audit: {}. Score {}/100, {} files scanned, {} findings.",
+        report.project_name, report.score, report.files_scanned, report.total_findings
+    );
+    localize_text(lang, &zh, &en)
+}
+
 pub fn beautify_summary(lang: UiuxLang) -> String {
     localize_text(lang, "已生成 UI 美化建议。", "UI beautify suggestions generated.")
 }
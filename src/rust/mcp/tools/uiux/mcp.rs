@@ -10,15 +10,19 @@ use serde::Serialize;
 use crate::config::load_standalone_config;
 use crate::mcp::types::SkillRunRequest;
 
+use super::audit;
 use super::engine;
+use super::export::{ExportFile, ExportTarget};
 use super::localize;
 use super::response::{UiuxError, UiuxResponse};
 use super::types::{
-    UiuxDesignSystemRequest, UiuxLang, UiuxMode, UiuxOutputFormat, UiuxSearchRequest,
+    UiuxAuditRequest, UiuxDesignSystemRequest, UiuxLang, UiuxMode, UiuxOutputFormat, UiuxSearchRequest,
     UiuxStackRequest, UiuxSuggestRequest,
 };
 
 const DEFAULT_MAX_RESULTS: u32 = 3;
+const DEFAULT_AUDIT_FINDINGS: usize = 50;
+const MAX_AUDIT_FINDINGS: usize = 500;
 
 #[derive(Clone, Copy)]
 struct UiuxDefaults {
@@ -124,12 +128,22 @@ struct UiuxDesignSystemData {
     design_system: Option<engine::DesignSystem>,
     #[serde(skip_serializing_if = "Option::is_none")]
     persisted: Option<engine::PersistSummary>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    exports: Vec<ExportFile>,
     #[serde(skip_serializing_if = "Option::is_none")]
     beautify: Option<engine::BeautifyResult>,
     #[serde(skip_serializing_if = "Option::is_none")]
     legacy_text: Option<String>,
 }
 
+#[derive(Serialize)]
+struct UiuxAuditData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    report: Option<audit::AuditReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    legacy_text: Option<String>,
+}
+
 #[derive(Serialize)]
 struct UiuxSuggestData {
     result: engine::SuggestResult,
@@ -149,7 +163,7 @@ impl UiuxTool {
             "type": "object",
             "properties": {
                 "query": { "type": "string", "description": "搜索查询" },
-                "domain": { "type": "string", "description": "领域（可选）" },
+                "domain": { "type": "string", "description": "领域（可选，含知识包中的自定义领域）" },
                 "max_results": { "type": "number", "description": "最大结果数（可选）" },
                 "output_format": { "type": "string", "enum": ["json", "text"], "description": "输出格式（json/text）" },
                 "lang": { "type": "string", "enum": ["zh", "en"], "description": "输出语言（zh/en）" },
@@ -207,7 +221,12 @@ impl UiuxTool {
                 "output_dir": { "type": "string", "description": "输出目录（可选）" },
                 "output_format": { "type": "string", "enum": ["json", "text"], "description": "输出格式（json/text）" },
                 "lang": { "type": "string", "enum": ["zh", "en"], "description": "输出语言（zh/en）" },
-                "mode": { "type": "string", "enum": ["design_system", "beautify"], "description": "模式（design_system/beautify）" }
+                "mode": { "type": "string", "enum": ["design_system", "beautify"], "description": "模式（design_system/beautify）" },
+                "exports": {
+                    "type": "array",
+                    "items": { "type": "string", "enum": ["tailwind", "css", "tokens", "figma"] },
+                    "description": "额外导出：tailwind(tailwind.config.js) / css(CSS 变量) / tokens(W3C 设计令牌) / figma(Figma Tokens)；persist 为 true 时写入设计系统目录"
+                }
             },
             "required": ["query"]
         });
@@ -225,6 +244,34 @@ impl UiuxTool {
             });
         }
 
+        let audit_schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "project_root": { "type": "string", "description": "待扫描的项目根目录（可选，默认当前工作目录）" },
+                "project_name": { "type": "string", "description": "设计系统项目名（存在多个 design-system 时必填）" },
+                "query": { "type": "string", "description": "未找到已持久化的设计系统时，按此查询即时生成（可选）" },
+                "output_dir": { "type": "string", "description": "design-system 目录所在位置（可选，默认 project_root）" },
+                "max_findings": { "type": "integer", "description": "返回的最大问题数（默认 50）" },
+                "output_format": { "type": "string", "enum": ["json", "text"], "description": "输出格式（json/text）" },
+                "lang": { "type": "string", "enum": ["zh", "en"], "description": "输出语言（zh/en）" }
+            }
+        });
+
+        if let serde_json::Value::Object(schema_map) = audit_schema {
+            tools.push(Tool {
+                name: Cow::Borrowed("uiux_audit"),
+                description: Some(Cow::Borrowed(
+                    "按已生成的设计系统审计项目 CSS/SCSS/Tailwind/Vue/React 源码：色板外颜色、未声明字体、非 4px 栅格间距与反模式，返回文件/行号与得分",
+                )),
+                input_schema: Arc::new(schema_map),
+                annotations: None,
+                icons: None,
+                meta: None,
+                output_schema: None,
+                title: Some("UI/UX 设计系统审计".to_string()),
+            });
+        }
+
         let suggest_schema = serde_json::json!({
             "type": "object",
             "properties": {
@@ -274,6 +321,11 @@ impl UiuxTool {
                     .map_err(|e| McpError::invalid_params(format!("参数解析失败: {}", e), None))?;
                 handle_suggest(req, defaults)
             }
+            "uiux_audit" => {
+                let req: UiuxAuditRequest = serde_json::from_value(arguments)
+                    .map_err(|e| McpError::invalid_params(format!("参数解析失败: {}", e), None))?;
+                handle_audit(req, defaults)
+            }
             _ => Err(McpError::invalid_params(format!("未知的工具: {}", tool_name), None)),
         }
     }
@@ -287,9 +339,15 @@ impl UiuxTool {
                     .query
                     .clone()
                     .ok_or_else(|| McpError::invalid_params("缺少 query 参数".to_string(), None))?;
+                let exports = request
+                    .params
+                    .get("exports")
+                    .and_then(|v| v.as_array())
+                    .map(|items| items.iter().filter_map(|v| v.as_str()).filter_map(ExportTarget::parse).collect())
+                    .unwrap_or_default();
                 let req = UiuxDesignSystemRequest {
                     query,
-                    project_name: None,
+                    project_name: skill_param_str(request, "project_name"),
                     format: None,
                     persist: Some(false),
                     page: None,
@@ -297,6 +355,7 @@ impl UiuxTool {
                     output_format: Some(UiuxOutputFormat::Text),
                     lang: None,
                     mode: Some(UiuxMode::DesignSystem),
+                    exports,
                 };
                 handle_design_system(req, defaults)
             }
@@ -307,8 +366,12 @@ impl UiuxTool {
                     .ok_or_else(|| McpError::invalid_params("缺少 query 参数".to_string(), None))?;
                 let req = UiuxSearchRequest {
                     query,
-                    domain: None,
-                    max_results: None,
+                    domain: skill_param_str(request, "domain"),
+                    max_results: request
+                        .params
+                        .get("max_results")
+                        .and_then(|v| v.as_u64())
+                        .map(|v| v as u32),
                     output_format: Some(UiuxOutputFormat::Text),
                     lang: None,
                     mode: Some(UiuxMode::Search),
@@ -338,6 +401,7 @@ impl UiuxTool {
                         output_format: Some(output_format),
                         lang: None,
                         mode: Some(UiuxMode::DesignSystem),
+                        exports: options.exports,
                     };
                     return handle_design_system(req, defaults);
                 }
@@ -466,6 +530,7 @@ fn handle_design_system(
                 mode,
                 design_system: None,
                 persisted: None,
+                exports: Vec::new(),
                 beautify: None,
                 legacy_text: None,
             };
@@ -485,6 +550,7 @@ fn handle_design_system(
             mode,
             design_system: None,
             persisted: None,
+            exports: Vec::new(),
             beautify: Some(beautify),
             legacy_text: None,
         };
@@ -500,6 +566,7 @@ fn handle_design_system(
         req.persist.unwrap_or(false),
         req.page.as_deref(),
         output_dir.as_deref(),
+        &req.exports,
     )
     .map_err(|e| McpError::internal_error(e, None))?;
 
@@ -518,12 +585,64 @@ fn handle_design_system(
         mode,
         design_system: Some(output.design_system),
         persisted: output.persisted,
+        exports: output.exports,
         beautify: None,
         legacy_text,
     };
     build_response("uiux_design_system", lang, data, text, vec![])
 }
 
+fn handle_audit(req: UiuxAuditRequest, defaults: UiuxDefaults) -> Result<CallToolResult, McpError> {
+    let lang = resolve_lang(req.lang, defaults);
+    let output_format = resolve_output_format(req.output_format, defaults);
+    let project_root = req
+        .project_root
+        .as_ref()
+        .map(PathBuf::from)
+        .or_else(|| std::env::current_dir().ok())
+        .ok_or_else(|| McpError::invalid_params("无法确定项目根目录".to_string(), None))?;
+    let base_dir = req.output_dir.as_ref().map(PathBuf::from).unwrap_or_else(|| project_root.clone());
+
+    // 优先使用已持久化的设计系统，缺失时按 query 即时生成
+    let loaded = audit::load_design_system(&base_dir, req.project_name.as_deref());
+    let (design_system, source) = match (loaded, req.query.as_deref().filter(|q| !q.trim().is_empty())) {
+        (Ok((design_system, path)), _) => {
+            let source = path.strip_prefix(&base_dir).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+            (design_system, source)
+        }
+        (Err(_), Some(query)) => {
+            let output = engine::generate_design_system(query, req.project_name.as_deref(), None, false, None, None, &[])
+                .map_err(|e| McpError::internal_error(e, None))?;
+            (output.design_system, format!("query: {}", query))
+        }
+        (Err(err), None) => {
+            let data = UiuxAuditData {
+                report: None,
+                legacy_text: None,
+            };
+            let text = localize::error_text(lang, &err);
+            return build_response("uiux_audit", lang, data, text, vec![UiuxError::new("design_system_not_found", &err)]);
+        }
+    };
+
+    let max_findings = req
+        .max_findings
+        .unwrap_or(DEFAULT_AUDIT_FINDINGS)
+        .clamp(1, MAX_AUDIT_FINDINGS);
+    let report = audit::audit_project(&design_system, source, &project_root, max_findings);
+    let legacy_text = if matches!(output_format, UiuxOutputFormat::Text) {
+        Some(audit::format_report(&report))
+    } else {
+        None
+    };
+    let text = localize::audit_summary(lang, &report);
+    let data = UiuxAuditData {
+        report: Some(report),
+        legacy_text,
+    };
+    build_response("uiux_audit", lang, data, text, vec![])
+}
+
 fn handle_suggest(req: UiuxSuggestRequest, defaults: UiuxDefaults) -> Result<CallToolResult, McpError> {
     let lang = resolve_lang(req.lang, defaults);
     let output_format = resolve_output_format(req.output_format, defaults);
@@ -542,6 +661,16 @@ fn handle_suggest(req: UiuxSuggestRequest, defaults: UiuxDefaults) -> Result<Cal
     build_response("uiux_suggest", lang, data, text, vec![])
 }
 
+/// 技能动作 input_schema 声明的字符串参数
+fn skill_param_str(request: &SkillRunRequest, name: &str) -> Option<String> {
+    request
+        .params
+        .get(name)
+        .and_then(|v| v.as_str())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
 #[derive(Default)]
 struct CliOptions {
     query: Option<String>,
@@ -555,6 +684,7 @@ struct CliOptions {
     page: Option<String>,
     project_name: Option<String>,
     output_dir: Option<String>,
+    exports: Vec<ExportTarget>,
 }
 
 fn parse_cli_args(args: Vec<String>) -> CliOptions {
@@ -606,6 +736,12 @@ fn parse_cli_args(args: Vec<String>) -> CliOptions {
                     opts.output_dir = Some(value);
                 }
             }
+            "--export" | "-e" => {
+                // 支持逗号分隔：--export tailwind,css
+                if let Some(value) = iter.next() {
+                    opts.exports.extend(value.split(',').filter_map(ExportTarget::parse));
+                }
+            }
             _ => {
                 if opts.query.is_none() {
                     opts.query = Some(token);
@@ -1,11 +1,34 @@
 // UI/UX Pro Max MCP 工具模块
 // 提供 Rust 原生的 UI/UX 设计检索与设计系统生成
 
+pub mod audit;
 pub mod engine;
+pub mod export;
+pub mod index_cache;
 pub mod localize;
 pub mod mcp;
+pub mod packs;
 pub mod response;
 pub mod sanitize;
+pub mod tokenizer;
 pub mod types;
 
 pub use mcp::UiuxTool;
+
+use std::sync::Arc;
+
+use crate::mcp::tools::skills::builtin::{BuiltinFuture, BuiltinRegistry, BuiltinSkill};
+use crate::mcp::types::SkillRunRequest;
+
+/// ui-ux-pro-max 技能的 Rust 原生实现
+struct UiuxSkill;
+
+impl BuiltinSkill for UiuxSkill {
+    fn call<'a>(&'a self, action: &'a str, request: &'a SkillRunRequest) -> BuiltinFuture<'a> {
+        Box::pin(UiuxTool::call_from_skill(action, request))
+    }
+}
+
+pub fn register_skills(registry: &mut BuiltinRegistry) {
+    registry.register("ui-ux-pro-max", Arc::new(UiuxSkill));
+}
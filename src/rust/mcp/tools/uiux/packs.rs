@@ -0,0 +1,249 @@
+// UI/UX 自定义知识包
+// 从用户目录与项目目录加载知识包（清单 + CSV/JSON 数据），在运行时并入内嵌数据的检索索引
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::log_debug;
+
+/// 知识包清单文件名
+pub const PACK_MANIFEST: &str = "uiux-pack.json";
+
+/// 知识包根目录名（位于 `~/.sanshu/` 与 `<项目>/.sanshu/` 下）
+const PACKS_DIR: &str = "uiux-packs";
+
+/// 知识包清单
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackManifest {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// 领域数据：同名领域与内嵌数据合并，新名称作为新领域
+    #[serde(default)]
+    pub domains: Vec<PackDomain>,
+    /// 技术栈指南：同名栈与内嵌数据合并
+    #[serde(default)]
+    pub stacks: Vec<PackDomain>,
+}
+
+/// 清单中的单个数据集
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackDomain {
+    pub name: String,
+    /// 数据文件（相对知识包目录，.csv 或 .json）
+    pub file: String,
+    /// 参与检索的列（合并到已有领域时可省略，沿用内置配置）
+    #[serde(default)]
+    pub search_cols: Vec<String>,
+    /// 输出的列（省略时沿用内置配置，新领域则输出全部列）
+    #[serde(default)]
+    pub output_cols: Vec<String>,
+    /// 自动识别领域的关键词
+    #[serde(default)]
+    pub keywords: Vec<String>,
+}
+
+/// 已加载的数据集
+#[derive(Debug, Clone)]
+pub struct PackData {
+    pub domain: PackDomain,
+    /// 来源标识（知识包名/文件名）
+    pub source: String,
+    pub rows: Vec<HashMap<String, String>>,
+}
+
+/// 已加载的知识包
+#[derive(Debug, Clone)]
+pub struct KnowledgePack {
+    pub manifest: PackManifest,
+    pub dir: PathBuf,
+    pub domains: Vec<PackData>,
+    pub stacks: Vec<PackData>,
+}
+
+/// 知识包搜索目录：用户级在前，项目级在后（同名领域按加载顺序依次合并）
+pub fn pack_roots(project_root: Option<&Path>) -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if let Some(home) = dirs::home_dir() {
+        roots.push(home.join(".sanshu").join(PACKS_DIR));
+    }
+    if let Some(root) = project_root {
+        roots.push(root.join(".sanshu").join(PACKS_DIR));
+    }
+    roots
+}
+
+/// 加载各目录下的知识包（每个子目录一个包），无效的包记录日志后跳过
+pub fn load_packs(roots: &[PathBuf]) -> Vec<KnowledgePack> {
+    let mut packs = Vec::new();
+    for root in roots {
+        let Ok(entries) = fs::read_dir(root) else {
+            continue;
+        };
+        let mut dirs: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.join(PACK_MANIFEST).is_file())
+            .collect();
+        dirs.sort();
+
+        for dir in dirs {
+            match load_pack(&dir) {
+                Ok(pack) => {
+                    log_debug!("UIUX 知识包已加载: {} ({})", pack.manifest.name, dir.display());
+                    packs.push(pack);
+                }
+                Err(err) => log_debug!("UIUX 知识包加载失败: {} -> {}", dir.display(), err),
+            }
+        }
+    }
+    packs
+}
+
+/// 列出各知识包目录下的全部文件（含子目录），用于计算索引指纹
+pub fn pack_files(roots: &[PathBuf]) -> Vec<PathBuf> {
+    fn collect(dir: &Path, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.is_dir() {
+                collect(&path, files);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    for root in roots {
+        let Ok(entries) = fs::read_dir(root) else {
+            continue;
+        };
+        for dir in entries.flatten().map(|entry| entry.path()) {
+            if dir.join(PACK_MANIFEST).is_file() {
+                collect(&dir, &mut files);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// 加载单个知识包目录
+pub fn load_pack(dir: &Path) -> Result<KnowledgePack, String> {
+    let manifest_path = dir.join(PACK_MANIFEST);
+    let content = fs::read_to_string(&manifest_path)
+        .map_err(|e| format!("读取清单失败: {}", e))?;
+    let manifest: PackManifest = serde_json::from_str(&content)
+        .map_err(|e| format!("解析清单失败: {}", e))?;
+
+    let load_all = |items: &[PackDomain]| -> Result<Vec<PackData>, String> {
+        items
+            .iter()
+            .map(|domain| {
+                let path = dir.join(&domain.file);
+                let rows = load_rows(&path)
+                    .map_err(|e| format!("{}: {}", domain.file, e))?;
+                Ok(PackData {
+                    domain: domain.clone(),
+                    source: format!("{}/{}", manifest.name, domain.file),
+                    rows,
+                })
+            })
+            .collect()
+    };
+    let domains = load_all(&manifest.domains)?;
+    let stacks = load_all(&manifest.stacks)?;
+
+    Ok(KnowledgePack {
+        manifest,
+        dir: dir.to_path_buf(),
+        domains,
+        stacks,
+    })
+}
+
+/// 按扩展名读取 CSV 或 JSON（对象数组）数据
+fn load_rows(path: &Path) -> Result<Vec<HashMap<String, String>>, String> {
+    let bytes = fs::read(path).map_err(|e| format!("读取失败: {}", e))?;
+    match path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref() {
+        Some("csv") => super::engine::parse_csv(&bytes),
+        Some("json") => parse_json_rows(&bytes),
+        _ => Err("仅支持 .csv 与 .json 数据文件".to_string()),
+    }
+}
+
+fn parse_json_rows(bytes: &[u8]) -> Result<Vec<HashMap<String, String>>, String> {
+    let value: Value = serde_json::from_slice(bytes).map_err(|e| format!("解析 JSON 失败: {}", e))?;
+    let Value::Array(items) = value else {
+        return Err("JSON 数据必须是对象数组".to_string());
+    };
+
+    items
+        .into_iter()
+        .map(|item| match item {
+            Value::Object(map) => Ok(map
+                .into_iter()
+                .map(|(key, value)| (key, value_to_text(value)))
+                .collect()),
+            _ => Err("JSON 数据必须是对象数组".to_string()),
+        })
+        .collect()
+}
+
+/// 单元格统一转为文本：数组以逗号连接
+fn value_to_text(value: Value) -> String {
+    match value {
+        Value::String(s) => s,
+        Value::Null => String::new(),
+        Value::Array(items) => items
+            .into_iter()
+            .map(value_to_text)
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(", "),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_pack_with_csv_and_json() {
+        let root = std::env::temp_dir().join(format!("sanshu-uiux-packs-{}", std::process::id()));
+        let dir = root.join("acme");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(PACK_MANIFEST),
+            r#"{
+                "name": "acme",
+                "domains": [
+                    { "name": "color", "file": "colors.csv" },
+                    { "name": "brand", "file": "brand.json", "search_cols": ["Name", "Tags"], "keywords": ["acme"] }
+                ]
+            }"#,
+        )
+        .unwrap();
+        fs::write(dir.join("colors.csv"), "Product Type,Keywords,Primary (Hex)\nAcme Console,acme brand,#FF5500\n").unwrap();
+        fs::write(dir.join("brand.json"), r#"[{"Name": "Acme Orange", "Tags": ["brand", "primary"], "Weight": 600}]"#).unwrap();
+        // 缺少清单的目录被忽略
+        fs::create_dir_all(root.join("not-a-pack")).unwrap();
+
+        let packs = load_packs(std::slice::from_ref(&root));
+        assert_eq!(packs.len(), 1);
+        let pack = &packs[0];
+        assert_eq!(pack.domains[0].rows[0].get("Primary (Hex)").map(String::as_str), Some("#FF5500"));
+        assert_eq!(pack.domains[1].source, "acme/brand.json");
+        assert_eq!(pack.domains[1].rows[0].get("Tags").map(String::as_str), Some("brand, primary"));
+        assert_eq!(pack.domains[1].rows[0].get("Weight").map(String::as_str), Some("600"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,350 @@
+// UI/UX 检索分词
+// 英文轻量词干化、中文（CJK）二元切分、中英关键词互译与拼写容错
+
+use std::collections::HashSet;
+
+/// 英文词最短长度（字节），与旧版分词保持一致
+const MIN_WORD_LEN: usize = 3;
+
+/// 参与模糊匹配的最短词长
+const MIN_FUZZY_LEN: usize = 5;
+
+/// 中英关键词对照：中文查询扩展出英文词，英文查询扩展出中文词（用于中文知识包）
+const SYNONYMS: &[(&str, &[&str])] = &[
+    ("颜色", &["color"]),
+    ("配色", &["color", "palette"]),
+    ("色彩", &["color"]),
+    ("色板", &["palette"]),
+    ("深色", &["dark"]),
+    ("暗色", &["dark"]),
+    ("暗黑", &["dark"]),
+    ("浅色", &["light"]),
+    ("模式", &["mode"]),
+    ("字体", &["font", "typography"]),
+    ("排版", &["typography"]),
+    ("标题", &["heading"]),
+    ("衬线", &["serif"]),
+    ("图表", &["chart"]),
+    ("折线图", &["line", "chart"]),
+    ("柱状图", &["bar", "chart"]),
+    ("饼图", &["pie", "chart"]),
+    ("热力图", &["heatmap"]),
+    ("漏斗", &["funnel"]),
+    ("可视化", &["visualization"]),
+    ("趋势", &["trend"]),
+    ("仪表盘", &["dashboard"]),
+    ("看板", &["dashboard"]),
+    ("控制台", &["dashboard", "admin"]),
+    ("后台", &["admin", "dashboard"]),
+    ("落地页", &["landing", "page"]),
+    ("着陆页", &["landing", "page"]),
+    ("首屏", &["hero"]),
+    ("页面", &["page"]),
+    ("转化", &["conversion"]),
+    ("定价", &["pricing"]),
+    ("价格", &["pricing"]),
+    ("评价", &["testimonial"]),
+    ("按钮", &["button"]),
+    ("表单", &["form"]),
+    ("输入框", &["input"]),
+    ("导航", &["navigation"]),
+    ("菜单", &["menu", "navigation"]),
+    ("卡片", &["card"]),
+    ("表格", &["table"]),
+    ("弹窗", &["modal"]),
+    ("图标", &["icon"]),
+    ("动画", &["animation"]),
+    ("动效", &["animation", "motion"]),
+    ("滚动", &["scroll"]),
+    ("键盘", &["keyboard"]),
+    ("触摸", &["touch"]),
+    ("无障碍", &["accessibility", "wcag"]),
+    ("可访问性", &["accessibility"]),
+    ("移动端", &["mobile"]),
+    ("手机", &["mobile"]),
+    ("响应式", &["responsive"]),
+    ("极简", &["minimalism", "minimal"]),
+    ("简约", &["minimalism", "minimal"]),
+    ("毛玻璃", &["glassmorphism", "glass"]),
+    ("玻璃", &["glassmorphism", "glass"]),
+    ("新拟态", &["neumorphism"]),
+    ("粗野", &["brutalism"]),
+    ("扁平", &["flat"]),
+    ("极光", &["aurora"]),
+    ("渐变", &["gradient"]),
+    ("阴影", &["shadow"]),
+    ("圆角", &["rounded"]),
+    ("风格", &["style"]),
+    ("样式", &["style"]),
+    ("设计", &["design"]),
+    ("界面", &["interface"]),
+    ("组件", &["component"]),
+    ("提示词", &["prompt"]),
+    ("变量", &["variable"]),
+    ("清单", &["checklist"]),
+    ("电商", &["ecommerce"]),
+    ("商城", &["ecommerce", "shop"]),
+    ("金融", &["fintech", "finance"]),
+    ("银行", &["banking", "finance"]),
+    ("医疗", &["healthcare", "medical"]),
+    ("健康", &["health", "wellness"]),
+    ("游戏", &["gaming", "game"]),
+    ("作品集", &["portfolio"]),
+    ("加密", &["crypto"]),
+    ("教育", &["education"]),
+    ("博客", &["blog"]),
+    ("社交", &["social"]),
+    ("新闻", &["news"]),
+    ("科技", &["tech", "technology"]),
+    ("专业", &["professional"]),
+    ("企业", &["enterprise", "corporate"]),
+    ("活泼", &["playful"]),
+    ("俏皮", &["playful"]),
+    ("优雅", &["elegant"]),
+    ("奢华", &["luxury"]),
+    ("现代", &["modern"]),
+    ("复古", &["retro", "vintage"]),
+    ("儿童", &["kids", "children"]),
+    ("餐厅", &["restaurant"]),
+    ("餐饮", &["restaurant", "food"]),
+    ("旅游", &["travel"]),
+    ("酒店", &["hotel"]),
+    ("房地产", &["real", "estate"]),
+    ("健身", &["fitness"]),
+    ("美容", &["beauty"]),
+    ("性能", &["performance"]),
+    ("渲染", &["rendering"]),
+];
+
+/// 文档与关键词分词：英文词干化，CJK 连续字符切为二元组
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for segment in segments(&text.to_lowercase()) {
+        match segment {
+            Segment::Word(word) => {
+                if word.len() >= MIN_WORD_LEN {
+                    tokens.push(stem(&word));
+                }
+            }
+            Segment::Cjk(chars) => push_cjk_ngrams(&chars, &mut tokens),
+        }
+    }
+    tokens
+}
+
+/// 查询分词：在 `tokenize` 基础上追加中英对照扩展词
+pub fn tokenize_query(text: &str) -> Vec<String> {
+    let lower = text.to_lowercase();
+    let mut tokens = tokenize(&lower);
+    let mut seen: HashSet<String> = tokens.iter().cloned().collect();
+    let mut expanded = Vec::new();
+
+    for (zh, en) in SYNONYMS {
+        if lower.contains(zh) {
+            expanded.extend(en.iter().map(|word| stem(word)));
+        } else if en.iter().any(|word| tokens.contains(&stem(word))) {
+            let chars: Vec<char> = zh.chars().collect();
+            push_cjk_ngrams(&chars, &mut expanded);
+        }
+    }
+
+    for token in expanded {
+        if seen.insert(token.clone()) {
+            tokens.push(token);
+        }
+    }
+    tokens
+}
+
+/// 在词表中查找拼写最接近的词（仅 ASCII 且足够长的词），距离相同取字典序最小者
+pub fn fuzzy_match<'a, I>(token: &str, vocabulary: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a String>,
+{
+    let max_distance = max_edit_distance(token)?;
+    let mut best: Option<(usize, &'a str)> = None;
+    for candidate in vocabulary {
+        if candidate.len() < MIN_FUZZY_LEN || candidate.len().abs_diff(token.len()) > max_distance {
+            continue;
+        }
+        let Some(distance) = bounded_levenshtein(token, candidate, max_distance) else {
+            continue;
+        };
+        let better = match best {
+            None => true,
+            Some((best_distance, best_term)) => {
+                distance < best_distance || (distance == best_distance && candidate.as_str() < best_term)
+            }
+        };
+        if better {
+            best = Some((distance, candidate.as_str()));
+        }
+    }
+    best.map(|(_, term)| term)
+}
+
+/// 两个词是否拼写相近
+pub fn is_fuzzy_match(a: &str, b: &str) -> bool {
+    let Some(max_distance) = max_edit_distance(a) else {
+        return false;
+    };
+    b.len() >= MIN_FUZZY_LEN && bounded_levenshtein(a, b, max_distance).is_some()
+}
+
+enum Segment {
+    Word(String),
+    Cjk(Vec<char>),
+}
+
+/// 按字符类别切分：字母数字组成英文词，CJK 字符组成连续片段，其余为分隔符
+fn segments(text: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut word = String::new();
+    let mut cjk = Vec::new();
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            if !word.is_empty() {
+                segments.push(Segment::Word(std::mem::take(&mut word)));
+            }
+            cjk.push(c);
+        } else {
+            if !cjk.is_empty() {
+                segments.push(Segment::Cjk(std::mem::take(&mut cjk)));
+            }
+            if c.is_alphanumeric() || c == '_' {
+                word.push(c);
+            } else if !word.is_empty() {
+                segments.push(Segment::Word(std::mem::take(&mut word)));
+            }
+        }
+    }
+    if !word.is_empty() {
+        segments.push(Segment::Word(word));
+    }
+    if !cjk.is_empty() {
+        segments.push(Segment::Cjk(cjk));
+    }
+    segments
+}
+
+/// 单字保留原样，多字切为相邻二元组
+fn push_cjk_ngrams(chars: &[char], tokens: &mut Vec<String>) {
+    if chars.len() == 1 {
+        tokens.push(chars[0].to_string());
+        return;
+    }
+    for pair in chars.windows(2) {
+        tokens.push(pair.iter().collect());
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x3040..=0x30FF     // 日文假名
+            | 0x3400..=0x4DBF // CJK 扩展 A
+            | 0x4E00..=0x9FFF // CJK 统一汉字
+            | 0xAC00..=0xD7AF // 韩文音节
+            | 0xF900..=0xFAFF // CJK 兼容汉字
+    )
+}
+
+/// 轻量英文词干化：仅处理复数与 -ing，保证结果仍是可读的词
+fn stem(word: &str) -> String {
+    if !word.is_ascii() {
+        return word.to_string();
+    }
+    if let Some(base) = word.strip_suffix("ies") {
+        if base.len() >= 2 {
+            return format!("{}y", base);
+        }
+    }
+    if word.ends_with("sses") || word.ends_with("xes") || word.ends_with("ches") || word.ends_with("shes") {
+        return word[..word.len() - 2].to_string();
+    }
+    if word.len() > 3
+        && word.ends_with('s')
+        && !["ss", "us", "is", "os"].iter().any(|suffix| word.ends_with(suffix))
+    {
+        return word[..word.len() - 1].to_string();
+    }
+    if let Some(base) = word.strip_suffix("ing") {
+        if base.len() >= 5 {
+            // running -> run 这类双写辅音还原
+            let bytes = base.as_bytes();
+            let doubled = bytes[bytes.len() - 1] == bytes[bytes.len() - 2]
+                && !matches!(bytes[bytes.len() - 1], b'l' | b's' | b'z');
+            return if doubled { base[..base.len() - 1].to_string() } else { base.to_string() };
+        }
+    }
+    word.to_string()
+}
+
+/// 允许的编辑距离：5~7 个字符容错 1 处，更长容错 2 处
+fn max_edit_distance(token: &str) -> Option<usize> {
+    if !token.is_ascii() || token.len() < MIN_FUZZY_LEN {
+        return None;
+    }
+    Some(if token.len() <= 7 { 1 } else { 2 })
+}
+
+/// 编辑距离不超过上限时返回距离
+fn bounded_levenshtein(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a = a.as_bytes();
+    let b = b.as_bytes();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        let mut row_min = current[0];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            current[j + 1] = (previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1);
+            row_min = row_min.min(current[j + 1]);
+        }
+        if row_min > max {
+            return None;
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    let distance = previous[b.len()];
+    (distance <= max).then_some(distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_mixed_text() {
+        assert_eq!(tokenize("Glassmorphism cards, UI"), vec!["glassmorphism", "card"]);
+        assert_eq!(tokenize("深色模式 dashboards"), vec!["深色", "色模", "模式", "dashboard"]);
+        assert_eq!(tokenize("rendering charts"), vec!["render", "chart"]);
+        assert_eq!(tokenize("landing pages"), vec!["landing", "page"]);
+
+        let query = tokenize_query("深色仪表盘配色");
+        for expected in ["dark", "dashboard", "color", "palette", "仪表"] {
+            assert!(query.contains(&expected.to_string()), "missing {}", expected);
+        }
+        // 英文查询扩展出中文二元组，便于命中中文知识包
+        assert!(tokenize_query("pricing table").contains(&"定价".to_string()));
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        let vocabulary: Vec<String> = ["glassmorphism", "neumorphism", "dashboard", "card"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(fuzzy_match("glasmorphism", &vocabulary), Some("glassmorphism"));
+        assert_eq!(fuzzy_match("dashbord", &vocabulary), Some("dashboard"));
+        assert_eq!(fuzzy_match("cart", &vocabulary), None);
+        assert!(is_fuzzy_match("minimalsim", "minimalism"));
+        assert!(!is_fuzzy_match("brutal", "portal"));
+    }
+}
@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::export::ExportTarget;
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum UiuxOutputFormat {
@@ -79,6 +81,31 @@ pub struct UiuxDesignSystemRequest {
     pub lang: Option<UiuxLang>,
     #[serde(default)]
     pub mode: Option<UiuxMode>,
+    /// 额外导出的文件（tailwind/css/tokens/figma）
+    #[serde(default)]
+    pub exports: Vec<ExportTarget>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UiuxAuditRequest {
+    /// 待扫描的项目根目录（默认 MCP 进程工作目录）
+    #[serde(default)]
+    pub project_root: Option<String>,
+    /// 设计系统项目名（存在多个设计系统时必填）
+    #[serde(default)]
+    pub project_name: Option<String>,
+    /// 未找到已持久化的设计系统时，按该查询即时生成
+    #[serde(default)]
+    pub query: Option<String>,
+    /// design-system 目录所在位置（默认 project_root）
+    #[serde(default)]
+    pub output_dir: Option<String>,
+    #[serde(default)]
+    pub max_findings: Option<usize>,
+    #[serde(default)]
+    pub output_format: Option<UiuxOutputFormat>,
+    #[serde(default)]
+    pub lang: Option<UiuxLang>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -1,6 +1,10 @@
 use chrono;
 use serde::{Deserialize, Serialize};
 
+use crate::mcp::tools::icon::optimize::SvgOptimizeOptions;
+use crate::mcp::tools::icon::types::IconItem;
+use crate::mcp::tools::interaction::ZhiField;
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct ZhiRequest {
     #[schemars(description = "要显示给用户的消息")]
@@ -23,6 +27,9 @@ pub struct ZhiRequest {
     #[schemars(description = "UI/UX 上下文追加原因（可选）")]
     #[serde(default)]
     pub uiux_reason: Option<String>,
+    #[schemars(description = "结构化表单字段（可选），结果以 JSON 返回在 structured_content 中")]
+    #[serde(default)]
+    pub fields: Vec<ZhiField>,
 }
 
 fn default_is_markdown() -> bool {
@@ -85,6 +92,13 @@ pub struct SkillRunRequest {
     #[schemars(description = "追加参数（可选）")]
     #[serde(default)]
     pub args: Option<Vec<String>>,
+    #[schemars(description = "项目根目录绝对路径（可选，默认使用客户端 roots）")]
+    #[serde(default)]
+    pub project_root_path: Option<String>,
+    /// 动作 input_schema 声明的其他命名参数
+    #[schemars(skip)]
+    #[serde(flatten)]
+    pub params: serde_json::Map<String, serde_json::Value>,
 }
 
 fn default_category() -> String {
@@ -119,6 +133,53 @@ pub struct TuRequest {
     pub project_root: Option<String>,
 }
 
+/// 图标搜索请求（"icon_search" 工具，无界面）
+#[derive(Debug, Deserialize)]
+pub struct IconSearchToolRequest {
+    /// 搜索关键词
+    pub query: String,
+    /// 图标风格：line/fill/flat/all
+    #[serde(default)]
+    pub style: Option<String>,
+    /// 填充类型：single/multi/all
+    #[serde(default)]
+    pub fills: Option<String>,
+    /// 页码，默认 1
+    #[serde(default)]
+    pub page: Option<u32>,
+    /// 每页数量，默认 20
+    #[serde(default)]
+    pub page_size: Option<u32>,
+    /// 图标来源：iconfont/local（未指定时使用配置）
+    #[serde(default)]
+    pub provider: Option<String>,
+}
+
+/// 图标保存请求（"icon_save" 工具，无界面）
+#[derive(Debug, Deserialize)]
+pub struct IconSaveToolRequest {
+    /// 要保存的图标（icon_search 返回的条目，至少包含 id 与 name）
+    pub icons: Vec<IconItem>,
+    /// 保存目录（相对路径基于项目根目录，未指定时使用配置）
+    #[serde(default)]
+    pub save_path: Option<String>,
+    /// 项目根目录路径
+    #[serde(default)]
+    pub project_root: Option<String>,
+    /// 保存格式（未指定时使用配置）
+    #[serde(default)]
+    pub format: Option<String>,
+    /// PNG 尺寸
+    #[serde(default)]
+    pub png_size: Option<u32>,
+    /// 是否覆盖已存在的文件（未指定时询问用户）
+    #[serde(default)]
+    pub overwrite: Option<bool>,
+    /// SVG 优化选项
+    #[serde(default)]
+    pub optimize: Option<SvgOptimizeOptions>,
+}
+
 /// 图标保存结果响应
 #[derive(Debug, Serialize, Deserialize)]
 pub struct IconSaveResponse {
@@ -142,6 +203,9 @@ pub struct PopupRequest {
     pub uiux_intent: Option<String>,
     pub uiux_context_policy: Option<String>,
     pub uiux_reason: Option<String>,
+    /// 结构化表单字段（为空时不序列化，保持旧版 UI 兼容）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<ZhiField>,
 }
 
 /// 新的结构化响应数据格式
@@ -150,16 +214,30 @@ pub struct McpResponse {
     pub user_input: Option<String>,
     pub selected_options: Vec<String>,
     pub images: Vec<ImageAttachment>,
+    /// 结构化表单值（仅当请求包含 fields 时存在）
+    #[serde(default)]
+    pub form_values: Option<serde_json::Map<String, serde_json::Value>>,
+    /// 非图片附件（如 Telegram 中发送的文档）
+    #[serde(default)]
+    pub attachments: Vec<FileAttachment>,
     pub metadata: ResponseMetadata,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImageAttachment {
     pub data: String,
     pub media_type: String,
     pub filename: Option<String>,
 }
 
+/// 文件附件（data 为 Base64 编码）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileAttachment {
+    pub data: String,
+    pub media_type: String,
+    pub filename: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ResponseMetadata {
     pub timestamp: Option<String>,
@@ -214,7 +292,28 @@ pub fn build_send_response(
     request_id: Option<String>,
     source: &str,
 ) -> String {
-    let response = build_mcp_response(user_input, selected_options, images, request_id, source);
+    build_form_send_response(user_input, selected_options, images, None, vec![], request_id, source)
+}
+
+/// 构建携带表单值和文件附件的发送操作响应
+pub fn build_form_send_response(
+    user_input: Option<String>,
+    selected_options: Vec<String>,
+    images: Vec<ImageAttachment>,
+    form_values: Option<serde_json::Map<String, serde_json::Value>>,
+    attachments: Vec<FileAttachment>,
+    request_id: Option<String>,
+    source: &str,
+) -> String {
+    let mut response = build_mcp_response(user_input, selected_options, images, request_id, source);
+    if let Some(obj) = response.as_object_mut() {
+        if let Some(values) = form_values {
+            obj.insert("form_values".to_string(), serde_json::Value::Object(values));
+        }
+        if !attachments.is_empty() {
+            obj.insert("attachments".to_string(), serde_json::json!(attachments));
+        }
+    }
     response.to_string()
 }
 
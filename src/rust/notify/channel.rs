@@ -0,0 +1,233 @@
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use std::time::Duration;
+
+use crate::constants::notification::{REPLY_POLL_INTERVAL_MS, REQUEST_TIMEOUT_SECS};
+use crate::mcp::tools::interaction::ZhiField;
+use crate::telegram::TelegramEvent;
+
+/// 发送给远程渠道的 zhi 请求
+#[derive(Debug, Clone, Default)]
+pub struct NotificationPrompt {
+    pub request_id: String,
+    pub message: String,
+    pub predefined_options: Vec<String>,
+    pub is_markdown: bool,
+    /// 结构化表单字段（仅 Telegram 以按钮形式呈现）
+    pub fields: Vec<ZhiField>,
+    /// 项目根目录（Telegram 论坛话题按项目拆分时使用）
+    pub project_root_path: Option<String>,
+}
+
+/// 从远程渠道收到的答复
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChannelReply {
+    /// 选择选项并/或输入补充说明后发送
+    Send {
+        selected_options: Vec<String>,
+        user_input: String,
+    },
+    /// 继续
+    Continue,
+}
+
+/// 请求的最终状态，用于更新渠道中的消息
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromptState {
+    /// 已答复（附带答复来源或摘要）
+    Answered { summary: String },
+    /// 请求已取消或关闭
+    Closed,
+}
+
+impl PromptState {
+    pub fn describe(&self) -> String {
+        match self {
+            PromptState::Answered { summary } => format!("✅ 已答复：{}", summary),
+            PromptState::Closed => "⏹️ 请求已关闭".to_string(),
+        }
+    }
+}
+
+/// 远程通知渠道（含 Telegram）
+///
+/// 发送请求（含选项）、接收答复、更新请求状态。
+/// 不支持回复的渠道（如邮件、群机器人 Webhook）只发送通知
+pub trait NotificationChannel: Send {
+    /// 渠道名称（用于日志与答复来源）
+    fn name(&self) -> &'static str;
+
+    /// 是否支持接收答复
+    fn supports_reply(&self) -> bool {
+        false
+    }
+
+    /// 发送请求
+    fn send_prompt<'a>(&'a mut self, prompt: &'a NotificationPrompt) -> BoxFuture<'a, Result<()>>;
+
+    /// 检查一次是否有新答复，没有时返回 Ok(None)
+    fn receive_reply(&mut self) -> BoxFuture<'_, Result<Option<ChannelReply>>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    /// 两次检查答复之间的间隔（自行阻塞等待更新的渠道返回零）
+    fn poll_interval(&self) -> Duration {
+        Duration::from_millis(REPLY_POLL_INTERVAL_MS)
+    }
+
+    /// 取出答复之前的实时交互事件（如切换选项、上传图片），由调度器转发到弹窗
+    fn take_events(&mut self) -> Vec<TelegramEvent> {
+        Vec::new()
+    }
+
+    /// 更新请求状态（如在原消息下回复“已答复”）
+    fn update_state<'a>(&'a mut self, _state: &'a PromptState) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// 生成纯文本形式的请求内容，可回复的渠道附带回复说明
+pub fn format_prompt_text(prompt: &NotificationPrompt, supports_reply: bool) -> String {
+    let mut text = prompt.message.trim().to_string();
+
+    if !prompt.predefined_options.is_empty() {
+        text.push_str("\n\n选项：");
+        for (index, option) in prompt.predefined_options.iter().enumerate() {
+            text.push_str(&format!("\n{}. {}", index + 1, option));
+        }
+    }
+
+    if supports_reply {
+        text.push_str("\n\n💬 回复编号选择选项（如 1,3），其余内容作为补充说明；回复“继续”继续");
+    }
+    text
+}
+
+/// 解析渠道中的文本回复
+///
+/// 首行全部为有效编号时视为选择选项；与某个选项完全一致的回复视为选择该选项
+pub fn parse_reply(text: &str, predefined_options: &[String]) -> Option<ChannelReply> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    if text == "继续" || text.eq_ignore_ascii_case("continue") {
+        return Some(ChannelReply::Continue);
+    }
+
+    if let Some(option) = predefined_options.iter().find(|o| o.as_str() == text) {
+        return Some(ChannelReply::Send {
+            selected_options: vec![option.clone()],
+            user_input: String::new(),
+        });
+    }
+
+    let (first_line, rest) = text.split_once('\n').unwrap_or((text, ""));
+    let indexes: Option<Vec<usize>> = first_line
+        .split(|c: char| c == ',' || c == '，' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse::<usize>().ok().filter(|i| (1..=predefined_options.len()).contains(i)))
+        .collect();
+
+    match indexes {
+        Some(indexes) if !indexes.is_empty() => {
+            let mut selected_options = Vec::new();
+            for index in indexes {
+                let option = predefined_options[index - 1].clone();
+                if !selected_options.contains(&option) {
+                    selected_options.push(option);
+                }
+            }
+            Some(ChannelReply::Send {
+                selected_options,
+                user_input: rest.trim().to_string(),
+            })
+        }
+        _ => Some(ChannelReply::Send {
+            selected_options: Vec::new(),
+            user_input: text.to_string(),
+        }),
+    }
+}
+
+/// 回复者是否有权限答复（允许列表为空时不限制，与 Telegram 的 allowed_user_ids 一致）
+pub(crate) fn is_allowed_sender(allowed_user_ids: &[String], sender: Option<&str>) -> bool {
+    allowed_user_ids.is_empty() || sender.map(|id| allowed_user_ids.iter().any(|a| a.trim() == id)).unwrap_or(false)
+}
+
+/// 渠道共用的 HTTP 客户端
+pub(crate) fn http_client() -> Result<reqwest::Client> {
+    crate::network::create_http_client(None, REQUEST_TIMEOUT_SECS).map_err(|e| anyhow::anyhow!(e))
+}
+
+/// 将字符串截断到指定字符数（超出部分以省略号表示）
+pub(crate) fn truncate_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> Vec<String> {
+        vec!["修复".to_string(), "跳过".to_string(), "回滚".to_string()]
+    }
+
+    #[test]
+    fn test_parse_reply() {
+        assert_eq!(parse_reply(" 继续 ", &options()), Some(ChannelReply::Continue));
+        assert_eq!(
+            parse_reply("1，3\n顺便补上测试", &options()),
+            Some(ChannelReply::Send {
+                selected_options: vec!["修复".to_string(), "回滚".to_string()],
+                user_input: "顺便补上测试".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_reply("跳过", &options()),
+            Some(ChannelReply::Send {
+                selected_options: vec!["跳过".to_string()],
+                user_input: String::new(),
+            })
+        );
+        // 超出范围的编号按普通文本处理
+        assert_eq!(
+            parse_reply("4", &options()),
+            Some(ChannelReply::Send {
+                selected_options: Vec::new(),
+                user_input: "4".to_string(),
+            })
+        );
+        assert_eq!(parse_reply("  ", &options()), None);
+    }
+
+    #[test]
+    fn test_format_prompt_text() {
+        let prompt = NotificationPrompt {
+            request_id: "req".to_string(),
+            message: "是否继续？".to_string(),
+            predefined_options: options(),
+            ..Default::default()
+        };
+        let text = format_prompt_text(&prompt, true);
+        assert!(text.starts_with("是否继续？\n\n选项：\n1. 修复\n2. 跳过\n3. 回滚"));
+        assert!(text.contains("回复编号"));
+        assert!(!format_prompt_text(&prompt, false).contains("回复编号"));
+        assert_eq!(truncate_chars("你好世界", 3), "你好…");
+    }
+
+    #[test]
+    fn test_is_allowed_sender() {
+        assert!(is_allowed_sender(&[], Some("U1")));
+        assert!(is_allowed_sender(&[], None));
+        let allowed = vec!["U1".to_string()];
+        assert!(is_allowed_sender(&allowed, Some("U1")));
+        assert!(!is_allowed_sender(&allowed, Some("U2")));
+        assert!(!is_allowed_sender(&allowed, None));
+    }
+}
@@ -0,0 +1,251 @@
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::{mpsc, watch};
+
+use super::{
+    build_channel, build_enabled_channels, finish_active_request, is_request_active, set_active_request,
+    ChannelReply, NotificationChannel, NotificationPrompt, PromptState, CHANNEL_NAMES,
+};
+use crate::config::{save_config, AppConfig, AppState, NotificationConfig};
+use crate::constants::notification::REPLY_POLL_INTERVAL_MS;
+use crate::mcp::tools::interaction::ZhiField;
+use crate::telegram::TelegramEvent;
+use crate::{log_debug, log_important};
+
+/// 获取通知渠道配置
+#[tauri::command]
+pub async fn get_notification_config(state: State<'_, AppState>) -> Result<NotificationConfig, String> {
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(config.notification_config.clone())
+}
+
+/// 设置通知渠道配置
+#[tauri::command]
+pub async fn set_notification_config(
+    notification_config: NotificationConfig,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| format!("获取配置失败: {}", e))?;
+        config.notification_config = notification_config;
+    }
+
+    save_config(&state, &app)
+        .await
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+    Ok(())
+}
+
+/// 使用当前配置向指定渠道发送测试消息（不要求已启用）
+#[tauri::command]
+pub async fn test_notification_channel(
+    channel: String,
+    notification_config: NotificationConfig,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    if !CHANNEL_NAMES.contains(&channel.as_str()) {
+        return Err(format!("未知的通知渠道: {}", channel));
+    }
+    if channel == "telegram" {
+        return Err("Telegram 请在 Telegram 设置中测试连接".to_string());
+    }
+
+    let app_config = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| format!("获取配置失败: {}", e))?;
+        AppConfig {
+            notification_config,
+            ..config.clone()
+        }
+    };
+    let mut instance = build_channel(&channel, &app_config).map_err(|e| e.to_string())?;
+    let prompt = NotificationPrompt {
+        request_id: "test".to_string(),
+        message: "🤖 三术通知测试消息，收到即表示配置正确".to_string(),
+        ..Default::default()
+    };
+    instance
+        .send_prompt(&prompt)
+        .await
+        .map_err(|e| format!("发送失败: {}", e))?;
+    Ok("测试消息发送成功".to_string())
+}
+
+/// 将请求同步到已启用的通知渠道（含 Telegram），并等待可回复渠道的答复
+///
+/// 实时交互与答复统一以远程交互事件通知弹窗
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn start_notification_sync(
+    message: String,
+    predefined_options: Vec<String>,
+    is_markdown: bool,
+    fields: Option<Vec<ZhiField>>,
+    request_id: Option<String>,
+    project_root_path: Option<String>,
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let app_config = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| format!("获取配置失败: {}", e))?;
+        config.clone()
+    };
+
+    let channels = build_enabled_channels(&app_config);
+    if channels.is_empty() {
+        return Ok(());
+    }
+
+    let prompt = NotificationPrompt {
+        request_id: request_id.unwrap_or_else(crate::mcp::utils::generate_request_id),
+        message,
+        predefined_options,
+        is_markdown,
+        fields: fields.unwrap_or_default(),
+        project_root_path,
+    };
+    set_active_request(&prompt.request_id);
+
+    tokio::spawn(run_channels(channels, prompt, app_handle));
+    Ok(())
+}
+
+/// 统一调度：发送请求后每个可回复渠道独立轮询，第一个答复生效，随后更新所有渠道的状态
+async fn run_channels(channels: Vec<Box<dyn NotificationChannel>>, prompt: NotificationPrompt, app_handle: AppHandle) {
+    let (reply_tx, mut reply_rx) = mpsc::unbounded_channel();
+    let (state_tx, state_rx) = watch::channel(None);
+    let mut pollers = Vec::new();
+
+    for mut channel in channels {
+        match channel.send_prompt(&prompt).await {
+            Ok(()) if channel.supports_reply() => pollers.push(tokio::spawn(poll_channel(
+                channel,
+                reply_tx.clone(),
+                state_rx.clone(),
+                app_handle.clone(),
+            ))),
+            Ok(()) => {}
+            Err(e) => log_important!(warn, "通知渠道 {} 发送失败: {}", channel.name(), e),
+        }
+    }
+    drop(reply_tx);
+    if pollers.is_empty() {
+        return;
+    }
+
+    let final_state = loop {
+        tokio::select! {
+            received = reply_rx.recv() => {
+                let Some((source, reply)) = received else {
+                    return;
+                };
+                // 请求已在其他地方答复时忽略迟到的答复
+                if !is_request_active(&prompt.request_id) {
+                    break PromptState::Closed;
+                }
+                log_important!(info, "收到来自 {} 的答复", source);
+                finish_active_request();
+                emit_reply(&app_handle, reply);
+                break PromptState::Answered {
+                    summary: format!("来自 {}", source),
+                };
+            }
+            _ = tokio::time::sleep(Duration::from_millis(REPLY_POLL_INTERVAL_MS)) => {
+                if !is_request_active(&prompt.request_id) {
+                    break PromptState::Closed;
+                }
+            }
+        }
+    };
+
+    let _ = state_tx.send(Some(final_state));
+    for poller in pollers {
+        let _ = poller.await;
+    }
+}
+
+/// 单个渠道的轮询任务：转发实时事件与答复，收到最终状态后更新渠道消息并退出
+async fn poll_channel(
+    mut channel: Box<dyn NotificationChannel>,
+    reply_tx: mpsc::UnboundedSender<(&'static str, ChannelReply)>,
+    mut state_rx: watch::Receiver<Option<PromptState>>,
+    app_handle: AppHandle,
+) {
+    loop {
+        let final_state = state_rx.borrow().clone();
+        if let Some(state) = final_state {
+            if let Err(e) = channel.update_state(&state).await {
+                log_debug!("通知渠道 {} 更新状态失败: {}", channel.name(), e);
+            }
+            return;
+        }
+
+        let interval = channel.poll_interval();
+        let result = tokio::select! {
+            changed = state_rx.changed() => {
+                if changed.is_err() {
+                    return;
+                }
+                continue;
+            }
+            result = async {
+                tokio::time::sleep(interval).await;
+                channel.receive_reply().await
+            } => result,
+        };
+
+        for event in channel.take_events() {
+            emit_event(&app_handle, &event);
+        }
+        match result {
+            Ok(Some(reply)) => {
+                let _ = reply_tx.send((channel.name(), reply));
+            }
+            Ok(None) => {}
+            Err(e) => log_debug!("通知渠道 {} 接收回复失败: {}", channel.name(), e),
+        }
+    }
+}
+
+/// 将答复转换为弹窗已支持的远程交互事件
+fn emit_reply(app_handle: &AppHandle, reply: ChannelReply) {
+    let events = match reply {
+        ChannelReply::Continue => vec![TelegramEvent::ContinuePressed],
+        ChannelReply::Send {
+            selected_options,
+            user_input,
+        } => {
+            let mut events: Vec<TelegramEvent> = selected_options
+                .into_iter()
+                .map(|option| TelegramEvent::OptionToggled { option, selected: true })
+                .collect();
+            if !user_input.is_empty() {
+                events.push(TelegramEvent::TextUpdated { text: user_input });
+            }
+            events.push(TelegramEvent::SendPressed);
+            events
+        }
+    };
+
+    for event in events {
+        emit_event(app_handle, &event);
+    }
+}
+
+fn emit_event(app_handle: &AppHandle, event: &TelegramEvent) {
+    if let Err(e) = app_handle.emit("telegram-event", event) {
+        log_important!(warn, "远程交互事件发送失败: {}", e);
+    }
+}
@@ -0,0 +1,115 @@
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use futures_util::future::BoxFuture;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::Deserialize;
+
+use super::channel::{format_prompt_text, http_client, NotificationChannel, NotificationPrompt};
+use crate::config::WebhookBotConfig;
+
+#[derive(Debug, Deserialize)]
+struct DingTalkResponse {
+    #[serde(default)]
+    errcode: i64,
+    #[serde(default)]
+    errmsg: String,
+}
+
+/// 钉钉自定义机器人（群机器人 Webhook 只能发送，不能接收回复）
+pub struct DingTalkChannel {
+    config: WebhookBotConfig,
+    client: reqwest::Client,
+}
+
+impl DingTalkChannel {
+    pub fn new(config: WebhookBotConfig) -> Result<Self> {
+        if config.webhook_url.trim().is_empty() {
+            return Err(anyhow::anyhow!("钉钉配置不完整：需要 Webhook 地址"));
+        }
+        Ok(Self {
+            config,
+            client: http_client()?,
+        })
+    }
+
+    /// 开启加签时在 URL 上附加 timestamp 与 sign
+    fn signed_url(&self) -> String {
+        if self.config.secret.is_empty() {
+            return self.config.webhook_url.clone();
+        }
+        let timestamp = chrono::Utc::now().timestamp_millis();
+        let separator = if self.config.webhook_url.contains('?') { '&' } else { '?' };
+        format!(
+            "{}{}timestamp={}&sign={}",
+            self.config.webhook_url,
+            separator,
+            timestamp,
+            utf8_percent_encode(&sign(timestamp, &self.config.secret), NON_ALPHANUMERIC)
+        )
+    }
+}
+
+impl NotificationChannel for DingTalkChannel {
+    fn name(&self) -> &'static str {
+        "dingtalk"
+    }
+
+    fn send_prompt<'a>(&'a mut self, prompt: &'a NotificationPrompt) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let body = serde_json::json!({
+                "msgtype": "text",
+                "text": { "content": format_prompt_text(prompt, false) },
+            });
+
+            let response: DingTalkResponse = self
+                .client
+                .post(self.signed_url())
+                .json(&body)
+                .send()
+                .await?
+                .json()
+                .await?;
+            if response.errcode != 0 {
+                return Err(anyhow::anyhow!("钉钉机器人错误 {}: {}", response.errcode, response.errmsg));
+            }
+            Ok(())
+        })
+    }
+}
+
+/// 钉钉签名：以 secret 为密钥对 `timestamp\nsecret` 做 HmacSHA256，再 Base64 编码
+fn sign(timestamp: i64, secret: &str) -> String {
+    let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, secret.as_bytes());
+    let content = format!("{}\n{}", timestamp, secret);
+    STANDARD.encode(ring::hmac::sign(&key, content.as_bytes()).as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::mock::MockServer;
+
+    #[tokio::test]
+    async fn test_dingtalk_signed_send() {
+        let server = MockServer::start(vec![("/robot/send", r#"{"errcode":0,"errmsg":"ok"}"#)]).await;
+        let mut channel = DingTalkChannel::new(WebhookBotConfig {
+            enabled: true,
+            webhook_url: format!("{}/robot/send?access_token=abc", server.base_url()),
+            secret: "SEC123".to_string(),
+        })
+        .unwrap();
+
+        let prompt = NotificationPrompt {
+            request_id: "req".to_string(),
+            message: "构建完成，是否发布？".to_string(),
+            predefined_options: vec!["发布".to_string()],
+            ..Default::default()
+        };
+        channel.send_prompt(&prompt).await.unwrap();
+
+        let request = &server.requests()[0];
+        assert!(request.contains("access_token=abc&timestamp="));
+        assert!(request.contains("&sign="));
+        assert!(request.contains("1. 发布"));
+    }
+}
@@ -0,0 +1,263 @@
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use serde::Deserialize;
+
+use super::channel::{
+    format_prompt_text, http_client, is_allowed_sender, parse_reply, truncate_chars, ChannelReply, NotificationChannel,
+    NotificationPrompt, PromptState,
+};
+use crate::config::DiscordConfig;
+use crate::constants::notification::DISCORD_MAX_MESSAGE_LENGTH;
+
+#[derive(Debug, Deserialize)]
+struct DiscordMessage {
+    id: String,
+    #[serde(default)]
+    content: String,
+    author: DiscordUser,
+    #[serde(default)]
+    message_reference: Option<DiscordReference>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscordUser {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    bot: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscordReference {
+    #[serde(default)]
+    message_id: Option<String>,
+}
+
+/// Discord Bot 渠道：请求发送到频道，在频道中回复（优先识别对请求消息的“回复”）
+pub struct DiscordChannel {
+    config: DiscordConfig,
+    client: reqwest::Client,
+    options: Vec<String>,
+    /// 请求消息ID
+    message_id: Option<String>,
+    /// 已处理的最新消息ID（Discord 的 Snowflake ID 随时间递增）
+    last_seen_id: Option<String>,
+}
+
+impl DiscordChannel {
+    pub fn new(config: DiscordConfig) -> Result<Self> {
+        if config.bot_token.trim().is_empty() || config.channel_id.trim().is_empty() {
+            return Err(anyhow::anyhow!("Discord 配置不完整：需要 Bot Token 与 Channel ID"));
+        }
+        Ok(Self {
+            config,
+            client: http_client()?,
+            options: Vec::new(),
+            message_id: None,
+            last_seen_id: None,
+        })
+    }
+
+    fn messages_url(&self) -> String {
+        format!(
+            "{}/channels/{}/messages",
+            self.config.api_base_url.trim_end_matches('/'),
+            self.config.channel_id
+        )
+    }
+
+    async fn post_message(&self, content: &str, reply_to: Option<&str>) -> Result<DiscordMessage> {
+        let mut body = serde_json::json!({
+            "content": truncate_chars(content, DISCORD_MAX_MESSAGE_LENGTH),
+        });
+        if let Some(reply_to) = reply_to {
+            body["message_reference"] = serde_json::json!({ "message_id": reply_to });
+        }
+
+        let response = self
+            .client
+            .post(self.messages_url())
+            .header("Authorization", format!("Bot {}", self.config.bot_token))
+            .json(&body)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Discord API 错误: {} {}",
+                response.status(),
+                response.text().await.unwrap_or_default()
+            ));
+        }
+        Ok(response.json().await?)
+    }
+}
+
+impl NotificationChannel for DiscordChannel {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    fn supports_reply(&self) -> bool {
+        true
+    }
+
+    fn send_prompt<'a>(&'a mut self, prompt: &'a NotificationPrompt) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let message = self.post_message(&format_prompt_text(prompt, true), None).await?;
+            self.options = prompt.predefined_options.clone();
+            self.last_seen_id = Some(message.id.clone());
+            self.message_id = Some(message.id);
+            Ok(())
+        })
+    }
+
+    fn receive_reply(&mut self) -> BoxFuture<'_, Result<Option<ChannelReply>>> {
+        Box::pin(async move {
+            let (Some(message_id), Some(after)) = (self.message_id.clone(), self.last_seen_id.clone()) else {
+                return Ok(None);
+            };
+
+            let response = self
+                .client
+                .get(self.messages_url())
+                .header("Authorization", format!("Bot {}", self.config.bot_token))
+                .query(&[("after", after.as_str()), ("limit", "50")])
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                return Err(anyhow::anyhow!("Discord API 错误: {}", response.status()));
+            }
+            let messages: Vec<DiscordMessage> = response.json().await?;
+
+            let mut replies: Vec<DiscordMessage> = messages
+                .into_iter()
+                .filter(|m| is_newer_id(&m.id, &after))
+                .collect();
+            replies.sort_by(|a, b| compare_id(&a.id, &b.id));
+            if let Some(last) = replies.last() {
+                self.last_seen_id = Some(last.id.clone());
+            }
+
+            // 跳过 Bot 消息与不在允许列表中的用户
+            replies.retain(|m| !m.author.bot && is_allowed_sender(&self.config.allowed_user_ids, Some(&m.author.id)));
+            if replies.is_empty() {
+                return Ok(None);
+            }
+
+            // 显式回复请求消息的优先，否则取最早的一条
+            let is_reply_to_prompt = |m: &DiscordMessage| {
+                m.message_reference.as_ref().and_then(|r| r.message_id.as_deref()) == Some(message_id.as_str())
+            };
+            let reply = replies
+                .iter()
+                .find(|m| is_reply_to_prompt(m))
+                .or_else(|| replies.first())
+                .map(|m| m.content.clone())
+                .unwrap_or_default();
+            Ok(parse_reply(&reply, &self.options))
+        })
+    }
+
+    fn update_state<'a>(&'a mut self, state: &'a PromptState) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let reply_to = self.message_id.clone();
+            self.post_message(&state.describe(), reply_to.as_deref()).await?;
+            Ok(())
+        })
+    }
+}
+
+/// Snowflake ID 为十进制数字字符串，按数值比较
+fn compare_id(a: &str, b: &str) -> std::cmp::Ordering {
+    a.parse::<u64>().unwrap_or(0).cmp(&b.parse::<u64>().unwrap_or(0))
+}
+
+fn is_newer_id(id: &str, than: &str) -> bool {
+    compare_id(id, than) == std::cmp::Ordering::Greater
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::mock::MockServer;
+
+    #[tokio::test]
+    async fn test_discord_prompt_and_reply() {
+        let server = MockServer::start(vec![
+            (
+                "POST /channels/C1/messages",
+                r#"{"id":"100","content":"请求","author":{"id":"B1","bot":true}}"#,
+            ),
+            (
+                "GET /channels/C1/messages",
+                r#"[
+                    {"id":"103","content":"自动消息","author":{"id":"B1","bot":true}},
+                    {"id":"101","content":"继续","author":{"id":"U2"}},
+                    {"id":"102","content":"1\n顺便补上测试","author":{"id":"U1"},"message_reference":{"message_id":"100"}}
+                ]"#,
+            ),
+        ])
+        .await;
+
+        let mut channel = DiscordChannel::new(DiscordConfig {
+            enabled: true,
+            bot_token: "token".to_string(),
+            channel_id: "C1".to_string(),
+            api_base_url: server.base_url(),
+            allowed_user_ids: vec!["U1".to_string()],
+        })
+        .unwrap();
+
+        let prompt = NotificationPrompt {
+            request_id: "req".to_string(),
+            message: "选择操作".to_string(),
+            predefined_options: vec!["修复".to_string(), "跳过".to_string()],
+            ..Default::default()
+        };
+        channel.send_prompt(&prompt).await.unwrap();
+
+        // U2 不在允许列表中，其“继续”被忽略；Bot 消息同样跳过
+        assert_eq!(
+            channel.receive_reply().await.unwrap(),
+            Some(ChannelReply::Send {
+                selected_options: vec!["修复".to_string()],
+                user_input: "顺便补上测试".to_string(),
+            })
+        );
+        // 已处理的消息不会被重复处理
+        assert_eq!(channel.receive_reply().await.unwrap(), None);
+
+        channel
+            .update_state(&PromptState::Answered {
+                summary: "来自 discord".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        assert!(requests[0].contains("Bot token"));
+        assert!(requests[0].contains("1. 修复"));
+        assert!(requests[1].starts_with("GET /channels/C1/messages?after=100"));
+        assert!(requests[2].contains("after=103"));
+        assert!(requests[3].contains(r#""message_reference":{"message_id":"100"}"#));
+    }
+
+    #[tokio::test]
+    async fn test_discord_api_error() {
+        let server = MockServer::start(Vec::new()).await;
+        let mut channel = DiscordChannel::new(DiscordConfig {
+            enabled: true,
+            bot_token: "token".to_string(),
+            channel_id: "C1".to_string(),
+            api_base_url: server.base_url(),
+            allowed_user_ids: Vec::new(),
+        })
+        .unwrap();
+
+        let error = channel.send_prompt(&NotificationPrompt::default()).await.unwrap_err();
+        assert!(error.to_string().contains("404"));
+        // 未发送成功时不轮询答复
+        assert_eq!(channel.receive_reply().await.unwrap(), None);
+        assert_eq!(server.requests().len(), 1);
+    }
+}
@@ -0,0 +1,100 @@
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use super::channel::{format_prompt_text, truncate_chars, NotificationChannel, NotificationPrompt};
+use crate::config::EmailConfig;
+
+/// SMTP 邮件渠道（仅发送提醒）
+pub struct EmailChannel {
+    config: EmailConfig,
+}
+
+impl EmailChannel {
+    pub fn new(config: EmailConfig) -> Result<Self> {
+        if config.smtp_host.trim().is_empty() || config.from.trim().is_empty() || config.to.is_empty() {
+            return Err(anyhow::anyhow!("邮件配置不完整：需要 SMTP 服务器、发件人与收件人"));
+        }
+        Ok(Self { config })
+    }
+
+    fn transport(&self) -> Result<AsyncSmtpTransport<Tokio1Executor>> {
+        let host = self.config.smtp_host.trim();
+        let builder = match self.config.security.as_str() {
+            "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(host)?,
+            // 仅用于本地或内网中继
+            "none" => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+            _ => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?,
+        };
+
+        let mut builder = builder.port(self.config.smtp_port);
+        if !self.config.username.is_empty() {
+            builder = builder.credentials(Credentials::new(
+                self.config.username.clone(),
+                self.config.password.clone(),
+            ));
+        }
+        Ok(builder.build())
+    }
+
+    fn build_message(&self, prompt: &NotificationPrompt) -> Result<Message> {
+        let first_line = prompt.message.lines().next().unwrap_or_default().trim();
+        let mut builder = Message::builder()
+            .from(self.config.from.parse()?)
+            .subject(format!("[三术] {}", truncate_chars(first_line, 60)))
+            .header(ContentType::TEXT_PLAIN);
+        for to in &self.config.to {
+            builder = builder.to(to.parse()?);
+        }
+        Ok(builder.body(format_prompt_text(prompt, false))?)
+    }
+}
+
+impl NotificationChannel for EmailChannel {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    fn send_prompt<'a>(&'a mut self, prompt: &'a NotificationPrompt) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let message = self.build_message(prompt)?;
+            self.transport()?
+                .send(message)
+                .await
+                .map_err(|e| anyhow::anyhow!("邮件发送失败: {}", e))?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_message() {
+        let channel = EmailChannel::new(EmailConfig {
+            enabled: true,
+            smtp_host: "localhost".to_string(),
+            smtp_port: 2525,
+            security: "none".to_string(),
+            username: String::new(),
+            password: String::new(),
+            from: "sanshu@example.com".to_string(),
+            to: vec!["dev@example.com".to_string()],
+        })
+        .unwrap();
+
+        let prompt = NotificationPrompt {
+            request_id: "req".to_string(),
+            message: "部署前确认\n详细说明".to_string(),
+            predefined_options: vec!["部署".to_string()],
+            ..Default::default()
+        };
+        let formatted = String::from_utf8(channel.build_message(&prompt).unwrap().formatted()).unwrap();
+        assert!(formatted.contains("To: dev@example.com"));
+        assert!(formatted.contains("Subject:"));
+    }
+}
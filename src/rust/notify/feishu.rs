@@ -0,0 +1,130 @@
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use futures_util::future::BoxFuture;
+use serde::Deserialize;
+
+use super::channel::{format_prompt_text, http_client, NotificationChannel, NotificationPrompt};
+use crate::config::WebhookBotConfig;
+
+#[derive(Debug, Deserialize)]
+struct FeishuResponse {
+    #[serde(default)]
+    code: i64,
+    #[serde(default)]
+    msg: String,
+}
+
+/// 飞书自定义机器人（群机器人 Webhook 只能发送，不能接收回复）
+pub struct FeishuChannel {
+    config: WebhookBotConfig,
+    client: reqwest::Client,
+}
+
+impl FeishuChannel {
+    pub fn new(config: WebhookBotConfig) -> Result<Self> {
+        if config.webhook_url.trim().is_empty() {
+            return Err(anyhow::anyhow!("飞书配置不完整：需要 Webhook 地址"));
+        }
+        Ok(Self {
+            config,
+            client: http_client()?,
+        })
+    }
+}
+
+impl NotificationChannel for FeishuChannel {
+    fn name(&self) -> &'static str {
+        "feishu"
+    }
+
+    fn send_prompt<'a>(&'a mut self, prompt: &'a NotificationPrompt) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let mut body = serde_json::json!({
+                "msg_type": "text",
+                "content": { "text": format_prompt_text(prompt, false) },
+            });
+            if !self.config.secret.is_empty() {
+                let timestamp = chrono::Utc::now().timestamp();
+                body["timestamp"] = serde_json::Value::String(timestamp.to_string());
+                body["sign"] = serde_json::Value::String(sign(timestamp, &self.config.secret));
+            }
+
+            let response: FeishuResponse = self
+                .client
+                .post(&self.config.webhook_url)
+                .json(&body)
+                .send()
+                .await?
+                .json()
+                .await?;
+            if response.code != 0 {
+                return Err(anyhow::anyhow!("飞书机器人错误 {}: {}", response.code, response.msg));
+            }
+            Ok(())
+        })
+    }
+}
+
+/// 飞书签名：以 `timestamp\nsecret` 为密钥对空内容做 HmacSHA256，再 Base64 编码
+fn sign(timestamp: i64, secret: &str) -> String {
+    let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, format!("{}\n{}", timestamp, secret).as_bytes());
+    STANDARD.encode(ring::hmac::sign(&key, b"").as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::mock::MockServer;
+
+    #[test]
+    fn test_sign() {
+        let signature = sign(1700000000, "secret");
+        assert_eq!(signature, sign(1700000000, "secret"));
+        assert_ne!(signature, sign(1700000001, "secret"));
+        assert_eq!(STANDARD.decode(&signature).unwrap().len(), 32);
+    }
+
+    #[tokio::test]
+    async fn test_feishu_signed_send() {
+        let server = MockServer::start(vec![("/bot/v2/hook/abc", r#"{"code":0,"msg":"success"}"#)]).await;
+        let mut channel = FeishuChannel::new(WebhookBotConfig {
+            enabled: true,
+            webhook_url: format!("{}/open-apis/bot/v2/hook/abc", server.base_url()),
+            secret: "secret".to_string(),
+        })
+        .unwrap();
+        assert!(!channel.supports_reply());
+
+        let prompt = NotificationPrompt {
+            request_id: "req".to_string(),
+            message: "构建完成，是否发布？".to_string(),
+            predefined_options: vec!["发布".to_string()],
+            ..Default::default()
+        };
+        channel.send_prompt(&prompt).await.unwrap();
+        assert_eq!(channel.receive_reply().await.unwrap(), None);
+
+        let request = &server.requests()[0];
+        let body: serde_json::Value = serde_json::from_str(request.split_once("\r\n\r\n").unwrap().1).unwrap();
+        assert_eq!(body["msg_type"], "text");
+        assert!(body["content"]["text"].as_str().unwrap().contains("1. 发布"));
+        let timestamp: i64 = body["timestamp"].as_str().unwrap().parse().unwrap();
+        assert_eq!(body["sign"], sign(timestamp, "secret"));
+    }
+
+    #[tokio::test]
+    async fn test_feishu_error_code() {
+        let server = MockServer::start(vec![("/hook/abc", r#"{"code":19021,"msg":"sign match fail"}"#)]).await;
+        let mut channel = FeishuChannel::new(WebhookBotConfig {
+            enabled: true,
+            webhook_url: format!("{}/hook/abc", server.base_url()),
+            secret: String::new(),
+        })
+        .unwrap();
+
+        let error = channel.send_prompt(&NotificationPrompt::default()).await.unwrap_err();
+        assert!(error.to_string().contains("19021"));
+        // 未配置密钥时不附带签名
+        assert!(!server.requests()[0].contains("\"sign\""));
+    }
+}
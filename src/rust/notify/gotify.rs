@@ -0,0 +1,105 @@
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+
+use super::channel::{format_prompt_text, http_client, NotificationChannel, NotificationPrompt};
+use crate::config::GotifyConfig;
+
+/// Gotify 渠道（仅推送通知）
+pub struct GotifyChannel {
+    config: GotifyConfig,
+    client: reqwest::Client,
+}
+
+impl GotifyChannel {
+    pub fn new(config: GotifyConfig) -> Result<Self> {
+        if config.server_url.trim().is_empty() || config.app_token.trim().is_empty() {
+            return Err(anyhow::anyhow!("Gotify 配置不完整：需要服务器地址与应用 Token"));
+        }
+        Ok(Self {
+            config,
+            client: http_client()?,
+        })
+    }
+}
+
+impl NotificationChannel for GotifyChannel {
+    fn name(&self) -> &'static str {
+        "gotify"
+    }
+
+    fn send_prompt<'a>(&'a mut self, prompt: &'a NotificationPrompt) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let content_type = if prompt.is_markdown { "text/markdown" } else { "text/plain" };
+            let response = self
+                .client
+                .post(format!("{}/message", self.config.server_url.trim_end_matches('/')))
+                .header("X-Gotify-Key", &self.config.app_token)
+                .json(&serde_json::json!({
+                    "title": "三术 · 等待答复",
+                    "message": format_prompt_text(prompt, false),
+                    "priority": 5,
+                    "extras": { "client::display": { "contentType": content_type } },
+                }))
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                return Err(anyhow::anyhow!(
+                    "Gotify 推送失败: {} {}",
+                    response.status(),
+                    response.text().await.unwrap_or_default()
+                ));
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::mock::MockServer;
+
+    #[tokio::test]
+    async fn test_gotify_send() {
+        let server = MockServer::start(vec![("/gotify/message", r#"{"id":1}"#)]).await;
+        let mut channel = GotifyChannel::new(GotifyConfig {
+            enabled: true,
+            server_url: format!("{}/gotify/", server.base_url()),
+            app_token: "app-token".to_string(),
+        })
+        .unwrap();
+        assert!(!channel.supports_reply());
+
+        let prompt = NotificationPrompt {
+            request_id: "req".to_string(),
+            message: "**部署完成**".to_string(),
+            predefined_options: vec!["验收".to_string()],
+            is_markdown: true,
+            ..Default::default()
+        };
+        channel.send_prompt(&prompt).await.unwrap();
+        assert_eq!(channel.receive_reply().await.unwrap(), None);
+
+        let request = &server.requests()[0];
+        assert!(request.starts_with("POST /gotify/message "));
+        assert!(request.to_ascii_lowercase().contains("x-gotify-key: app-token"));
+        assert!(request.contains(r#""contentType":"text/markdown""#));
+        assert!(request.contains("1. 验收"));
+        // 不支持回复的渠道不附带回复说明
+        assert!(!request.contains("回复编号"));
+    }
+
+    #[tokio::test]
+    async fn test_gotify_error() {
+        let server = MockServer::start(Vec::new()).await;
+        let mut channel = GotifyChannel::new(GotifyConfig {
+            enabled: true,
+            server_url: server.base_url(),
+            app_token: "app-token".to_string(),
+        })
+        .unwrap();
+
+        let error = channel.send_prompt(&NotificationPrompt::default()).await.unwrap_err();
+        assert!(error.to_string().contains("Gotify 推送失败"));
+    }
+}
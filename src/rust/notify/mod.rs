@@ -0,0 +1,99 @@
+// 远程通知渠道
+// 统一的 NotificationChannel 接口，支持 Telegram、Slack、Discord、飞书、钉钉、ntfy、Gotify 与邮件
+
+pub mod channel;
+pub mod commands;
+pub mod dingtalk;
+pub mod discord;
+pub mod email;
+pub mod feishu;
+pub mod gotify;
+pub mod ntfy;
+pub mod slack;
+pub mod telegram;
+
+#[cfg(test)]
+pub(crate) mod mock;
+
+pub use channel::{ChannelReply, NotificationChannel, NotificationPrompt, PromptState};
+pub use commands::*;
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+
+use crate::config::AppConfig;
+use crate::log_important;
+
+/// 所有渠道名称（与配置字段一致，Telegram 使用独立的 telegram_config）
+pub const CHANNEL_NAMES: &[&str] = &["telegram", "slack", "discord", "feishu", "dingtalk", "ntfy", "gotify", "email"];
+
+/// 当前等待答复的请求ID，答复后置空以停止各渠道的轮询
+static ACTIVE_REQUEST: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+/// 按名称创建渠道（不检查是否启用）
+pub fn build_channel(name: &str, app_config: &AppConfig) -> Result<Box<dyn NotificationChannel>> {
+    let config = &app_config.notification_config;
+    let channel: Box<dyn NotificationChannel> = match name {
+        "telegram" => Box::new(telegram::TelegramChannel::new(
+            app_config.telegram_config.clone(),
+            app_config.reply_config.enable_continue_reply,
+        )?),
+        "slack" => Box::new(slack::SlackChannel::new(config.slack.clone())?),
+        "discord" => Box::new(discord::DiscordChannel::new(config.discord.clone())?),
+        "feishu" => Box::new(feishu::FeishuChannel::new(config.feishu.clone())?),
+        "dingtalk" => Box::new(dingtalk::DingTalkChannel::new(config.dingtalk.clone())?),
+        "ntfy" => Box::new(ntfy::NtfyChannel::new(config.ntfy.clone())?),
+        "gotify" => Box::new(gotify::GotifyChannel::new(config.gotify.clone())?),
+        "email" => Box::new(email::EmailChannel::new(config.email.clone())?),
+        _ => return Err(anyhow::anyhow!("未知的通知渠道: {}", name)),
+    };
+    Ok(channel)
+}
+
+/// 创建所有已启用的渠道，配置不完整的渠道记录日志后跳过
+pub fn build_enabled_channels(app_config: &AppConfig) -> Vec<Box<dyn NotificationChannel>> {
+    let config = &app_config.notification_config;
+    let enabled = [
+        ("telegram", app_config.telegram_config.enabled),
+        ("slack", config.slack.enabled),
+        ("discord", config.discord.enabled),
+        ("feishu", config.feishu.enabled),
+        ("dingtalk", config.dingtalk.enabled),
+        ("ntfy", config.ntfy.enabled),
+        ("gotify", config.gotify.enabled),
+        ("email", config.email.enabled),
+    ];
+
+    enabled
+        .into_iter()
+        .filter(|(_, enabled)| *enabled)
+        .filter_map(|(name, _)| match build_channel(name, app_config) {
+            Ok(channel) => Some(channel),
+            Err(e) => {
+                log_important!(warn, "通知渠道 {} 初始化失败: {}", name, e);
+                None
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn set_active_request(request_id: &str) {
+    if let Ok(mut active) = ACTIVE_REQUEST.lock() {
+        *active = Some(request_id.to_string());
+    }
+}
+
+pub(crate) fn is_request_active(request_id: &str) -> bool {
+    ACTIVE_REQUEST
+        .lock()
+        .map(|active| active.as_deref() == Some(request_id))
+        .unwrap_or(false)
+}
+
+/// 请求已答复（弹窗或任一渠道），停止等待渠道回复
+pub fn finish_active_request() {
+    if let Ok(mut active) = ACTIVE_REQUEST.lock() {
+        *active = None;
+    }
+}
@@ -0,0 +1,233 @@
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use serde::Deserialize;
+
+use super::channel::{
+    format_prompt_text, http_client, parse_reply, ChannelReply, NotificationChannel, NotificationPrompt, PromptState,
+};
+use crate::config::NtfyConfig;
+use crate::constants::notification::NTFY_MAX_ACTIONS;
+
+#[derive(Debug, Deserialize)]
+struct NtfyMessage {
+    id: String,
+    #[serde(default)]
+    time: i64,
+    #[serde(default)]
+    event: String,
+    #[serde(default)]
+    message: String,
+}
+
+/// ntfy 渠道：请求发布到 `<topic>`，通知上的按钮或手动发布到 `<topic>-reply` 即可答复
+pub struct NtfyChannel {
+    config: NtfyConfig,
+    client: reqwest::Client,
+    options: Vec<String>,
+    /// 轮询回复话题的起点（时间戳或消息ID）
+    since: Option<String>,
+}
+
+impl NtfyChannel {
+    pub fn new(config: NtfyConfig) -> Result<Self> {
+        if config.server_url.trim().is_empty() || config.topic.trim().is_empty() {
+            return Err(anyhow::anyhow!("ntfy 配置不完整：需要服务器地址与话题"));
+        }
+        Ok(Self {
+            config,
+            client: http_client()?,
+            options: Vec::new(),
+            since: None,
+        })
+    }
+
+    fn server(&self) -> &str {
+        self.config.server_url.trim_end_matches('/')
+    }
+
+    fn reply_topic(&self) -> String {
+        format!("{}-reply", self.config.topic)
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        if self.config.access_token.is_empty() {
+            request
+        } else {
+            request.bearer_auth(&self.config.access_token)
+        }
+    }
+
+    async fn publish(&self, body: serde_json::Value) -> Result<NtfyMessage> {
+        let response = self
+            .authorize(self.client.post(format!("{}/", self.server())))
+            .json(&body)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "ntfy 发布失败: {} {}",
+                response.status(),
+                response.text().await.unwrap_or_default()
+            ));
+        }
+        Ok(response.json().await?)
+    }
+
+    /// 通知上的快捷按钮：点击即向回复话题发布对应内容
+    fn build_actions(&self, prompt: &NotificationPrompt) -> Vec<serde_json::Value> {
+        let reply_url = format!("{}/{}", self.server(), self.reply_topic());
+        let mut headers = serde_json::Map::new();
+        if !self.config.access_token.is_empty() {
+            headers.insert(
+                "Authorization".to_string(),
+                serde_json::Value::String(format!("Bearer {}", self.config.access_token)),
+            );
+        }
+
+        prompt
+            .predefined_options
+            .iter()
+            .map(String::as_str)
+            .chain(std::iter::once("继续"))
+            .take(NTFY_MAX_ACTIONS)
+            .map(|label| {
+                serde_json::json!({
+                    "action": "http",
+                    "label": label,
+                    "url": reply_url,
+                    "method": "POST",
+                    "headers": headers,
+                    "body": label,
+                    "clear": true,
+                })
+            })
+            .collect()
+    }
+}
+
+impl NotificationChannel for NtfyChannel {
+    fn name(&self) -> &'static str {
+        "ntfy"
+    }
+
+    fn supports_reply(&self) -> bool {
+        true
+    }
+
+    fn send_prompt<'a>(&'a mut self, prompt: &'a NotificationPrompt) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let message = format!(
+                "{}\n\n（也可发布到话题 {} 进行回复）",
+                format_prompt_text(prompt, true),
+                self.reply_topic()
+            );
+            let published = self
+                .publish(serde_json::json!({
+                    "topic": self.config.topic,
+                    "title": "三术 · 等待答复",
+                    "message": message,
+                    "markdown": prompt.is_markdown,
+                    "tags": ["speech_balloon"],
+                    "priority": 4,
+                    "actions": self.build_actions(prompt),
+                }))
+                .await?;
+
+            self.options = prompt.predefined_options.clone();
+            // 只接收请求发出之后的回复
+            self.since = Some(published.time.max(chrono::Utc::now().timestamp()).to_string());
+            Ok(())
+        })
+    }
+
+    fn receive_reply(&mut self) -> BoxFuture<'_, Result<Option<ChannelReply>>> {
+        Box::pin(async move {
+            let Some(since) = self.since.clone() else {
+                return Ok(None);
+            };
+
+            let response = self
+                .authorize(self.client.get(format!("{}/{}/json", self.server(), self.reply_topic())))
+                .query(&[("poll", "1"), ("since", since.as_str())])
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                return Err(anyhow::anyhow!("ntfy 订阅失败: {}", response.status()));
+            }
+
+            // 响应为每行一个 JSON 的消息流
+            let body = response.text().await?;
+            for message in parse_messages(&body) {
+                self.since = Some(message.id.clone());
+                if let Some(reply) = parse_reply(&message.message, &self.options) {
+                    return Ok(Some(reply));
+                }
+            }
+            Ok(None)
+        })
+    }
+
+    fn update_state<'a>(&'a mut self, state: &'a PromptState) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.publish(serde_json::json!({
+                "topic": self.config.topic,
+                "title": "三术",
+                "message": state.describe(),
+                "tags": ["white_check_mark"],
+            }))
+            .await?;
+            Ok(())
+        })
+    }
+}
+
+fn parse_messages(body: &str) -> Vec<NtfyMessage> {
+    let mut messages: Vec<NtfyMessage> = body
+        .lines()
+        .filter_map(|line| serde_json::from_str::<NtfyMessage>(line).ok())
+        .filter(|m| m.event == "message")
+        .collect();
+    messages.sort_by_key(|m| m.time);
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::mock::MockServer;
+
+    #[tokio::test]
+    async fn test_ntfy_prompt_and_reply() {
+        let server = MockServer::start(vec![
+            (
+                "/sanshu-reply/json",
+                "{\"id\":\"k1\",\"time\":1700000001,\"event\":\"open\"}\n{\"id\":\"m1\",\"time\":1700000002,\"event\":\"message\",\"message\":\"继续\"}\n",
+            ),
+            ("/", r#"{"id":"p1","time":1700000000,"event":"message"}"#),
+        ])
+        .await;
+
+        let mut channel = NtfyChannel::new(NtfyConfig {
+            enabled: true,
+            server_url: server.base_url(),
+            topic: "sanshu".to_string(),
+            access_token: String::new(),
+        })
+        .unwrap();
+
+        let prompt = NotificationPrompt {
+            request_id: "req".to_string(),
+            message: "需要确认".to_string(),
+            predefined_options: vec!["确认".to_string(), "取消".to_string(), "稍后".to_string()],
+            is_markdown: true,
+            ..Default::default()
+        };
+        channel.send_prompt(&prompt).await.unwrap();
+        assert_eq!(channel.receive_reply().await.unwrap(), Some(ChannelReply::Continue));
+
+        // 按钮数量受 ntfy 限制
+        let publish = &server.requests()[0];
+        assert!(publish.contains("\"label\":\"稍后\""));
+        assert!(!publish.contains("\"label\":\"继续\""));
+    }
+}
@@ -0,0 +1,239 @@
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use serde::Deserialize;
+
+use super::channel::{
+    format_prompt_text, http_client, is_allowed_sender, parse_reply, ChannelReply, NotificationChannel,
+    NotificationPrompt, PromptState,
+};
+use crate::config::SlackConfig;
+
+/// Slack Web API 响应（只取用到的字段）
+#[derive(Debug, Deserialize)]
+struct SlackResponse {
+    ok: bool,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    ts: Option<String>,
+    #[serde(default)]
+    channel: Option<String>,
+    #[serde(default)]
+    messages: Vec<SlackMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SlackMessage {
+    ts: String,
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    bot_id: Option<String>,
+    #[serde(default)]
+    user: Option<String>,
+}
+
+/// Slack Bot 渠道：请求作为频道消息发送，在该消息的讨论串中回复即可答复
+pub struct SlackChannel {
+    config: SlackConfig,
+    client: reqwest::Client,
+    options: Vec<String>,
+    /// 请求消息所在频道与时间戳（即讨论串ID）
+    thread: Option<(String, String)>,
+    /// 已处理的最新回复时间戳
+    last_seen_ts: Option<String>,
+}
+
+impl SlackChannel {
+    pub fn new(config: SlackConfig) -> Result<Self> {
+        if config.bot_token.trim().is_empty() || config.channel_id.trim().is_empty() {
+            return Err(anyhow::anyhow!("Slack 配置不完整：需要 Bot Token 与 Channel ID"));
+        }
+        Ok(Self {
+            config,
+            client: http_client()?,
+            options: Vec::new(),
+            thread: None,
+            last_seen_ts: None,
+        })
+    }
+
+    fn url(&self, method: &str) -> String {
+        format!("{}/{}", self.config.api_base_url.trim_end_matches('/'), method)
+    }
+
+    async fn post_message(&self, text: &str, thread_ts: Option<&str>) -> Result<SlackResponse> {
+        let channel = self
+            .thread
+            .as_ref()
+            .map(|(channel, _)| channel.clone())
+            .unwrap_or_else(|| self.config.channel_id.clone());
+        let mut body = serde_json::json!({ "channel": channel, "text": text });
+        if let Some(thread_ts) = thread_ts {
+            body["thread_ts"] = serde_json::Value::String(thread_ts.to_string());
+        }
+
+        let response: SlackResponse = self
+            .client
+            .post(self.url("chat.postMessage"))
+            .bearer_auth(&self.config.bot_token)
+            .json(&body)
+            .send()
+            .await?
+            .json()
+            .await?;
+        check_response(response)
+    }
+}
+
+impl NotificationChannel for SlackChannel {
+    fn name(&self) -> &'static str {
+        "slack"
+    }
+
+    fn supports_reply(&self) -> bool {
+        true
+    }
+
+    fn send_prompt<'a>(&'a mut self, prompt: &'a NotificationPrompt) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let response = self.post_message(&format_prompt_text(prompt, true), None).await?;
+            let ts = response.ts.ok_or_else(|| anyhow::anyhow!("Slack 未返回消息时间戳"))?;
+            let channel = response.channel.unwrap_or_else(|| self.config.channel_id.clone());
+
+            self.options = prompt.predefined_options.clone();
+            self.last_seen_ts = Some(ts.clone());
+            self.thread = Some((channel, ts));
+            Ok(())
+        })
+    }
+
+    fn receive_reply(&mut self) -> BoxFuture<'_, Result<Option<ChannelReply>>> {
+        Box::pin(async move {
+            let Some((channel, thread_ts)) = self.thread.clone() else {
+                return Ok(None);
+            };
+
+            let oldest = self.last_seen_ts.clone().unwrap_or_else(|| thread_ts.clone());
+            let response: SlackResponse = self
+                .client
+                .get(self.url("conversations.replies"))
+                .bearer_auth(&self.config.bot_token)
+                .query(&[("channel", channel.as_str()), ("ts", thread_ts.as_str()), ("oldest", oldest.as_str())])
+                .send()
+                .await?
+                .json()
+                .await?;
+            let response = check_response(response)?;
+
+            // 跳过请求消息本身、已处理的回复与 Bot 自己发送的消息
+            let mut replies: Vec<SlackMessage> = response
+                .messages
+                .into_iter()
+                .filter(|m| m.ts != thread_ts && m.bot_id.is_none())
+                .filter(|m| is_newer_ts(&m.ts, &oldest))
+                .collect();
+            replies.sort_by(|a, b| compare_ts(&a.ts, &b.ts));
+
+            for reply in replies {
+                self.last_seen_ts = Some(reply.ts.clone());
+                // 不在允许列表中的成员回复不视为答复
+                if !is_allowed_sender(&self.config.allowed_user_ids, reply.user.as_deref()) {
+                    continue;
+                }
+                if let Some(parsed) = parse_reply(&reply.text, &self.options) {
+                    return Ok(Some(parsed));
+                }
+            }
+            Ok(None)
+        })
+    }
+
+    fn update_state<'a>(&'a mut self, state: &'a PromptState) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let Some((_, thread_ts)) = self.thread.clone() else {
+                return Ok(());
+            };
+            self.post_message(&state.describe(), Some(&thread_ts)).await?;
+            Ok(())
+        })
+    }
+}
+
+fn check_response(response: SlackResponse) -> Result<SlackResponse> {
+    if !response.ok {
+        return Err(anyhow::anyhow!(
+            "Slack API 错误: {}",
+            response.error.as_deref().unwrap_or("unknown_error")
+        ));
+    }
+    Ok(response)
+}
+
+/// Slack 时间戳形如 "1700000000.000100"，分别比较整数与小数部分（f64 精度不足）
+fn compare_ts(a: &str, b: &str) -> std::cmp::Ordering {
+    let parse = |ts: &str| {
+        let (secs, micros) = ts.split_once('.').unwrap_or((ts, "0"));
+        (secs.parse::<u64>().unwrap_or(0), format!("{:0<6}", micros).parse::<u64>().unwrap_or(0))
+    };
+    parse(a).cmp(&parse(b))
+}
+
+fn is_newer_ts(ts: &str, than: &str) -> bool {
+    compare_ts(ts, than) == std::cmp::Ordering::Greater
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::mock::MockServer;
+
+    #[tokio::test]
+    async fn test_slack_prompt_and_reply() {
+        let server = MockServer::start(vec![
+            ("/chat.postMessage", r#"{"ok":true,"channel":"C1","ts":"100.000001"}"#),
+            (
+                "/conversations.replies",
+                r#"{"ok":true,"messages":[
+                    {"ts":"100.000001","text":"请求","bot_id":"B1"},
+                    {"ts":"100.000002","text":"继续","user":"U2"},
+                    {"ts":"100.000003","text":"2\n补充说明","user":"U1"}
+                ]}"#,
+            ),
+        ])
+        .await;
+
+        let mut channel = SlackChannel::new(SlackConfig {
+            enabled: true,
+            bot_token: "xoxb-test".to_string(),
+            channel_id: "C1".to_string(),
+            api_base_url: server.base_url(),
+            allowed_user_ids: vec!["U1".to_string()],
+        })
+        .unwrap();
+
+        let prompt = NotificationPrompt {
+            request_id: "req".to_string(),
+            message: "选择操作".to_string(),
+            predefined_options: vec!["修复".to_string(), "跳过".to_string()],
+            ..Default::default()
+        };
+        channel.send_prompt(&prompt).await.unwrap();
+
+        // U2 不在允许列表中，其“继续”被忽略
+        let reply = channel.receive_reply().await.unwrap();
+        assert_eq!(
+            reply,
+            Some(ChannelReply::Send {
+                selected_options: vec!["跳过".to_string()],
+                user_input: "补充说明".to_string(),
+            })
+        );
+        // 同一条回复不会被重复处理
+        assert_eq!(channel.receive_reply().await.unwrap(), None);
+
+        let requests = server.requests();
+        assert!(requests[0].contains("Bearer xoxb-test"));
+        assert!(requests[0].contains("1. 修复"));
+    }
+}
@@ -0,0 +1,341 @@
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use std::collections::HashSet;
+use std::time::Duration;
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardButtonKind, UpdateKind};
+
+use super::channel::{ChannelReply, NotificationChannel, NotificationPrompt, PromptState};
+use crate::config::TelegramConfig;
+use crate::constants::notification::REPLY_POLL_INTERVAL_MS;
+use crate::mcp::tools::interaction::ZhiField;
+use crate::telegram::core::build_feedback_message;
+use crate::telegram::form::{apply_form_callback, parse_form_text, FORM_CALLBACK_PREFIX};
+use crate::telegram::hub::UpdateHub;
+use crate::telegram::media::{build_media_ack, download_message_media, ReceivedMedia, TelegramMedia};
+use crate::telegram::routing::RequestRouter;
+use crate::telegram::{handle_callback_query, handle_text_message, TelegramCore, TelegramEvent};
+
+/// Telegram Bot 渠道：选项以按钮呈现，操作键盘发送或继续
+///
+/// 切换选项、表单赋值与附件作为实时事件同步到弹窗，按下“发送”或“继续”时返回答复
+pub struct TelegramChannel {
+    config: TelegramConfig,
+    continue_reply_enabled: bool,
+    session: Option<TelegramSession>,
+    events: Vec<TelegramEvent>,
+    /// 答复是否来自本渠道（此时已发送反馈消息，无需再更新状态）
+    answered: bool,
+}
+
+/// 一次请求的会话状态
+struct TelegramSession {
+    core: TelegramCore,
+    hub: UpdateHub,
+    router: RequestRouter,
+    options: Vec<String>,
+    options_message_id: Option<i32>,
+    fields: Vec<ZhiField>,
+    form_message_id: Option<i32>,
+    selected_options: HashSet<String>,
+    user_input: String,
+    form_values: serde_json::Map<String, serde_json::Value>,
+    /// 已接收的图片与文件（仅用于计数提示）
+    media: ReceivedMedia,
+}
+
+impl TelegramChannel {
+    pub fn new(config: TelegramConfig, continue_reply_enabled: bool) -> Result<Self> {
+        if config.bot_token.trim().is_empty() || config.chat_id.trim().is_empty() {
+            return Err(anyhow::anyhow!("Telegram配置不完整"));
+        }
+        Ok(Self {
+            config,
+            continue_reply_enabled,
+            session: None,
+            events: Vec::new(),
+            answered: false,
+        })
+    }
+}
+
+impl NotificationChannel for TelegramChannel {
+    fn name(&self) -> &'static str {
+        "telegram"
+    }
+
+    fn supports_reply(&self) -> bool {
+        true
+    }
+
+    fn send_prompt<'a>(&'a mut self, prompt: &'a NotificationPrompt) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let mut core = TelegramCore::from_config(&self.config)
+                .await
+                .map_err(|e| anyhow::anyhow!("创建Telegram核心失败: {}", e))?;
+            if self.config.use_forum_topics {
+                core.enter_project_topic(prompt.project_root_path.as_deref()).await;
+            }
+
+            // 先登记请求路由再发送消息，避免错过快速答复
+            let hub = UpdateHub::open(&core).map_err(|e| anyhow::anyhow!("打开Telegram更新队列失败: {}", e))?;
+            let mut router = RequestRouter::register(&hub, &core, &prompt.request_id)
+                .map_err(|e| anyhow::anyhow!("登记Telegram请求失败: {}", e))?;
+
+            let message_ids = core
+                .send_options_message(&prompt.message, &prompt.predefined_options, prompt.is_markdown)
+                .await?;
+            let options_message_id = if prompt.predefined_options.is_empty() {
+                None
+            } else {
+                message_ids.last().copied()
+            };
+            router.track(message_ids);
+
+            // 发送表单消息（如有），表单值通过事件同步到弹窗
+            let form_message_id = if prompt.fields.is_empty() {
+                None
+            } else {
+                Some(core.send_form_message(&prompt.fields, &serde_json::Map::new()).await?)
+            };
+            router.track(form_message_id);
+
+            // 短暂延迟确保消息顺序
+            tokio::time::sleep(Duration::from_millis(500)).await;
+
+            let operation_message_id = core.send_operation_message(self.continue_reply_enabled).await?;
+            router.track([operation_message_id]);
+
+            self.session = Some(TelegramSession {
+                core,
+                hub,
+                router,
+                options: prompt.predefined_options.clone(),
+                options_message_id,
+                fields: prompt.fields.clone(),
+                form_message_id,
+                selected_options: HashSet::new(),
+                user_input: String::new(),
+                form_values: serde_json::Map::new(),
+                media: ReceivedMedia::default(),
+            });
+            Ok(())
+        })
+    }
+
+    fn receive_reply(&mut self) -> BoxFuture<'_, Result<Option<ChannelReply>>> {
+        Box::pin(async move {
+            let Some(session) = self.session.as_mut() else {
+                return Ok(None);
+            };
+            // 请求已在弹窗中答复时路由会被释放
+            if !session.router.is_active() {
+                return Ok(None);
+            }
+            session.router.heartbeat();
+
+            for update in session.hub.next_updates().await {
+                let reply = match update.kind {
+                    UpdateKind::CallbackQuery(callback_query) => {
+                        session.handle_callback(&callback_query, &mut self.events).await;
+                        None
+                    }
+                    UpdateKind::Message(message) => session.handle_message(&message, &mut self.events).await,
+                    _ => None,
+                };
+                if reply.is_some() {
+                    self.answered = true;
+                    return Ok(reply);
+                }
+            }
+            Ok(None)
+        })
+    }
+
+    fn poll_interval(&self) -> Duration {
+        // 等待答复时由更新队列自行等待（长轮询或读取共享队列的间隔）
+        match &self.session {
+            Some(session) if session.router.is_active() => Duration::ZERO,
+            _ => Duration::from_millis(REPLY_POLL_INTERVAL_MS),
+        }
+    }
+
+    fn take_events(&mut self) -> Vec<TelegramEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn update_state<'a>(&'a mut self, state: &'a PromptState) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            // 结束会话即释放请求路由
+            let Some(session) = self.session.take() else {
+                return Ok(());
+            };
+            if !self.answered {
+                session.core.send_message(&state.describe()).await?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl TelegramSession {
+    /// 处理按钮回调：表单按钮更新表单值，选项按钮切换选中状态
+    async fn handle_callback(&mut self, callback_query: &CallbackQuery, events: &mut Vec<TelegramEvent>) {
+        // 只处理本请求消息上的按钮
+        if !self.router.accepts_callback(callback_query) {
+            return;
+        }
+        if !self.router.is_authorized_callback(callback_query) {
+            let _ = self
+                .core
+                .bot
+                .answer_callback_query(callback_query.id.clone())
+                .text("你没有权限答复此请求")
+                .show_alert(true)
+                .await;
+            return;
+        }
+
+        if let Some(data) = callback_query
+            .data
+            .as_deref()
+            .filter(|data| data.starts_with(FORM_CALLBACK_PREFIX))
+        {
+            if let Some(key) = apply_form_callback(&self.fields, &mut self.form_values, data) {
+                let value = self.form_values.get(&key).cloned().unwrap_or_default();
+                events.push(TelegramEvent::FormValueUpdated { key, value });
+                self.refresh_form_keyboard().await;
+            }
+            let _ = self.core.bot.answer_callback_query(callback_query.id.clone()).await;
+            return;
+        }
+
+        // 只有当有预定义选项时才处理选项按钮
+        if self.options.is_empty() {
+            return;
+        }
+        if let Some(message) = &callback_query.message {
+            self.options_message_id.get_or_insert(message.id().0);
+        }
+
+        if let Ok(Some(option)) = handle_callback_query(&self.core.bot, callback_query, self.core.chat_id).await {
+            let selected = if self.selected_options.remove(&option) {
+                false
+            } else {
+                self.selected_options.insert(option.clone());
+                true
+            };
+            events.push(TelegramEvent::OptionToggled { option, selected });
+
+            if let Some(msg_id) = self.options_message_id {
+                let _ = self
+                    .core
+                    .update_inline_keyboard(msg_id, &self.options, &self.selected())
+                    .await;
+            }
+        }
+    }
+
+    /// 处理消息：附件与文本同步到弹窗，“发送”“继续”返回答复
+    async fn handle_message(&mut self, message: &Message, events: &mut Vec<TelegramEvent>) -> Option<ChannelReply> {
+        // 只处理发给本请求且发送者有权限的消息
+        if !self.router.accepts_message(message) {
+            return None;
+        }
+
+        // 记录包含选项按钮的消息，用于后续更新按钮状态
+        if !self.options.is_empty() {
+            let contains_our_options = message
+                .reply_markup()
+                .map(|markup| {
+                    markup.inline_keyboard.iter().flatten().any(|button| {
+                        matches!(&button.kind, InlineKeyboardButtonKind::CallbackData(data) if data.starts_with("toggle:"))
+                    })
+                })
+                .unwrap_or(false);
+            if contains_our_options {
+                self.options_message_id = Some(message.id.0);
+            }
+        }
+
+        // 图片 / 文件转发到弹窗，随发送一并提交
+        match download_message_media(&self.core.bot, message, self.core.chat_id).await {
+            Ok(Some(received)) => {
+                let name = received.file_name();
+                events.push(match &received {
+                    TelegramMedia::Image(image) => TelegramEvent::ImageReceived {
+                        data_url: format!("data:{};base64,{}", image.media_type, image.data),
+                    },
+                    TelegramMedia::File(file) => TelegramEvent::FileReceived {
+                        attachment: file.clone(),
+                    },
+                });
+                self.media.push(received);
+                let _ = self.core.send_message(&build_media_ack(&name, &self.media)).await;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                let _ = self.core.send_message(&format!("❌ 附件接收失败：{}", e)).await;
+            }
+        }
+
+        let event = handle_text_message(message, self.core.chat_id, None).await.ok()??;
+        match event {
+            TelegramEvent::SendPressed => {
+                let selected_options = self.selected();
+                let feedback = build_feedback_message(&selected_options, &self.user_input, false);
+                let _ = self.core.send_message(&feedback).await;
+                Some(ChannelReply::Send {
+                    selected_options,
+                    user_input: self.user_input.clone(),
+                })
+            }
+            TelegramEvent::ContinuePressed => {
+                let _ = self.core.send_message(&build_feedback_message(&[], "", true)).await;
+                Some(ChannelReply::Continue)
+            }
+            TelegramEvent::TextUpdated { text } => {
+                // `key=值` 形式的行视为表单赋值，逐个同步到弹窗
+                let (assignments, remaining) = parse_form_text(&self.fields, &text);
+                let has_assignments = !assignments.is_empty();
+                for (key, value) in assignments {
+                    self.form_values.insert(key.clone(), value.clone());
+                    events.push(TelegramEvent::FormValueUpdated { key, value });
+                }
+                if has_assignments {
+                    self.refresh_form_keyboard().await;
+                    if remaining.is_empty() {
+                        return None;
+                    }
+                }
+
+                let text = if has_assignments { remaining } else { text };
+                self.user_input = text.clone();
+                events.push(TelegramEvent::TextUpdated { text });
+                None
+            }
+            other => {
+                events.push(other);
+                None
+            }
+        }
+    }
+
+    /// 已选选项（按原始顺序）
+    fn selected(&self) -> Vec<String> {
+        self.options
+            .iter()
+            .filter(|option| self.selected_options.contains(*option))
+            .cloned()
+            .collect()
+    }
+
+    async fn refresh_form_keyboard(&self) {
+        if let Some(msg_id) = self.form_message_id {
+            let _ = self
+                .core
+                .update_form_keyboard(msg_id, &self.fields, &self.form_values)
+                .await;
+        }
+    }
+}
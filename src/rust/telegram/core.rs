@@ -4,13 +4,21 @@ use serde::Serialize;
 use teloxide::{
     prelude::*,
     types::{
-        ChatId, InlineKeyboardButton, InlineKeyboardMarkup, KeyboardButton, KeyboardMarkup,
-        MessageId, ParseMode,
+        ChatId, InlineKeyboardButton, InlineKeyboardMarkup, InputFile, KeyboardButton,
+        KeyboardMarkup, MessageId, ParseMode, Recipient, ThreadId,
     },
     Bot,
 };
 
-use super::markdown::process_telegram_markdown;
+use super::form::{build_form_message, create_form_keyboard};
+use super::hub::bot_state_dir;
+use super::routing::{topic_name, ProjectTopics};
+use super::webhook::WebhookConfig;
+use crate::config::TelegramConfig;
+use crate::constants::telegram::API_BASE_URL;
+use super::markdown::{render_telegram_markdown, render_telegram_plain, TelegramPart};
+use crate::mcp::tools::interaction::ZhiField;
+use crate::mcp::types::FileAttachment;
 
 /// Telegram事件类型
 #[derive(Debug, Clone, Serialize)]
@@ -24,12 +32,24 @@ pub enum TelegramEvent {
     ContinuePressed,
     /// 发送按钮点击
     SendPressed,
+    /// 表单字段值更新
+    FormValueUpdated { key: String, value: serde_json::Value },
+    /// 收到图片（data URL 格式，与前端粘贴图片一致）
+    ImageReceived { data_url: String },
+    /// 收到非图片文件
+    FileReceived { attachment: FileAttachment },
 }
 
 /// Telegram Bot 核心功能
 pub struct TelegramCore {
     pub bot: Bot,
     pub chat_id: ChatId,
+    /// 论坛话题（群组开启话题且按项目拆分时使用）
+    pub thread_id: Option<ThreadId>,
+    /// 允许答复的用户ID，为空时不限制
+    pub allowed_user_ids: Vec<i64>,
+    /// Webhook 模式配置，为 None 时使用长轮询
+    pub webhook: Option<WebhookConfig>,
 }
 
 impl TelegramCore {
@@ -59,7 +79,73 @@ impl TelegramCore {
             ChatId(id)
         };
 
-        Ok(Self { bot, chat_id })
+        Ok(Self {
+            bot,
+            chat_id,
+            thread_id: None,
+            allowed_user_ids: Vec::new(),
+            webhook: None,
+        })
+    }
+
+    /// 根据配置创建实例，支持 @username 形式的公开群组
+    pub async fn from_config(config: &TelegramConfig) -> Result<Self> {
+        let api_url = if config.api_base_url == API_BASE_URL {
+            None
+        } else {
+            Some(config.api_base_url.clone())
+        };
+
+        let chat_id = config.chat_id.trim();
+        let mut core = if let Some(username) = chat_id.strip_prefix('@') {
+            let mut core = Self::new_with_api_url(config.bot_token.clone(), "0".to_string(), api_url)?;
+            let chat = core
+                .bot
+                .get_chat(Recipient::ChannelUsername(format!("@{}", username)))
+                .await
+                .map_err(|e| anyhow::anyhow!("无法解析 {}: {}", chat_id, e))?;
+            core.chat_id = chat.id;
+            core
+        } else {
+            Self::new_with_api_url(config.bot_token.clone(), chat_id.to_string(), api_url)?
+        };
+
+        core.allowed_user_ids = config.allowed_user_ids.clone();
+        core.webhook = WebhookConfig::from_config(config);
+        Ok(core)
+    }
+
+    /// 切换到项目对应的论坛话题，不存在时自动创建
+    ///
+    /// 群组未开启话题或创建失败时继续使用主聊天
+    pub async fn enter_project_topic(&mut self, project_root: Option<&str>) {
+        let Some(project_root) = project_root.filter(|p| !p.trim().is_empty()) else {
+            return;
+        };
+        let Ok(dir) = bot_state_dir(&self.bot) else {
+            return;
+        };
+
+        let mut topics = ProjectTopics::load(&dir);
+        if let Some(thread_id) = topics.get(self.chat_id.0, project_root) {
+            self.thread_id = Some(ThreadId(MessageId(thread_id)));
+            return;
+        }
+
+        match self
+            .bot
+            .create_forum_topic(self.chat_id, topic_name(project_root))
+            .await
+        {
+            Ok(topic) => {
+                topics.insert(self.chat_id.0, project_root, topic.thread_id.0 .0);
+                if let Err(e) = topics.save(&dir) {
+                    log_important!(warn, "保存 Telegram 话题映射失败: {}", e);
+                }
+                self.thread_id = Some(topic.thread_id);
+            }
+            Err(e) => log_important!(warn, "创建 Telegram 话题失败，使用主聊天: {}", e),
+        }
     }
 
     /// 发送普通消息
@@ -73,67 +159,167 @@ impl TelegramCore {
         message: &str,
         use_markdown: bool,
     ) -> Result<()> {
-        let mut send_request = self.bot.send_message(self.chat_id, message);
-
-        // 如果启用Markdown，设置解析模式
-        if use_markdown {
-            send_request = send_request.parse_mode(ParseMode::MarkdownV2);
-        }
-
-        send_request
+        let parts = Self::render_parts(message, use_markdown);
+        self.send_parts(parts, None)
             .await
-            .map_err(|e| anyhow::anyhow!("发送消息失败: {}", e))?;
-
-        Ok(())
+            .map(|_| ())
+            .map_err(|e| anyhow::anyhow!("发送消息失败: {}", e))
     }
 
-    /// 发送选项消息（消息一）
+    /// 发送选项消息（消息一），返回已发送的消息ID
     pub async fn send_options_message(
         &self,
         message: &str,
         predefined_options: &[String],
         is_markdown: bool,
-    ) -> Result<()> {
-        // 处理消息内容
-        let processed_message = if is_markdown {
-            process_telegram_markdown(message)
+    ) -> Result<Vec<i32>> {
+        let parts = Self::render_parts(message, is_markdown);
+
+        // 只有当有预定义选项时才添加inline keyboard
+        let inline_keyboard = if predefined_options.is_empty() {
+            None
         } else {
-            message.to_string()
+            Some(Self::create_inline_keyboard(predefined_options, &[])?)
         };
 
-        // 创建消息发送请求
-        let mut send_request = self.bot.send_message(self.chat_id, processed_message);
+        self.send_parts(parts, inline_keyboard)
+            .await
+            .map_err(|e| anyhow::anyhow!("发送选项消息失败: {}", e))
+    }
 
-        // 只有当有预定义选项时才添加inline keyboard
-        if !predefined_options.is_empty() {
-            let inline_keyboard = Self::create_inline_keyboard(predefined_options, &[])?;
-            send_request = send_request.reply_markup(inline_keyboard);
+    /// 将消息渲染为待发送片段
+    fn render_parts(message: &str, use_markdown: bool) -> Vec<TelegramPart> {
+        if use_markdown {
+            render_telegram_markdown(message)
+        } else {
+            render_telegram_plain(message)
         }
+    }
 
-        // 如果是Markdown，设置解析模式
-        if is_markdown {
-            send_request = send_request.parse_mode(ParseMode::MarkdownV2);
+    /// 依次发送渲染后的片段，返回已发送的消息ID
+    ///
+    /// 键盘附加在最后一条文本上；若最后一个片段是文件或图片，则单独发送一条带键盘的提示
+    async fn send_parts(
+        &self,
+        parts: Vec<TelegramPart>,
+        reply_markup: Option<InlineKeyboardMarkup>,
+    ) -> Result<Vec<i32>> {
+        let last_index = parts.len().saturating_sub(1);
+        let mut reply_markup = reply_markup;
+        let mut message_ids = Vec::new();
+
+        for (index, part) in parts.into_iter().enumerate() {
+            let result = match part {
+                TelegramPart::Text(html) => {
+                    let mut request = self.text_request(html).parse_mode(ParseMode::Html);
+                    if index == last_index {
+                        if let Some(markup) = reply_markup.take() {
+                            request = request.reply_markup(markup);
+                        }
+                    }
+                    request.await
+                }
+                TelegramPart::Document {
+                    file_name,
+                    content,
+                    caption,
+                } => {
+                    let mut request = self
+                        .bot
+                        .send_document(self.chat_id, InputFile::memory(content).file_name(file_name))
+                        .caption(caption);
+                    if let Some(thread_id) = self.thread_id {
+                        request = request.message_thread_id(thread_id);
+                    }
+                    request.await
+                }
+                TelegramPart::Photo {
+                    file_name,
+                    content,
+                    caption,
+                } => {
+                    let mut request = self
+                        .bot
+                        .send_photo(self.chat_id, InputFile::memory(content).file_name(file_name))
+                        .caption(caption);
+                    if let Some(thread_id) = self.thread_id {
+                        request = request.message_thread_id(thread_id);
+                    }
+                    request.await
+                }
+            };
+
+            match result {
+                Ok(msg) => message_ids.push(msg.id.0),
+                Err(e) => Self::tolerate_parsing_error(e)?,
+            }
         }
 
-        match send_request.await {
-            Ok(_) => Ok(()),
-            Err(e) => {
-                let error_str = e.to_string();
+        if let Some(markup) = reply_markup {
+            match self.text_request("请选择：".to_string()).reply_markup(markup).await {
+                Ok(msg) => message_ids.push(msg.id.0),
+                Err(e) => Self::tolerate_parsing_error(e)?,
+            }
+        }
 
-                // 检查是否是JSON解析错误但消息实际发送成功
-                let has_parsing_json = error_str.contains("parsing JSON");
-                let has_ok_true = error_str.contains("\\\"ok\\\":true");
+        Ok(message_ids)
+    }
 
-                if has_parsing_json && has_ok_true {
-                    // 消息实际发送成功
-                    Ok(())
-                } else {
-                    Err(anyhow::anyhow!("发送选项消息失败: {}", e))
-                }
-            }
+    /// 创建发往当前聊天（及话题）的文本消息请求
+    fn text_request(&self, text: String) -> <Bot as Requester>::SendMessage {
+        let request = self.bot.send_message(self.chat_id, text);
+        match self.thread_id {
+            Some(thread_id) => request.message_thread_id(thread_id),
+            None => request,
+        }
+    }
+
+    /// 检查是否是JSON解析错误但消息实际发送成功
+    fn tolerate_parsing_error(e: teloxide::RequestError) -> Result<()> {
+        let error_str = e.to_string();
+        if error_str.contains("parsing JSON") && error_str.contains("\\\"ok\\\":true") {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("{}", e))
         }
     }
 
+    /// 发送表单消息，返回消息ID（用于后续更新按钮状态）
+    pub async fn send_form_message(
+        &self,
+        fields: &[ZhiField],
+        values: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<i32> {
+        let keyboard = create_form_keyboard(fields, values);
+
+        let msg = self
+            .text_request(build_form_message(fields))
+            .reply_markup(keyboard)
+            .await
+            .map_err(|e| anyhow::anyhow!("发送表单消息失败: {}", e))?;
+
+        Ok(msg.id.0)
+    }
+
+    /// 更新表单消息中的按钮状态
+    pub async fn update_form_keyboard(
+        &self,
+        message_id: i32,
+        fields: &[ZhiField],
+        values: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<()> {
+        let keyboard = create_form_keyboard(fields, values);
+
+        // 键盘更新失败通常不是致命错误
+        let _ = self
+            .bot
+            .edit_message_reply_markup(self.chat_id, MessageId(message_id))
+            .reply_markup(keyboard)
+            .await;
+
+        Ok(())
+    }
+
     /// 发送操作消息（消息二）
     pub async fn send_operation_message(&self, continue_reply_enabled: bool) -> Result<i32> {
         // 创建reply keyboard
@@ -143,8 +329,7 @@ impl TelegramCore {
         let operation_message = "键盘上选择操作完成对话";
 
         match self
-            .bot
-            .send_message(self.chat_id, operation_message)
+            .text_request(operation_message.to_string())
             .reply_markup(reply_keyboard)
             .await
         {
@@ -273,7 +458,8 @@ pub async fn handle_text_message(
         }
     }
 
-    if let Some(text) = message.text() {
+    // 图片 / 文件的说明文字同样视为文本输入
+    if let Some(text) = message.text().or_else(|| message.caption()) {
         let event = match text {
             "⏩继续" => TelegramEvent::ContinuePressed,
             "↗️发送" => TelegramEvent::SendPressed,
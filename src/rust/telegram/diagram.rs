@@ -0,0 +1,632 @@
+// Telegram 图表渲染
+// 将 Mermaid 流程图和 diff 代码块绘制为 SVG，再用 resvg 转为 PNG
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use usvg::{PostProcessingSteps, TreeParsing, TreePostProc};
+
+use super::markdown::{display_width, is_wide_char};
+
+/// diff 图片最多渲染的行数（更长的 diff 以文件发送）
+const MAX_DIFF_LINES: usize = 300;
+/// Mermaid 图最多节点数
+const MAX_MERMAID_NODES: usize = 80;
+/// 输出图片的最大边长（像素）
+const MAX_IMAGE_SIDE: f32 = 4096.0;
+
+const SANS_FONTS: &str = "Noto Sans CJK SC, Source Han Sans SC, Microsoft YaHei, PingFang SC, WenQuanYi Micro Hei, DejaVu Sans, Arial, sans-serif";
+const MONO_FONTS: &str = "JetBrains Mono, Consolas, Menlo, DejaVu Sans Mono, Noto Sans Mono CJK SC, monospace";
+
+/// 系统字体库（加载较慢，进程内只加载一次）
+static FONT_DB: Lazy<usvg::fontdb::Database> = Lazy::new(|| {
+    let mut db = usvg::fontdb::Database::new();
+    db.load_system_fonts();
+    db
+});
+
+static LINK_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(<)?(-\.+-|-{2,}|={2,}|~{3,})(>|o\b|x\b)?").unwrap());
+
+/// 将 SVG 渲染为 PNG（2 倍缩放，白色背景）
+pub fn svg_to_png(svg: &str) -> Result<Vec<u8>> {
+    let mut tree = usvg::Tree::from_str(svg, &usvg::Options::default())
+        .map_err(|e| anyhow::anyhow!("SVG 解析失败: {}", e))?;
+    tree.postprocess(PostProcessingSteps::default(), &FONT_DB);
+
+    let size = tree.size;
+    let scale = (MAX_IMAGE_SIDE / size.width().max(size.height())).min(2.0);
+    let width = (size.width() * scale).ceil() as u32;
+    let height = (size.height() * scale).ceil() as u32;
+
+    let mut pixmap = tiny_skia::Pixmap::new(width, height)
+        .ok_or_else(|| anyhow::anyhow!("创建画布失败: {}x{}", width, height))?;
+    pixmap.fill(tiny_skia::Color::WHITE);
+    resvg::render(&tree, tiny_skia::Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+
+    pixmap
+        .encode_png()
+        .map_err(|e| anyhow::anyhow!("PNG 编码失败: {}", e))
+}
+
+/// 渲染 diff 代码块为 PNG
+pub fn render_diff_png(diff: &str) -> Result<Vec<u8>> {
+    svg_to_png(&build_diff_svg(diff)?)
+}
+
+/// 渲染 Mermaid 流程图为 PNG（仅支持 graph / flowchart）
+pub fn render_mermaid_png(source: &str) -> Result<Vec<u8>> {
+    let chart = parse_flowchart(source)?;
+    svg_to_png(&build_flowchart_svg(&chart))
+}
+
+// ============ diff ============
+
+fn build_diff_svg(diff: &str) -> Result<String> {
+    const CHAR_WIDTH: f32 = 8.4;
+    const LINE_HEIGHT: f32 = 20.0;
+    const PADDING: f32 = 12.0;
+
+    let lines: Vec<String> = diff.lines().map(|l| l.replace('\t', "    ")).collect();
+    if lines.is_empty() {
+        anyhow::bail!("diff 内容为空");
+    }
+    if lines.len() > MAX_DIFF_LINES {
+        anyhow::bail!("diff 过长（{} 行）", lines.len());
+    }
+
+    let max_width = lines.iter().map(|l| display_width(l)).max().unwrap_or(0);
+    let width = (max_width as f32 * CHAR_WIDTH + PADDING * 2.0).max(200.0);
+    let height = lines.len() as f32 * LINE_HEIGHT + PADDING * 2.0;
+
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}"><rect width="{w}" height="{h}" fill="#ffffff"/>"##,
+        w = width,
+        h = height
+    );
+
+    for (index, line) in lines.iter().enumerate() {
+        let y = PADDING + index as f32 * LINE_HEIGHT;
+        let (background, color, weight) = diff_line_style(line);
+        if let Some(background) = background {
+            let _ = write!(
+                svg,
+                r#"<rect x="0" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                y, width, LINE_HEIGHT, background
+            );
+        }
+        let _ = write!(
+            svg,
+            r#"<text x="{}" y="{}" font-family="{}" font-size="14" font-weight="{}" fill="{}" xml:space="preserve">{}</text>"#,
+            PADDING,
+            y + LINE_HEIGHT * 0.72,
+            MONO_FONTS,
+            weight,
+            color,
+            escape_xml(line)
+        );
+    }
+
+    svg.push_str("</svg>");
+    Ok(svg)
+}
+
+/// diff 行样式：(背景色, 文字颜色, 字重)
+fn diff_line_style(line: &str) -> (Option<&'static str>, &'static str, &'static str) {
+    if line.starts_with("+++") || line.starts_with("---") || line.starts_with("diff ") || line.starts_with("index ") {
+        (Some("#f6f8fa"), "#57606a", "bold")
+    } else if line.starts_with("@@") {
+        (Some("#ddf4ff"), "#0550ae", "normal")
+    } else if line.starts_with('+') {
+        (Some("#e6ffec"), "#116329", "normal")
+    } else if line.starts_with('-') {
+        (Some("#ffebe9"), "#82071e", "normal")
+    } else {
+        (None, "#24292f", "normal")
+    }
+}
+
+// ============ Mermaid 流程图 ============
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NodeShape {
+    Rect,
+    Round,
+    Stadium,
+    Diamond,
+    Circle,
+}
+
+#[derive(Debug, Clone)]
+struct FlowNode {
+    label: String,
+    shape: NodeShape,
+}
+
+#[derive(Debug, Clone)]
+struct FlowEdge {
+    from: usize,
+    to: usize,
+    label: Option<String>,
+    dashed: bool,
+    thick: bool,
+    arrow: bool,
+    hidden: bool,
+}
+
+#[derive(Debug, Default)]
+struct Flowchart {
+    horizontal: bool,
+    reversed: bool,
+    nodes: Vec<FlowNode>,
+    edges: Vec<FlowEdge>,
+    ids: HashMap<String, usize>,
+}
+
+fn parse_flowchart(source: &str) -> Result<Flowchart> {
+    let mut lines = source
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with("%%"));
+
+    let header = lines.next().ok_or_else(|| anyhow::anyhow!("Mermaid 内容为空"))?;
+    let mut header_parts = header.split_whitespace();
+    let kind = header_parts.next().unwrap_or_default();
+    if kind != "graph" && kind != "flowchart" {
+        anyhow::bail!("暂不支持的 Mermaid 图表类型: {}", kind);
+    }
+
+    let mut chart = Flowchart::default();
+    match header_parts.next().map(|d| d.trim_end_matches(';')) {
+        Some("LR") => chart.horizontal = true,
+        Some("RL") => {
+            chart.horizontal = true;
+            chart.reversed = true;
+        }
+        Some("BT") => chart.reversed = true,
+        _ => {}
+    }
+
+    for line in lines {
+        for statement in line.split(';') {
+            chart.parse_statement(statement.trim())?;
+        }
+    }
+
+    if chart.nodes.is_empty() {
+        anyhow::bail!("Mermaid 图中没有节点");
+    }
+    if chart.nodes.len() > MAX_MERMAID_NODES {
+        anyhow::bail!("Mermaid 节点过多（{} 个）", chart.nodes.len());
+    }
+    Ok(chart)
+}
+
+impl Flowchart {
+    fn parse_statement(&mut self, statement: &str) -> Result<()> {
+        const IGNORED: [&str; 7] = ["subgraph ", "classDef ", "class ", "style ", "linkStyle ", "click ", "direction "];
+        if statement.is_empty() || statement == "end" || IGNORED.iter().any(|p| statement.starts_with(p)) {
+            return Ok(());
+        }
+
+        let (mut previous, mut rest) = self
+            .parse_node_group(statement)
+            .ok_or_else(|| anyhow::anyhow!("无法解析 Mermaid 语句: {}", statement))?;
+
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                break;
+            }
+            let (link, after_link) = parse_link(rest)
+                .ok_or_else(|| anyhow::anyhow!("无法解析 Mermaid 连线: {}", statement))?;
+            let (targets, after_targets) = self
+                .parse_node_group(after_link.trim_start())
+                .ok_or_else(|| anyhow::anyhow!("无法解析 Mermaid 语句: {}", statement))?;
+
+            for &from in &previous {
+                for &to in &targets {
+                    self.edges.push(FlowEdge { from, to, ..link.clone() });
+                }
+            }
+            previous = targets;
+            rest = after_targets;
+        }
+        Ok(())
+    }
+
+    /// 解析 `A & B[标签]` 形式的节点组
+    fn parse_node_group<'a>(&mut self, input: &'a str) -> Option<(Vec<usize>, &'a str)> {
+        let (first, mut rest) = self.parse_node(input)?;
+        let mut nodes = vec![first];
+        while let Some(after) = rest.trim_start().strip_prefix('&') {
+            let (node, after_node) = self.parse_node(after.trim_start())?;
+            nodes.push(node);
+            rest = after_node;
+        }
+        Some((nodes, rest))
+    }
+
+    fn parse_node<'a>(&mut self, input: &'a str) -> Option<(usize, &'a str)> {
+        let id_len: usize = input
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .map(char::len_utf8)
+            .sum();
+        if id_len == 0 {
+            return None;
+        }
+        let id = &input[..id_len];
+        let mut rest = &input[id_len..];
+
+        const SHAPES: [(&str, &str, NodeShape); 10] = [
+            ("((", "))", NodeShape::Circle),
+            ("([", "])", NodeShape::Stadium),
+            ("[[", "]]", NodeShape::Rect),
+            ("[(", ")]", NodeShape::Stadium),
+            ("[/", "/]", NodeShape::Rect),
+            ("{{", "}}", NodeShape::Diamond),
+            ("[", "]", NodeShape::Rect),
+            ("(", ")", NodeShape::Round),
+            ("{", "}", NodeShape::Diamond),
+            (">", "]", NodeShape::Rect),
+        ];
+
+        let mut shape = None;
+        for (open, close, kind) in SHAPES {
+            if let Some(after_open) = rest.strip_prefix(open) {
+                let end = after_open.find(close)?;
+                let label = after_open[..end].trim().trim_matches('"').to_string();
+                shape = Some((label, kind));
+                rest = &after_open[end + close.len()..];
+                break;
+            }
+        }
+
+        // 忽略 :::className 样式标记
+        if let Some(after) = rest.strip_prefix(":::") {
+            let class_len: usize = after
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '-')
+                .map(char::len_utf8)
+                .sum();
+            rest = &after[class_len..];
+        }
+
+        let index = match self.ids.get(id) {
+            Some(&index) => index,
+            None => {
+                self.nodes.push(FlowNode {
+                    label: id.to_string(),
+                    shape: NodeShape::Rect,
+                });
+                self.ids.insert(id.to_string(), self.nodes.len() - 1);
+                self.nodes.len() - 1
+            }
+        };
+        if let Some((label, kind)) = shape {
+            self.nodes[index] = FlowNode { label, shape: kind };
+        }
+        Some((index, rest))
+    }
+}
+
+/// 解析连线，返回连线样式（from/to 由调用方填充）和剩余文本
+fn parse_link(input: &str) -> Option<(FlowEdge, &str)> {
+    let captures = LINK_RE.captures(input)?;
+    let token = captures.get(2)?.as_str();
+    let head = captures.get(3).map(|m| m.as_str());
+    let mut rest = &input[captures.get(0)?.end()..];
+
+    let mut edge = FlowEdge {
+        from: 0,
+        to: 0,
+        label: None,
+        dashed: token.contains('.'),
+        thick: token.starts_with('='),
+        arrow: head == Some(">"),
+        hidden: token.starts_with('~'),
+    };
+
+    // `-- 文本 -->` 形式的连线文字
+    if head.is_none() && matches!(token, "--" | "==" | "-.") && rest.starts_with(char::is_whitespace) {
+        const CLOSINGS: [&str; 6] = ["-.->", "-->", "==>", "---", "===", "-.-"];
+        let (position, closing) = CLOSINGS
+            .iter()
+            .filter_map(|c| rest.find(c).map(|p| (p, *c)))
+            .min_by_key(|(p, _)| *p)?;
+        edge.label = Some(rest[..position].trim().to_string());
+        edge.arrow = closing.ends_with('>');
+        edge.dashed = closing.contains('.');
+        rest = &rest[position + closing.len()..];
+    }
+
+    // `-->|文本|` 形式的连线文字
+    let trimmed = rest.trim_start();
+    if let Some(after) = trimmed.strip_prefix('|') {
+        let end = after.find('|')?;
+        edge.label = Some(after[..end].trim().trim_matches('"').to_string());
+        rest = &after[end + 1..];
+    }
+
+    edge.label = edge.label.filter(|l| !l.is_empty());
+    Some((edge, rest))
+}
+
+/// 节点布局结果
+#[derive(Debug, Clone, Copy)]
+struct NodeBox {
+    cx: f32,
+    cy: f32,
+    width: f32,
+    height: f32,
+}
+
+fn node_size(node: &FlowNode) -> (f32, f32) {
+    let text_width = node
+        .label
+        .chars()
+        .map(|c| if is_wide_char(c) { 14.0 } else { 7.8 })
+        .sum::<f32>();
+    let width = (text_width + 32.0).max(64.0);
+    match node.shape {
+        NodeShape::Diamond => (width + 32.0, 64.0),
+        NodeShape::Circle => {
+            let d = width.max(56.0);
+            (d, d)
+        }
+        _ => (width, 40.0),
+    }
+}
+
+/// 分层布局：按最长路径分层，层内按前驱重心排序
+fn layout_flowchart(chart: &Flowchart) -> (Vec<NodeBox>, f32, f32) {
+    const PADDING: f32 = 24.0;
+    const RANK_GAP: f32 = 64.0;
+    const NODE_GAP: f32 = 36.0;
+
+    let count = chart.nodes.len();
+    let mut ranks = vec![0usize; count];
+    for _ in 0..count {
+        let mut changed = false;
+        for edge in chart.edges.iter().filter(|e| e.from != e.to) {
+            let candidate = ranks[edge.from] + 1;
+            if candidate > ranks[edge.to] && candidate < count {
+                ranks[edge.to] = candidate;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let rank_count = ranks.iter().max().copied().unwrap_or(0) + 1;
+    let mut layers: Vec<Vec<usize>> = vec![Vec::new(); rank_count];
+    for (index, rank) in ranks.iter().enumerate() {
+        layers[*rank].push(index);
+    }
+
+    // 按上一层前驱的平均位置排序，减少交叉
+    for rank in 1..rank_count {
+        let previous: HashMap<usize, usize> = layers[rank - 1].iter().enumerate().map(|(pos, &n)| (n, pos)).collect();
+        let mut keyed: Vec<(f32, usize)> = layers[rank]
+            .iter()
+            .enumerate()
+            .map(|(pos, &node)| {
+                let preds: Vec<usize> = chart
+                    .edges
+                    .iter()
+                    .filter(|e| e.to == node)
+                    .filter_map(|e| previous.get(&e.from).copied())
+                    .collect();
+                let key = if preds.is_empty() {
+                    pos as f32
+                } else {
+                    preds.iter().sum::<usize>() as f32 / preds.len() as f32
+                };
+                (key, node)
+            })
+            .collect();
+        keyed.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        layers[rank] = keyed.into_iter().map(|(_, node)| node).collect();
+    }
+
+    let sizes: Vec<(f32, f32)> = chart.nodes.iter().map(node_size).collect();
+    // 主轴：层方向；交叉轴：层内排列方向
+    let main_size = |n: usize| if chart.horizontal { sizes[n].0 } else { sizes[n].1 };
+    let cross_size = |n: usize| if chart.horizontal { sizes[n].1 } else { sizes[n].0 };
+
+    let layer_main: Vec<f32> = layers
+        .iter()
+        .map(|layer| layer.iter().map(|&n| main_size(n)).fold(0.0, f32::max))
+        .collect();
+    let layer_cross: Vec<f32> = layers
+        .iter()
+        .map(|layer| {
+            layer.iter().map(|&n| cross_size(n)).sum::<f32>() + NODE_GAP * (layer.len().saturating_sub(1)) as f32
+        })
+        .collect();
+
+    let total_main = layer_main.iter().sum::<f32>() + RANK_GAP * (rank_count - 1) as f32;
+    let total_cross = layer_cross.iter().copied().fold(0.0, f32::max);
+
+    let mut boxes = vec![
+        NodeBox {
+            cx: 0.0,
+            cy: 0.0,
+            width: 0.0,
+            height: 0.0
+        };
+        count
+    ];
+    let mut main_offset = 0.0;
+    for (rank, layer) in layers.iter().enumerate() {
+        let main_center = main_offset + layer_main[rank] / 2.0;
+        let main_pos = if chart.reversed { total_main - main_center } else { main_center };
+        let mut cross_offset = (total_cross - layer_cross[rank]) / 2.0;
+        for &node in layer {
+            let cross_pos = cross_offset + cross_size(node) / 2.0;
+            cross_offset += cross_size(node) + NODE_GAP;
+            let (cx, cy) = if chart.horizontal { (main_pos, cross_pos) } else { (cross_pos, main_pos) };
+            boxes[node] = NodeBox {
+                cx: cx + PADDING,
+                cy: cy + PADDING,
+                width: sizes[node].0,
+                height: sizes[node].1,
+            };
+        }
+        main_offset += layer_main[rank] + RANK_GAP;
+    }
+
+    let (width, height) = if chart.horizontal { (total_main, total_cross) } else { (total_cross, total_main) };
+    (boxes, width + PADDING * 2.0, height + PADDING * 2.0)
+}
+
+/// 连线端点：从节点中心指向目标点，截断在节点边框上
+fn clip_to_box(node: &NodeBox, toward_x: f32, toward_y: f32) -> (f32, f32) {
+    let dx = toward_x - node.cx;
+    let dy = toward_y - node.cy;
+    if dx.abs() < f32::EPSILON && dy.abs() < f32::EPSILON {
+        return (node.cx, node.cy);
+    }
+    let tx = if dx.abs() > f32::EPSILON { (node.width / 2.0) / dx.abs() } else { f32::INFINITY };
+    let ty = if dy.abs() > f32::EPSILON { (node.height / 2.0) / dy.abs() } else { f32::INFINITY };
+    let t = tx.min(ty);
+    (node.cx + dx * t, node.cy + dy * t)
+}
+
+fn build_flowchart_svg(chart: &Flowchart) -> String {
+    let (boxes, width, height) = layout_flowchart(chart);
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}"><defs><marker id="arrow" viewBox="0 0 10 10" refX="9" refY="5" markerWidth="8" markerHeight="8" orient="auto"><path d="M0,0 L10,5 L0,10 z" fill="#333333"/></marker></defs><rect width="{w}" height="{h}" fill="#ffffff"/>"##,
+        w = width,
+        h = height
+    );
+
+    // 先画连线，节点覆盖在上方
+    let mut labels = String::new();
+    for edge in chart.edges.iter().filter(|e| !e.hidden && e.from != e.to) {
+        let from = &boxes[edge.from];
+        let to = &boxes[edge.to];
+        let (x1, y1) = clip_to_box(from, to.cx, to.cy);
+        let (x2, y2) = clip_to_box(to, from.cx, from.cy);
+
+        let style = format!(
+            r##"fill="none" stroke="#333333" stroke-width="{}"{}{}"##,
+            if edge.thick { 3.0 } else { 1.5 },
+            if edge.dashed { r#" stroke-dasharray="6 4""# } else { "" },
+            if edge.arrow { r#" marker-end="url(#arrow)""# } else { "" }
+        );
+        let _ = write!(svg, r#"<path d="M{:.1},{:.1} L{:.1},{:.1}" {}/>"#, x1, y1, x2, y2, style);
+
+        if let Some(label) = &edge.label {
+            let (mx, my) = ((x1 + x2) / 2.0, (y1 + y2) / 2.0);
+            let label_width = display_width(label) as f32 * 7.5 + 12.0;
+            let _ = write!(
+                labels,
+                r##"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="20" fill="#ffffff" fill-opacity="0.9"/><text x="{:.1}" y="{:.1}" font-family="{}" font-size="12" fill="#333333" text-anchor="middle">{}</text>"##,
+                mx - label_width / 2.0,
+                my - 10.0,
+                label_width,
+                mx,
+                my + 4.0,
+                SANS_FONTS,
+                escape_xml(label)
+            );
+        }
+    }
+    svg.push_str(&labels);
+
+    for (node, b) in chart.nodes.iter().zip(boxes.iter()) {
+        let (left, top) = (b.cx - b.width / 2.0, b.cy - b.height / 2.0);
+        let style = r##"fill="#ececff" stroke="#9370db" stroke-width="1.5""##;
+        let _ = match node.shape {
+            NodeShape::Rect => write!(
+                svg,
+                r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" rx="4" {}/>"#,
+                left, top, b.width, b.height, style
+            ),
+            NodeShape::Round | NodeShape::Stadium => write!(
+                svg,
+                r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" rx="{:.1}" {}/>"#,
+                left,
+                top,
+                b.width,
+                b.height,
+                if node.shape == NodeShape::Stadium { b.height / 2.0 } else { 10.0 },
+                style
+            ),
+            NodeShape::Diamond => write!(
+                svg,
+                r#"<polygon points="{:.1},{:.1} {:.1},{:.1} {:.1},{:.1} {:.1},{:.1}" {}/>"#,
+                b.cx, top, left + b.width, b.cy, b.cx, top + b.height, left, b.cy, style
+            ),
+            NodeShape::Circle => write!(
+                svg,
+                r#"<ellipse cx="{:.1}" cy="{:.1}" rx="{:.1}" ry="{:.1}" {}/>"#,
+                b.cx,
+                b.cy,
+                b.width / 2.0,
+                b.height / 2.0,
+                style
+            ),
+        };
+        let _ = write!(
+            svg,
+            r##"<text x="{:.1}" y="{:.1}" font-family="{}" font-size="14" fill="#333333" text-anchor="middle">{}</text>"##,
+            b.cx,
+            b.cy + 5.0,
+            SANS_FONTS,
+            escape_xml(&node.label)
+        );
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_flowchart() {
+        let chart = parse_flowchart(
+            "graph LR\n  A[开始] --> B{是否通过?}\n  B -->|是| C(发布)\n  B -- 否 --> D((回滚))\n  C & D -.-> E",
+        )
+        .expect("flowchart");
+
+        assert!(chart.horizontal);
+        assert_eq!(chart.nodes.len(), 5);
+        assert_eq!(chart.nodes[1].shape, NodeShape::Diamond);
+        assert_eq!(chart.nodes[3].shape, NodeShape::Circle);
+        assert_eq!(chart.edges.len(), 5);
+        assert_eq!(chart.edges[1].label.as_deref(), Some("是"));
+        assert_eq!(chart.edges[2].label.as_deref(), Some("否"));
+        assert!(chart.edges[4].dashed && chart.edges[4].arrow);
+
+        assert!(parse_flowchart("sequenceDiagram\nA->>B: hi").is_err());
+    }
+
+    #[test]
+    fn test_render_png() {
+        let png = render_mermaid_png("graph TD\nA-->B\nA-->C").expect("mermaid png");
+        assert!(png.starts_with(&[0x89, b'P', b'N', b'G']));
+
+        let png = render_diff_png("--- a\n+++ b\n@@ -1 +1 @@\n-old\n+new").expect("diff png");
+        assert!(png.starts_with(&[0x89, b'P', b'N', b'G']));
+    }
+}
@@ -0,0 +1,167 @@
+use serde_json::{Map, Value};
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+
+use crate::mcp::tools::interaction::{ZhiDangerLevel, ZhiField, ZhiFieldKind};
+
+/// 表单回调数据前缀（callback_data 限长 64 字节，使用字段/选项下标）
+pub const FORM_CALLBACK_PREFIX: &str = "form:";
+
+/// 生成表单说明消息（纯文本）
+pub fn build_form_message(fields: &[ZhiField]) -> String {
+    let mut message = "📋 请填写表单：\n".to_string();
+
+    for field in fields {
+        let required = if field.required { "（必填）" } else { "" };
+        let hint = match field.kind {
+            ZhiFieldKind::SingleSelect => "点击下方按钮单选".to_string(),
+            ZhiFieldKind::MultiSelect => "点击下方按钮多选".to_string(),
+            ZhiFieldKind::Boolean => "点击下方按钮切换".to_string(),
+            ZhiFieldKind::Confirm => match field.danger_level {
+                Some(ZhiDangerLevel::High) => "⚠️ 高危操作，点击下方按钮确认".to_string(),
+                Some(ZhiDangerLevel::Medium) => "⚠️ 点击下方按钮确认".to_string(),
+                _ => "点击下方按钮确认".to_string(),
+            },
+            ZhiFieldKind::Text | ZhiFieldKind::Number | ZhiFieldKind::FilePath => {
+                format!("回复 {}=<值>", field.key)
+            }
+        };
+        message.push_str(&format!("\n• {}{}：{}", field.label, required, hint));
+        if let Some(description) = &field.description {
+            message.push_str(&format!("\n  {}", description));
+        }
+    }
+
+    message
+}
+
+/// 创建表单 inline keyboard（仅包含可点击的字段）
+pub fn create_form_keyboard(fields: &[ZhiField], values: &Map<String, Value>) -> InlineKeyboardMarkup {
+    let mut rows = Vec::new();
+
+    for (field_index, field) in fields.iter().enumerate() {
+        let current = values.get(&field.key);
+        match field.kind {
+            ZhiFieldKind::SingleSelect | ZhiFieldKind::MultiSelect => {
+                for (chunk_index, chunk) in field.options.chunks(2).enumerate() {
+                    let row = chunk
+                        .iter()
+                        .enumerate()
+                        .map(|(offset, option)| {
+                            let option_index = chunk_index * 2 + offset;
+                            let selected = is_option_selected(current, option);
+                            let text = if selected {
+                                format!("✅ {}: {}", field.label, option)
+                            } else {
+                                format!("{}: {}", field.label, option)
+                            };
+                            InlineKeyboardButton::callback(
+                                text,
+                                format!("{}{}:{}", FORM_CALLBACK_PREFIX, field_index, option_index),
+                            )
+                        })
+                        .collect();
+                    rows.push(row);
+                }
+            }
+            ZhiFieldKind::Boolean | ZhiFieldKind::Confirm => {
+                let checked = current.and_then(Value::as_bool).unwrap_or(false);
+                let mark = if checked { "✅" } else { "⬜" };
+                rows.push(vec![InlineKeyboardButton::callback(
+                    format!("{} {}", mark, field.label),
+                    format!("{}{}:0", FORM_CALLBACK_PREFIX, field_index),
+                )]);
+            }
+            _ => {}
+        }
+    }
+
+    InlineKeyboardMarkup::new(rows)
+}
+
+/// 应用表单按钮回调，返回被更新的字段 key
+pub fn apply_form_callback(
+    fields: &[ZhiField],
+    values: &mut Map<String, Value>,
+    data: &str,
+) -> Option<String> {
+    let rest = data.strip_prefix(FORM_CALLBACK_PREFIX)?;
+    let (field_part, option_part) = rest.split_once(':')?;
+    let field = fields.get(field_part.parse::<usize>().ok()?)?;
+    let option_index = option_part.parse::<usize>().ok()?;
+
+    match field.kind {
+        ZhiFieldKind::SingleSelect => {
+            let option = field.options.get(option_index)?;
+            values.insert(field.key.clone(), Value::String(option.clone()));
+        }
+        ZhiFieldKind::MultiSelect => {
+            let option = field.options.get(option_index)?;
+            let mut selected: Vec<Value> = values
+                .get(&field.key)
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default();
+            if let Some(pos) = selected.iter().position(|v| v.as_str() == Some(option)) {
+                selected.remove(pos);
+            } else {
+                selected.push(Value::String(option.clone()));
+            }
+            values.insert(field.key.clone(), Value::Array(selected));
+        }
+        ZhiFieldKind::Boolean | ZhiFieldKind::Confirm => {
+            let checked = values.get(&field.key).and_then(Value::as_bool).unwrap_or(false);
+            values.insert(field.key.clone(), Value::Bool(!checked));
+        }
+        _ => return None,
+    }
+
+    Some(field.key.clone())
+}
+
+/// 从文本消息中解析 `key=值` 形式的表单赋值
+///
+/// 返回 (已解析的赋值, 剩余的普通文本)
+pub fn parse_form_text(fields: &[ZhiField], text: &str) -> (Vec<(String, Value)>, String) {
+    let mut assignments = Vec::new();
+    let mut remaining = Vec::new();
+
+    for line in text.lines() {
+        let parsed = line.split_once('=').and_then(|(key, value)| {
+            let key = key.trim();
+            fields
+                .iter()
+                .find(|f| f.key == key)
+                .map(|field| (field.key.clone(), parse_field_text(field, value.trim())))
+        });
+
+        match parsed {
+            Some(assignment) => assignments.push(assignment),
+            None => remaining.push(line),
+        }
+    }
+
+    (assignments, remaining.join("\n").trim().to_string())
+}
+
+/// 将文本值转换为字段对应的 JSON 值（最终合法性由表单校验负责）
+fn parse_field_text(field: &ZhiField, value: &str) -> Value {
+    match field.kind {
+        ZhiFieldKind::MultiSelect => Value::Array(
+            value
+                .split(',')
+                .map(|item| item.trim())
+                .filter(|item| !item.is_empty())
+                .map(|item| Value::String(item.to_string()))
+                .collect(),
+        ),
+        _ => Value::String(value.to_string()),
+    }
+}
+
+fn is_option_selected(current: Option<&Value>, option: &str) -> bool {
+    match current {
+        Some(Value::String(s)) => s == option,
+        Some(Value::Array(items)) => items.iter().any(|v| v.as_str() == Some(option)),
+        _ => false,
+    }
+}
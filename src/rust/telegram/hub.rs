@@ -0,0 +1,287 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use teloxide::prelude::*;
+use teloxide::types::Update;
+use tokio::sync::mpsc;
+
+use super::webhook::{WebhookConfig, WebhookServer};
+use super::TelegramCore;
+use crate::constants::telegram::{
+    LONG_POLL_TIMEOUT_SECS, MAX_SPOOLED_UPDATES, SPOOL_READ_INTERVAL_MS,
+};
+use crate::{log_debug, log_important};
+
+/// 共享更新队列中的一条记录
+#[derive(Debug, Serialize, Deserialize)]
+struct SpooledUpdate {
+    seq: u64,
+    update: Update,
+}
+
+/// 多进程共享的 Telegram 更新分发器
+///
+/// 同一个 Bot 同时只能有一个 getUpdates 调用方，否则更新会被其他请求“抢走”。
+/// 各进程竞争锁文件上的系统文件锁选出一个轮询者，轮询者将更新写入共享队列，所有进程从队列读取。
+/// Webhook 模式下由轮询者启动接收服务，同样写入共享队列
+pub struct UpdateHub {
+    bot: Bot,
+    dir: PathBuf,
+    instance_id: String,
+    last_seq: u64,
+    /// 轮询者持有的锁文件（系统文件锁随文件关闭或进程退出释放）
+    poller_lock: Option<fs::File>,
+    opened_at: chrono::DateTime<chrono::Utc>,
+    webhook: Option<WebhookConfig>,
+    /// 轮询者持有的 Webhook 接收服务及其更新通道
+    receiver: Option<(WebhookServer, mpsc::UnboundedReceiver<Update>)>,
+    /// 长轮询模式下是否已清除残留的 Webhook
+    webhook_cleared: bool,
+}
+
+impl UpdateHub {
+    /// 打开 Bot 对应的共享队列，只接收打开之后到达的更新
+    pub fn open(core: &TelegramCore) -> Result<Self> {
+        Self::open_in(core, bot_state_dir(&core.bot)?)
+    }
+
+    /// 在指定状态目录中打开共享队列
+    pub fn open_in(core: &TelegramCore, dir: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let mut hub = Self {
+            bot: core.bot.clone(),
+            dir,
+            instance_id: format!("{}-{}", std::process::id(), uuid::Uuid::new_v4()),
+            last_seq: 0,
+            poller_lock: None,
+            opened_at: chrono::Utc::now(),
+            webhook: core.webhook.clone(),
+            receiver: None,
+            webhook_cleared: false,
+        };
+        hub.last_seq = hub.read_spool().last().map(|u| u.seq).unwrap_or(0);
+        Ok(hub)
+    }
+
+    /// 状态目录（路由、话题等共享文件所在位置）
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 等待并返回新的更新
+    ///
+    /// 轮询者会阻塞在长轮询（或等待 Webhook 推送）上；其他进程短暂休眠后读取共享队列
+    pub async fn next_updates(&mut self) -> Vec<Update> {
+        if self.try_lead() {
+            let result = if self.webhook.is_some() {
+                self.receive_webhook().await
+            } else {
+                self.poll_once().await
+            };
+            if let Err(e) = result {
+                log_debug!("Telegram 更新接收失败: {}", e);
+                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+            }
+        } else {
+            tokio::time::sleep(tokio::time::Duration::from_millis(SPOOL_READ_INTERVAL_MS)).await;
+        }
+
+        let updates: Vec<SpooledUpdate> = self
+            .read_spool()
+            .into_iter()
+            .filter(|u| u.seq > self.last_seq)
+            .collect();
+        if let Some(last) = updates.last() {
+            self.last_seq = last.seq;
+        }
+        updates
+            .into_iter()
+            .map(|u| u.update)
+            .filter(|u| !self.is_stale(u))
+            .collect()
+    }
+
+    /// 打开队列之前发出的消息不可能是对本请求的答复（如无人监听期间积压的消息）
+    fn is_stale(&self, update: &Update) -> bool {
+        match &update.kind {
+            teloxide::types::UpdateKind::Message(message) => {
+                message.date < self.opened_at - chrono::Duration::seconds(5)
+            }
+            _ => false,
+        }
+    }
+
+    /// 尝试成为（或保持）轮询者
+    ///
+    /// 以系统文件锁选举：加锁是原子的，持有者退出（包括崩溃）后锁自动释放
+    fn try_lead(&mut self) -> bool {
+        if self.poller_lock.is_some() {
+            return true;
+        }
+
+        let Some(file) = try_lock_poller(&self.dir) else {
+            return false;
+        };
+        log_debug!("成为 Telegram 更新轮询者: {}", self.instance_id);
+        self.poller_lock = Some(file);
+        true
+    }
+
+    async fn poll_once(&mut self) -> Result<()> {
+        // 设置过 Webhook 的 Bot 无法调用 getUpdates，切回长轮询时先清除
+        if !self.webhook_cleared {
+            self.bot.delete_webhook().await?;
+            self.webhook_cleared = true;
+        }
+
+        let offset_path = self.dir.join("offset");
+        let offset = fs::read_to_string(&offset_path)
+            .ok()
+            .and_then(|s| s.trim().parse::<i32>().ok())
+            .unwrap_or(0);
+
+        let updates = self
+            .bot
+            .get_updates()
+            .offset(offset)
+            .timeout(LONG_POLL_TIMEOUT_SECS)
+            .await?;
+        if updates.is_empty() {
+            return Ok(());
+        }
+
+        let next_offset = updates.last().map(|u| u.id.0 as i32 + 1).unwrap_or(offset);
+        self.append_spool(updates)?;
+        fs::write(&offset_path, next_offset.to_string())?;
+        Ok(())
+    }
+
+    /// Webhook 模式：确保接收服务已启动，等待推送并写入共享队列
+    async fn receive_webhook(&mut self) -> Result<()> {
+        if self.receiver.is_none() {
+            let config = self.webhook.clone().ok_or_else(|| anyhow::anyhow!("未配置 Webhook"))?;
+            let (sender, receiver) = mpsc::unbounded_channel();
+            let server = WebhookServer::start(&config, sender).await?;
+
+            let url = reqwest::Url::parse(&config.public_url)
+                .map_err(|e| anyhow::anyhow!("无效的 Webhook 地址: {}", e))?;
+            self.bot
+                .set_webhook(url)
+                .secret_token(config.secret_token.clone())
+                .await
+                .map_err(|e| anyhow::anyhow!("设置 Webhook 失败: {}", e))?;
+            log_important!(info, "Telegram Webhook 已设置: {}", config.public_url);
+            self.receiver = Some((server, receiver));
+        }
+
+        let Some((_, receiver)) = self.receiver.as_mut() else {
+            return Ok(());
+        };
+
+        // 超时返回以便刷新轮询锁
+        let timeout = tokio::time::Duration::from_secs(LONG_POLL_TIMEOUT_SECS as u64);
+        let mut updates = match tokio::time::timeout(timeout, receiver.recv()).await {
+            Ok(Some(update)) => vec![update],
+            Ok(None) => {
+                self.receiver = None;
+                return Err(anyhow::anyhow!("Webhook 接收服务已停止"));
+            }
+            Err(_) => return Ok(()),
+        };
+        while let Ok(update) = receiver.try_recv() {
+            updates.push(update);
+        }
+
+        self.append_spool(updates)
+    }
+
+    fn append_spool(&self, updates: Vec<Update>) -> Result<()> {
+        let spool_path = self.dir.join("updates.jsonl");
+        let mut entries = self.read_spool();
+        let mut seq = entries.last().map(|u| u.seq).unwrap_or(0);
+
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(&spool_path)?;
+        for update in updates {
+            seq += 1;
+            let entry = SpooledUpdate { seq, update };
+            writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+            entries.push(entry);
+        }
+        drop(file);
+
+        // 队列过长时保留最近的记录（写临时文件后替换，避免读到半截内容）
+        if entries.len() > MAX_SPOOLED_UPDATES * 2 {
+            let keep = &entries[entries.len() - MAX_SPOOLED_UPDATES..];
+            let tmp_path = self.dir.join("updates.jsonl.tmp");
+            let mut content = String::new();
+            for entry in keep {
+                content.push_str(&serde_json::to_string(entry)?);
+                content.push('\n');
+            }
+            fs::write(&tmp_path, content)?;
+            fs::rename(&tmp_path, &spool_path)?;
+        }
+        Ok(())
+    }
+
+    fn read_spool(&self) -> Vec<SpooledUpdate> {
+        let content = fs::read_to_string(self.dir.join("updates.jsonl")).unwrap_or_default();
+        content
+            .lines()
+            // 正在写入的半行解析失败时跳过，下次读取时再处理
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+}
+
+/// 尝试获取状态目录下的轮询锁，成功时返回持有锁的文件
+///
+/// 锁文件始终保留不删除，否则不同进程可能锁住不同的文件
+fn try_lock_poller(dir: &Path) -> Option<fs::File> {
+    let file = match fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(dir.join("poller.lock"))
+    {
+        Ok(file) => file,
+        Err(e) => {
+            log_debug!("打开 Telegram 轮询锁失败: {}", e);
+            return None;
+        }
+    };
+    file.try_lock().ok().map(|_| file)
+}
+
+/// Bot 的共享状态目录（按 Bot ID 区分，不包含 Token 密钥部分）
+pub(crate) fn bot_state_dir(bot: &Bot) -> Result<PathBuf> {
+    let bot_id = bot.token().split(':').next().unwrap_or("default");
+    let dir = dirs::config_dir()
+        .ok_or_else(|| anyhow::anyhow!("无法获取配置目录"))?
+        .join("sanshu")
+        .join("telegram")
+        .join(bot_id);
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poller_lock_is_exclusive() {
+        let dir = std::env::temp_dir().join(format!("sanshu-telegram-lock-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let leader = try_lock_poller(&dir).expect("首个进程应成为轮询者");
+        assert!(try_lock_poller(&dir).is_none());
+        // 轮询者退出后其他进程可以接管
+        drop(leader);
+        assert!(try_lock_poller(&dir).is_some());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
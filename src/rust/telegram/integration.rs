@@ -5,6 +5,7 @@ use teloxide::prelude::*;
 use tokio::sync::Mutex;
 
 use super::core::{handle_text_message, TelegramCore, TelegramEvent};
+use super::hub::UpdateHub;
 use crate::log_important;
 
 /// Telegram集成管理器
@@ -92,104 +93,95 @@ impl TelegramIntegration {
         let (stop_tx, mut stop_rx) = tokio::sync::oneshot::channel();
         self.stop_sender = Some(stop_tx);
 
+        // 通过共享更新队列接收，避免与其他进程争抢 getUpdates
+        let mut hub = UpdateHub::open(&self.core)?;
+
         // 启动监听任务
         tokio::spawn(async move {
-            let mut offset = 0i32;
-
             loop {
                 tokio::select! {
                     _ = &mut stop_rx => {
                         break;
                     }
-                    _ = tokio::time::sleep(tokio::time::Duration::from_millis(1000)) => {
-                        // 轮询获取更新
-                        match bot.get_updates().offset(offset).await {
-                            Ok(updates) => {
-                                for update in updates {
-                                    offset = update.id.0 as i32 + 1;
-
-                                    // 处理不同类型的更新
-                                    match update.kind {
-                                        teloxide::types::UpdateKind::CallbackQuery(callback_query) => {
-                                            // 处理callback query
-                                            if let Some(message) = &callback_query.message {
-                                                if message.chat().id != chat_id {
-                                                    continue;
-                                                }
-                                            }
+                    updates = hub.next_updates() => {
+                        for update in updates {
+                            // 处理不同类型的更新
+                            match update.kind {
+                                teloxide::types::UpdateKind::CallbackQuery(callback_query) => {
+                                    // 处理callback query
+                                    if let Some(message) = &callback_query.message {
+                                        if message.chat().id != chat_id {
+                                            continue;
+                                        }
+                                    }
 
-                                            if let Some(data) = &callback_query.data {
-                                                if data.starts_with("toggle:") {
-                                                    let option = data.strip_prefix("toggle:").unwrap().to_string();
-
-                                                    // 切换选项状态
-                                                    let selected = {
-                                                        let mut selected_opts = selected_options.lock().await;
-                                                        if selected_opts.contains(&option) {
-                                                            selected_opts.retain(|x| x != &option);
-                                                            false
-                                                        } else {
-                                                            selected_opts.push(option.clone());
-                                                            true
-                                                        }
-                                                    };
-
-                                                    // 发送更新后的事件到前端
-                                                    let event = TelegramEvent::OptionToggled {
-                                                        option: option.clone(),
-                                                        selected,
-                                                    };
-
-                                                    if let Err(e) = app_handle.emit("telegram-event", &event) {
-                                                        log_important!(warn, "Telegram事件发送失败: {}", e);
-                                                    }
+                                    if let Some(data) = &callback_query.data {
+                                        if data.starts_with("toggle:") {
+                                            let option = data.strip_prefix("toggle:").unwrap().to_string();
+
+                                            // 切换选项状态
+                                            let selected = {
+                                                let mut selected_opts = selected_options.lock().await;
+                                                if selected_opts.contains(&option) {
+                                                    selected_opts.retain(|x| x != &option);
+                                                    false
+                                                } else {
+                                                    selected_opts.push(option.clone());
+                                                    true
                                                 }
-                                            }
+                                            };
 
-                                            // 回答callback query
-                                            let _ = bot.answer_callback_query(callback_query.id).await;
-                                        }
-                                        teloxide::types::UpdateKind::Message(message) => {
-                                            // 获取操作消息ID
-                                            let op_msg_id = {
-                                                let op_id = operation_message_id.lock().await;
-                                                *op_id
+                                            // 发送更新后的事件到前端
+                                            let event = TelegramEvent::OptionToggled {
+                                                option: option.clone(),
+                                                selected,
                                             };
 
-                                            // 使用核心模块的处理函数
-                                            match handle_text_message(
-                                                &message,
-                                                chat_id,
-                                                op_msg_id,
-                                            ).await {
-                                                Ok(Some(event)) => {
-                                                    // 如果是文本更新，保存到用户输入
-                                                    if let TelegramEvent::TextUpdated { text } = &event {
-                                                        let mut input = user_input.lock().await;
-                                                        *input = text.clone();
-                                                    }
-
-                                                    // 发送事件到前端
-                                                    if let Err(e) = app_handle.emit("telegram-event", &event) {
-                                                        log_important!(warn, "Telegram文本事件发送失败: {}", e);
-                                                    }
-                                                }
-                                                Ok(None) => {
-                                                    // 文本消息被过滤或忽略
-                                                }
-                                                Err(e) => {
-                                                    log_important!(warn, "文本消息处理失败: {}", e);
-                                                }
+                                            if let Err(e) = app_handle.emit("telegram-event", &event) {
+                                                log_important!(warn, "Telegram事件发送失败: {}", e);
                                             }
                                         }
-                                        _ => {
-                                            // 忽略其他类型的更新
+                                    }
+
+                                    // 回答callback query
+                                    let _ = bot.answer_callback_query(callback_query.id).await;
+                                }
+                                teloxide::types::UpdateKind::Message(message) => {
+                                    // 获取操作消息ID
+                                    let op_msg_id = {
+                                        let op_id = operation_message_id.lock().await;
+                                        *op_id
+                                    };
+
+                                    // 使用核心模块的处理函数
+                                    match handle_text_message(
+                                        &message,
+                                        chat_id,
+                                        op_msg_id,
+                                    ).await {
+                                        Ok(Some(event)) => {
+                                            // 如果是文本更新，保存到用户输入
+                                            if let TelegramEvent::TextUpdated { text } = &event {
+                                                let mut input = user_input.lock().await;
+                                                *input = text.clone();
+                                            }
+
+                                            // 发送事件到前端
+                                            if let Err(e) = app_handle.emit("telegram-event", &event) {
+                                                log_important!(warn, "Telegram文本事件发送失败: {}", e);
+                                            }
+                                        }
+                                        Ok(None) => {
+                                            // 文本消息被过滤或忽略
+                                        }
+                                        Err(e) => {
+                                            log_important!(warn, "文本消息处理失败: {}", e);
                                         }
                                     }
                                 }
-                            }
-                            Err(_e) => {
-                                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                                _ => {
+                                    // 忽略其他类型的更新
+                                }
                             }
                         }
                     }
@@ -1,163 +1,702 @@
-use regex;
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
 
-/// 智能处理 Telegram MarkdownV2 格式
+use super::diagram::{render_diff_png, render_mermaid_png};
+use crate::constants::telegram::{MAX_INLINE_CODE_CHARS, MAX_INLINE_CODE_LINES, MAX_MESSAGE_LENGTH};
+
+/// 渲染后的 Telegram 消息片段（按发送顺序排列）
+#[derive(Debug, Clone, PartialEq)]
+pub enum TelegramPart {
+    /// HTML 格式文本（parse_mode = HTML），长度不超过单条消息上限
+    Text(String),
+    /// 以文件形式发送的长代码
+    Document {
+        file_name: String,
+        content: Vec<u8>,
+        caption: String,
+    },
+    /// 渲染为图片的 Mermaid 图表或代码差异
+    Photo {
+        file_name: String,
+        content: Vec<u8>,
+        caption: String,
+    },
+}
+
+/// 将 Markdown 渲染为 Telegram 消息片段
 ///
-/// 支持尽可能多的 Markdown 格式，同时确保消息能正确发送
-pub fn process_telegram_markdown(text: &str) -> String {
-    let mut result = text.to_string();
+/// 文本使用 Telegram HTML 格式并自动按 4096 字符切分；表格转为等宽文本，
+/// 过长代码转为文件，Mermaid / diff 代码块渲染为 PNG
+pub fn render_telegram_markdown(markdown: &str) -> Vec<TelegramPart> {
+    let mut renderer = Renderer::default();
+    renderer.run(markdown);
+    assemble_parts(renderer.blocks, MAX_MESSAGE_LENGTH)
+}
 
-    // 第一步：保护代码块和行内代码
-    let mut protected_segments = Vec::new();
-    let mut segment_index = 0;
+/// 将纯文本转为 Telegram 消息片段（仅转义并切分）
+pub fn render_telegram_plain(text: &str) -> Vec<TelegramPart> {
+    assemble_parts(vec![Block::Html(escape_html(text))], MAX_MESSAGE_LENGTH)
+}
 
-    // 保护代码块 ```language\ncode\n```
-    result = protect_code_blocks(result, &mut protected_segments, &mut segment_index);
+/// 渲染中间结果：顶层块
+#[derive(Debug)]
+enum Block {
+    Html(String),
+    Pre { lang: Option<String>, code: String },
+    Part(TelegramPart),
+}
 
-    // 保护行内代码 `code`
-    result = protect_inline_code(result, &mut protected_segments, &mut segment_index);
+/// 表格收集状态
+#[derive(Default)]
+struct TableState {
+    alignments: Vec<Alignment>,
+    rows: Vec<Vec<String>>,
+    cell: String,
+}
 
-    // 第二步：处理 Markdown 格式
-    // 转换标准 Markdown 到 Telegram MarkdownV2
-    result = convert_markdown_to_telegram(result);
+/// 代码块收集状态
+struct CodeState {
+    lang: Option<String>,
+    code: String,
+}
 
-    // 第三步：转义剩余的特殊字符
-    result = escape_remaining_special_chars(result);
+#[derive(Default)]
+struct Renderer {
+    blocks: Vec<Block>,
+    buf: String,
+    /// 列表栈：有序列表记录下一个序号
+    lists: Vec<Option<u64>>,
+    quote_depth: usize,
+    table: Option<TableState>,
+    code: Option<CodeState>,
+}
 
-    // 第四步：恢复保护的代码
-    for (i, segment) in protected_segments.into_iter().enumerate() {
-        let placeholder = format!("CODEBLOCK{}", i);
-        result = result.replace(&placeholder, &segment);
+impl Renderer {
+    fn run(&mut self, markdown: &str) {
+        let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+        for event in Parser::new_ext(markdown, options) {
+            self.handle(event);
+        }
+        self.flush();
     }
 
-    result
-}
+    /// 当前是否处于列表或引用等容器内
+    fn in_container(&self) -> bool {
+        !self.lists.is_empty() || self.quote_depth > 0
+    }
+
+    fn flush(&mut self) {
+        let html = self.buf.trim().to_string();
+        self.buf.clear();
+        if !html.is_empty() {
+            self.blocks.push(Block::Html(html));
+        }
+    }
 
-/// 保护代码块
-fn protect_code_blocks(mut text: String, protected: &mut Vec<String>, index: &mut usize) -> String {
-    while let Some(start) = text.find("```") {
-        if let Some(end_start) = text[start + 3..].find("```") {
-            let end_pos = start + 3 + end_start + 3;
-            let code_block = text[start..end_pos].to_string();
-            let placeholder = format!("CODEBLOCK{}", *index);
-            protected.push(code_block);
-            text.replace_range(start..end_pos, &placeholder);
-            *index += 1;
+    /// 块级元素结束：顶层时生成独立块，容器内仅换行
+    fn end_block(&mut self) {
+        if self.in_container() {
+            self.ensure_newline();
         } else {
-            break;
+            self.flush();
+        }
+    }
+
+    fn ensure_newline(&mut self) {
+        if !self.buf.is_empty() && !self.buf.ends_with('\n') {
+            self.buf.push('\n');
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if let Some(code) = self.code.as_mut() {
+            code.code.push_str(text);
+        } else if let Some(table) = self.table.as_mut() {
+            table.cell.push_str(text);
+        } else {
+            self.buf.push_str(&escape_html(text));
+        }
+    }
+
+    /// 行内标记（表格单元格内忽略格式）
+    fn push_markup(&mut self, markup: &str) {
+        if self.table.is_none() {
+            self.buf.push_str(markup);
+        }
+    }
+
+    fn handle(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => self.push_text(&text),
+            Event::Code(code) => {
+                if let Some(table) = self.table.as_mut() {
+                    table.cell.push_str(&code);
+                } else {
+                    self.buf.push_str(&format!("<code>{}</code>", escape_html(&code)));
+                }
+            }
+            Event::Html(html) | Event::InlineHtml(html) => self.push_text(&html),
+            Event::SoftBreak | Event::HardBreak => {
+                if let Some(table) = self.table.as_mut() {
+                    table.cell.push(' ');
+                } else {
+                    self.push_text("\n");
+                }
+            }
+            Event::Rule => {
+                self.flush();
+                self.blocks.push(Block::Html("──────────".to_string()));
+            }
+            Event::TaskListMarker(checked) => {
+                self.buf.push_str(if checked { "☑ " } else { "☐ " });
+            }
+            Event::FootnoteReference(name) => self.push_text(&format!("[{}]", name)),
+            Event::InlineMath(math) | Event::DisplayMath(math) => self.push_text(&math),
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Paragraph => {}
+            Tag::Heading { .. } => self.buf.push_str("<b>"),
+            Tag::BlockQuote(_) => {
+                self.ensure_newline();
+                // Telegram 不支持嵌套引用，只保留最外层
+                if self.quote_depth == 0 {
+                    self.buf.push_str("<blockquote>");
+                }
+                self.quote_depth += 1;
+            }
+            Tag::CodeBlock(kind) => {
+                let lang = match kind {
+                    CodeBlockKind::Fenced(info) => info
+                        .split_whitespace()
+                        .next()
+                        .map(|s| s.to_lowercase())
+                        .filter(|s| !s.is_empty()),
+                    CodeBlockKind::Indented => None,
+                };
+                self.code = Some(CodeState { lang, code: String::new() });
+            }
+            Tag::List(start) => {
+                self.ensure_newline();
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.ensure_newline();
+                let depth = self.lists.len().saturating_sub(1);
+                let indent = "  ".repeat(depth);
+                let marker = match self.lists.last_mut() {
+                    Some(Some(n)) => {
+                        let marker = format!("{}.", n);
+                        *n += 1;
+                        marker
+                    }
+                    _ => if depth == 0 { "•" } else { "◦" }.to_string(),
+                };
+                self.buf.push_str(&format!("{}{} ", indent, marker));
+            }
+            Tag::Table(alignments) => {
+                self.table = Some(TableState {
+                    alignments,
+                    ..Default::default()
+                });
+            }
+            Tag::TableHead | Tag::TableRow => {
+                if let Some(table) = self.table.as_mut() {
+                    table.rows.push(Vec::new());
+                }
+            }
+            Tag::TableCell => {
+                if let Some(table) = self.table.as_mut() {
+                    table.cell.clear();
+                }
+            }
+            Tag::Emphasis => self.push_markup("<i>"),
+            Tag::Strong => self.push_markup("<b>"),
+            Tag::Strikethrough => self.push_markup("<s>"),
+            Tag::Link { dest_url, .. } => {
+                self.push_markup(&format!("<a href=\"{}\">", escape_attr(&dest_url)));
+            }
+            Tag::Image { dest_url, .. } => {
+                self.push_markup(&format!("🖼 <a href=\"{}\">", escape_attr(&dest_url)));
+            }
+            _ => {}
         }
     }
-    text
-}
 
-/// 保护行内代码
-fn protect_inline_code(mut text: String, protected: &mut Vec<String>, index: &mut usize) -> String {
-    let mut start = 0;
-    while let Some(pos) = text[start..].find('`') {
-        let abs_pos = start + pos;
-        if let Some(end_pos) = text[abs_pos + 1..].find('`') {
-            let abs_end = abs_pos + 1 + end_pos;
-            let inline_code = text[abs_pos..=abs_end].to_string();
-            let placeholder = format!("CODEBLOCK{}", *index);
-            protected.push(inline_code);
-            text.replace_range(abs_pos..=abs_end, &placeholder);
-            *index += 1;
-            start = abs_pos + placeholder.len();
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph => self.end_block(),
+            TagEnd::Heading(_) => {
+                self.buf.push_str("</b>");
+                self.end_block();
+            }
+            TagEnd::BlockQuote(_) => {
+                self.quote_depth = self.quote_depth.saturating_sub(1);
+                if self.quote_depth == 0 {
+                    let trimmed = self.buf.trim_end().len();
+                    self.buf.truncate(trimmed);
+                    self.buf.push_str("</blockquote>");
+                }
+                self.end_block();
+            }
+            TagEnd::CodeBlock => {
+                if let Some(code) = self.code.take() {
+                    self.finish_code_block(code);
+                }
+            }
+            TagEnd::List(_) => {
+                self.lists.pop();
+                self.end_block();
+            }
+            TagEnd::Item => self.ensure_newline(),
+            TagEnd::Table => {
+                if let Some(table) = self.table.take() {
+                    let text = format_table(&table);
+                    self.push_pre(None, text);
+                }
+            }
+            TagEnd::TableCell => {
+                if let Some(table) = self.table.as_mut() {
+                    let cell = table.cell.trim().to_string();
+                    if let Some(row) = table.rows.last_mut() {
+                        row.push(cell);
+                    }
+                }
+            }
+            TagEnd::Emphasis => self.push_markup("</i>"),
+            TagEnd::Strong => self.push_markup("</b>"),
+            TagEnd::Strikethrough => self.push_markup("</s>"),
+            TagEnd::Link | TagEnd::Image => self.push_markup("</a>"),
+            _ => {}
+        }
+    }
+
+    /// 代码块：Mermaid / diff 转图片，过长转文件，其余保持等宽块
+    fn finish_code_block(&mut self, state: CodeState) {
+        let code = state.code.trim_end_matches('\n').to_string();
+        let lang = state.lang;
+
+        if !self.in_container() {
+            let rendered = match lang.as_deref() {
+                Some("mermaid") => render_mermaid_png(&code).ok().map(|png| ("diagram.png", png, "📊 Mermaid 图表")),
+                Some("diff") | Some("patch") => render_diff_png(&code).ok().map(|png| ("changes.png", png, "📝 代码差异")),
+                _ => None,
+            };
+            if let Some((file_name, content, caption)) = rendered {
+                self.flush();
+                self.blocks.push(Block::Part(TelegramPart::Photo {
+                    file_name: file_name.to_string(),
+                    content,
+                    caption: caption.to_string(),
+                }));
+                return;
+            }
+
+            let line_count = code.lines().count();
+            if line_count > MAX_INLINE_CODE_LINES || code.chars().count() > MAX_INLINE_CODE_CHARS {
+                self.flush();
+                self.blocks.push(Block::Part(TelegramPart::Document {
+                    file_name: format!("snippet.{}", code_file_extension(lang.as_deref())),
+                    content: code.into_bytes(),
+                    caption: format!("📎 代码较长（{} 行），已作为文件发送", line_count),
+                }));
+                return;
+            }
+        }
+
+        self.push_pre(lang, code);
+    }
+
+    /// 等宽块：顶层时独立成块（便于切分），容器内直接内联
+    fn push_pre(&mut self, lang: Option<String>, code: String) {
+        if self.in_container() {
+            self.ensure_newline();
+            self.buf.push_str(&format_pre(lang.as_deref(), &code));
+            self.buf.push('\n');
         } else {
-            break;
+            self.flush();
+            self.blocks.push(Block::Pre { lang, code });
+        }
+    }
+}
+
+/// 将表格渲染为对齐的等宽文本
+fn format_table(table: &TableState) -> String {
+    let columns = table.rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    let mut widths = vec![0usize; columns];
+    for row in &table.rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(display_width(cell));
+        }
+    }
+
+    let mut lines = Vec::new();
+    for (row_index, row) in table.rows.iter().enumerate() {
+        let cells: Vec<String> = (0..columns)
+            .map(|i| {
+                let cell = row.get(i).map(String::as_str).unwrap_or("");
+                pad_cell(cell, widths[i], table.alignments.get(i).copied().unwrap_or(Alignment::None))
+            })
+            .collect();
+        lines.push(cells.join(" │ ").trim_end().to_string());
+
+        // 表头分隔线
+        if row_index == 0 {
+            let separator: Vec<String> = widths.iter().map(|w| "─".repeat(*w)).collect();
+            lines.push(separator.join("─┼─"));
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn pad_cell(cell: &str, width: usize, alignment: Alignment) -> String {
+    let padding = width.saturating_sub(display_width(cell));
+    match alignment {
+        Alignment::Right => format!("{}{}", " ".repeat(padding), cell),
+        Alignment::Center => {
+            let left = padding / 2;
+            format!("{}{}{}", " ".repeat(left), cell, " ".repeat(padding - left))
         }
+        _ => format!("{}{}", cell, " ".repeat(padding)),
     }
-    text
 }
 
-/// 转换标准 Markdown 到 Telegram MarkdownV2
-fn convert_markdown_to_telegram(text: String) -> String {
-    let mut result = text;
+/// 估算等宽字体下的显示宽度（CJK 等全角字符占两列）
+pub(crate) fn display_width(text: &str) -> usize {
+    text.chars().map(|c| if is_wide_char(c) { 2 } else { 1 }).sum()
+}
+
+pub(crate) fn is_wide_char(c: char) -> bool {
+    matches!(c as u32,
+        0x1100..=0x115F | 0x2E80..=0x303E | 0x3041..=0x33FF | 0x3400..=0x4DBF |
+        0x4E00..=0x9FFF | 0xA000..=0xA4CF | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF |
+        0xFE30..=0xFE4F | 0xFF00..=0xFF60 | 0xFFE0..=0xFFE6 | 0x1F300..=0x1FAFF)
+}
+
+fn format_pre(lang: Option<&str>, code: &str) -> String {
+    match lang {
+        Some(lang) => format!(
+            "<pre><code class=\"language-{}\">{}</code></pre>",
+            escape_attr(lang),
+            escape_html(code)
+        ),
+        None => format!("<pre>{}</pre>", escape_html(code)),
+    }
+}
 
-    // 处理标题 - 转换为引用格式，更明显
-    // # Title -> >Title
-    // ## Title -> >Title
-    if let Ok(header_regex) = regex::Regex::new(r"^(#{1,6})\s+(.+)$") {
-        let lines: Vec<&str> = result.split('\n').collect();
-        let mut new_lines = Vec::new();
+fn code_file_extension(lang: Option<&str>) -> &'static str {
+    match lang {
+        Some("rust" | "rs") => "rs",
+        Some("typescript" | "ts") => "ts",
+        Some("javascript" | "js") => "js",
+        Some("tsx") => "tsx",
+        Some("jsx") => "jsx",
+        Some("vue") => "vue",
+        Some("python" | "py") => "py",
+        Some("go" | "golang") => "go",
+        Some("java") => "java",
+        Some("kotlin" | "kt") => "kt",
+        Some("c") => "c",
+        Some("cpp" | "c++" | "cc") => "cpp",
+        Some("csharp" | "cs") => "cs",
+        Some("bash" | "sh" | "shell" | "zsh") => "sh",
+        Some("powershell" | "ps1") => "ps1",
+        Some("json") => "json",
+        Some("yaml" | "yml") => "yaml",
+        Some("toml") => "toml",
+        Some("html") => "html",
+        Some("css") => "css",
+        Some("scss") => "scss",
+        Some("sql") => "sql",
+        Some("markdown" | "md") => "md",
+        Some("diff" | "patch") => "diff",
+        Some("mermaid") => "mmd",
+        _ => "txt",
+    }
+}
 
-        for line in lines {
-            if let Some(captures) = header_regex.captures(line) {
-                let title = captures.get(2).unwrap().as_str();
-                new_lines.push(format!(">{}", title));
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn escape_attr(text: &str) -> String {
+    escape_html(text).replace('"', "&quot;")
+}
+
+/// Telegram 按 UTF-16 计算长度，这里按 HTML 源码估算（偏保守）
+fn message_length(text: &str) -> usize {
+    text.encode_utf16().count()
+}
+
+/// 将顶层块合并为不超过上限的消息
+fn assemble_parts(blocks: Vec<Block>, limit: usize) -> Vec<TelegramPart> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+
+    for block in blocks {
+        let pieces = match block {
+            Block::Html(html) => split_html(&html, limit),
+            Block::Pre { lang, code } => split_pre(lang.as_deref(), &code, limit),
+            Block::Part(part) => {
+                if !current.is_empty() {
+                    parts.push(TelegramPart::Text(std::mem::take(&mut current)));
+                }
+                parts.push(part);
+                continue;
+            }
+        };
+
+        for piece in pieces {
+            if current.is_empty() {
+                current = piece;
+            } else if message_length(&current) + 2 + message_length(&piece) <= limit {
+                current.push_str("\n\n");
+                current.push_str(&piece);
             } else {
-                new_lines.push(line.to_string());
+                parts.push(TelegramPart::Text(std::mem::replace(&mut current, piece)));
             }
         }
-        result = new_lines.join("\n");
     }
 
-    // 处理粗体 **text** -> *text*
-    if let Ok(bold_regex) = regex::Regex::new(r"\*\*([^*]+)\*\*") {
-        while let Some(captures) = bold_regex.captures(&result) {
-            let full_match = captures.get(0).unwrap().as_str();
-            let content = captures.get(1).unwrap().as_str();
-            result = result.replace(full_match, &format!("*{}*", content));
+    if !current.is_empty() {
+        parts.push(TelegramPart::Text(current));
+    }
+    parts
+}
+
+/// 切分超长等宽块，每段保持独立的 <pre>
+fn split_pre(lang: Option<&str>, code: &str, limit: usize) -> Vec<String> {
+    let whole = format_pre(lang, code);
+    if message_length(&whole) <= limit {
+        return vec![whole];
+    }
+
+    let overhead = message_length(&format_pre(lang, ""));
+    let budget = limit.saturating_sub(overhead).max(1);
+    let mut chunks: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for line in code.lines() {
+        for segment in split_by_budget(line, budget) {
+            let extra = if current.is_empty() { 0 } else { 1 };
+            if !current.is_empty()
+                && message_length(&escape_html(&current)) + extra + message_length(&escape_html(&segment)) > budget
+            {
+                chunks.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push('\n');
+            }
+            current.push_str(&segment);
         }
     }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks.iter().map(|chunk| format_pre(lang, chunk)).collect()
+}
+
+/// 按转义后长度切分单行纯文本
+fn split_by_budget(line: &str, budget: usize) -> Vec<String> {
+    if message_length(&escape_html(line)) <= budget {
+        return vec![line.to_string()];
+    }
 
-    // 处理引用 > text -> >text (保持不变，但确保格式正确)
-    let lines: Vec<&str> = result.split('\n').collect();
-    let mut new_lines = Vec::new();
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+    for c in line.chars() {
+        let len = message_length(&escape_html(&c.to_string()));
+        if current_len + len > budget && !current.is_empty() {
+            segments.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+        current.push(c);
+        current_len += len;
+    }
+    if !current.is_empty() {
+        segments.push(current);
+    }
+    segments
+}
 
-    for line in lines {
-        if line.trim_start().starts_with('>') {
-            // 保持引用格式
-            new_lines.push(line.to_string());
-        } else {
-            new_lines.push(line.to_string());
+/// 切分超长 HTML 块：按行切分，并在断点处闭合/重新打开未闭合的标签
+fn split_html(html: &str, limit: usize) -> Vec<String> {
+    if message_length(html) <= limit {
+        return vec![html.to_string()];
+    }
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    // 未闭合标签：(标签名, 原始开始标签)
+    let mut open_tags: Vec<(String, String)> = Vec::new();
+
+    let closing_len = |tags: &[(String, String)]| -> usize {
+        tags.iter().map(|(name, _)| name.len() + 3).sum()
+    };
+
+    let lines: Vec<&str> = html.split('\n').collect();
+    for (index, line) in lines.iter().enumerate() {
+        let line = if index + 1 < lines.len() { format!("{}\n", line) } else { line.to_string() };
+        let budget = limit.saturating_sub(closing_len(&open_tags) * 2 + 16).max(64);
+
+        for segment in split_html_line(&line, budget) {
+            if !current.is_empty()
+                && message_length(&current) + message_length(&segment) + closing_len(&open_tags) > limit
+            {
+                // 闭合当前块内所有打开的标签，并在下一块重新打开
+                let mut chunk = std::mem::take(&mut current);
+                for (name, _) in open_tags.iter().rev() {
+                    chunk.push_str(&format!("</{}>", name));
+                }
+                chunks.push(chunk.trim_end().to_string());
+                current = open_tags.iter().map(|(_, open)| open.as_str()).collect();
+            }
+            track_tags(&segment, &mut open_tags);
+            current.push_str(&segment);
         }
     }
-    result = new_lines.join("\n");
 
-    result
+    if !current.trim().is_empty() {
+        chunks.push(current.trim_end().to_string());
+    }
+    chunks
 }
 
-/// 转义剩余的特殊字符
-fn escape_remaining_special_chars(text: String) -> String {
-    let mut result = text;
+/// 将单行切成不超过预算的片段，避免切断标签和实体
+fn split_html_line(line: &str, budget: usize) -> Vec<String> {
+    if message_length(line) <= budget {
+        return vec![line.to_string()];
+    }
 
-    // 需要转义的字符，但排除我们想保留的格式字符
-    let chars_to_escape = [
-        // 不转义 * 因为我们用它做粗体
-        // 不转义 > 因为我们用它做引用
-        // 不转义 ` 因为已经保护了代码
-        '_', '[', ']', '(', ')', '~', '#', '+', '-', '=', '|', '{', '}', '.', '!',
-    ];
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut in_tag = false;
+    let mut in_entity = false;
 
-    for &ch in &chars_to_escape {
-        result = result.replace(ch, &format!("\\{}", ch));
+    for c in line.chars() {
+        match c {
+            '<' => in_tag = true,
+            '&' => in_entity = true,
+            _ => {}
+        }
+        current.push(c);
+        match c {
+            '>' => in_tag = false,
+            ';' => in_entity = false,
+            _ => {}
+        }
+        if !in_tag && !in_entity && message_length(&current) >= budget {
+            segments.push(std::mem::take(&mut current));
+        }
     }
+    if !current.is_empty() {
+        segments.push(current);
+    }
+    segments
+}
+
+/// 根据片段中的标签更新打开标签栈
+fn track_tags(segment: &str, open_tags: &mut Vec<(String, String)>) {
+    let mut rest = segment;
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else { break };
+        let tag = &rest[start..start + end + 1];
+        rest = &rest[start + end + 1..];
 
-    result
+        if let Some(name) = tag.strip_prefix("</") {
+            let name = name.trim_end_matches('>').trim();
+            if let Some(pos) = open_tags.iter().rposition(|(n, _)| n == name) {
+                open_tags.truncate(pos);
+            }
+        } else {
+            let name: String = tag[1..]
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric())
+                .collect();
+            if !name.is_empty() {
+                open_tags.push((name, tag.to_string()));
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn texts(parts: &[TelegramPart]) -> Vec<&str> {
+        parts
+            .iter()
+            .filter_map(|p| match p {
+                TelegramPart::Text(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
     #[test]
     fn test_markdown_processing() {
         let input = "# 标题\n\n**粗体文本**\n\n`代码`\n\n```rust\nfn main() {}\n```";
-        let result = process_telegram_markdown(input);
-        
-        // 验证标题转换为引用
-        assert!(result.contains(">标题"));
-        // 验证粗体转换
-        assert!(result.contains("*粗体文本*"));
-        // 验证代码块保持不变
-        assert!(result.contains("```rust\nfn main() {}\n```"));
+        let parts = render_telegram_markdown(input);
+        let text = texts(&parts).join("\n");
+
+        assert!(text.contains("<b>标题</b>"));
+        assert!(text.contains("<b>粗体文本</b>"));
+        assert!(text.contains("<code>代码</code>"));
+        assert!(text.contains("<pre><code class=\"language-rust\">fn main() {}</code></pre>"));
     }
 
     #[test]
     fn test_special_char_escaping() {
-        let input = "测试_下划线和[方括号]";
-        let result = process_telegram_markdown(input);
-        
-        assert!(result.contains("测试\\_下划线和\\[方括号\\]"));
+        let parts = render_telegram_markdown("测试_下划线和[方括号] a < b && c > d");
+        let text = texts(&parts).join("\n");
+
+        assert!(text.contains("测试_下划线和[方括号]"));
+        assert!(text.contains("a &lt; b &amp;&amp; c &gt; d"));
+    }
+
+    #[test]
+    fn test_nested_list_and_table() {
+        let input = "- 一级\n  - 二级\n1. 第一\n2. 第二\n\n| 名称 | 值 |\n|:--|--:|\n| 中文 | 1 |\n| ab | 100 |";
+        let text = texts(&render_telegram_markdown(input)).join("\n");
+
+        assert!(text.contains("• 一级\n  ◦ 二级"));
+        assert!(text.contains("1. 第一\n2. 第二"));
+        assert!(text.contains("<pre>名称 │  值\n─────┼────\n中文 │   1\nab   │ 100</pre>"));
+    }
+
+    #[test]
+    fn test_long_message_split_keeps_tags_balanced() {
+        let line = "<b>粗体</b> 普通文本 ".repeat(20);
+        let input = format!("> {}", vec![line; 60].join("\n> "));
+        let parts = render_telegram_markdown(&input);
+        let texts = texts(&parts);
+
+        assert!(texts.len() > 1);
+        for text in texts {
+            assert!(message_length(text) <= MAX_MESSAGE_LENGTH);
+            assert_eq!(text.matches("<blockquote>").count(), text.matches("</blockquote>").count());
+        }
+    }
+
+    #[test]
+    fn test_long_code_becomes_document() {
+        let code = (0..MAX_INLINE_CODE_LINES + 5).map(|i| format!("let x{} = {};", i, i)).collect::<Vec<_>>().join("\n");
+        let parts = render_telegram_markdown(&format!("说明\n\n```rust\n{}\n```\n\n结尾", code));
+
+        assert_eq!(parts.len(), 3);
+        match &parts[1] {
+            TelegramPart::Document { file_name, content, .. } => {
+                assert_eq!(file_name, "snippet.rs");
+                assert_eq!(String::from_utf8_lossy(content), code);
+            }
+            other => panic!("unexpected part: {:?}", other),
+        }
     }
 }
@@ -3,10 +3,22 @@ use std::collections::HashSet;
 use teloxide::prelude::*;
 
 use crate::config::load_standalone_config;
-use crate::mcp::types::{build_continue_response, build_send_response, PopupRequest};
+use crate::mcp::tools::interaction::form::{validate_form_values, ZhiField};
+use crate::mcp::types::{build_continue_response, build_form_send_response, PopupRequest};
+use crate::telegram::form::{apply_form_callback, parse_form_text, FORM_CALLBACK_PREFIX};
+use crate::telegram::hub::UpdateHub;
+use crate::telegram::media::{build_media_ack, download_message_media, ReceivedMedia};
+use crate::telegram::routing::RequestRouter;
 use crate::telegram::{handle_callback_query, handle_text_message, TelegramCore, TelegramEvent};
 use crate::log_important;
 
+/// 表单交互状态
+struct FormState {
+    fields: Vec<ZhiField>,
+    values: serde_json::Map<String, serde_json::Value>,
+    message_id: Option<i32>,
+}
+
 /// 处理纯Telegram模式的MCP请求（不启动GUI）
 pub async fn handle_telegram_only_mcp_request(request_file: &str) -> Result<()> {
     // 读取MCP请求文件
@@ -28,100 +40,136 @@ pub async fn handle_telegram_only_mcp_request(request_file: &str) -> Result<()>
     }
 
     // 创建Telegram核心实例，使用配置中的API URL
-    let api_url = if telegram_config.api_base_url == crate::constants::telegram::API_BASE_URL {
-        None
-    } else {
-        Some(telegram_config.api_base_url.clone())
-    };
+    let mut core = TelegramCore::from_config(telegram_config).await?;
+    if telegram_config.use_forum_topics {
+        core.enter_project_topic(request.project_root_path.as_deref()).await;
+    }
 
-    let core = TelegramCore::new_with_api_url(
-        telegram_config.bot_token.clone(),
-        telegram_config.chat_id.clone(),
-        api_url,
-    )?;
+    // 先登记请求路由再发送消息，避免错过快速答复
+    let hub = UpdateHub::open(&core)?;
+    let mut router = RequestRouter::register(&hub, &core, &request.id)?;
 
     // 发送消息到Telegram
     let predefined_options = request.predefined_options.clone().unwrap_or_default();
 
-    // 发送选项消息
-    core.send_options_message(&request.message, &predefined_options, request.is_markdown)
+    // 发送选项消息（带选项时键盘位于最后一条消息上）
+    let message_ids = core
+        .send_options_message(&request.message, &predefined_options, request.is_markdown)
         .await?;
+    let options_message_id = if predefined_options.is_empty() {
+        None
+    } else {
+        message_ids.last().copied()
+    };
+    router.track(message_ids);
+
+    // 发送表单消息（如有）
+    let mut form = FormState {
+        fields: request.fields.clone(),
+        values: serde_json::Map::new(),
+        message_id: None,
+    };
+    if !form.fields.is_empty() {
+        let form_message_id = core.send_form_message(&form.fields, &form.values).await?;
+        form.message_id = Some(form_message_id);
+        router.track([form_message_id]);
+    }
 
     // 短暂延迟确保消息顺序
     tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
 
     // 发送操作消息（假设启用继续回复）
-    core.send_operation_message(true).await?;
+    let operation_message_id = core.send_operation_message(true).await?;
+    router.track([operation_message_id]);
 
     // 启动消息监听循环
-    start_telegram_mcp_listener(core, request, predefined_options).await
+    start_telegram_mcp_listener(core, hub, router, request, predefined_options, options_message_id, form).await
 }
 
 /// 启动Telegram MCP消息监听循环
 async fn start_telegram_mcp_listener(
     core: TelegramCore,
+    mut hub: UpdateHub,
+    mut router: RequestRouter,
     request: PopupRequest,
     predefined_options: Vec<String>,
+    mut options_message_id: Option<i32>,
+    mut form: FormState,
 ) -> Result<()> {
-    let mut offset = 0i32;
     let mut selected_options: HashSet<String> = HashSet::new();
     let mut user_input = String::new();
-    let mut options_message_id: Option<i32> = None;
-
-    // 获取当前最新的消息ID作为基准
-    if let Ok(updates) = core.bot.get_updates().limit(10).await {
-        if let Some(update) = updates.last() {
-            offset = update.id.0 as i32 + 1;
-        }
-    }
+    let mut media = ReceivedMedia::default();
 
     // 监听循环（简化版本，只等待发送或继续操作）
     loop {
-        match core.bot.get_updates().offset(offset).timeout(10).await {
-            Ok(updates) => {
-                for update in updates {
-                    offset = update.id.0 as i32 + 1;
-
-                    match update.kind {
-                        teloxide::types::UpdateKind::CallbackQuery(callback_query) => {
-                            if let Err(e) = handle_callback_query_update(
-                                &core,
-                                &callback_query,
-                                &predefined_options,
-                                &mut selected_options,
-                                &mut options_message_id,
-                            ).await {
-                                log_important!(warn, "处理callback query失败: {}", e);
-                            }
-                        }
-                        teloxide::types::UpdateKind::Message(message) => {
-                            // 处理选项消息ID识别
-                            if let Err(e) = handle_message_update(
-                                &core,
-                                &message,
-                                &predefined_options,
-                                &mut options_message_id,
-                                &mut user_input,
-                                &selected_options,
-                                &request,
-                            ).await {
-                                if let Some(_result) = e.downcast_ref::<ProcessingComplete>() {
-                                    return Ok(());
-                                }
-                                log_important!(warn, "处理消息失败: {}", e);
-                            }
+        router.heartbeat();
+
+        // 共享更新队列内部已处理轮询间隔
+        for update in hub.next_updates().await {
+            match update.kind {
+                teloxide::types::UpdateKind::CallbackQuery(callback_query) => {
+                    // 只处理本请求消息上的按钮
+                    if !router.accepts_callback(&callback_query) {
+                        continue;
+                    }
+                    if !router.is_authorized_callback(&callback_query) {
+                        let _ = core
+                            .bot
+                            .answer_callback_query(callback_query.id.clone())
+                            .text("你没有权限答复此请求")
+                            .show_alert(true)
+                            .await;
+                        continue;
+                    }
+
+                    // 表单按钮回调单独处理
+                    let is_form_callback = callback_query
+                        .data
+                        .as_deref()
+                        .map(|data| data.starts_with(FORM_CALLBACK_PREFIX))
+                        .unwrap_or(false);
+                    if is_form_callback {
+                        handle_form_callback_update(&core, &callback_query, &mut form).await;
+                        continue;
+                    }
+
+                    if let Err(e) = handle_callback_query_update(
+                        &core,
+                        &callback_query,
+                        &predefined_options,
+                        &mut selected_options,
+                        &mut options_message_id,
+                    ).await {
+                        log_important!(warn, "处理callback query失败: {}", e);
+                    }
+                }
+                teloxide::types::UpdateKind::Message(message) => {
+                    // 只处理发给本请求且发送者有权限的消息
+                    if !router.accepts_message(&message) {
+                        continue;
+                    }
+
+                    // 处理选项消息ID识别
+                    if let Err(e) = handle_message_update(
+                        &core,
+                        &message,
+                        &predefined_options,
+                        &mut options_message_id,
+                        &mut user_input,
+                        &selected_options,
+                        &mut form,
+                        &mut media,
+                        &request,
+                    ).await {
+                        if let Some(_result) = e.downcast_ref::<ProcessingComplete>() {
+                            return Ok(());
                         }
-                        _ => {}
+                        log_important!(warn, "处理消息失败: {}", e);
                     }
                 }
-            }
-            Err(_) => {
-                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                _ => {}
             }
         }
-
-        // 短暂延迟避免过于频繁的请求
-        tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
     }
 }
 
@@ -166,6 +214,7 @@ async fn handle_callback_query_update(
 }
 
 /// 处理消息更新
+#[allow(clippy::too_many_arguments)]
 async fn handle_message_update(
     core: &TelegramCore,
     message: &teloxide::types::Message,
@@ -173,16 +222,50 @@ async fn handle_message_update(
     options_message_id: &mut Option<i32>,
     user_input: &mut String,
     selected_options: &HashSet<String>,
+    form: &mut FormState,
+    media: &mut ReceivedMedia,
     request: &PopupRequest,
 ) -> Result<()> {
     // 识别选项消息ID
     identify_options_message_id(message, predefined_options, options_message_id);
 
+    // 下载图片 / 文件，随发送一并提交
+    match download_message_media(&core.bot, message, core.chat_id).await {
+        Ok(Some(received)) => {
+            let name = received.file_name();
+            media.push(received);
+            let _ = core.send_message(&build_media_ack(&name, media)).await;
+        }
+        Ok(None) => {}
+        Err(e) => {
+            let _ = core.send_message(&format!("❌ 附件接收失败：{}", e)).await;
+        }
+    }
+
     // 处理文本消息事件
     if let Ok(Some(event)) = handle_text_message(message, core.chat_id, None).await {
         match event {
             TelegramEvent::SendPressed => {
-                handle_send_pressed(core, selected_options, user_input, request).await?;
+                // 表单未通过校验时提示错误并继续等待
+                let form_values = if form.fields.is_empty() {
+                    None
+                } else {
+                    match validate_form_values(&form.fields, &form.values) {
+                        Ok(values) => Some(values),
+                        Err(errors) => {
+                            let detail = errors
+                                .iter()
+                                .map(|e| format!("• {}", e))
+                                .collect::<Vec<_>>()
+                                .join("\n");
+                            let _ = core
+                                .send_message(&format!("❌ 表单校验失败：\n{}", detail))
+                                .await;
+                            return Ok(());
+                        }
+                    }
+                };
+                handle_send_pressed(core, selected_options, user_input, form_values, media, request).await?;
                 return Err(ProcessingComplete.into());
             }
             TelegramEvent::ContinuePressed => {
@@ -190,7 +273,21 @@ async fn handle_message_update(
                 return Err(ProcessingComplete.into());
             }
             TelegramEvent::TextUpdated { text } => {
-                *user_input = text;
+                // `key=值` 形式的行视为表单赋值，其余作为补充说明
+                let (assignments, remaining) = parse_form_text(&form.fields, &text);
+                if !assignments.is_empty() {
+                    for (key, value) in assignments {
+                        form.values.insert(key, value);
+                    }
+                    if let Some(msg_id) = form.message_id {
+                        let _ = core.update_form_keyboard(msg_id, &form.fields, &form.values).await;
+                    }
+                    if !remaining.is_empty() {
+                        *user_input = remaining;
+                    }
+                } else {
+                    *user_input = text;
+                }
             }
             _ => {}
         }
@@ -199,6 +296,29 @@ async fn handle_message_update(
     Ok(())
 }
 
+/// 处理表单按钮回调
+async fn handle_form_callback_update(
+    core: &TelegramCore,
+    callback_query: &teloxide::types::CallbackQuery,
+    form: &mut FormState,
+) {
+    if let Some(message) = &callback_query.message {
+        if message.chat().id != core.chat_id {
+            return;
+        }
+    }
+
+    if let Some(data) = &callback_query.data {
+        if apply_form_callback(&form.fields, &mut form.values, data).is_some() {
+            if let Some(msg_id) = form.message_id {
+                let _ = core.update_form_keyboard(msg_id, &form.fields, &form.values).await;
+            }
+        }
+    }
+
+    let _ = core.bot.answer_callback_query(callback_query.id.clone()).await;
+}
+
 /// 识别选项消息ID
 fn identify_options_message_id(
     message: &teloxide::types::Message,
@@ -239,6 +359,8 @@ async fn handle_send_pressed(
     core: &TelegramCore,
     selected_options: &HashSet<String>,
     user_input: &str,
+    form_values: Option<serde_json::Map<String, serde_json::Value>>,
+    media: &mut ReceivedMedia,
     request: &PopupRequest,
 ) -> Result<()> {
     // 使用统一的响应构建函数
@@ -250,10 +372,12 @@ async fn handle_send_pressed(
         Some(user_input.to_string())
     };
 
-    let response = build_send_response(
+    let response = build_form_send_response(
         user_input_option,
         selected_list.clone(),
-        vec![], // 无GUI模式下没有图片
+        std::mem::take(&mut media.images),
+        form_values,
+        std::mem::take(&mut media.files),
         Some(request.id.clone()),
         "telegram",
     );
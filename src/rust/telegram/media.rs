@@ -0,0 +1,208 @@
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use teloxide::net::Download;
+use teloxide::prelude::*;
+use teloxide::types::FileId;
+
+use crate::constants::telegram::MAX_ATTACHMENT_BYTES;
+use crate::mcp::types::{FileAttachment, ImageAttachment};
+
+/// 可直接作为图片返回给 AI 的类型
+const INLINE_IMAGE_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+/// 用户在 Telegram 中发送的媒体
+#[derive(Debug)]
+pub enum TelegramMedia {
+    Image(ImageAttachment),
+    File(FileAttachment),
+}
+
+impl TelegramMedia {
+    pub fn file_name(&self) -> String {
+        match self {
+            TelegramMedia::Image(image) => image.filename.clone().unwrap_or_else(|| "图片".to_string()),
+            TelegramMedia::File(file) => file.filename.clone(),
+        }
+    }
+}
+
+/// 已接收的图片与文件
+#[derive(Debug, Default)]
+pub struct ReceivedMedia {
+    pub images: Vec<ImageAttachment>,
+    pub files: Vec<FileAttachment>,
+}
+
+impl ReceivedMedia {
+    pub fn push(&mut self, media: TelegramMedia) {
+        match media {
+            TelegramMedia::Image(image) => self.images.push(image),
+            TelegramMedia::File(file) => self.files.push(file),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.images.len() + self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// 下载消息中的图片或文档
+///
+/// 非目标聊天或不含媒体的消息返回 Ok(None)
+pub async fn download_message_media(
+    bot: &Bot,
+    message: &Message,
+    target_chat_id: ChatId,
+) -> Result<Option<TelegramMedia>> {
+    if message.chat.id != target_chat_id {
+        return Ok(None);
+    }
+
+    if let Some(photo) = message.photo().and_then(|sizes| sizes.iter().max_by_key(|p| p.width * p.height)) {
+        // Telegram 会将照片统一压缩为 JPEG
+        check_size(photo.file.size as usize)?;
+        let content = download_file(bot, &photo.file.id).await?;
+        return Ok(Some(TelegramMedia::Image(ImageAttachment {
+            data: STANDARD.encode(content),
+            media_type: "image/jpeg".to_string(),
+            filename: Some(format!("photo_{}.jpg", photo.file.unique_id.0)),
+        })));
+    }
+
+    if let Some(document) = message.document() {
+        check_size(document.file.size as usize)?;
+        let file_name = document
+            .file_name
+            .clone()
+            .unwrap_or_else(|| format!("file_{}", document.file.unique_id.0));
+        let media_type = document
+            .mime_type
+            .as_ref()
+            .map(|m| m.essence_str().to_string())
+            .filter(|m| m != "application/octet-stream")
+            .unwrap_or_else(|| guess_media_type(&file_name).to_string());
+
+        let content = download_file(bot, &document.file.id).await?;
+        let data = STANDARD.encode(content);
+
+        // 以文件形式发送的截图同样作为图片返回
+        let media = if INLINE_IMAGE_TYPES.contains(&media_type.as_str()) {
+            TelegramMedia::Image(ImageAttachment {
+                data,
+                media_type,
+                filename: Some(file_name),
+            })
+        } else {
+            TelegramMedia::File(FileAttachment {
+                data,
+                media_type,
+                filename: file_name,
+            })
+        };
+        return Ok(Some(media));
+    }
+
+    Ok(None)
+}
+
+/// 生成接收确认消息
+pub fn build_media_ack(name: &str, received: &ReceivedMedia) -> String {
+    format!(
+        "📎 已接收：{}\n当前共 {} 张图片、{} 个文件，点击“↗️发送”一并提交",
+        name,
+        received.images.len(),
+        received.files.len()
+    )
+}
+
+async fn download_file(bot: &Bot, file_id: &FileId) -> Result<Vec<u8>> {
+    let file = bot
+        .get_file(file_id.clone())
+        .await
+        .map_err(|e| anyhow::anyhow!("获取文件信息失败: {}", e))?;
+
+    let mut content = Vec::with_capacity(file.meta.size as usize);
+    bot.download_file(&file.path, &mut content)
+        .await
+        .map_err(|e| anyhow::anyhow!("下载文件失败: {}", e))?;
+
+    check_size(content.len())?;
+    Ok(content)
+}
+
+fn check_size(size: usize) -> Result<()> {
+    if size > MAX_ATTACHMENT_BYTES {
+        return Err(anyhow::anyhow!(
+            "文件过大（{:.1} MB），最大支持 {} MB",
+            size as f64 / (1024.0 * 1024.0),
+            MAX_ATTACHMENT_BYTES / (1024 * 1024)
+        ));
+    }
+    Ok(())
+}
+
+/// 根据扩展名推断 MIME 类型（Telegram 未提供时使用）
+pub fn guess_media_type(file_name: &str) -> &'static str {
+    let ext = file_name
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "json" => "application/json",
+        "md" | "markdown" => "text/markdown",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "txt" | "log" | "rs" | "ts" | "js" | "vue" | "py" | "go" | "java" | "toml" | "yaml" | "yml"
+        | "xml" | "sh" | "diff" | "patch" => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guess_media_type() {
+        assert_eq!(guess_media_type("截图.PNG"), "image/png");
+        assert_eq!(guess_media_type("error.log"), "text/plain");
+        assert_eq!(guess_media_type("report.pdf"), "application/pdf");
+        assert_eq!(guess_media_type("README"), "application/octet-stream");
+    }
+
+    #[test]
+    fn test_received_media_counts() {
+        let mut received = ReceivedMedia::default();
+        assert!(received.is_empty());
+
+        received.push(TelegramMedia::Image(ImageAttachment {
+            data: "aGk=".to_string(),
+            media_type: "image/jpeg".to_string(),
+            filename: Some("photo.jpg".to_string()),
+        }));
+        let file = FileAttachment {
+            data: "aGk=".to_string(),
+            media_type: "text/plain".to_string(),
+            filename: "a.log".to_string(),
+        };
+        let file = TelegramMedia::File(file);
+        let name = file.file_name();
+        received.push(file);
+        let ack = build_media_ack(&name, &received);
+
+        assert_eq!(received.len(), 2);
+        assert!(ack.contains("a.log"));
+        assert!(ack.contains("1 张图片、1 个文件"));
+    }
+}
@@ -1,8 +1,14 @@
 pub mod commands;
 pub mod core;
+pub mod diagram;
+pub mod form;
+pub mod hub;
 pub mod integration;
 pub mod markdown;
+pub mod media;
 pub mod mcp_handler;
+pub mod routing;
+pub mod webhook;
 
 pub use commands::*;
 pub use core::{
@@ -10,5 +16,5 @@ pub use core::{
     TelegramEvent,
 };
 pub use integration::TelegramIntegration;
-pub use markdown::process_telegram_markdown;
+pub use markdown::{render_telegram_markdown, render_telegram_plain, TelegramPart};
 pub use mcp_handler::handle_telegram_only_mcp_request;
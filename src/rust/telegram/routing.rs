@@ -0,0 +1,301 @@
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use teloxide::types::{CallbackQuery, Message};
+
+use super::hub::UpdateHub;
+use super::TelegramCore;
+use crate::constants::telegram::ROUTE_STALE_MS;
+use crate::log_important;
+
+/// 当前进程登记的路由文件，进程退出前统一清理
+static ACTIVE_ROUTES: Lazy<Mutex<Vec<PathBuf>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// 待答复请求的路由信息（每个请求一个文件，供共享同一 Bot 的进程互相查看）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RequestRoute {
+    pub request_id: String,
+    pub chat_id: i64,
+    /// 论坛话题ID（非话题群组为 None）
+    pub thread_id: Option<i32>,
+    /// 为该请求发送的消息ID，回复这些消息即视为答复该请求
+    pub message_ids: Vec<i32>,
+    pub created_at: i64,
+    pub heartbeat_at: i64,
+}
+
+/// 本进程持有的请求路由
+pub struct RequestRouter {
+    path: PathBuf,
+    routes_dir: PathBuf,
+    route: RequestRoute,
+    allowed_user_ids: Vec<i64>,
+}
+
+impl RequestRouter {
+    /// 登记一个待答复请求
+    pub fn register(hub: &UpdateHub, core: &TelegramCore, request_id: &str) -> Result<Self> {
+        let routes_dir = hub.dir().join("routes");
+        fs::create_dir_all(&routes_dir)?;
+
+        let now = chrono::Utc::now().timestamp_millis();
+        let path = routes_dir.join(format!("{}.json", sanitize_file_name(request_id)));
+        let router = Self {
+            path,
+            routes_dir,
+            route: RequestRoute {
+                request_id: request_id.to_string(),
+                chat_id: core.chat_id.0,
+                thread_id: core.thread_id.map(|t| t.0 .0),
+                message_ids: Vec::new(),
+                created_at: now,
+                heartbeat_at: now,
+            },
+            allowed_user_ids: core.allowed_user_ids.clone(),
+        };
+        router.save()?;
+
+        if let Ok(mut active) = ACTIVE_ROUTES.lock() {
+            active.push(router.path.clone());
+        }
+        Ok(router)
+    }
+
+    /// 记录为该请求发送的消息
+    pub fn track(&mut self, message_ids: impl IntoIterator<Item = i32>) {
+        self.route.message_ids.extend(message_ids.into_iter().filter(|id| *id > 0));
+        if let Err(e) = self.save() {
+            log_important!(warn, "保存 Telegram 请求路由失败: {}", e);
+        }
+    }
+
+    /// 刷新心跳，表明请求仍在等待答复
+    pub fn heartbeat(&mut self) {
+        if !self.is_active() {
+            return;
+        }
+        self.route.heartbeat_at = chrono::Utc::now().timestamp_millis();
+        let _ = self.save();
+    }
+
+    /// 请求是否仍在等待答复（已通过其他途径答复时由 release_active_routes 释放）
+    pub fn is_active(&self) -> bool {
+        ACTIVE_ROUTES
+            .lock()
+            .map(|active| active.contains(&self.path))
+            .unwrap_or(true)
+    }
+
+    /// 消息是否属于本请求（已校验发送者权限）
+    pub fn accepts_message(&self, message: &Message) -> bool {
+        if message.chat.id.0 != self.route.chat_id {
+            return false;
+        }
+        let sender = message.from.as_ref().map(|u| u.id.0 as i64);
+        if !is_authorized(&self.allowed_user_ids, sender) {
+            return false;
+        }
+
+        let thread_id = message.thread_id.map(|t| t.0 .0);
+        let reply_to = message
+            .reply_to_message()
+            .map(|m| m.id.0)
+            // 话题内的普通消息会“回复”话题根消息，不视为显式回复
+            .filter(|id| Some(*id) != thread_id);
+
+        let routes = self.live_routes();
+        route_message(&routes, message.chat.id.0, thread_id, reply_to) == Some(self.route.request_id.as_str())
+    }
+
+    /// 按钮回调是否属于本请求（按钮所在消息必须是本请求发送的）
+    pub fn accepts_callback(&self, callback_query: &CallbackQuery) -> bool {
+        callback_query
+            .message
+            .as_ref()
+            .map(|m| m.chat().id.0 == self.route.chat_id && self.route.message_ids.contains(&m.id().0))
+            .unwrap_or(false)
+    }
+
+    /// 回调发送者是否有权限
+    pub fn is_authorized_callback(&self, callback_query: &CallbackQuery) -> bool {
+        is_authorized(&self.allowed_user_ids, Some(callback_query.from.id.0 as i64))
+    }
+
+    fn save(&self) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string(&self.route)?)?;
+        Ok(())
+    }
+
+    /// 读取所有仍在等待的请求路由（忽略心跳超时的残留文件）
+    fn live_routes(&self) -> Vec<RequestRoute> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut routes: Vec<RequestRoute> = fs::read_dir(&self.routes_dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+                    .filter_map(|content| serde_json::from_str::<RequestRoute>(&content).ok())
+                    .filter(|route| now - route.heartbeat_at < ROUTE_STALE_MS)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // 本请求以内存中的状态为准
+        routes.retain(|route| route.request_id != self.route.request_id);
+        routes.push(self.route.clone());
+        routes
+    }
+}
+
+impl Drop for RequestRouter {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+        if let Ok(mut active) = ACTIVE_ROUTES.lock() {
+            active.retain(|path| path != &self.path);
+        }
+    }
+}
+
+/// 清理当前进程登记的所有路由（请求已在弹窗中答复，或进程直接退出时 Drop 不会执行）
+pub fn release_active_routes() {
+    if let Ok(mut active) = ACTIVE_ROUTES.lock() {
+        for path in active.drain(..) {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// 判断消息应交给哪个请求
+///
+/// 显式回复某个请求的消息时交给该请求；否则交给同一聊天、同一话题中最新的请求
+pub fn route_message<'a>(
+    routes: &'a [RequestRoute],
+    chat_id: i64,
+    thread_id: Option<i32>,
+    reply_to: Option<i32>,
+) -> Option<&'a str> {
+    let in_chat = || routes.iter().filter(move |route| route.chat_id == chat_id);
+
+    if let Some(reply_to) = reply_to {
+        if let Some(route) = in_chat().find(|route| route.message_ids.contains(&reply_to)) {
+            return Some(route.request_id.as_str());
+        }
+    }
+
+    in_chat()
+        .filter(|route| route.thread_id == thread_id)
+        .max_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.request_id.cmp(&b.request_id)))
+        .map(|route| route.request_id.as_str())
+}
+
+/// 发送者是否在授权列表中（列表为空时不限制）
+pub fn is_authorized(allowed_user_ids: &[i64], sender: Option<i64>) -> bool {
+    allowed_user_ids.is_empty() || sender.map(|id| allowed_user_ids.contains(&id)).unwrap_or(false)
+}
+
+/// 项目与论坛话题的对应关系
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ProjectTopics {
+    /// key 为 `chat_id:项目路径`
+    topics: HashMap<String, i32>,
+}
+
+impl ProjectTopics {
+    pub fn load(dir: &Path) -> Self {
+        fs::read_to_string(dir.join("topics.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        fs::write(dir.join("topics.json"), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, chat_id: i64, project_root: &str) -> Option<i32> {
+        self.topics.get(&Self::key(chat_id, project_root)).copied()
+    }
+
+    pub fn insert(&mut self, chat_id: i64, project_root: &str, thread_id: i32) {
+        self.topics.insert(Self::key(chat_id, project_root), thread_id);
+    }
+
+    fn key(chat_id: i64, project_root: &str) -> String {
+        format!("{}:{}", chat_id, project_root.trim_end_matches(['/', '\\']))
+    }
+}
+
+/// 根据项目路径生成话题名称
+pub fn topic_name(project_root: &str) -> String {
+    let name = project_root
+        .trim_end_matches(['/', '\\'])
+        .rsplit(['/', '\\'])
+        .next()
+        .filter(|s| !s.is_empty())
+        .unwrap_or(project_root);
+    // Telegram 话题名称最长 128 字符
+    format!("📁 {}", name).chars().take(128).collect()
+}
+
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(id: &str, thread_id: Option<i32>, message_ids: &[i32], created_at: i64) -> RequestRoute {
+        RequestRoute {
+            request_id: id.to_string(),
+            chat_id: -100,
+            thread_id,
+            message_ids: message_ids.to_vec(),
+            created_at,
+            heartbeat_at: created_at,
+        }
+    }
+
+    #[test]
+    fn test_route_message() {
+        let routes = vec![
+            route("a", None, &[10, 11], 1),
+            route("b", None, &[20, 21], 2),
+            route("c", Some(7), &[30], 3),
+        ];
+
+        // 显式回复优先
+        assert_eq!(route_message(&routes, -100, None, Some(10)), Some("a"));
+        // 未回复时交给同一话题中最新的请求
+        assert_eq!(route_message(&routes, -100, None, None), Some("b"));
+        assert_eq!(route_message(&routes, -100, Some(7), None), Some("c"));
+        // 回复了未知消息时按未回复处理
+        assert_eq!(route_message(&routes, -100, None, Some(99)), Some("b"));
+        // 其他聊天或没有请求的话题
+        assert_eq!(route_message(&routes, 42, None, None), None);
+        assert_eq!(route_message(&routes, -100, Some(8), None), None);
+    }
+
+    #[test]
+    fn test_authorization_and_topics() {
+        assert!(is_authorized(&[], None));
+        assert!(is_authorized(&[1, 2], Some(2)));
+        assert!(!is_authorized(&[1, 2], Some(3)));
+        assert!(!is_authorized(&[1], None));
+
+        assert_eq!(topic_name("/home/dev/sanshu/"), "📁 sanshu");
+        assert_eq!(topic_name("D:\\repo\\web"), "📁 web");
+
+        let mut topics = ProjectTopics::default();
+        topics.insert(-100, "/home/dev/sanshu/", 7);
+        assert_eq!(topics.get(-100, "/home/dev/sanshu"), Some(7));
+        assert_eq!(topics.get(-200, "/home/dev/sanshu"), None);
+    }
+}
@@ -0,0 +1,365 @@
+use anyhow::Result;
+use std::net::SocketAddr;
+use teloxide::types::Update;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::config::TelegramConfig;
+use crate::constants::telegram::{MAX_WEBHOOK_BODY_BYTES, WEBHOOK_SECRET_HEADER};
+use crate::{log_debug, log_important};
+
+/// Webhook 模式配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookConfig {
+    /// 本地监听地址，如 127.0.0.1:8787
+    pub bind_addr: String,
+    /// Telegram 推送使用的公网 HTTPS 地址（通常由反向代理或隧道转发到监听地址）
+    pub public_url: String,
+    /// 校验请求头 X-Telegram-Bot-Api-Secret-Token
+    pub secret_token: String,
+}
+
+impl WebhookConfig {
+    /// 从应用配置读取，未启用或配置不完整时返回 None（回退到长轮询）
+    pub fn from_config(config: &TelegramConfig) -> Option<Self> {
+        if !config.webhook_enabled {
+            return None;
+        }
+        if config.webhook_public_url.trim().is_empty() {
+            log_important!(warn, "已启用 Telegram Webhook，但未配置公网地址，回退到轮询模式");
+            return None;
+        }
+
+        let secret_token = if config.webhook_secret.trim().is_empty() {
+            derive_secret_token(&config.bot_token)
+        } else {
+            config.webhook_secret.trim().to_string()
+        };
+
+        Some(Self {
+            bind_addr: config.webhook_bind_addr.trim().to_string(),
+            public_url: config.webhook_public_url.trim().to_string(),
+            secret_token,
+        })
+    }
+
+    /// 公网地址中的路径部分，只接受推送到该路径的请求
+    fn path(&self) -> String {
+        reqwest::Url::parse(&self.public_url)
+            .map(|url| url.path().to_string())
+            .unwrap_or_else(|_| "/".to_string())
+    }
+}
+
+/// 未配置密钥时由 Bot Token 派生（各进程结果一致，且不泄露 Token）
+fn derive_secret_token(bot_token: &str) -> String {
+    let digest = ring::digest::digest(&ring::digest::SHA256, bot_token.as_bytes());
+    hex::encode(digest.as_ref())
+}
+
+/// 内嵌的 Webhook 接收服务，Drop 时停止监听
+pub struct WebhookServer {
+    task: JoinHandle<()>,
+    local_addr: SocketAddr,
+}
+
+impl WebhookServer {
+    /// 绑定监听地址并开始接收推送，收到的更新发送到 sender
+    pub async fn start(config: &WebhookConfig, sender: mpsc::UnboundedSender<Update>) -> Result<Self> {
+        let listener = TcpListener::bind(&config.bind_addr)
+            .await
+            .map_err(|e| anyhow::anyhow!("Webhook 监听 {} 失败: {}", config.bind_addr, e))?;
+        let local_addr = listener.local_addr()?;
+        log_important!(info, "Telegram Webhook 已在 {} 监听", local_addr);
+
+        let path = config.path();
+        let secret_token = config.secret_token.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                let (stream, _) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        log_debug!("Webhook 接受连接失败: {}", e);
+                        continue;
+                    }
+                };
+                let path = path.clone();
+                let secret_token = secret_token.clone();
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(stream, &path, &secret_token, &sender).await {
+                        log_debug!("Webhook 请求处理失败: {}", e);
+                    }
+                });
+            }
+        });
+
+        Ok(Self { task, local_addr })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for WebhookServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// 请求头校验结果
+#[derive(Debug, PartialEq)]
+enum HeadCheck {
+    /// 校验通过，附带请求体长度
+    Accept(usize),
+    Reject(u16, &'static str),
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    path: &str,
+    secret_token: &str,
+    sender: &mpsc::UnboundedSender<Update>,
+) -> Result<()> {
+    // 读取请求头
+    let mut buf = Vec::with_capacity(4096);
+    let head_end = loop {
+        if let Some(pos) = find_head_end(&buf) {
+            break pos;
+        }
+        if buf.len() > 16 * 1024 {
+            return respond(&mut stream, 431, "Request Header Fields Too Large").await;
+        }
+        let mut chunk = [0u8; 4096];
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&buf[..head_end]).to_string();
+    let content_length = match check_request_head(&head, path, secret_token) {
+        HeadCheck::Accept(len) => len,
+        HeadCheck::Reject(status, reason) => return respond(&mut stream, status, reason).await,
+    };
+
+    // 读取请求体
+    let mut body = buf[head_end + 4..].to_vec();
+    while body.len() < content_length {
+        let mut chunk = vec![0u8; (content_length - body.len()).min(64 * 1024)];
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(content_length);
+
+    match serde_json::from_slice::<Update>(&body) {
+        Ok(update) => {
+            let _ = sender.send(update);
+            respond(&mut stream, 200, "OK").await
+        }
+        Err(e) => {
+            log_debug!("Webhook 更新解析失败: {}", e);
+            // 返回 200 避免 Telegram 反复重试无法解析的更新
+            respond(&mut stream, 200, "OK").await
+        }
+    }
+}
+
+fn find_head_end(buf: &[u8]) -> Option<usize> {
+    buf.windows(4).position(|w| w == b"\r\n\r\n")
+}
+
+/// 校验请求行、路径、密钥与请求体长度
+fn check_request_head(head: &str, path: &str, secret_token: &str) -> HeadCheck {
+    let mut lines = head.lines();
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let target = parts.next().unwrap_or_default();
+    let target_path = target.split('?').next().unwrap_or_default();
+
+    if method != "POST" {
+        return HeadCheck::Reject(405, "Method Not Allowed");
+    }
+    if target_path.trim_end_matches('/') != path.trim_end_matches('/') {
+        return HeadCheck::Reject(404, "Not Found");
+    }
+
+    let mut secret = None;
+    let mut content_length = None;
+    for line in lines {
+        if let Some((name, value)) = line.split_once(':') {
+            let name = name.trim();
+            if name.eq_ignore_ascii_case(WEBHOOK_SECRET_HEADER) {
+                secret = Some(value.trim());
+            } else if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    // 常量时间比较，避免按响应耗时逐字节猜测密钥
+    let authorized = secret
+        .map(|secret| ring::constant_time::verify_slices_are_equal(secret.as_bytes(), secret_token.as_bytes()).is_ok())
+        .unwrap_or(false);
+    if !authorized {
+        return HeadCheck::Reject(401, "Unauthorized");
+    }
+    match content_length {
+        None => HeadCheck::Reject(411, "Length Required"),
+        Some(len) if len > MAX_WEBHOOK_BODY_BYTES => HeadCheck::Reject(413, "Payload Too Large"),
+        Some(len) => HeadCheck::Accept(len),
+    }
+}
+
+async fn respond(stream: &mut TcpStream, status: u16, reason: &str) -> Result<()> {
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        status, reason
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn head(secret: &str, length: usize) -> String {
+        format!(
+            "POST /tg/hook HTTP/1.1\r\nHost: example.com\r\nX-Telegram-Bot-Api-Secret-Token: {}\r\nContent-Length: {}",
+            secret, length
+        )
+    }
+
+    #[test]
+    fn test_check_request_head() {
+        assert_eq!(check_request_head(&head("s3cret", 42), "/tg/hook", "s3cret"), HeadCheck::Accept(42));
+        assert_eq!(check_request_head(&head("wrong", 42), "/tg/hook", "s3cret"), HeadCheck::Reject(401, "Unauthorized"));
+        assert_eq!(check_request_head(&head("s3cre", 42), "/tg/hook", "s3cret"), HeadCheck::Reject(401, "Unauthorized"));
+        assert_eq!(
+            check_request_head("POST /tg/hook HTTP/1.1\r\nContent-Length: 42", "/tg/hook", "s3cret"),
+            HeadCheck::Reject(401, "Unauthorized")
+        );
+        assert_eq!(check_request_head(&head("s3cret", 42), "/other", "s3cret"), HeadCheck::Reject(404, "Not Found"));
+        assert!(matches!(
+            check_request_head(&head("s3cret", MAX_WEBHOOK_BODY_BYTES + 1), "/tg/hook", "s3cret"),
+            HeadCheck::Reject(413, _)
+        ));
+        assert!(matches!(
+            check_request_head("GET /tg/hook HTTP/1.1", "/tg/hook", "s3cret"),
+            HeadCheck::Reject(405, _)
+        ));
+    }
+
+    #[test]
+    fn test_derive_secret_token() {
+        let secret = derive_secret_token("123:abc");
+        assert_eq!(secret.len(), 64);
+        assert_eq!(secret, derive_secret_token("123:abc"));
+        assert!(secret.chars().all(|c| c.is_ascii_alphanumeric()));
+    }
+
+    #[tokio::test]
+    async fn test_webhook_server_forwards_updates() {
+        let config = WebhookConfig {
+            bind_addr: "127.0.0.1:0".to_string(),
+            public_url: "https://example.com/tg/hook".to_string(),
+            secret_token: "s3cret".to_string(),
+        };
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let server = WebhookServer::start(&config, tx).await.unwrap();
+
+        let body = r#"{"update_id":7,"message":{"message_id":1,"date":1700000000,"chat":{"id":42,"type":"private","first_name":"dev"},"from":{"id":42,"is_bot":false,"first_name":"dev"},"text":"继续"}}"#;
+        let mut stream = TcpStream::connect(server.local_addr()).await.unwrap();
+        let request = format!("{}\r\n\r\n{}", head("s3cret", body.len()), body);
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"));
+
+        let update = rx.recv().await.unwrap();
+        assert_eq!(update.id.0, 7);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_mode_against_mock_bot_api() {
+        use crate::notify::mock::MockServer;
+        use crate::telegram::hub::UpdateHub;
+        use crate::telegram::TelegramCore;
+
+        // 本地模拟 Bot API（方法名大小写不敏感，两种写法都应答）
+        let api = MockServer::start(vec![
+            ("/SetWebhook", r#"{"ok":true,"result":true}"#),
+            ("/setWebhook", r#"{"ok":true,"result":true}"#),
+        ])
+        .await;
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let config = TelegramConfig {
+            bot_token: "990031001:mock-token".to_string(),
+            chat_id: "42".to_string(),
+            api_base_url: api.base_url(),
+            webhook_enabled: true,
+            webhook_bind_addr: format!("127.0.0.1:{}", port),
+            webhook_public_url: "https://example.com/tg/hook".to_string(),
+            webhook_secret: "s3cret".to_string(),
+            ..crate::config::default_telegram_config()
+        };
+
+        let core = TelegramCore::from_config(&config).await.unwrap();
+        let state_dir = std::env::temp_dir().join(format!("sanshu-telegram-webhook-{}", std::process::id()));
+        let mut hub = UpdateHub::open_in(&core, state_dir.clone()).unwrap();
+
+        // 接收服务启动后按 Telegram 的方式推送一条更新
+        let body = format!(
+            r#"{{"update_id":9,"message":{{"message_id":1,"date":{},"chat":{{"id":42,"type":"private","first_name":"dev"}},"from":{{"id":42,"is_bot":false,"first_name":"dev"}},"text":"继续"}}}}"#,
+            chrono::Utc::now().timestamp()
+        );
+        let push = tokio::spawn(async move {
+            loop {
+                if let Ok(mut stream) = TcpStream::connect(("127.0.0.1", port)).await {
+                    let request = format!(
+                        "POST /tg/hook HTTP/1.1\r\nHost: example.com\r\n{}: s3cret\r\nContent-Length: {}\r\n\r\n{}",
+                        WEBHOOK_SECRET_HEADER,
+                        body.len(),
+                        body
+                    );
+                    stream.write_all(request.as_bytes()).await.unwrap();
+                    let mut response = String::new();
+                    stream.read_to_string(&mut response).await.unwrap();
+                    return response;
+                }
+                tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+            }
+        });
+
+        let updates = hub.next_updates().await;
+        drop(hub);
+        let _ = std::fs::remove_dir_all(state_dir);
+
+        assert!(push.await.unwrap().starts_with("HTTP/1.1 200"));
+        assert_eq!(updates.iter().map(|u| u.id.0).collect::<Vec<_>>(), vec![9]);
+
+        // setWebhook 发往配置的 API 地址，并携带公网地址与密钥
+        let requests = api.requests();
+        let set_webhook = requests
+            .iter()
+            .find(|r| r.to_ascii_lowercase().contains("/bot990031001:mock-token/setwebhook"))
+            .expect("未调用 setWebhook");
+        assert!(set_webhook.contains("https://example.com/tg/hook"));
+        assert!(set_webhook.contains("s3cret"));
+    }
+}
@@ -1,6 +1,8 @@
 use crate::config::{save_config, load_config, AppState, ReplyConfig, WindowConfig, CustomPrompt, CustomPromptConfig, ShortcutConfig, ShortcutBinding};
 use crate::constants::{window, ui, validation};
-use crate::mcp::types::{build_continue_response, build_send_response, ImageAttachment, PopupRequest};
+use crate::mcp::types::{
+    build_continue_response, build_form_send_response, FileAttachment, ImageAttachment, PopupRequest,
+};
 use crate::mcp::handlers::create_tauri_popup;
 use tauri::{AppHandle, Manager, State};
 
@@ -408,6 +410,10 @@ pub async fn send_mcp_response(
         return Err("响应内容不能为空".to_string());
     }
 
+    // 请求已答复，不再接收 Telegram 及其他渠道的消息
+    crate::telegram::routing::release_active_routes();
+    crate::notify::finish_active_request();
+
     // 检查是否为MCP模式
     let is_mcp_mode = args.len() >= 3 && args[1] == "--mcp-request";
 
@@ -585,13 +591,17 @@ pub fn build_mcp_send_response(
     user_input: Option<String>,
     selected_options: Vec<String>,
     images: Vec<ImageAttachment>,
+    form_values: Option<serde_json::Map<String, serde_json::Value>>,
+    attachments: Option<Vec<FileAttachment>>,
     request_id: Option<String>,
     source: String,
 ) -> Result<String, String> {
-    Ok(build_send_response(
+    Ok(build_form_send_response(
         user_input,
         selected_options,
         images,
+        form_values,
+        attachments.unwrap_or_default(),
         request_id,
         &source,
     ))
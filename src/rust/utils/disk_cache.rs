@@ -0,0 +1,303 @@
+// 通用磁盘缓存
+// 条目按命名空间存放在各自的子目录中，每个条目一个 JSON 文件，跨进程复用
+// 文件修改时间记录最近访问时间，总占用超出上限时按 LRU 淘汰
+
+use anyhow::Result;
+use ring::digest::{Context, SHA256};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::log_debug;
+
+/// 磁盘上的缓存条目
+#[derive(Serialize, Deserialize)]
+struct CacheFile<T> {
+    /// 写入时间（Unix 秒），用于判断过期
+    created_at: u64,
+    value: T,
+}
+
+/// 仅读取写入时间（统计与清理时不解析内容）
+#[derive(Deserialize)]
+struct CacheFileHeader {
+    created_at: u64,
+}
+
+/// 读取到的缓存条目（包括已过期的条目）
+pub struct CacheEntry<T> {
+    pub value: T,
+    /// 写入时间（Unix 秒）
+    pub created_at: u64,
+    /// 写入至今的秒数
+    pub age_secs: u64,
+    pub expired: bool,
+}
+
+/// 磁盘缓存统计
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiskCacheStats {
+    pub total_entries: usize,
+    pub expired_entries: usize,
+    pub total_bytes: u64,
+}
+
+/// 基于文件的 LRU 缓存
+///
+/// 写入采用临时文件 + 重命名，多个进程可同时使用
+pub struct DiskCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    ttl_secs: AtomicU64,
+}
+
+impl DiskCache {
+    pub fn new(dir: PathBuf, max_bytes: u64, ttl_secs: u64) -> Self {
+        Self {
+            dir,
+            max_bytes,
+            ttl_secs: AtomicU64::new(ttl_secs),
+        }
+    }
+
+    pub fn ttl_secs(&self) -> u64 {
+        self.ttl_secs.load(Ordering::Relaxed)
+    }
+
+    pub fn set_ttl_secs(&self, secs: u64) {
+        self.ttl_secs.store(secs, Ordering::Relaxed);
+    }
+
+    /// 读取未过期的条目，过期或损坏的条目直接删除
+    pub fn get<T: DeserializeOwned>(&self, namespace: &str, key: &str) -> Option<T> {
+        let entry = self.get_entry(namespace, key)?;
+        if entry.expired {
+            let _ = fs::remove_file(self.entry_path(namespace, key));
+            return None;
+        }
+        Some(entry.value)
+    }
+
+    /// 读取条目（包括已过期的条目，由调用方决定是否使用），并刷新其访问时间
+    pub fn get_entry<T: DeserializeOwned>(&self, namespace: &str, key: &str) -> Option<CacheEntry<T>> {
+        let path = self.entry_path(namespace, key);
+        let content = fs::read(&path).ok()?;
+        let file: CacheFile<T> = match serde_json::from_slice(&content) {
+            Ok(file) => file,
+            Err(_) => {
+                let _ = fs::remove_file(&path);
+                return None;
+            }
+        };
+
+        touch(&path);
+        Some(CacheEntry {
+            expired: self.is_expired(file.created_at),
+            age_secs: now_secs().saturating_sub(file.created_at),
+            created_at: file.created_at,
+            value: file.value,
+        })
+    }
+
+    /// 写入条目，超出容量时淘汰最久未访问的条目
+    pub fn put<T: Serialize>(&self, namespace: &str, key: &str, value: &T) -> Result<()> {
+        let content = serde_json::to_vec(&CacheFile {
+            created_at: now_secs(),
+            value,
+        })?;
+        write_atomic(&self.entry_path(namespace, key), &content)?;
+        self.evict();
+        Ok(())
+    }
+
+    /// 统计条目数、过期条目数与磁盘占用
+    pub fn stats(&self) -> DiskCacheStats {
+        let mut stats = DiskCacheStats::default();
+        for (path, size, _) in self.list_entries() {
+            stats.total_entries += 1;
+            stats.total_bytes += size;
+            if self.is_entry_expired(&path) {
+                stats.expired_entries += 1;
+            }
+        }
+        stats
+    }
+
+    /// 清理缓存，返回（清理数量，剩余数量）
+    pub fn clear(&self, expired_only: bool) -> (usize, usize) {
+        let mut cleared = 0;
+        let mut remaining = 0;
+        for (path, _, _) in self.list_entries() {
+            let remove = !expired_only || self.is_entry_expired(&path);
+            if remove && fs::remove_file(&path).is_ok() {
+                cleared += 1;
+            } else {
+                remaining += 1;
+            }
+        }
+        (cleared, remaining)
+    }
+
+    fn entry_path(&self, namespace: &str, key: &str) -> PathBuf {
+        self.dir.join(namespace).join(format!("{}.json", hash_key(key)))
+    }
+
+    fn is_expired(&self, created_at: u64) -> bool {
+        now_secs().saturating_sub(created_at) > self.ttl_secs()
+    }
+
+    /// 无法读取写入时间的条目视为过期
+    fn is_entry_expired(&self, path: &Path) -> bool {
+        match read_created_at(path) {
+            Some(created_at) => self.is_expired(created_at),
+            None => true,
+        }
+    }
+
+    /// 列出所有命名空间下的条目：（路径，大小，最近访问时间）
+    fn list_entries(&self) -> Vec<(PathBuf, u64, SystemTime)> {
+        let Ok(namespaces) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut entries = Vec::new();
+        for namespace in namespaces.flatten() {
+            let Ok(read_dir) = fs::read_dir(namespace.path()) else {
+                continue;
+            };
+            for entry in read_dir.flatten() {
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                    continue;
+                }
+                if let Ok(metadata) = entry.metadata() {
+                    let accessed = metadata.modified().unwrap_or(UNIX_EPOCH);
+                    entries.push((path, metadata.len(), accessed));
+                }
+            }
+        }
+        entries
+    }
+
+    /// 总占用超过上限时，按最近访问时间从旧到新删除
+    fn evict(&self) {
+        let mut entries = self.list_entries();
+        let mut total: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        if total <= self.max_bytes {
+            return;
+        }
+
+        entries.sort_by_key(|(_, _, accessed)| *accessed);
+        let mut evicted = 0;
+        for (path, size, _) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            if fs::remove_file(&path).is_ok() {
+                total = total.saturating_sub(size);
+                evicted += 1;
+            }
+        }
+        log_debug!("缓存 {} 超出容量，已淘汰 {} 个条目", self.dir.display(), evicted);
+    }
+}
+
+/// 写入文件（临时文件 + 重命名），读取方不会看到写了一半的内容
+fn write_atomic(path: &Path, content: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temp_path = path.with_extension(format!("tmp{}", std::process::id()));
+    fs::write(&temp_path, content)?;
+    fs::rename(&temp_path, path)
+}
+
+fn hash_key(key: &str) -> String {
+    let mut context = Context::new(&SHA256);
+    context.update(key.as_bytes());
+    hex::encode(&context.finish().as_ref()[..16])
+}
+
+fn read_created_at(path: &Path) -> Option<u64> {
+    let content = fs::read(path).ok()?;
+    serde_json::from_slice::<CacheFileHeader>(&content)
+        .ok()
+        .map(|header| header.created_at)
+}
+
+/// 将文件修改时间更新为当前时间，作为 LRU 的访问记录
+fn touch(path: &Path) {
+    if let Ok(file) = fs::File::options().write(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_cache(name: &str, max_bytes: u64) -> DiskCache {
+        let dir = std::env::temp_dir().join(format!("sanshu-disk-cache-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        DiskCache::new(dir, max_bytes, 60)
+    }
+
+    #[test]
+    fn test_put_get_and_clear() {
+        let cache = test_cache("basic", 1024 * 1024);
+        cache.put("svg", "42", &"<svg/>".to_string()).unwrap();
+        cache.put("search", "home:all", &vec![1u64, 2, 3]).unwrap();
+
+        assert_eq!(cache.get::<String>("svg", "42").as_deref(), Some("<svg/>"));
+        assert_eq!(cache.get::<Vec<u64>>("search", "home:all"), Some(vec![1, 2, 3]));
+        assert_eq!(cache.get::<String>("svg", "43"), None);
+        assert_eq!(cache.get::<String>("search", "42"), None);
+        assert_eq!(cache.stats().total_entries, 2);
+
+        // 有效期为 0 时，写入时间早于当前秒的条目视为过期；get_entry 仍可读取
+        cache.set_ttl_secs(0);
+        let path = cache.entry_path("svg", "42");
+        fs::write(&path, r#"{"created_at":1,"value":"<svg/>"}"#).unwrap();
+        assert_eq!(cache.stats().expired_entries, 1);
+        let stale = cache.get_entry::<String>("svg", "42").unwrap();
+        assert!(stale.expired);
+        assert_eq!(stale.created_at, 1);
+        assert_eq!(cache.clear(true), (1, 1));
+        assert_eq!(cache.clear(false), (1, 0));
+        let _ = fs::remove_dir_all(&cache.dir);
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let cache = test_cache("lru", 200);
+        let value = "x".repeat(60);
+        cache.put("svg", "old", &value).unwrap();
+        cache.put("svg", "recent", &value).unwrap();
+
+        // 让 "old" 成为最久未访问的条目
+        let old_path = cache.entry_path("svg", "old");
+        fs::File::options()
+            .write(true)
+            .open(&old_path)
+            .unwrap()
+            .set_modified(UNIX_EPOCH + Duration::from_secs(1))
+            .unwrap();
+
+        // 淘汰跨命名空间统计占用
+        cache.put("search", "new", &value).unwrap();
+        assert_eq!(cache.get::<String>("svg", "old"), None);
+        assert!(cache.get::<String>("svg", "recent").is_some());
+        assert!(cache.get::<String>("search", "new").is_some());
+        let _ = fs::remove_dir_all(&cache.dir);
+    }
+}